@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Datelike, TimeDelta, TimeZone};
+
+use crate::task::CronTask;
+
+/// Run time a task may use per calendar month.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuntimeBudget {
+    monthly: TimeDelta,
+    carry_over: bool,
+}
+
+impl RuntimeBudget {
+    pub fn monthly(limit: TimeDelta) -> Self {
+        Self {
+            monthly: limit,
+            carry_over: false,
+        }
+    }
+
+    /// Adds what was left unused of the previous month's allowance to each
+    /// month. Only one month is carried over, unused time does not pile up.
+    pub fn with_carry_over(mut self) -> Self {
+        self.carry_over = true;
+        self
+    }
+
+    pub fn limit(&self) -> TimeDelta {
+        self.monthly
+    }
+
+    pub fn carries_over(&self) -> bool {
+        self.carry_over
+    }
+}
+
+/// Run time used per task and month. Schedulers record the runs they
+/// dispatch in the ledger of their cron as they end, callers persist the
+/// entries between restarts.
+#[derive(Debug, Default, Clone)]
+pub struct BudgetLedger {
+    used: HashMap<(String, i32, u32), TimeDelta>,
+}
+
+impl BudgetLedger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `runtime` to the month of `date` for the task known as `key`,
+    /// see [`CronTask::budget_key`].
+    pub fn record<Tz: TimeZone>(&mut self, key: &str, date: DateTime<Tz>, runtime: TimeDelta) {
+        *self
+            .used
+            .entry((key.to_string(), date.year(), date.month()))
+            .or_default() += runtime;
+    }
+
+    pub fn used(&self, key: &str, year: i32, month: u32) -> TimeDelta {
+        self.used
+            .get(&(key.to_string(), year, month))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Recorded entries as task key, year, month and run time, for
+    /// persistence.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, i32, u32, TimeDelta)> {
+        self.used
+            .iter()
+            .map(|((key, year, month), used)| (key.as_str(), *year, *month, *used))
+    }
+
+    /// Run time `task` has left in the month of `date`, `None` when it has
+    /// no budget.
+    pub fn remaining<Tz: TimeZone>(
+        &self,
+        task: &CronTask,
+        date: DateTime<Tz>,
+    ) -> Option<TimeDelta> {
+        let budget = task.budget()?;
+        let key = task.budget_key();
+        let (year, month) = (date.year(), date.month());
+
+        let carried = if budget.carry_over {
+            let (year, month) = match month {
+                1 => (year - 1, 12),
+                month => (year, month - 1),
+            };
+            (budget.monthly - self.used(&key, year, month)).max(TimeDelta::zero())
+        } else {
+            TimeDelta::zero()
+        };
+
+        Some(budget.monthly + carried - self.used(&key, year, month))
+    }
+
+    pub fn is_exhausted<Tz: TimeZone>(&self, task: &CronTask, date: DateTime<Tz>) -> bool {
+        self.remaining(task, date)
+            .is_some_and(|remaining| remaining <= TimeDelta::zero())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn task(budget: RuntimeBudget) -> CronTask {
+        CronTask::builder()
+            .minutes(0)
+            .path("/usr/bin/batch".to_string())
+            .budget(budget)
+            .build()
+            .unwrap()
+    }
+
+    fn at(month: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, month, 10, 0, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_remaining() {
+        let task = task(RuntimeBudget::monthly(TimeDelta::hours(10)));
+        let mut ledger = BudgetLedger::new();
+
+        ledger.record(&task.budget_key(), at(3), TimeDelta::hours(4));
+        ledger.record(&task.budget_key(), at(3), TimeDelta::hours(6));
+
+        assert_eq!(ledger.remaining(&task, at(3)), Some(TimeDelta::zero()));
+        assert!(ledger.is_exhausted(&task, at(3)));
+        assert_eq!(ledger.remaining(&task, at(4)), Some(TimeDelta::hours(10)));
+        assert_eq!(ledger.entries().count(), 1);
+    }
+
+    #[test]
+    fn test_carry_over() {
+        let task = task(RuntimeBudget::monthly(TimeDelta::hours(10)).with_carry_over());
+        let mut ledger = BudgetLedger::new();
+
+        ledger.record(&task.budget_key(), at(1), TimeDelta::hours(7));
+
+        assert_eq!(ledger.remaining(&task, at(2)), Some(TimeDelta::hours(13)));
+        // Carried from the unrecorded previous month, but no further.
+        assert_eq!(ledger.remaining(&task, at(3)), Some(TimeDelta::hours(20)));
+    }
+
+    #[test]
+    fn test_no_budget() {
+        let task = CronTask::builder().allow_commandless().build().unwrap();
+
+        assert_eq!(BudgetLedger::new().remaining(&task, at(1)), None);
+    }
+}
@@ -0,0 +1,237 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error as _};
+
+use crate::{
+    cron::Cron,
+    error::{CronError, Result},
+    scheduler::{CatchUpPolicy, FiringMode},
+};
+
+/// Version of the bundle format written by this build. Bundles of a later
+/// version are rejected rather than half read.
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// A whole scheduling setup as one serializable value, to move it between
+/// hosts or keep it under version control: the cron with its tasks, groups
+/// and their calendars, paused tasks and blackouts, the policies of the
+/// scheduler, and where its state is kept. The state itself stays in its
+/// store.
+///
+/// Any serde format works, such as JSON:
+///
+/// ```ignore
+/// let bundle = ThreadScheduler::builder(cron, SystemClock)
+///     .catch_up(CatchUpPolicy::Latest)
+///     .export_bundle();
+/// std::fs::write("schedule.json", serde_json::to_string_pretty(&bundle)?)?;
+///
+/// let bundle: Bundle = serde_json::from_str(&std::fs::read_to_string("schedule.json")?)?;
+/// let store = open_store(bundle.state.as_deref());
+/// let scheduler = ThreadScheduler::import_bundle(bundle, SystemClock)
+///     .state_store(store)
+///     .run(dispatch);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Bundle {
+    pub cron: Cron,
+    pub firing_mode: FiringMode,
+    /// How the scheduler catches up on start, not at all when `None`.
+    pub catch_up: Option<CatchUpPolicy>,
+    /// Reference of the state store, see
+    /// [`crate::state::StateStore::reference`].
+    pub state: Option<String>,
+}
+
+/// The cron is read or written in place, the rest of the bundle next to
+/// it.
+#[derive(Serialize, Deserialize)]
+struct BundleRepr<C> {
+    version: u32,
+    cron: C,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    paused: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    blackouts: Vec<WindowRepr>,
+    /// `exact` or `coarse`, the former when left out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    firing_mode: Option<String>,
+    /// Such as `latest` or `within 300s`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    catch_up: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    state: Option<String>,
+}
+
+/// A blackout as RFC 3339 dates, the end left out of it.
+#[derive(Serialize, Deserialize)]
+struct WindowRepr {
+    start: String,
+    end: String,
+}
+
+fn parse_date(s: &str) -> Result<DateTime<Local>> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|date| date.with_timezone(&Local))
+        .map_err(|_| CronError::InvalidExpression(format!("invalid date `{s}`")))
+}
+
+impl Serialize for Bundle {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut paused: Vec<String> = self.cron.paused_tasks().map(str::to_string).collect();
+        paused.sort();
+
+        BundleRepr {
+            version: BUNDLE_VERSION,
+            cron: &self.cron,
+            paused,
+            blackouts: self
+                .cron
+                .blackouts()
+                .iter()
+                .map(|window| WindowRepr {
+                    start: window.start.to_rfc3339(),
+                    end: window.end.to_rfc3339(),
+                })
+                .collect(),
+            firing_mode: Some(self.firing_mode)
+                .filter(|mode| *mode != FiringMode::Exact)
+                .map(|mode| mode.to_string()),
+            catch_up: self.catch_up.map(|policy| policy.to_string()),
+            state: self.state.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Bundle {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let repr = BundleRepr::<Cron>::deserialize(deserializer)?;
+        repr.into_bundle().map_err(D::Error::custom)
+    }
+}
+
+impl BundleRepr<Cron> {
+    fn into_bundle(self) -> Result<Bundle> {
+        if self.version > BUNDLE_VERSION {
+            return Err(CronError::InvalidExpression(format!(
+                "bundle version {} is newer than {BUNDLE_VERSION}",
+                self.version
+            )));
+        }
+        let mut cron = self.cron;
+        for name in &self.paused {
+            if !cron.set_task_paused(name, true) {
+                return Err(CronError::InvalidExpression(format!(
+                    "no task named `{name}` to pause"
+                )));
+            }
+        }
+        for window in self.blackouts {
+            cron.add_blackout(parse_date(&window.start)?..parse_date(&window.end)?);
+        }
+
+        Ok(Bundle {
+            cron,
+            firing_mode: self
+                .firing_mode
+                .map(|mode| mode.parse())
+                .transpose()?
+                .unwrap_or_default(),
+            catch_up: self.catch_up.map(|policy| policy.parse()).transpose()?,
+            state: self.state,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeDelta, TimeZone};
+
+    use super::*;
+    use crate::{
+        group::{OverlapPolicy, TaskGroup},
+        realtime::FixedClock,
+        scheduler::ThreadScheduler,
+        state::{SchedulerState, StateStore},
+        task::CronTask,
+    };
+
+    /// Store found again by its path, keeping nothing.
+    struct FileStore(&'static str);
+
+    impl StateStore for FileStore {
+        fn load(&mut self) -> Result<Option<SchedulerState>> {
+            Ok(None)
+        }
+
+        fn save(&mut self, _: &SchedulerState) -> Result<()> {
+            Ok(())
+        }
+
+        fn reference(&self) -> Option<String> {
+            Some(self.0.to_string())
+        }
+    }
+
+    #[test]
+    fn test_bundle() {
+        let start = Local.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        let mut cron = Cron::new().with_lookahead(TimeDelta::days(1));
+        cron.add_task(CronTask::parse("0 3 * * * /usr/bin/backup").unwrap());
+        cron.add_task(
+            CronTask::builder()
+                .name("report")
+                .group("nightly")
+                .path("/usr/bin/report".to_string())
+                .build()
+                .unwrap(),
+        );
+        cron.add_group(
+            TaskGroup::new("nightly")
+                .with_max_concurrent(1)
+                .with_overlap_policy(OverlapPolicy::Skip),
+        );
+        cron.set_task_paused("report", true);
+        cron.add_blackout(start..start + TimeDelta::hours(2));
+
+        let bundle = ThreadScheduler::builder(cron, FixedClock(start))
+            .firing_mode(FiringMode::Coarse)
+            .catch_up(CatchUpPolicy::Within(TimeDelta::minutes(5)))
+            .state_store(FileStore("/var/lib/cron/state"))
+            .export_bundle();
+        let json = serde_json::to_string(&bundle).unwrap();
+        let back: Bundle = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(serde_json::to_string(&back).unwrap(), json);
+        assert!(json.contains(r#""catch_up":"within 300s""#));
+        assert_eq!(back.firing_mode, FiringMode::Coarse);
+        assert_eq!(back.state.as_deref(), Some("/var/lib/cron/state"));
+        assert!(back.cron.is_task_paused("report"));
+        assert_eq!(back.cron.blackouts(), bundle.cron.blackouts());
+        assert_eq!(
+            back.cron.group("nightly").unwrap().overlap_policy(),
+            OverlapPolicy::Skip
+        );
+
+        // The store is opened by the caller, the rest is set up as it was.
+        let imported = ThreadScheduler::import_bundle(back, FixedClock(start)).export_bundle();
+        assert_eq!(imported.firing_mode, FiringMode::Coarse);
+        assert_eq!(imported.catch_up, bundle.catch_up);
+        assert_eq!(imported.state, None);
+        assert_eq!(imported.cron.into_iter().count(), 2);
+    }
+
+    #[test]
+    fn test_bundle_rejects() {
+        let invalid = [
+            r#"{"version":2,"cron":{}}"#,
+            r#"{"version":1,"cron":{},"paused":["missing"]}"#,
+            r#"{"version":1,"cron":{},"catch_up":"sometimes"}"#,
+            r#"{"version":1,"cron":{},"blackouts":[{"start":"noon","end":"later"}]}"#,
+        ];
+
+        for json in invalid {
+            assert!(serde_json::from_str::<Bundle>(json).is_err(), "{json}");
+        }
+    }
+}
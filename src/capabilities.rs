@@ -0,0 +1,207 @@
+use std::ops::RangeInclusive;
+
+/// What this build of the crate understands, for services that need to
+/// agree on a schedule syntax before storing schedules in a shared place.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    pub version: &'static str,
+    /// Empty without the `parser` feature, nothing can be parsed then.
+    pub dialects: Vec<DialectCapabilities>,
+    /// Cargo features compiled in.
+    pub features: Vec<&'static str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DialectCapabilities {
+    pub name: &'static str,
+    /// Accepted numbers of schedule fields.
+    pub fields: RangeInclusive<usize>,
+    /// Tokens accepted beyond `*`, `,`, `-` and `/`.
+    pub tokens: Vec<&'static str>,
+}
+
+#[cfg(feature = "parser")]
+const NICKNAMES: [&str; 8] = [
+    "@reboot",
+    "@hourly",
+    "@daily",
+    "@midnight",
+    "@weekly",
+    "@monthly",
+    "@yearly",
+    "@annually",
+];
+
+const FEATURES: [(&str, bool); 45] = [
+    ("std", cfg!(feature = "std")),
+    ("parser", cfg!(feature = "parser")),
+    ("crontab", cfg!(feature = "crontab")),
+    ("env", cfg!(feature = "env")),
+    ("check", cfg!(feature = "check")),
+    ("executor", cfg!(feature = "executor")),
+    ("run-as", cfg!(feature = "run-as")),
+    ("run-id", cfg!(feature = "run-id")),
+    ("regex", cfg!(feature = "regex")),
+    ("systemd", cfg!(feature = "systemd")),
+    ("rrule", cfg!(feature = "rrule")),
+    ("ics", cfg!(feature = "ics")),
+    ("clap", cfg!(feature = "clap")),
+    ("serde", cfg!(feature = "serde")),
+    ("toml", cfg!(feature = "toml")),
+    ("yaml", cfg!(feature = "yaml")),
+    ("schemars", cfg!(feature = "schemars")),
+    ("bundle", cfg!(feature = "bundle")),
+    ("test-util", cfg!(feature = "test-util")),
+    ("describe", cfg!(feature = "describe")),
+    ("describe-fr", cfg!(feature = "describe-fr")),
+    ("english", cfg!(feature = "english")),
+    ("chrono-tz", cfg!(feature = "chrono-tz")),
+    ("time", cfg!(feature = "time")),
+    ("clock", cfg!(feature = "clock")),
+    ("scheduler", cfg!(feature = "scheduler")),
+    ("simulator", cfg!(feature = "simulator")),
+    ("budget", cfg!(feature = "budget")),
+    ("group", cfg!(feature = "group")),
+    ("retry", cfg!(feature = "retry")),
+    ("shard", cfg!(feature = "shard")),
+    ("success", cfg!(feature = "success")),
+    ("countdown", cfg!(feature = "countdown")),
+    ("debug", cfg!(feature = "debug")),
+    ("fingerprint", cfg!(feature = "fingerprint")),
+    ("lint", cfg!(feature = "lint")),
+    ("owner", cfg!(feature = "owner")),
+    ("pair", cfg!(feature = "pair")),
+    ("patch", cfg!(feature = "patch")),
+    ("preset", cfg!(feature = "preset")),
+    ("capabilities", cfg!(feature = "capabilities")),
+    ("tokio", cfg!(feature = "tokio")),
+    ("stream", cfg!(feature = "stream")),
+    ("tui", cfg!(feature = "tui")),
+    ("full", cfg!(feature = "full")),
+];
+
+/// Reports the version, dialects and features of this build.
+pub fn capabilities() -> Capabilities {
+    Capabilities {
+        version: env!("CARGO_PKG_VERSION"),
+        dialects: dialects(),
+        features: FEATURES
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| *name)
+            .collect(),
+    }
+}
+
+#[cfg(feature = "parser")]
+fn dialects() -> Vec<DialectCapabilities> {
+    let mut unix = vec!["L", "W", "#", "H"];
+    unix.extend(NICKNAMES);
+
+    vec![
+        DialectCapabilities {
+            name: "unix",
+            fields: 5..=5,
+            tokens: unix,
+        },
+        DialectCapabilities {
+            name: "unix-seconds",
+            fields: 6..=6,
+            tokens: vec!["L", "W", "#", "H"],
+        },
+        DialectCapabilities {
+            name: "quartz",
+            fields: 6..=7,
+            tokens: vec!["L", "W", "#", "H", "?"],
+        },
+        DialectCapabilities {
+            name: "eventbridge",
+            fields: 6..=6,
+            tokens: vec!["L", "W", "#", "H", "?", "cron()"],
+        },
+    ]
+}
+
+#[cfg(not(feature = "parser"))]
+fn dialects() -> Vec<DialectCapabilities> {
+    Vec::new()
+}
+
+impl Capabilities {
+    pub fn dialect(&self, name: &str) -> Option<&DialectCapabilities> {
+        self.dialects.iter().find(|dialect| dialect.name == name)
+    }
+
+    pub fn has_feature(&self, feature: &str) -> bool {
+        self.features.contains(&feature)
+    }
+
+    pub fn to_json(&self) -> String {
+        let strings = |items: &[&str]| {
+            items
+                .iter()
+                .map(|item| format!("\"{item}\""))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let dialects = self
+            .dialects
+            .iter()
+            .map(|dialect| {
+                format!(
+                    "{{\"name\":\"{}\",\"fields\":{{\"min\":{},\"max\":{}}},\"tokens\":[{}]}}",
+                    dialect.name,
+                    dialect.fields.start(),
+                    dialect.fields.end(),
+                    strings(&dialect.tokens)
+                )
+            })
+            .collect::<Vec<_>>();
+
+        format!(
+            "{{\"version\":\"{}\",\"dialects\":[{}],\"features\":[{}]}}",
+            self.version,
+            dialects.join(","),
+            strings(&self.features)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capabilities() {
+        let capabilities = capabilities();
+
+        assert_eq!(capabilities.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(capabilities.has_feature("parser"), cfg!(feature = "parser"));
+        assert!(!capabilities.has_feature("unknown"));
+        assert!(
+            capabilities
+                .to_json()
+                .starts_with(&format!("{{\"version\":\"{}\"", capabilities.version))
+        );
+    }
+
+    #[cfg(feature = "parser")]
+    #[test]
+    fn test_capabilities_dialects() {
+        use crate::{parser::Dialect, task::CronTask};
+
+        let capabilities = capabilities();
+        let quartz = capabilities.dialect("quartz").unwrap();
+
+        assert_eq!(quartz.fields, 6..=7);
+        assert!(quartz.tokens.contains(&"?"));
+        assert!(!capabilities.dialect("unix").unwrap().tokens.contains(&"?"));
+        assert!(capabilities.dialect("unknown").is_none());
+        assert!(CronTask::parse_with(Dialect::Quartz, "0 15 10 ? * 6#3 2025 /bin/true").is_ok());
+        assert!(
+            capabilities
+                .to_json()
+                .contains("{\"name\":\"unix\",\"fields\":{\"min\":5,\"max\":5}")
+        );
+    }
+}
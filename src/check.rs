@@ -0,0 +1,142 @@
+use std::process::Command;
+
+use crate::{
+    cron::Cron,
+    error::{CronError, Result},
+    task::CronTask,
+};
+
+#[derive(Debug)]
+pub struct CheckFailure {
+    /// Position of the failing task in the `Cron`.
+    pub index: usize,
+    pub error: CronError,
+}
+
+impl CronTask {
+    /// Runs the task's check command, if any, through the shell. A non-zero
+    /// exit status is reported as a failure.
+    pub fn run_check(&self) -> Result<()> {
+        let Some(check) = self.check() else {
+            return Ok(());
+        };
+
+        let status = shell(check)
+            .status()
+            .map_err(|e| CronError::CheckFailed(check.to_string(), e.to_string()))?;
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(CronError::CheckFailed(
+                check.to_string(),
+                status.to_string(),
+            ))
+        }
+    }
+}
+
+impl Cron {
+    /// Runs the check command of every task and collects the failures.
+    pub fn validate(&self) -> std::result::Result<(), Vec<CheckFailure>> {
+        let failures: Vec<CheckFailure> = self
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, task)| {
+                task.run_check()
+                    .err()
+                    .map(|error| CheckFailure { index, error })
+            })
+            .collect();
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(failures)
+        }
+    }
+}
+
+#[cfg(unix)]
+pub(crate) fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(command);
+    cmd
+}
+
+#[cfg(windows)]
+pub(crate) fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.arg("/C").arg(command);
+    cmd
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_check_without_command() {
+        let task = CronTask::builder().allow_commandless().build().unwrap();
+
+        assert!(task.run_check().is_ok());
+    }
+
+    #[test]
+    fn test_run_check() {
+        let ok = CronTask::builder()
+            .check("test -d /")
+            .allow_commandless()
+            .build()
+            .unwrap();
+        let failing = CronTask::builder()
+            .check("test -x /nonexistent/binary")
+            .allow_commandless()
+            .build()
+            .unwrap();
+
+        assert!(ok.run_check().is_ok());
+        assert!(matches!(
+            failing.run_check(),
+            Err(CronError::CheckFailed(cmd, _)) if cmd == "test -x /nonexistent/binary"
+        ));
+    }
+
+    #[test]
+    fn test_validate_collects_failures() {
+        let mut cron = Cron::new();
+
+        cron.add_task(
+            CronTask::builder()
+                .check("true")
+                .allow_commandless()
+                .build()
+                .unwrap(),
+        );
+        cron.add_task(
+            CronTask::builder()
+                .check("false")
+                .allow_commandless()
+                .build()
+                .unwrap(),
+        );
+        cron.add_task(CronTask::builder().allow_commandless().build().unwrap());
+        cron.add_task(
+            CronTask::builder()
+                .check("exit 3")
+                .allow_commandless()
+                .build()
+                .unwrap(),
+        );
+
+        let failures = cron.validate().unwrap_err();
+        let indexes: Vec<usize> = failures.iter().map(|f| f.index).collect();
+
+        assert_eq!(indexes, [1, 3]);
+    }
+
+    #[test]
+    fn test_validate_empty_cron() {
+        assert!(Cron::new().validate().is_ok());
+    }
+}
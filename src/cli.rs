@@ -0,0 +1,113 @@
+use std::ffi::OsStr;
+
+use clap::{
+    Arg, Command,
+    builder::{TypedValueParser, ValueParserFactory},
+    error::ErrorKind,
+};
+
+use crate::{parser::Dialect, task::CronTask};
+
+/// Value parser for `--schedule "*/5 * * * *"` style arguments. The command
+/// after the schedule is optional, so a bare schedule gives a task with an
+/// empty path.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CronTaskParser {
+    dialect: Dialect,
+}
+
+impl CronTaskParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn dialect(mut self, dialect: Dialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    fn example(&self) -> &'static str {
+        match self.dialect {
+            Dialect::Unix => "*/5 * * * *",
+            Dialect::Quartz => "0 */5 * ? * *",
+            Dialect::EventBridge => "cron(*/5 * * * ? *)",
+        }
+    }
+}
+
+impl TypedValueParser for CronTaskParser {
+    type Value = CronTask;
+
+    fn parse_ref(
+        &self,
+        cmd: &Command,
+        arg: Option<&Arg>,
+        value: &OsStr,
+    ) -> Result<Self::Value, clap::Error> {
+        let value = value
+            .to_str()
+            .ok_or_else(|| clap::Error::new(ErrorKind::InvalidUtf8).with_cmd(cmd))?;
+
+        CronTask::parse_with(self.dialect, value).map_err(|error| {
+            let arg = arg.map_or_else(|| "...".to_string(), |arg| arg.to_string());
+            let message = format!(
+                "invalid value '{value}' for '{arg}': {error}\n\n  \
+                 tip: expected a schedule such as '{}'\n",
+                self.example()
+            );
+
+            clap::Error::raw(ErrorKind::ValueValidation, message).with_cmd(cmd)
+        })
+    }
+}
+
+impl ValueParserFactory for CronTask {
+    type Parser = CronTaskParser;
+
+    fn value_parser() -> Self::Parser {
+        CronTaskParser::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::{Arg, Command, value_parser};
+
+    use super::*;
+
+    fn command(parser: impl TypedValueParser<Value = CronTask>) -> Command {
+        Command::new("app").arg(Arg::new("schedule").long("schedule").value_parser(parser))
+    }
+
+    #[test]
+    fn test_value_parser() {
+        let matches = command(value_parser!(CronTask))
+            .try_get_matches_from(["app", "--schedule", "*/5 * * * *"])
+            .unwrap();
+        let task = matches.get_one::<CronTask>("schedule").unwrap();
+
+        assert_eq!(task.to_string(), "*/5 * * * * \"\"");
+    }
+
+    #[test]
+    fn test_value_parser_dialect() {
+        let matches = command(CronTaskParser::new().dialect(Dialect::Quartz))
+            .try_get_matches_from(["app", "--schedule", "0 0 12 ? * 2"])
+            .unwrap();
+        let task = matches.get_one::<CronTask>("schedule").unwrap();
+
+        assert_eq!(task.hour().to_string(), "12");
+    }
+
+    #[test]
+    fn test_value_parser_error() {
+        let error = command(value_parser!(CronTask))
+            .try_get_matches_from(["app", "--schedule", "61 * * * *"])
+            .unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::ValueValidation);
+        let rendered = error.to_string();
+        assert!(rendered.contains("invalid value '61 * * * *' for '--schedule <schedule>'"));
+        assert!(rendered.contains("tip: expected a schedule such as '*/5 * * * *'"));
+    }
+}
@@ -0,0 +1,95 @@
+use std::borrow::Cow;
+
+use crate::error::{CronError, Result};
+
+/// Splits a command line into words the way a POSIX shell would, honoring
+/// single quotes, double quotes and backslashes. Nothing is expanded.
+pub fn split(line: &str) -> Result<Vec<String>> {
+    let invalid = |reason: &str| CronError::InvalidExpression(format!("{reason} in `{line}`"));
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => words.extend(word.take()),
+            '\'' => {
+                let word = word.get_or_insert_default();
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err(invalid("unterminated quote")),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_default();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => word.push(c),
+                            Some(c) => word.extend(['\\', c]),
+                            None => return Err(invalid("unterminated quote")),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err(invalid("unterminated quote")),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some(c) => word.get_or_insert_default().push(c),
+                None => return Err(invalid("trailing backslash")),
+            },
+            c => word.get_or_insert_default().push(c),
+        }
+    }
+    words.extend(word);
+
+    Ok(words)
+}
+
+/// Quotes `word` for a POSIX shell, leaving it as is when it needs no
+/// quoting.
+pub fn quote(word: &str) -> Cow<'_, str> {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "_@%+=:,./-".contains(c);
+
+    if !word.is_empty() && word.chars().all(safe) {
+        Cow::Borrowed(word)
+    } else {
+        Cow::Owned(format!("'{}'", word.replace('\'', r"'\''")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split() {
+        assert_eq!(
+            split("rsync -av /src '/my dst'").unwrap(),
+            ["rsync", "-av", "/src", "/my dst"]
+        );
+        assert_eq!(
+            split(r#"echo "a \"b\" \n" c\ d ''"#).unwrap(),
+            ["echo", r#"a "b" \n"#, "c d", ""]
+        );
+        assert!(split("  ").unwrap().is_empty());
+        assert!(split("echo 'open").is_err());
+        assert!(split("echo \\").is_err());
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("/usr/bin/rsync"), "/usr/bin/rsync");
+        assert_eq!(quote("my dst"), "'my dst'");
+        assert_eq!(quote("it's"), r"'it'\''s'");
+        assert_eq!(quote(""), "''");
+
+        let words = ["a b", "it's", "$HOME", ""];
+        let line = words.map(quote).join(" ");
+        assert_eq!(split(&line).unwrap(), words);
+    }
+}
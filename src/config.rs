@@ -0,0 +1,327 @@
+use std::{collections::BTreeMap, path::Path};
+
+use serde::Deserialize;
+
+use crate::{
+    cron::Cron,
+    error::{CronError, Result},
+    parser::{Field, parse_bare_schedule, parse_field, parse_year},
+    serialize::{GroupRepr, RuleRepr, enabled, parse_dst_policy, parse_offset},
+    task::{CronTask, CronTaskBuilder},
+};
+
+/// Scheduled jobs as declared in a config file.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CronConfig {
+    #[serde(default)]
+    tasks: Vec<TaskConfig>,
+    #[serde(default)]
+    groups: Vec<GroupRepr>,
+    /// In seconds.
+    lookahead: Option<i64>,
+    instance: Option<u32>,
+}
+
+/// A task declared either with a `schedule` string, such as
+/// `*/5 * * * *`, or with one entry per field, missing fields matching
+/// everything. Field values use crontab syntax and numbering.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TaskConfig {
+    name: Option<String>,
+    #[serde(default = "enabled")]
+    enabled: bool,
+    command: String,
+    schedule: Option<String>,
+    second: Option<FieldConfig>,
+    minute: Option<FieldConfig>,
+    hour: Option<FieldConfig>,
+    month_day: Option<FieldConfig>,
+    month: Option<FieldConfig>,
+    week_day: Option<FieldConfig>,
+    year: Option<FieldConfig>,
+    group: Option<String>,
+    owner: Option<String>,
+    #[serde(default)]
+    annotations: BTreeMap<String, String>,
+    offset: Option<String>,
+    #[cfg(feature = "chrono-tz")]
+    timezone: Option<String>,
+    dst_fold: Option<String>,
+    dst_gap: Option<String>,
+    #[serde(default)]
+    rules: Vec<RuleRepr>,
+    #[serde(default)]
+    priority: i32,
+}
+
+/// A field value, which config formats let users write as a bare number.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FieldConfig {
+    Number(i64),
+    Text(String),
+}
+
+impl FieldConfig {
+    fn text(&self) -> String {
+        match self {
+            FieldConfig::Number(n) => n.to_string(),
+            FieldConfig::Text(s) => s.clone(),
+        }
+    }
+}
+
+impl Cron {
+    /// Loads the tasks declared in a TOML file, as `[[tasks]]` tables.
+    /// Disabled tasks are left out.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(path: impl AsRef<Path>) -> Result<Cron> {
+        Self::from_toml_str(&std::fs::read_to_string(path)?)
+    }
+
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(s: &str) -> Result<Cron> {
+        toml::from_str::<CronConfig>(s)
+            .map_err(|e| CronError::InvalidConfig(e.message().to_string()))?
+            .into_cron()
+    }
+
+    /// Loads the tasks declared in a YAML file, under a `tasks` list.
+    /// Disabled tasks are left out.
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(path: impl AsRef<Path>) -> Result<Cron> {
+        Self::from_yaml_str(&std::fs::read_to_string(path)?)
+    }
+
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml_str(s: &str) -> Result<Cron> {
+        serde_yaml::from_str::<CronConfig>(s)
+            .map_err(|e| CronError::InvalidConfig(e.to_string()))?
+            .into_cron()
+    }
+}
+
+impl CronConfig {
+    fn into_cron(self) -> Result<Cron> {
+        let mut cron = Cron::new();
+
+        for (index, task) in self.tasks.into_iter().enumerate() {
+            if !task.enabled {
+                continue;
+            }
+
+            let name = task
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("#{}", index + 1));
+            let task = task.into_task().map_err(|e| CronError::Task {
+                name,
+                source: Box::new(e),
+            })?;
+            cron.add_task(task);
+        }
+        for group in self.groups {
+            cron.add_group(group.into_group()?);
+        }
+        if let Some(seconds) = self.lookahead {
+            let lookahead = chrono::TimeDelta::try_seconds(seconds).ok_or_else(|| {
+                CronError::InvalidConfig(format!("lookahead of {seconds}s is too long"))
+            })?;
+            cron = cron.with_lookahead(lookahead);
+        }
+        if let Some(instance) = self.instance {
+            cron = cron.with_instance(instance);
+        }
+
+        Ok(cron)
+    }
+}
+
+impl TaskConfig {
+    fn into_task(self) -> Result<CronTask> {
+        let mut builder = match &self.schedule {
+            Some(schedule) if self.has_fields() => {
+                return Err(CronError::InvalidConfig(format!(
+                    "both `{schedule}` and separate fields are given"
+                )));
+            }
+            Some(schedule) => parse_bare_schedule(schedule)?,
+            None => self.fields()?,
+        }
+        .path(self.command);
+
+        if let Some(name) = self.name {
+            builder = builder.name(name);
+        }
+        if let Some(group) = self.group {
+            builder = builder.group(group);
+        }
+        if let Some(owner) = self.owner {
+            builder = builder.owner(owner);
+        }
+        for (key, value) in self.annotations {
+            builder = builder.annotation(key, value);
+        }
+        if let Some(offset) = self.offset {
+            builder = builder.offset(parse_offset(&offset)?);
+        }
+        #[cfg(feature = "chrono-tz")]
+        if let Some(timezone) = self.timezone {
+            builder = builder.timezone(crate::serialize::parse_timezone(&timezone)?);
+        }
+
+        for rule in self.rules {
+            builder = builder.rule(rule.into_rule()?);
+        }
+
+        builder
+            .dst_policy(parse_dst_policy(
+                self.dst_fold.as_deref(),
+                self.dst_gap.as_deref(),
+            )?)
+            .priority(self.priority)
+            .build()
+    }
+
+    fn has_fields(&self) -> bool {
+        [
+            &self.second,
+            &self.minute,
+            &self.hour,
+            &self.month_day,
+            &self.month,
+            &self.week_day,
+            &self.year,
+        ]
+        .iter()
+        .any(|field| field.is_some())
+    }
+
+    fn fields(&self) -> Result<CronTaskBuilder> {
+        let field = |value: &Option<FieldConfig>, field: Field| match value {
+            Some(value) => parse_field(&value.text(), field),
+            None => Ok(Default::default()),
+        };
+
+        let mut builder = CronTask::builder()
+            .minutes(field(&self.minute, Field::Minute)?)
+            .hour(field(&self.hour, Field::Hour)?)
+            .month_day(field(&self.month_day, Field::MonthDay)?)
+            .month(field(&self.month, Field::Month)?)
+            .week_day(field(&self.week_day, Field::WeekDay)?);
+
+        if let Some(second) = &self.second {
+            builder = builder.seconds(parse_field(&second.text(), Field::Second)?);
+        }
+        if let Some(year) = &self.year {
+            builder = builder.year(parse_year(&year.text())?);
+        }
+
+        Ok(builder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_from_toml() {
+        let cron = Cron::from_toml_str(
+            r#"
+            lookahead = 3600
+
+            [[tasks]]
+            name = "backup"
+            schedule = "0 3 * * *"
+            command = "/usr/bin/backup"
+            group = "maintenance"
+            annotations = { "deploy.id" = "42" }
+
+            [[tasks]]
+            name = "report"
+            minute = 30
+            hour = "9"
+            week_day = "MON-FRI"
+            command = "/usr/bin/report"
+
+            [[tasks]]
+            name = "legacy"
+            enabled = false
+            schedule = "* * * * *"
+            command = "/usr/bin/legacy"
+
+            [[groups]]
+            name = "maintenance"
+            max_concurrent = 1
+            "#,
+        )
+        .unwrap();
+        let tasks: Vec<_> = cron.into_iter().collect();
+
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].name(), Some("backup"));
+        assert_eq!(tasks[0].group(), Some("maintenance"));
+        assert_eq!(tasks[0].annotation("deploy.id"), Some("42"));
+        assert_eq!(tasks[1].to_string(), "30 9 * * 0-4 \"/usr/bin/report\"");
+        assert_eq!(cron.lookahead(), Some(chrono::TimeDelta::hours(1)));
+        assert_eq!(cron.group("maintenance").unwrap().max_concurrent(), Some(1));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_from_toml_errors() {
+        let error = Cron::from_toml_str(
+            r#"
+            [[tasks]]
+            name = "backup"
+            schedule = "61 * * * *"
+            command = "/usr/bin/backup"
+            "#,
+        )
+        .unwrap_err();
+        assert!(matches!(error, CronError::Task { ref name, .. } if name == "backup"));
+
+        let error = Cron::from_toml_str(
+            r#"
+            [[tasks]]
+            schedule = "0 * * * *"
+            hour = 3
+            command = "/usr/bin/backup"
+            "#,
+        )
+        .unwrap_err();
+        assert!(error.to_string().starts_with("Task `#1`: Invalid config"));
+
+        assert!(Cron::from_toml_str("[[tasks]]\ncommand = 1").is_err());
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_from_yaml() {
+        let cron = Cron::from_yaml_str(
+            "
+tasks:
+  - name: poll
+    schedule: '*/10 * * * * *'
+    command: /usr/bin/poll
+  - name: report
+    hour: 9
+    minute: 0
+    offset: '+05:30'
+    command: /usr/bin/report
+",
+        )
+        .unwrap();
+        let tasks: Vec<_> = cron.into_iter().collect();
+
+        assert_eq!(tasks[0].to_string(), "*/10 * * * * * \"/usr/bin/poll\"");
+        assert_eq!(
+            tasks[1].offset().map(|o| o.to_string()),
+            Some("+05:30".to_string())
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use chrono::{DateTime, TimeDelta, TimeZone};
+
+use crate::task::CronTask;
+
+/// Words used to render countdowns, so that dashboards can show them in
+/// their own language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CountdownLocale {
+    /// Wraps the duration, `{}` being replaced by it.
+    pub template: &'static str,
+    /// Shown when less than a second is left.
+    pub now: &'static str,
+    /// Suffixes for days, hours, minutes and seconds.
+    pub units: [&'static str; 4],
+}
+
+impl CountdownLocale {
+    pub const ENGLISH: CountdownLocale = CountdownLocale {
+        template: "in {}",
+        now: "now",
+        units: ["d", "h", "m", "s"],
+    };
+
+    pub const FRENCH: CountdownLocale = CountdownLocale {
+        template: "dans {}",
+        now: "maintenant",
+        units: ["j", "h", "min", "s"],
+    };
+
+    /// Renders `duration` with its two most significant units, such as
+    /// `in 3h 12m` or `in 2d 5h`.
+    pub fn format(&self, duration: TimeDelta) -> String {
+        let seconds = duration.num_seconds().max(0);
+        let amounts = [
+            seconds / 86_400,
+            seconds / 3600 % 24,
+            seconds / 60 % 60,
+            seconds % 60,
+        ];
+
+        let parts: Vec<String> = amounts
+            .iter()
+            .zip(self.units)
+            .skip_while(|(amount, _)| **amount == 0)
+            .take(2)
+            .filter(|(amount, _)| **amount != 0)
+            .map(|(amount, unit)| format!("{amount}{unit}"))
+            .collect();
+
+        if parts.is_empty() {
+            self.now.to_string()
+        } else {
+            self.template.replacen("{}", &parts.join(" "), 1)
+        }
+    }
+}
+
+impl Default for CountdownLocale {
+    fn default() -> Self {
+        CountdownLocale::ENGLISH
+    }
+}
+
+impl CronTask {
+    /// Time left from `from` until the next occurrence.
+    pub fn duration_until_next<Tz: TimeZone>(&self, from: DateTime<Tz>) -> Option<TimeDelta> {
+        self.try_next_occurrence(from.clone())
+            .map(|next| next.signed_duration_since(from))
+    }
+
+    /// Time left until the next occurrence, such as `in 3h 12m`.
+    pub fn human_time_until_next<Tz: TimeZone>(&self, from: DateTime<Tz>) -> Option<String> {
+        self.human_time_until_next_in(from, &CountdownLocale::ENGLISH)
+    }
+
+    pub fn human_time_until_next_in<Tz: TimeZone>(
+        &self,
+        from: DateTime<Tz>,
+        locale: &CountdownLocale,
+    ) -> Option<String> {
+        self.duration_until_next(from)
+            .map(|duration| locale.format(duration))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Timelike, Utc};
+
+    use super::*;
+
+    fn task() -> CronTask {
+        CronTask::builder()
+            .minutes(0)
+            .hour(12)
+            .path("/usr/bin/report".to_string())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_duration_until_next() {
+        let from = Utc.with_ymd_and_hms(2025, 6, 1, 8, 48, 0).unwrap();
+
+        assert_eq!(
+            task().duration_until_next(from),
+            Some(TimeDelta::minutes(3 * 60 + 12))
+        );
+        assert_eq!(
+            task().human_time_until_next(from).as_deref(),
+            Some("in 3h 12m")
+        );
+        assert_eq!(
+            task()
+                .human_time_until_next_in(from.with_hour(13).unwrap(), &CountdownLocale::FRENCH)
+                .as_deref(),
+            Some("dans 22h 12min")
+        );
+        assert_eq!(
+            CronTask::reboot("/bin/true").human_time_until_next(from),
+            None
+        );
+    }
+
+    #[test]
+    fn test_format() {
+        let format = |seconds| CountdownLocale::ENGLISH.format(TimeDelta::seconds(seconds));
+
+        assert_eq!(format(0), "now");
+        assert_eq!(format(42), "in 42s");
+        assert_eq!(format(61), "in 1m 1s");
+        assert_eq!(format(2 * 86_400 + 5 * 3600 + 59), "in 2d 5h");
+        assert_eq!(format(86_400 + 30), "in 1d");
+    }
+}
@@ -1,10 +1,36 @@
-use chrono::{DateTime, Local};
+use std::{
+    cmp::Reverse,
+    collections::HashSet,
+    ops::{Deref, DerefMut, Range},
+    sync::{Arc, Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
 
-use crate::task::CronTask;
+use chrono::{DateTime, Local, TimeDelta, TimeZone};
 
-#[derive(Debug, Default)]
+#[cfg(feature = "budget")]
+use crate::budget::BudgetLedger;
+#[cfg(feature = "group")]
+use crate::group::TaskGroup;
+use crate::{skip::SkipReason, task::CronTask};
+
+/// Most buckets [`Cron::histogram`] returns, wider buckets being used past
+/// it.
+pub const MAX_HISTOGRAM_BUCKETS: usize = 10_000;
+
+#[derive(Debug, Default, Clone)]
 pub struct Cron {
     tasks: Vec<CronTask>,
+    lookahead: Option<TimeDelta>,
+    #[cfg(feature = "shard")]
+    instance: Option<u32>,
+    #[cfg(feature = "group")]
+    groups: Vec<TaskGroup>,
+    #[cfg(feature = "budget")]
+    ledger: BudgetLedger,
+    /// Names of the paused tasks.
+    paused: HashSet<String>,
+    /// Windows no task runs in.
+    blackouts: Vec<Range<DateTime<Local>>>,
 }
 
 impl Cron {
@@ -12,19 +38,497 @@ impl Cron {
         Self::default()
     }
 
-    pub fn add_task(&mut self, task: CronTask) {
+    /// Bounds how far ahead `next_event` searches. Tasks with no occurrence
+    /// within the horizon are treated as idle.
+    pub fn with_lookahead(mut self, lookahead: TimeDelta) -> Self {
+        self.lookahead = Some(lookahead);
+        self
+    }
+
+    pub fn set_lookahead(&mut self, lookahead: Option<TimeDelta>) {
+        self.lookahead = lookahead;
+    }
+
+    pub fn lookahead(&self) -> Option<TimeDelta> {
+        self.lookahead
+    }
+
+    /// Sets the index of this instance among the replicas, so sharded tasks
+    /// only fire on the instance owning them.
+    #[cfg(feature = "shard")]
+    pub fn with_instance(mut self, instance: u32) -> Self {
+        self.instance = Some(instance);
+        self
+    }
+
+    #[cfg(feature = "shard")]
+    pub fn instance(&self) -> Option<u32> {
+        self.instance
+    }
+
+    /// Counts the scheduled runs of every active task per `bucket` over
+    /// `window`. Each entry holds the start of the bucket and its count.
+    /// Buckets are whole seconds, the finest slot a task fires in, and are
+    /// widened when `window` would need more than [`MAX_HISTOGRAM_BUCKETS`].
+    pub fn histogram(
+        &self,
+        window: Range<DateTime<Local>>,
+        bucket: TimeDelta,
+    ) -> Vec<(DateTime<Local>, usize)> {
+        if bucket <= TimeDelta::zero() || window.start >= window.end {
+            return Vec::new();
+        }
+
+        let span = (window.end - window.start).num_seconds().max(1) as u64;
+        let size =
+            (bucket.num_seconds().max(1) as u64).max(span.div_ceil(MAX_HISTOGRAM_BUCKETS as u64));
+        let mut counts = vec![0; span.div_ceil(size) as usize];
+        let last = counts.len() - 1;
+
+        for task in self.into_iter().filter(|task| self.runs_here(task)) {
+            for next in task.occurrences_between(window.clone()) {
+                let i = (next - window.start).num_seconds() as u64 / size;
+                counts[(i as usize).min(last)] += 1;
+            }
+        }
+
+        counts
+            .into_iter()
+            .enumerate()
+            .map(|(i, count)| {
+                (
+                    window.start + TimeDelta::seconds((i as u64 * size) as i64),
+                    count,
+                )
+            })
+            .collect()
+    }
+
+    /// Runs missed during `downtime`, split into batches to run one after
+    /// the other. Higher priority tasks come first, then earlier runs. A
+    /// batch holds at most `max_concurrent` runs, no more of a group than it
+    /// allows, and never the same task twice.
+    pub fn catch_up(
+        &self,
+        downtime: Range<DateTime<Local>>,
+        max_concurrent: usize,
+    ) -> Vec<Vec<(DateTime<Local>, &CronTask)>> {
+        let mut runs: Vec<(usize, DateTime<Local>, &CronTask)> = self
+            .into_iter()
+            .enumerate()
+            .filter(|(_, task)| !task.is_reboot() && self.runs_here(task))
+            .flat_map(|(i, task)| {
+                task.occurrences_between(downtime.clone())
+                    .map(move |date| (i, date, task))
+            })
+            .collect();
+        runs.sort_by_key(|(i, date, task)| (Reverse(task.priority()), *date, *i));
+
+        let max_concurrent = max_concurrent.max(1);
+        let mut batches: Vec<Vec<(usize, DateTime<Local>, &CronTask)>> = Vec::new();
+        for run @ (i, _, task) in runs {
+            #[cfg(feature = "group")]
+            let group_limit = task
+                .group()
+                .and_then(|name| self.group(name))
+                .and_then(TaskGroup::max_concurrent);
+            #[cfg(not(feature = "group"))]
+            let group_limit: Option<usize> = None;
+            let fits = |batch: &&mut Vec<(usize, DateTime<Local>, &CronTask)>| {
+                let in_group = batch
+                    .iter()
+                    .filter(|(_, _, other)| {
+                        other.group().is_some() && other.group() == task.group()
+                    })
+                    .count();
+
+                batch.len() < max_concurrent
+                    && batch.iter().all(|(other, _, _)| *other != i)
+                    && group_limit.is_none_or(|limit| in_group < limit)
+            };
+
+            match batches.iter_mut().find(fits) {
+                Some(batch) => batch.push(run),
+                None => batches.push(vec![run]),
+            }
+        }
+
+        batches
+            .into_iter()
+            .map(|batch| {
+                batch
+                    .into_iter()
+                    .map(|(_, date, task)| (date, task))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Tasks to run once when the scheduler starts.
+    pub fn reboot_tasks(&self) -> impl Iterator<Item = &CronTask> {
+        self.into_iter()
+            .filter(|task| task.is_reboot() && self.runs_here(task))
+    }
+
+    /// Whether any task runs on a schedule rather than only on start.
+    pub fn has_calendar_tasks(&self) -> bool {
+        self.into_iter()
+            .any(|task| !task.is_reboot() && self.runs_here(task))
+    }
+
+    /// Registers `group`, replacing any group with the same name, and hands
+    /// its policy down to its tasks.
+    #[cfg(feature = "group")]
+    pub fn add_group(&mut self, group: TaskGroup) {
+        for task in &mut self.tasks {
+            if task.group() == Some(group.name()) {
+                task.set_group_policy(Some(group.shared_policy()));
+            }
+        }
+
+        match self.groups.iter_mut().find(|g| g.name() == group.name()) {
+            Some(existing) => *existing = group,
+            None => self.groups.push(group),
+        }
+    }
+
+    #[cfg(feature = "group")]
+    pub fn group(&self, name: &str) -> Option<&TaskGroup> {
+        self.groups.iter().find(|g| g.name() == name)
+    }
+
+    #[cfg(feature = "group")]
+    pub fn group_mut(&mut self, name: &str) -> Option<&mut TaskGroup> {
+        self.groups.iter_mut().find(|g| g.name() == name)
+    }
+
+    #[cfg(feature = "group")]
+    pub fn groups(&self) -> &[TaskGroup] {
+        &self.groups
+    }
+
+    pub fn tasks_in_group<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a CronTask> {
+        self.into_iter()
+            .filter(move |task| task.group() == Some(name))
+    }
+
+    /// Enables or disables every task of the group at once. Returns `false`
+    /// if no such group is registered.
+    #[cfg(feature = "group")]
+    pub fn set_group_enabled(&mut self, name: &str, enabled: bool) -> bool {
+        match self.group_mut(name) {
+            Some(group) => {
+                group.set_enabled(enabled);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Pauses or resumes the tasks named `name`. Returns `false` if no task
+    /// has that name.
+    pub fn set_task_paused(&mut self, name: &str, paused: bool) -> bool {
+        if !self.into_iter().any(|task| task.name() == Some(name)) {
+            return false;
+        }
+        if paused {
+            self.paused.insert(name.to_string());
+        } else {
+            self.paused.remove(name);
+        }
+        true
+    }
+
+    pub fn is_task_paused(&self, name: &str) -> bool {
+        self.paused.contains(name)
+    }
+
+    /// Names of the paused tasks, in no particular order.
+    pub fn paused_tasks(&self) -> impl Iterator<Item = &str> {
+        self.paused.iter().map(String::as_str)
+    }
+
+    /// Skips every run starting within `window`, such as during a
+    /// maintenance.
+    pub fn add_blackout(&mut self, window: Range<DateTime<Local>>) {
+        self.blackouts.push(window);
+    }
+
+    pub fn blackouts(&self) -> &[Range<DateTime<Local>>] {
+        &self.blackouts
+    }
+
+    /// Why `task` does not run on this instance, if it doesn't.
+    pub fn skip_reason(&self, task: &CronTask) -> Option<SkipReason> {
+        if task.name().is_some_and(|name| self.is_task_paused(name)) {
+            return Some(SkipReason::Paused);
+        }
+        #[cfg(feature = "group")]
+        if let Some(group) = task.group().and_then(|name| self.group(name))
+            && !group.is_enabled()
+        {
+            return Some(SkipReason::GroupDisabled(group.name().to_string()));
+        }
+        #[cfg(feature = "shard")]
+        if let (Some(instance), Some(shard)) = (self.instance, task.shard())
+            && !shard.is_owned_by(instance)
+        {
+            return Some(SkipReason::NotOwner {
+                owner: shard.index(),
+            });
+        }
+
+        None
+    }
+
+    /// Same as `skip_reason`, also checking the blackouts and the run time
+    /// budget of the task for the month of `date`.
+    pub fn skip_reason_at<Tz: TimeZone>(
+        &self,
+        task: &CronTask,
+        date: DateTime<Tz>,
+    ) -> Option<SkipReason> {
+        let reason = self.skip_reason(task);
+        let local = date.with_timezone(&Local);
+        if reason.is_none() && self.blackouts.iter().any(|window| window.contains(&local)) {
+            return Some(SkipReason::Blackout);
+        }
+        #[cfg(feature = "budget")]
+        if reason.is_none() && self.ledger.is_exhausted(task, date) {
+            return Some(SkipReason::BudgetExhausted);
+        }
+
+        reason
+    }
+
+    fn runs_here(&self, task: &CronTask) -> bool {
+        self.skip_reason(task).is_none()
+    }
+
+    /// Run time recorded against task budgets.
+    #[cfg(feature = "budget")]
+    pub fn ledger(&self) -> &BudgetLedger {
+        &self.ledger
+    }
+
+    #[cfg(feature = "budget")]
+    pub fn ledger_mut(&mut self) -> &mut BudgetLedger {
+        &mut self.ledger
+    }
+
+    /// Adds `task`, which follows the policy of its group if registered.
+    #[cfg_attr(not(feature = "group"), allow(unused_mut))]
+    pub fn add_task(&mut self, mut task: CronTask) {
+        #[cfg(feature = "group")]
+        {
+            let group = task.group().and_then(|name| self.group(name));
+            task.set_group_policy(group.map(TaskGroup::shared_policy));
+        }
         self.tasks.push(task);
     }
 
     pub fn is_planified_at(&self, date: DateTime<Local>) -> bool {
-        self.into_iter().any(|task| task.matches(date))
+        self.into_iter()
+            .any(|task| task.matches(date) && self.skip_reason_at(task, date).is_none())
     }
 
+    /// Tasks running at `date`, higher priorities first, then by name with
+    /// unnamed tasks last, then in insertion order.
     pub fn get_all_planified_at(&self, date: DateTime<Local>) -> Vec<&CronTask> {
-        self.into_iter().filter(|task| task.matches(date)).collect()
+        let mut tasks: Vec<&CronTask> = self
+            .into_iter()
+            .filter(|task| task.matches(date) && self.skip_reason_at(task, date).is_none())
+            .collect();
+        tasks.sort_by_key(|task| run_order(task));
+        tasks
+    }
+
+    /// Tasks whose run starts at `date`, in the order of
+    /// `get_all_planified_at`. Unlike there, a task without a seconds field
+    /// is only due on the first second of its minute.
+    pub fn get_all_due_at(&self, date: DateTime<Local>) -> Vec<&CronTask> {
+        let mut tasks = self.get_all_planified_at(date);
+        tasks.retain(|task| task.slot_start(date) == date);
+        tasks
+    }
+
+    /// Every run within `window`, by date and then in the order of
+    /// `get_all_planified_at`.
+    pub fn get_all_planified_between(
+        &self,
+        window: Range<DateTime<Local>>,
+    ) -> Vec<(DateTime<Local>, &CronTask)> {
+        let mut runs: Vec<(DateTime<Local>, &CronTask)> = self
+            .into_iter()
+            .filter(|task| self.runs_here(task))
+            .flat_map(|task| {
+                task.occurrences_between(window.clone())
+                    .map(move |date| (date, task))
+            })
+            .filter(|(date, task)| self.skip_reason_at(task, *date).is_none())
+            .collect();
+        runs.sort_by_key(|(date, task)| (*date, run_order(task)));
+        runs
+    }
+
+    /// Tasks matching `date` that do not run here, with the reason why.
+    pub fn get_all_skipped_at(&self, date: DateTime<Local>) -> Vec<(&CronTask, SkipReason)> {
+        self.into_iter()
+            .filter(|task| task.matches(date))
+            .filter_map(|task| self.skip_reason_at(task, date).map(|reason| (task, reason)))
+            .collect()
+    }
+
+    /// Tasks whose run starting at `date` does not happen here, with the
+    /// reason why, the counterpart of `get_all_due_at`.
+    pub fn get_all_skipped_due_at(&self, date: DateTime<Local>) -> Vec<(&CronTask, SkipReason)> {
+        let mut skipped = self.get_all_skipped_at(date);
+        skipped.retain(|(task, _)| task.slot_start(date) == date);
+        skipped
+    }
+
+    pub fn next_event<Tz: TimeZone>(
+        &self,
+        from: DateTime<Tz>,
+    ) -> Option<(DateTime<Tz>, &CronTask)> {
+        self.next_among(from, |task| self.runs_here(task))
+    }
+
+    /// Same as `next_event`, also counting the runs skipped here, for
+    /// schedulers to report them.
+    pub fn next_scheduled<Tz: TimeZone>(
+        &self,
+        from: DateTime<Tz>,
+    ) -> Option<(DateTime<Tz>, &CronTask)> {
+        self.next_among(from, |_| true)
+    }
+
+    fn next_among<Tz: TimeZone>(
+        &self,
+        from: DateTime<Tz>,
+        filter: impl Fn(&CronTask) -> bool,
+    ) -> Option<(DateTime<Tz>, &CronTask)> {
+        self.into_iter()
+            .filter(|task| filter(task))
+            .filter_map(|task| Some((self.next_run_of(task, from.clone())?, task)))
+            .min_by(|(a, _), (b, _)| a.cmp(b))
+    }
+
+    /// Next run of `task` after `from`, skipped or not, within the
+    /// lookahead if there is one.
+    pub fn next_run_of<Tz: TimeZone>(
+        &self,
+        task: &CronTask,
+        from: DateTime<Tz>,
+    ) -> Option<DateTime<Tz>> {
+        match self.lookahead {
+            Some(lookahead) => task.next_occurrence_within(from, lookahead),
+            None => task.try_next_occurrence(from),
+        }
+    }
+}
+
+/// Handle on a [`Cron`] shared between schedulers and the code steering
+/// them, such as a console pausing tasks. Clones share the same cron, which
+/// schedulers read on each wake-up and are woken up for when it changes.
+#[derive(Clone, Default)]
+pub struct SharedCron(Arc<Shared>);
+
+#[derive(Default)]
+struct Shared {
+    cron: RwLock<Cron>,
+    /// Called after each change, dropped once they return `false`.
+    watchers: Mutex<Vec<Watcher>>,
+}
+
+type Watcher = Box<dyn Fn() -> bool + Send + Sync>;
+
+impl SharedCron {
+    pub fn new(cron: Cron) -> Self {
+        Self::from(cron)
+    }
+
+    pub fn read(&self) -> RwLockReadGuard<'_, Cron> {
+        // The cron is always left whole, a poisoned lock is still usable.
+        self.0.cron.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Locks the cron for a change, schedulers being woken up once the
+    /// guard is dropped.
+    pub fn write(&self) -> CronWriteGuard<'_> {
+        CronWriteGuard {
+            guard: Some(self.0.cron.write().unwrap_or_else(|e| e.into_inner())),
+            shared: &self.0,
+        }
+    }
+
+    /// Whether a handle other than this one exists, through which the cron
+    /// may still change.
+    #[cfg(feature = "scheduler")]
+    pub(crate) fn is_shared(&self) -> bool {
+        Arc::strong_count(&self.0) > 1
+    }
+
+    /// Calls `watcher` after each change, until it returns `false`.
+    #[cfg(feature = "scheduler")]
+    pub(crate) fn watch(&self, watcher: impl Fn() -> bool + Send + Sync + 'static) {
+        lock(&self.0.watchers).push(Box::new(watcher));
+    }
+}
+
+impl From<Cron> for SharedCron {
+    fn from(cron: Cron) -> Self {
+        Self(Arc::new(Shared {
+            cron: RwLock::new(cron),
+            watchers: Mutex::default(),
+        }))
+    }
+}
+
+impl std::fmt::Debug for SharedCron {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("SharedCron").field(&*self.read()).finish()
+    }
+}
+
+/// Write access to a [`SharedCron`], see [`SharedCron::write`].
+pub struct CronWriteGuard<'a> {
+    guard: Option<RwLockWriteGuard<'a, Cron>>,
+    shared: &'a Shared,
+}
+
+impl Deref for CronWriteGuard<'_> {
+    type Target = Cron;
+
+    fn deref(&self) -> &Cron {
+        self.guard.as_ref().expect("guard is only taken on drop")
+    }
+}
+
+impl DerefMut for CronWriteGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Cron {
+        self.guard.as_mut().expect("guard is only taken on drop")
     }
 }
 
+impl Drop for CronWriteGuard<'_> {
+    fn drop(&mut self) {
+        // Unlocked first, woken up schedulers read the cron right away.
+        drop(self.guard.take());
+        lock(&self.shared.watchers).retain(|watcher| watcher());
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    // Watchers are only added or dropped whole, a poisoned lock is still
+    // usable.
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn run_order(task: &CronTask) -> (Reverse<i32>, bool, Option<&str>) {
+    (Reverse(task.priority()), task.name().is_none(), task.name())
+}
+
 impl<'a> IntoIterator for &'a Cron {
     type Item = &'a CronTask;
     type IntoIter = std::slice::Iter<'a, CronTask>;
@@ -38,9 +542,16 @@ impl<'a> IntoIterator for &'a Cron {
 mod tests {
     use super::*;
 
-    use chrono::{DateTime, Local, TimeZone};
+    use chrono::{DateTime, Local, TimeDelta, TimeZone, Timelike};
 
-    use crate::value::{all, every, on};
+    #[cfg(feature = "budget")]
+    use crate::budget::RuntimeBudget;
+    #[cfg(feature = "group")]
+    use crate::{rule::ScheduleRule, value::CronValue};
+    use crate::{
+        task::CronTaskBuilder,
+        value::{all, every, on},
+    };
 
     fn make_datetime(year: i32, month: u32, day: u32, hour: u32, min: u32) -> DateTime<Local> {
         Local
@@ -171,6 +682,558 @@ mod tests {
         assert!(cron.is_planified_at(matching_date));
     }
 
+    fn make_named_task(path: &str, name: Option<&str>, priority: i32) -> CronTask {
+        let builder = CronTaskBuilder::from_task(&make_simple_task())
+            .path(path.to_string())
+            .priority(priority);
+
+        match name {
+            Some(name) => builder.name(name),
+            None => builder,
+        }
+        .build()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_get_all_planified_at_order() {
+        let mut cron = Cron::new();
+        for task in [
+            make_named_task("/bin/unnamed", None, 0),
+            make_named_task("/bin/b", Some("b"), 0),
+            make_named_task("/bin/urgent", None, 5),
+            make_named_task("/bin/a", Some("a"), 0),
+            make_named_task("/bin/unnamed-2", None, 0),
+        ] {
+            cron.add_task(task);
+        }
+
+        let paths: Vec<String> = cron
+            .get_all_planified_at(make_datetime(2024, 6, 15, 12, 0))
+            .iter()
+            .map(|task| task.path().display().to_string())
+            .collect();
+
+        assert_eq!(
+            paths,
+            [
+                "/bin/urgent",
+                "/bin/a",
+                "/bin/b",
+                "/bin/unnamed",
+                "/bin/unnamed-2"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_all_planified_between() {
+        let mut cron = Cron::new();
+        let every_ten = CronTaskBuilder::from_task(&make_named_task("/bin/poll", Some("poll"), 0))
+            .minutes(every(10))
+            .build()
+            .unwrap();
+        let hourly = CronTaskBuilder::from_task(&make_named_task("/bin/report", None, 1))
+            .minutes(on(0))
+            .build()
+            .unwrap();
+        cron.add_task(every_ten);
+        cron.add_task(hourly);
+
+        let runs: Vec<(u32, &str)> = cron
+            .get_all_planified_between(
+                make_datetime(2024, 6, 15, 11, 50)..make_datetime(2024, 6, 15, 12, 20),
+            )
+            .into_iter()
+            .map(|(date, task)| (date.minute(), task.path().to_str().unwrap()))
+            .collect();
+
+        assert_eq!(
+            runs,
+            [
+                (50, "/bin/poll"),
+                (0, "/bin/report"),
+                (0, "/bin/poll"),
+                (10, "/bin/poll"),
+            ]
+        );
+    }
+
+    #[cfg(feature = "budget")]
+    #[test]
+    fn test_budget_exhausted() {
+        let mut cron = Cron::new();
+        let task = CronTaskBuilder::from_task(&make_named_task("/bin/batch", Some("batch"), 0))
+            .budget(RuntimeBudget::monthly(TimeDelta::hours(10)))
+            .build()
+            .unwrap();
+        cron.add_task(task);
+
+        let date = make_datetime(2024, 6, 15, 12, 0);
+        assert_eq!(cron.get_all_planified_at(date).len(), 1);
+
+        cron.ledger_mut()
+            .record("batch", date, TimeDelta::hours(10));
+        assert!(cron.get_all_planified_at(date).is_empty());
+        assert_eq!(
+            cron.get_all_skipped_at(date)[0].1,
+            SkipReason::BudgetExhausted
+        );
+        assert_eq!(
+            cron.get_all_planified_at(make_datetime(2024, 7, 1, 12, 0))
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_next_event_empty_cron() {
+        let cron = Cron::new();
+
+        assert!(cron.next_event(make_datetime(2024, 1, 1, 12, 0)).is_none());
+    }
+
+    #[test]
+    fn test_next_event_earliest_task() {
+        let mut cron = Cron::new();
+        cron.add_task(
+            CronTask::builder()
+                .minutes(on(0))
+                .hour(on(18))
+                .allow_commandless()
+                .build()
+                .unwrap(),
+        );
+        cron.add_task(
+            CronTask::builder()
+                .minutes(on(30))
+                .hour(on(13))
+                .allow_commandless()
+                .build()
+                .unwrap(),
+        );
+
+        let (date, task) = cron.next_event(make_datetime(2024, 1, 1, 12, 0)).unwrap();
+
+        assert_eq!(date, make_datetime(2024, 1, 1, 13, 30));
+        assert!(task.matches(date));
+    }
+
+    #[test]
+    fn test_next_event_with_lookahead() {
+        let mut cron = Cron::new().with_lookahead(TimeDelta::hours(1));
+        cron.add_task(
+            CronTask::builder()
+                .minutes(on(0))
+                .hour(on(18))
+                .allow_commandless()
+                .build()
+                .unwrap(),
+        );
+
+        assert!(cron.next_event(make_datetime(2024, 1, 1, 12, 0)).is_none());
+
+        cron.set_lookahead(None);
+
+        assert!(cron.next_event(make_datetime(2024, 1, 1, 12, 0)).is_some());
+    }
+
+    #[cfg(feature = "shard")]
+    #[test]
+    fn test_sharded_task_runs_on_owner_only() {
+        let task = CronTask::builder()
+            .shard("reports", 3)
+            .allow_commandless()
+            .build()
+            .unwrap();
+        let owner = task.shard().unwrap().index();
+        let other = (owner + 1) % 3;
+        let date = make_datetime(2024, 1, 1, 12, 0);
+
+        let mut cron = Cron::new().with_instance(owner);
+        cron.add_task(task.clone());
+        assert!(cron.is_planified_at(date));
+
+        let mut cron = Cron::new().with_instance(other);
+        cron.add_task(task.clone());
+        assert!(!cron.is_planified_at(date));
+        assert!(cron.next_event(date).is_none());
+
+        let mut cron = Cron::new();
+        cron.add_task(task);
+        assert!(cron.is_planified_at(date));
+    }
+
+    #[cfg(feature = "shard")]
+    #[test]
+    fn test_sharded_task_invalid_total() {
+        assert!(
+            CronTask::builder()
+                .shard("reports", 0)
+                .allow_commandless()
+                .build()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_reboot_tasks() {
+        let mut cron = Cron::new();
+
+        cron.add_task(make_simple_task());
+        cron.add_task(CronTask::reboot("/usr/bin/warmup"));
+
+        let reboot: Vec<&CronTask> = cron.reboot_tasks().collect();
+
+        assert_eq!(reboot.len(), 1);
+        assert!(reboot[0].is_reboot());
+    }
+
+    #[cfg(feature = "group")]
+    #[test]
+    fn test_catch_up() {
+        let task = |path: &str, minute: u8, hour: CronValue, priority: i32| {
+            CronTask::builder()
+                .path(path.to_string())
+                .minutes(minute)
+                .hour(hour)
+                .priority(priority)
+        };
+        let mut cron = Cron::new();
+        cron.add_task(task("/low", 0, all(), 0).build().unwrap());
+        cron.add_task(task("/high", 30, on(2).into(), 10).build().unwrap());
+        for path in ["/db1", "/db2"] {
+            cron.add_task(task(path, 15, on(1).into(), 5).group("db").build().unwrap());
+        }
+        cron.add_group(TaskGroup::new("db").with_max_concurrent(1));
+
+        let start = make_datetime(2024, 1, 1, 0, 0);
+        let batches: Vec<Vec<(u32, &str)>> = cron
+            .catch_up(start..make_datetime(2024, 1, 1, 3, 0), 2)
+            .into_iter()
+            .map(|batch| {
+                batch
+                    .into_iter()
+                    .map(|(date, task)| (date.hour(), task.path().to_str().unwrap()))
+                    .collect()
+            })
+            .collect();
+
+        assert_eq!(
+            batches,
+            [
+                vec![(2, "/high"), (1, "/db1")],
+                vec![(1, "/db2"), (0, "/low")],
+                vec![(1, "/low")],
+                vec![(2, "/low")],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_histogram() {
+        let mut cron = Cron::new();
+        cron.add_task(
+            CronTask::builder()
+                .minutes(on(0))
+                .hour(every(6))
+                .allow_commandless()
+                .build()
+                .unwrap(),
+        );
+        cron.add_task(
+            CronTask::builder()
+                .minutes(every(30))
+                .hour(on(0))
+                .allow_commandless()
+                .build()
+                .unwrap(),
+        );
+
+        let start = make_datetime(2024, 1, 1, 0, 0);
+        let histogram = cron.histogram(start..start + TimeDelta::days(1), TimeDelta::hours(6));
+        let counts: Vec<usize> = histogram.iter().map(|(_, count)| *count).collect();
+
+        assert_eq!(counts, [3, 1, 1, 1]);
+        assert_eq!(histogram[1].0, make_datetime(2024, 1, 1, 6, 0));
+    }
+
+    #[test]
+    fn test_histogram_partial_bucket() {
+        let mut cron = Cron::new();
+        cron.add_task(make_simple_task());
+
+        let start = make_datetime(2024, 1, 1, 0, 0);
+        let histogram = cron.histogram(
+            start..start + TimeDelta::minutes(25),
+            TimeDelta::minutes(10),
+        );
+        let counts: Vec<usize> = histogram.iter().map(|(_, count)| *count).collect();
+
+        assert_eq!(counts, [10, 10, 5]);
+    }
+
+    #[test]
+    fn test_histogram_bounds_bucket_count() {
+        let mut cron = Cron::new();
+        cron.add_task(make_simple_task());
+
+        let start = make_datetime(2024, 1, 1, 0, 0);
+        let histogram = cron.histogram(
+            start..start + TimeDelta::days(31),
+            TimeDelta::milliseconds(1),
+        );
+
+        assert!(histogram.len() <= MAX_HISTOGRAM_BUCKETS);
+        assert_eq!(
+            histogram.iter().map(|(_, count)| count).sum::<usize>(),
+            31 * 24 * 60
+        );
+
+        let histogram = cron.histogram(
+            start..start + TimeDelta::minutes(2),
+            TimeDelta::milliseconds(1),
+        );
+        assert_eq!(histogram.len(), 120);
+    }
+
+    #[test]
+    fn test_histogram_empty_window() {
+        let mut cron = Cron::new();
+        cron.add_task(make_simple_task());
+
+        let start = make_datetime(2024, 1, 1, 0, 0);
+
+        assert!(cron.histogram(start..start, TimeDelta::hours(1)).is_empty());
+        assert!(
+            cron.histogram(start..start + TimeDelta::hours(1), TimeDelta::zero())
+                .is_empty()
+        );
+    }
+
+    #[cfg(feature = "group")]
+    #[test]
+    fn test_add_group_replaces_same_name() {
+        let mut cron = Cron::new();
+
+        cron.add_group(TaskGroup::new("reports"));
+        cron.add_group(TaskGroup::new("reports").with_max_concurrent(2));
+
+        assert_eq!(cron.groups().len(), 1);
+        assert_eq!(cron.group("reports").unwrap().max_concurrent(), Some(2));
+    }
+
+    #[test]
+    fn test_tasks_in_group() {
+        let mut cron = Cron::new();
+
+        cron.add_task(
+            CronTask::builder()
+                .group("reports")
+                .allow_commandless()
+                .build()
+                .unwrap(),
+        );
+        cron.add_task(make_simple_task());
+        cron.add_task(
+            CronTask::builder()
+                .group("reports")
+                .allow_commandless()
+                .build()
+                .unwrap(),
+        );
+
+        assert_eq!(cron.tasks_in_group("reports").count(), 2);
+        assert_eq!(cron.tasks_in_group("backups").count(), 0);
+    }
+
+    #[cfg(feature = "group")]
+    #[test]
+    fn test_disabled_group_is_not_planified() {
+        let mut cron = Cron::new();
+        let date = make_datetime(2024, 1, 1, 12, 0);
+
+        cron.add_group(TaskGroup::new("reports"));
+        cron.add_task(
+            CronTask::builder()
+                .group("reports")
+                .allow_commandless()
+                .build()
+                .unwrap(),
+        );
+
+        assert!(cron.is_planified_at(date));
+        assert!(cron.set_group_enabled("reports", false));
+        assert!(!cron.is_planified_at(date));
+        assert!(cron.next_event(date).is_none());
+        assert!(!cron.set_group_enabled("backups", false));
+    }
+
+    #[test]
+    fn test_paused_task_is_not_planified() {
+        let mut cron = Cron::new();
+        let date = make_datetime(2024, 1, 1, 12, 0);
+
+        cron.add_task(
+            CronTask::builder()
+                .name("report")
+                .allow_commandless()
+                .build()
+                .unwrap(),
+        );
+
+        assert!(cron.set_task_paused("report", true));
+        assert!(!cron.is_planified_at(date));
+        assert_eq!(
+            cron.skip_reason(cron.into_iter().next().unwrap()),
+            Some(SkipReason::Paused)
+        );
+        assert!(cron.set_task_paused("report", false));
+        assert!(cron.is_planified_at(date));
+        assert!(!cron.set_task_paused("backup", true));
+    }
+
+    #[cfg(feature = "group")]
+    #[test]
+    fn test_group_policy() {
+        let offset = chrono::FixedOffset::east_opt(2 * 3600).unwrap();
+        let weekends = CronTask::builder()
+            .week_day(CronValue::List(vec![
+                chrono::Weekday::Sat.into(),
+                chrono::Weekday::Sun.into(),
+            ]))
+            .allow_commandless()
+            .build()
+            .unwrap();
+        let task = |group: &str| {
+            CronTask::builder()
+                .minutes(on(0))
+                .hour(on(9))
+                .group(group)
+                .path("/usr/bin/report".to_string())
+        };
+
+        let mut cron = Cron::new();
+        cron.add_task(task("reports").build().unwrap());
+        cron.add_task(
+            task("reports")
+                .offset(chrono::FixedOffset::east_opt(0).unwrap())
+                .build()
+                .unwrap(),
+        );
+        cron.add_group(
+            TaskGroup::new("reports")
+                .with_offset(offset)
+                .with_calendar_rule(ScheduleRule::exclude(weekends)),
+        );
+        cron.add_task(task("reports").build().unwrap());
+        let tasks: Vec<&CronTask> = cron.into_iter().collect();
+
+        // Friday 2024-06-14, 07:00 UTC is 09:00 at the group's offset.
+        let friday = chrono::Utc.with_ymd_and_hms(2024, 6, 14, 7, 0, 0).unwrap();
+        assert!(tasks[0].matches(friday));
+        assert!(!tasks[1].matches(friday));
+        assert!(tasks[2].matches(friday));
+        assert_eq!(tasks[0].offset(), Some(offset));
+        assert_eq!(
+            tasks[0].to_string(),
+            "UTC+02:00 0 9 * * * \"/usr/bin/report\""
+        );
+
+        // The calendar leaves the weekend out, for the task with its own
+        // offset too.
+        assert_eq!(
+            tasks[0].try_next_occurrence(friday),
+            Some(chrono::Utc.with_ymd_and_hms(2024, 6, 17, 7, 0, 0).unwrap())
+        );
+        assert_eq!(
+            tasks[1].try_next_occurrence(friday),
+            Some(chrono::Utc.with_ymd_and_hms(2024, 6, 14, 9, 0, 0).unwrap())
+        );
+        assert_eq!(
+            tasks[1]
+                .try_next_occurrence(chrono::Utc.with_ymd_and_hms(2024, 6, 14, 10, 0, 0).unwrap()),
+            Some(chrono::Utc.with_ymd_and_hms(2024, 6, 17, 9, 0, 0).unwrap())
+        );
+
+        // Replacing the group replaces the policy its tasks follow.
+        cron.add_group(TaskGroup::new("reports"));
+        assert!(
+            cron.into_iter()
+                .all(|task| task.group_policy().unwrap().calendar().is_empty())
+        );
+        assert_eq!(cron.into_iter().next().unwrap().offset(), None);
+    }
+
+    #[cfg(all(feature = "group", feature = "shard"))]
+    #[test]
+    fn test_skip_reasons() {
+        let date = make_datetime(2024, 1, 1, 12, 0);
+        let sharded = CronTask::builder()
+            .shard("reports", 4)
+            .allow_commandless()
+            .build()
+            .unwrap();
+        let owner = sharded.shard().unwrap().index();
+
+        let mut cron = Cron::new().with_instance((owner + 1) % 4);
+        cron.add_group(TaskGroup::new("reports"));
+        cron.add_task(
+            CronTask::builder()
+                .group("reports")
+                .allow_commandless()
+                .build()
+                .unwrap(),
+        );
+        cron.add_task(sharded);
+        cron.add_task(make_simple_task());
+        cron.set_group_enabled("reports", false);
+
+        let skipped: Vec<SkipReason> = cron
+            .get_all_skipped_at(date)
+            .into_iter()
+            .map(|(_, reason)| reason)
+            .collect();
+
+        assert_eq!(
+            skipped,
+            [
+                SkipReason::GroupDisabled("reports".to_string()),
+                SkipReason::NotOwner { owner }
+            ]
+        );
+        assert_eq!(cron.get_all_planified_at(date).len(), 1);
+    }
+
+    #[test]
+    fn test_blackout() {
+        let mut cron = Cron::new();
+        cron.add_task(make_simple_task());
+        cron.add_blackout(make_datetime(2024, 1, 1, 12, 0)..make_datetime(2024, 1, 1, 13, 0));
+
+        assert!(
+            cron.get_all_due_at(make_datetime(2024, 1, 1, 12, 30))
+                .is_empty()
+        );
+        assert_eq!(
+            cron.get_all_skipped_due_at(make_datetime(2024, 1, 1, 12, 30))
+                .into_iter()
+                .map(|(_, reason)| reason)
+                .collect::<Vec<_>>(),
+            [SkipReason::Blackout]
+        );
+        assert_eq!(
+            cron.get_all_due_at(make_datetime(2024, 1, 1, 13, 0)).len(),
+            1
+        );
+        // The task is skipped, not idle, and still counts as scheduled.
+        assert_eq!(
+            cron.next_scheduled(make_datetime(2024, 1, 1, 12, 0))
+                .map(|(date, _)| date),
+            Some(make_datetime(2024, 1, 1, 12, 1))
+        );
+    }
+
     #[test]
     fn test_cron_task_builder_basic() {
         let task = CronTask::builder()
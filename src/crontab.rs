@@ -0,0 +1,962 @@
+use std::{collections::BTreeMap, fmt::Display, fs, path::Path, str::FromStr};
+
+use crate::{
+    command::quote,
+    cron::Cron,
+    error::{CronError, Result},
+    parser::{Field, parse_line, parse_system_line},
+    run_as::RunAs,
+    task::CronTask,
+    value::CronValue,
+    year::YearValue,
+    zone::Zone,
+};
+
+#[derive(Debug, Default)]
+pub struct Crontab {
+    cron: Cron,
+    env: Vec<(String, String)>,
+    trailing_comments: Vec<String>,
+    system: bool,
+}
+
+impl Crontab {
+    pub fn parse(s: &str) -> Result<Crontab> {
+        Self::parse_with(s, false)
+    }
+
+    /// Parses a system crontab such as `/etc/crontab`, whose lines name the
+    /// user each command runs as. It is written back in the same format.
+    pub fn parse_system(s: &str) -> Result<Crontab> {
+        Self::parse_with(s, true)
+    }
+
+    fn parse_with(s: &str, system: bool) -> Result<Crontab> {
+        let mut crontab = Crontab {
+            system,
+            ..Crontab::default()
+        };
+        let mut comments = Vec::new();
+        let mut zone = Zone::Local;
+
+        for (index, line) in s.lines().enumerate() {
+            let trimmed = line.trim();
+
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                comments.push(line.to_string());
+            } else if let Some((name, value)) = parse_assignment(trimmed) {
+                // Zones we can read go to the tasks below, others are kept
+                // for the daemon to resolve.
+                match (name.as_str(), Zone::parse(&value)) {
+                    ("CRON_TZ", Some(parsed)) => zone = parsed,
+                    _ => crontab.env.push((name, value)),
+                }
+            } else {
+                let task = parse_task(trimmed, std::mem::take(&mut comments), zone, system)
+                    .map_err(|e| CronError::Line {
+                        line: index + 1,
+                        source: Box::new(e),
+                    })?;
+                crontab.cron.add_task(task);
+            }
+        }
+
+        crontab.trailing_comments = comments;
+
+        Ok(crontab)
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Crontab> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn from_system_file(path: impl AsRef<Path>) -> Result<Crontab> {
+        Self::parse_system(&fs::read_to_string(path)?)
+    }
+
+    pub fn cron(&self) -> &Cron {
+        &self.cron
+    }
+
+    pub fn into_cron(self) -> Cron {
+        self.cron
+    }
+
+    /// `NAME=value` assignments such as `SHELL` or `MAILTO`, in file order.
+    pub fn env(&self) -> &[(String, String)] {
+        &self.env
+    }
+
+    pub fn var(&self, name: &str) -> Option<&str> {
+        self.env
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Comment and blank lines found after the last task.
+    pub fn trailing_comments(&self) -> &[String] {
+        &self.trailing_comments
+    }
+}
+
+impl FromStr for Crontab {
+    type Err = CronError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl Display for Crontab {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, value) in &self.env {
+            writeln!(f, "{name}={value}")?;
+        }
+
+        if self.system {
+            write!(f, "{}", self.cron.to_system_crontab_string())?;
+        } else {
+            write!(f, "{}", self.cron.to_crontab_string())?;
+        }
+
+        for comment in &self.trailing_comments {
+            writeln!(f, "{comment}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl CronTask {
+    /// Renders the task as a crontab line, numeric fields using crontab
+    /// numbering (Sunday is 0 in the week day field). A seconds field, if
+    /// any, is written first as in 6-field expressions. The year field,
+    /// rules and zone have no place on the line and are left out, and the
+    /// day fields are written as they are whatever their [`DayMatch`]:
+    /// [`CronTask::try_to_crontab_line`] refuses such tasks instead. A
+    /// working directory is changed to before running the command.
+    ///
+    /// [`DayMatch`]: crate::task::DayMatch
+    pub fn to_crontab_line(&self) -> String {
+        format!("{} {}", self.crontab_schedule(), self.crontab_command())
+            .trim_end()
+            .to_string()
+    }
+
+    /// Same as `to_crontab_line`, failing when cron would not read the line
+    /// back as the same schedule.
+    pub fn try_to_crontab_line(&self) -> Result<String> {
+        let mut lost = self.crontab_losses();
+        if Zone::of(self) != Zone::Local {
+            lost.push("the time zone, see `Cron::to_crontab_string`".to_string());
+        }
+
+        if lost.is_empty() {
+            Ok(self.to_crontab_line())
+        } else {
+            Err(CronError::Unconvertible(lost))
+        }
+    }
+
+    /// What of the schedule a crontab line cannot carry, leaving the zone
+    /// aside for the `CRON_TZ` lines of [`Cron::to_crontab_string`].
+    fn crontab_losses(&self) -> Vec<String> {
+        if self.is_reboot() {
+            return Vec::new();
+        }
+
+        let mut lost = Vec::new();
+        if *self.year() != YearValue::All {
+            lost.push("the year field".to_string());
+        }
+        if !self.rules().is_empty() {
+            lost.push("schedule rules".to_string());
+        }
+        // Cron matches either day field unless one of them starts with `*`.
+        let starred = |value, field| crontab_field(value, field).starts_with('*');
+        let either = !starred(self.month_day(), Field::MonthDay)
+            && !starred(self.week_day(), Field::WeekDay);
+        if either != self.matches_either_day() {
+            lost.push(format!("the `{}` day match", self.day_match()));
+        }
+        lost
+    }
+
+    /// Renders the task as a system crontab line, the account it runs as
+    /// following the schedule. Tasks with no account run as `root`.
+    pub fn to_system_crontab_line(&self) -> String {
+        let user = self
+            .run_as()
+            .map_or_else(|| "root".to_string(), RunAs::to_string);
+
+        format!(
+            "{} {user} {}",
+            self.crontab_schedule(),
+            self.crontab_command()
+        )
+        .trim_end()
+        .to_string()
+    }
+
+    fn crontab_command(&self) -> String {
+        let command = match self.current_dir() {
+            Some(dir) => format!(
+                "cd {} && {}",
+                quote(&dir.to_string_lossy()),
+                self.command_line()
+            ),
+            None => self.command_line(),
+        };
+
+        match self.stdin() {
+            Some(stdin) => format!("{}%{}", escape(&command), escape(stdin).replace('\n', "%")),
+            None => escape(&command),
+        }
+    }
+
+    /// The schedule part of `to_crontab_line`.
+    pub(crate) fn crontab_schedule(&self) -> String {
+        if self.is_reboot() {
+            return "@reboot".to_string();
+        }
+
+        let seconds = self
+            .second()
+            .map(|second| crontab_field(second, Field::Second) + " ")
+            .unwrap_or_default();
+        let fields = [
+            (self.minute(), Field::Minute),
+            (self.hour(), Field::Hour),
+            (self.month_day(), Field::MonthDay),
+            (self.month(), Field::Month),
+            (self.week_day(), Field::WeekDay),
+        ]
+        .map(|(value, field)| crontab_field(value, field));
+
+        format!("{seconds}{}", fields.join(" "))
+    }
+}
+
+impl Cron {
+    /// Renders every task, preceded by its attached comment lines. Tasks
+    /// with a fixed offset get a `CRON_TZ` line when the offset changes, an
+    /// empty one switching back to local time. Task variables are assigned
+    /// before the first task needing them, and emptied before the first one
+    /// that does not, assignments holding for the rest of the file.
+    pub fn to_crontab_string(&self) -> String {
+        self.render(CronTask::to_crontab_line)
+    }
+
+    /// Same as `to_crontab_string`, failing on the first task cron would
+    /// not read back as the same schedule, see
+    /// [`CronTask::try_to_crontab_line`].
+    pub fn try_to_crontab_string(&self) -> Result<String> {
+        for task in self {
+            let lost = task.crontab_losses();
+            if !lost.is_empty() {
+                return Err(CronError::Task {
+                    name: task
+                        .name()
+                        .map(str::to_string)
+                        .unwrap_or_else(|| task.command_line()),
+                    source: Box::new(CronError::Unconvertible(lost)),
+                });
+            }
+        }
+
+        Ok(self.to_crontab_string())
+    }
+
+    /// Same as `to_crontab_string` in the system crontab format, see
+    /// [`CronTask::to_system_crontab_line`].
+    pub fn to_system_crontab_string(&self) -> String {
+        self.render(CronTask::to_system_crontab_line)
+    }
+
+    fn render(&self, line: fn(&CronTask) -> String) -> String {
+        let mut current = Zone::Local;
+        let mut env = BTreeMap::new();
+
+        self.into_iter()
+            .flat_map(|task| {
+                let zone = (Zone::of(task) != current).then(|| {
+                    current = Zone::of(task);
+                    format!("CRON_TZ={current}")
+                });
+                let cleared = env
+                    .keys()
+                    .filter(|name| !task.env().contains_key(*name))
+                    .map(|name| format!("{name}="));
+                let assigned = task
+                    .env()
+                    .iter()
+                    .filter(|(name, value)| env.get(*name) != Some(*value))
+                    .map(|(name, value)| format!("{name}={}", assignment_value(value)));
+                let assignments: Vec<String> = cleared.chain(assigned).collect();
+                env = task.env().clone();
+
+                // Owners set in code get the comment that declares them.
+                let owner = task
+                    .owner()
+                    .filter(|owner| {
+                        !task
+                            .comments()
+                            .iter()
+                            .any(|comment| owner_comment(comment) == Some(owner))
+                    })
+                    .map(|owner| format!("# owner: {owner}"));
+                let annotations = task
+                    .annotations()
+                    .iter()
+                    .filter(|(key, value)| {
+                        !task.comments().iter().any(|comment| {
+                            annotation_comment(comment)
+                                .is_some_and(|(k, v)| k == **key && v == **value)
+                        })
+                    })
+                    .map(|(key, value)| format!("# annotation: {}={}", encode(key), encode(value)));
+
+                zone.into_iter()
+                    .chain(assignments)
+                    .chain(task.comments().iter().cloned())
+                    .chain(owner)
+                    .chain(annotations)
+                    .chain([line(task)])
+            })
+            .map(|line| line + "\n")
+            .collect()
+    }
+
+    /// Writes the crontab, failing as `try_to_crontab_string` does.
+    pub fn write_to(&self, path: impl AsRef<Path>) -> Result<()> {
+        Ok(fs::write(path, self.try_to_crontab_string()?)?)
+    }
+}
+
+/// Quotes values that reading the assignment back would otherwise trim or
+/// unquote.
+fn assignment_value(value: &str) -> String {
+    if value.is_empty() || value.trim() != value || value.starts_with(['"', '\'']) {
+        format!("\"{value}\"")
+    } else {
+        value.to_string()
+    }
+}
+
+/// Escapes `%`, which cron would otherwise turn into a line break and use
+/// to split the command from its standard input.
+fn escape(command: &str) -> String {
+    command.replace('%', "\\%")
+}
+
+fn crontab_field(value: &CronValue, field: Field) -> String {
+    match (value, field) {
+        (CronValue::All, _) => "*".to_string(),
+        (CronValue::Last, _) => "L".to_string(),
+        (CronValue::NearestWeekday(day), _) => format!("{day}W"),
+        (CronValue::Hashed(..), _) => value.to_string(),
+        (CronValue::Nth(day, n), Field::WeekDay) => format!("{}#{n}", (u8::from(day) + 1) % 7),
+        (CronValue::List(list), Field::WeekDay) if value.has_week_day_items() => list
+            .iter()
+            .map(|v| crontab_field(v, field))
+            .collect::<Vec<_>>()
+            .join(","),
+        (_, Field::WeekDay) => expand(value, 0..=6, |day| (day + 1) % 7),
+        (CronValue::Value(v), _) => u8::from(v).to_string(),
+        (CronValue::Range(r), _) => format!("{}-{}", r.start, r.end),
+        (CronValue::List(list), _) => list
+            .iter()
+            .map(|v| crontab_field(v, field))
+            .collect::<Vec<_>>()
+            .join(","),
+        (CronValue::Interval(base, step), _)
+            if matches!(**base, CronValue::All | CronValue::Range(_)) =>
+        {
+            format!("{}/{}", crontab_field(base, field), u8::from(step))
+        }
+        (CronValue::Interval(base, step), _) if matches!(**base, CronValue::Value(_)) => {
+            format!(
+                "{}-{}/{}",
+                crontab_field(base, field),
+                field.max(),
+                u8::from(step)
+            )
+        }
+        _ => expand(value, field.min()..=field.max(), |v| v),
+    }
+}
+
+/// Lists every value of `domain` matched by `value`, mapped to crontab
+/// numbering, collapsing consecutive values into ranges.
+fn expand(
+    value: &CronValue,
+    domain: std::ops::RangeInclusive<u8>,
+    to_crontab: impl Fn(u8) -> u8,
+) -> String {
+    let start = *domain.start();
+    let first = to_crontab(start);
+    let mut values: Vec<u8> = domain
+        .filter(|v| value.matches_in(*v, start))
+        .map(to_crontab)
+        .collect();
+    values.sort();
+
+    let mut runs: Vec<(u8, u8)> = Vec::new();
+    for v in values {
+        match runs.last_mut() {
+            Some((_, end)) if *end + 1 == v => *end = v,
+            _ => runs.push((v, v)),
+        }
+    }
+
+    // Nothing to match: fall back to a valid field rather than an empty one.
+    if runs.is_empty() {
+        return first.to_string();
+    }
+
+    runs.iter()
+        .map(|(start, end)| match end - start {
+            0 => start.to_string(),
+            1 => format!("{start},{end}"),
+            _ => format!("{start}-{end}"),
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Owner declared by a `# owner: <team>` comment.
+fn owner_comment(comment: &str) -> Option<&str> {
+    let owner = comment
+        .trim_start_matches('#')
+        .trim()
+        .strip_prefix("owner:")?
+        .trim();
+
+    (!owner.is_empty()).then_some(owner)
+}
+
+/// Annotation declared by a `# annotation: <key>=<value>` comment, its
+/// key and value percent-encoded.
+fn annotation_comment(comment: &str) -> Option<(String, String)> {
+    let (key, value) = comment
+        .trim_start_matches('#')
+        .trim()
+        .strip_prefix("annotation:")?
+        .trim()
+        .split_once('=')?;
+
+    Some((decode(key)?, decode(value)?))
+}
+
+/// Percent-encodes what would break an annotation comment: `%`, `=` and
+/// line breaks.
+fn encode(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '%' | '=' | '\n' | '\r' => format!("%{:02X}", c as u8),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+fn decode(s: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut rest = s.as_bytes();
+
+    while let Some((&byte, tail)) = rest.split_first() {
+        match byte {
+            b'%' => {
+                let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+                bytes.push(u8::from_str_radix(hex, 16).ok()?);
+                rest = &tail[2..];
+            }
+            byte => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+fn parse_task(line: &str, comments: Vec<String>, zone: Zone, system: bool) -> Result<CronTask> {
+    let builder = if system {
+        parse_system_line(line)?
+    } else {
+        parse_line(line)?
+    };
+    let builder = comments.into_iter().fold(builder, |builder, comment| {
+        let builder = match owner_comment(&comment) {
+            Some(owner) => builder.owner(owner),
+            None => builder,
+        };
+        match annotation_comment(&comment) {
+            Some((key, value)) => builder.annotation(key, value).comment(comment),
+            None => builder.comment(comment),
+        }
+    });
+
+    zone.apply(builder).build()
+}
+
+fn parse_assignment(line: &str) -> Option<(String, String)> {
+    let (name, value) = line.split_once('=')?;
+    let name = name.trim();
+    let mut chars = name.chars();
+
+    let is_identifier = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    if !is_identifier {
+        return None;
+    }
+
+    let value = value.trim();
+    let value = ['"', '\''].iter().fold(value, |value, quote| {
+        value
+            .strip_prefix(*quote)
+            .and_then(|v| v.strip_suffix(*quote))
+            .unwrap_or(value)
+    });
+
+    Some((name.to_string(), value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::FixedOffset;
+
+    use super::*;
+    use crate::{task::CronTaskBuilder, value::every};
+
+    const CRONTAB: &str = "\
+SHELL=/bin/bash
+MAILTO = \"ops@example.com\"
+
+# nightly backup
+0 2 * * * /usr/bin/backup --full
+
+*/15 9-17 * * 1-5 /usr/bin/poll
+# end of file
+";
+
+    #[test]
+    fn test_parse_crontab() {
+        let crontab = Crontab::parse(CRONTAB).unwrap();
+        let tasks: Vec<&CronTask> = crontab.cron().into_iter().collect();
+
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].to_string(), "0 2 * * * \"/usr/bin/backup --full\"");
+        assert_eq!(tasks[1].to_string(), "*/15 9-17 * * 0-4 \"/usr/bin/poll\"");
+    }
+
+    #[test]
+    fn test_parse_crontab_env() {
+        let crontab = Crontab::parse(CRONTAB).unwrap();
+
+        assert_eq!(crontab.env().len(), 2);
+        assert_eq!(crontab.var("SHELL"), Some("/bin/bash"));
+        assert_eq!(crontab.var("MAILTO"), Some("ops@example.com"));
+        assert_eq!(crontab.var("PATH"), None);
+    }
+
+    #[test]
+    fn test_parse_crontab_comments() {
+        let crontab = Crontab::parse(CRONTAB).unwrap();
+        let tasks: Vec<&CronTask> = crontab.cron().into_iter().collect();
+
+        assert_eq!(tasks[0].comments(), ["", "# nightly backup"]);
+        assert_eq!(tasks[1].comments(), [""]);
+        assert_eq!(crontab.trailing_comments(), ["# end of file"]);
+    }
+
+    #[test]
+    fn test_parse_crontab_error_line() {
+        let err = Crontab::parse("# header\n0 2 * * * /usr/bin/backup\n61 * * * * /bin/true\n")
+            .unwrap_err();
+
+        assert!(matches!(err, CronError::Line { line: 3, .. }));
+        assert!(err.to_string().starts_with("Line 3:"));
+    }
+
+    #[test]
+    fn test_crontab_round_trip() {
+        let crontab = Crontab::parse(CRONTAB).unwrap();
+        let exported = crontab.to_string();
+
+        assert_eq!(
+            exported,
+            "\
+SHELL=/bin/bash
+MAILTO=ops@example.com
+
+# nightly backup
+0 2 * * * /usr/bin/backup --full
+
+*/15 9-17 * * 1-5 /usr/bin/poll
+# end of file
+"
+        );
+        assert_eq!(Crontab::parse(&exported).unwrap().to_string(), exported);
+    }
+
+    #[test]
+    fn test_reboot_round_trip() {
+        let crontab = Crontab::parse("# warm caches\n@reboot /usr/bin/warmup\n").unwrap();
+
+        assert_eq!(
+            crontab.to_string(),
+            "# warm caches\n@reboot /usr/bin/warmup\n"
+        );
+        assert_eq!(crontab.cron().reboot_tasks().count(), 1);
+    }
+
+    #[test]
+    fn test_to_crontab_line_uses_numbers() {
+        use chrono::{Month, Weekday};
+
+        let task = CronTask::builder()
+            .minutes(0)
+            .hour(6)
+            .month(Month::March)
+            .week_day(Weekday::Sun)
+            .path("/usr/bin/report".to_string())
+            .build()
+            .unwrap();
+
+        assert_eq!(task.to_crontab_line(), "0 6 * 3 0 /usr/bin/report");
+    }
+
+    #[test]
+    fn test_to_crontab_line_with_seconds() {
+        let task = CronTask::parse_with_seconds("*/15 0 6 * * 1 /usr/bin/poll").unwrap();
+
+        assert_eq!(task.to_crontab_line(), "*/15 0 6 * * 1 /usr/bin/poll");
+    }
+
+    #[test]
+    fn test_to_crontab_line_escapes_command() {
+        let commands = [
+            "date +%Y-%m-%d > /tmp/today",
+            "printf '50\\%' | tee \"out file\"",
+            "echo 'single quotes' \"double\" $HOME",
+        ];
+
+        for command in commands {
+            let task = CronTask::builder()
+                .path(command.to_string())
+                .build()
+                .unwrap();
+            let line = task.to_crontab_line();
+
+            assert!(!line.replace("\\%", "").contains('%'), "{line}");
+            assert_eq!(
+                CronTask::parse(&line).unwrap().path().to_str(),
+                Some(command)
+            );
+        }
+        assert_eq!(
+            CronTask::reboot("date +%s").to_crontab_line(),
+            "@reboot date +\\%s"
+        );
+    }
+
+    #[test]
+    fn test_crontab_command_stdin() {
+        let line = "0 22 * * 1-5 mail -s \"It's 10pm\" joe%Joe,%%Where are your kids? 100\\%%";
+        let task = CronTask::parse(line).unwrap();
+
+        assert_eq!(task.path().to_str(), Some("mail -s \"It's 10pm\" joe"));
+        assert_eq!(task.stdin(), Some("Joe,\n\nWhere are your kids? 100%\n"));
+        assert_eq!(task.to_crontab_line(), line);
+
+        let task = CronTask::builder()
+            .path("cat > /tmp/out".to_string())
+            .stdin("50%\ndone")
+            .build()
+            .unwrap();
+        let back = CronTask::parse(&task.to_crontab_line()).unwrap();
+        assert_eq!(back.path(), task.path());
+        assert_eq!(back.stdin(), task.stdin());
+    }
+
+    #[test]
+    fn test_crontab_owner() {
+        let crontab =
+            Crontab::parse("# owner: ops@example.com\n0 3 * * * /usr/bin/backup\n").unwrap();
+        let task = crontab.cron().into_iter().next().unwrap();
+
+        assert_eq!(task.owner(), Some("ops@example.com"));
+        assert_eq!(
+            crontab.cron().to_crontab_string(),
+            "# owner: ops@example.com\n0 3 * * * /usr/bin/backup\n"
+        );
+
+        let mut cron = Cron::new();
+        cron.add_task(
+            CronTaskBuilder::from_task(&CronTask::daily("/usr/bin/report"))
+                .owner("web")
+                .build()
+                .unwrap(),
+        );
+        assert_eq!(
+            cron.to_crontab_string(),
+            "# owner: web\n0 0 * * * /usr/bin/report\n"
+        );
+    }
+
+    #[test]
+    fn test_system_crontab() {
+        let input = "SHELL=/bin/sh\n# nightly\n0 3 * * * backup:disk /usr/bin/backup\n@reboot root /usr/bin/warmup\n";
+        let crontab = Crontab::parse_system(input).unwrap();
+
+        let tasks: Vec<&CronTask> = crontab.cron().into_iter().collect();
+        assert_eq!(
+            tasks[0].run_as().map(RunAs::to_string).as_deref(),
+            Some("backup:disk")
+        );
+        assert_eq!(tasks[1].run_as().map(RunAs::user), Some("root"));
+        assert_eq!(crontab.to_string(), input);
+
+        assert_eq!(
+            CronTask::daily("/usr/bin/report").to_system_crontab_line(),
+            "0 0 * * * root /usr/bin/report"
+        );
+        assert!(Crontab::parse_system("0 3 * * * /usr/bin/backup\n").is_err());
+    }
+
+    #[test]
+    fn test_crontab_task_env() {
+        let task = |path: &str| CronTaskBuilder::from_task(&CronTask::daily(path));
+        let mut cron = Cron::new();
+        cron.add_task(
+            task("/usr/bin/sync")
+                .env("REGION", "eu-west")
+                .env("TAG", " v1 ")
+                .build()
+                .unwrap(),
+        );
+        cron.add_task(
+            task("/usr/bin/report")
+                .env("REGION", "eu-west")
+                .build()
+                .unwrap(),
+        );
+        cron.add_task(
+            task("./report.sh")
+                .current_dir("/srv/my app")
+                .build()
+                .unwrap(),
+        );
+
+        let crontab = cron.to_crontab_string();
+        assert_eq!(
+            crontab,
+            "REGION=eu-west\nTAG=\" v1 \"\n0 0 * * * /usr/bin/sync\n\
+             TAG=\n0 0 * * * /usr/bin/report\n\
+             REGION=\n0 0 * * * cd '/srv/my app' && ./report.sh\n"
+        );
+        assert_eq!(Crontab::parse(&crontab).unwrap().env()[1].1, " v1 ");
+        assert!(task("/bin/true").env("MY-VAR", "1").build().is_err());
+    }
+
+    #[test]
+    fn test_crontab_annotations() {
+        let mut cron = Cron::new();
+        cron.add_task(
+            CronTaskBuilder::from_task(&CronTask::daily("/usr/bin/report"))
+                .annotation("deploy.id", "42")
+                .annotation("note", "a=b\n100%")
+                .build()
+                .unwrap(),
+        );
+        let crontab = cron.to_crontab_string();
+
+        assert_eq!(
+            crontab,
+            "# annotation: deploy.id=42\n# annotation: note=a%3Db%0A100%25\n0 0 * * * /usr/bin/report\n"
+        );
+
+        let parsed = Crontab::parse(&crontab).unwrap();
+        let task = parsed.cron().into_iter().next().unwrap();
+        assert_eq!(
+            task.annotations(),
+            cron.into_iter().next().unwrap().annotations()
+        );
+        assert_eq!(parsed.cron().to_crontab_string(), crontab);
+    }
+
+    #[test]
+    fn test_multiline_command_rejected() {
+        let result = CronTask::builder()
+            .path("/usr/bin/backup\n* * * * * /usr/bin/evil".to_string())
+            .build();
+
+        assert!(matches!(result, Err(CronError::MultilineCommand(_))));
+    }
+
+    #[test]
+    fn test_to_crontab_line_week_days() {
+        let line = |expr: &str| CronTask::parse(expr).unwrap().to_crontab_line();
+
+        assert_eq!(line("* * * * 1-5"), "* * * * 1-5");
+        assert_eq!(line("* * * * 0,6"), "* * * * 0,6");
+        assert_eq!(line("* * * * */2"), "* * * * 0,2,4,6");
+        assert_eq!(line("* * * * 5-7"), "* * * * 0,5,6");
+    }
+
+    #[test]
+    fn test_to_crontab_line_nth_week_day() {
+        let line = |expr: &str| CronTask::parse(expr).unwrap().to_crontab_line();
+
+        assert_eq!(line("0 9 * * MON#2"), "0 9 * * 1#2");
+        assert_eq!(line("0 9 * * 0#1,5#3"), "0 9 * * 0#1,5#3");
+    }
+
+    #[test]
+    fn test_to_crontab_line_phase() {
+        let task = CronTask::builder()
+            .minutes(every(15u8).phase(7))
+            .path("/usr/bin/poll".to_string())
+            .build()
+            .unwrap();
+
+        assert_eq!(task.to_crontab_line(), "7-59/15 * * * * /usr/bin/poll");
+    }
+
+    #[test]
+    fn test_to_crontab_line_last_day() {
+        let task = CronTask::parse("0 0 L * * /usr/bin/close-books").unwrap();
+
+        assert_eq!(task.to_crontab_line(), "0 0 L * * /usr/bin/close-books");
+
+        let task = CronTask::parse("0 0 15W,L * * /usr/bin/close-books").unwrap();
+        assert_eq!(task.to_crontab_line(), "0 0 15W,L * * /usr/bin/close-books");
+    }
+
+    #[test]
+    fn test_cron_write_to() {
+        let mut cron = Cron::new();
+        cron.add_task(CronTask::parse("*/5 * * * * /usr/bin/poll").unwrap());
+
+        let path = std::env::temp_dir().join(format!("cron_dsl_{}.crontab", std::process::id()));
+        cron.write_to(&path).unwrap();
+        let written = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(written, "*/5 * * * * /usr/bin/poll\n");
+    }
+
+    #[test]
+    fn test_try_to_crontab_line() {
+        use chrono::{Local, TimeZone, Weekday};
+
+        use crate::task::DayMatch;
+
+        // 2025-06-02 is a Monday.
+        let monday = Local.with_ymd_and_hms(2025, 6, 2, 0, 0, 0).unwrap();
+        let both = CronTask::builder()
+            .minutes(0)
+            .hour(0)
+            .month_day(1)
+            .week_day(Weekday::Mon)
+            .path("/usr/bin/report".to_string())
+            .build()
+            .unwrap();
+        assert_eq!(both.to_crontab_line(), "0 0 1 * 1 /usr/bin/report");
+        assert!(!both.matches(monday));
+        assert!(
+            CronTask::parse(&both.to_crontab_line())
+                .unwrap()
+                .matches(monday)
+        );
+        assert!(matches!(
+            both.try_to_crontab_line(),
+            Err(CronError::Unconvertible(lost)) if lost == ["the `all` day match"]
+        ));
+
+        let either = CronTaskBuilder::from_task(&both)
+            .day_match(DayMatch::Any)
+            .build()
+            .unwrap();
+        assert_eq!(
+            either.try_to_crontab_line().unwrap(),
+            "0 0 1 * 1 /usr/bin/report"
+        );
+        // A starred field needs both to match, whatever the day match.
+        let stepped = CronTaskBuilder::from_task(&both)
+            .month_day(every(2))
+            .build()
+            .unwrap();
+        assert!(stepped.try_to_crontab_line().is_ok());
+
+        let yearly = CronTaskBuilder::from_task(&either)
+            .year(2026)
+            .build()
+            .unwrap();
+        assert!(yearly.try_to_crontab_line().is_err());
+
+        let mut cron = Cron::new();
+        cron.add_task(either);
+        assert!(cron.try_to_crontab_string().is_ok());
+        cron.add_task(both);
+        assert!(matches!(
+            cron.try_to_crontab_string(),
+            Err(CronError::Task { name, .. }) if name == "/usr/bin/report"
+        ));
+        let path = std::env::temp_dir().join(format!("cron_dsl_{}.lossy", std::process::id()));
+        assert!(cron.write_to(&path).is_err());
+    }
+
+    #[test]
+    fn test_parse_assignment() {
+        assert_eq!(
+            parse_assignment("PATH=/usr/bin:/bin"),
+            Some(("PATH".to_string(), "/usr/bin:/bin".to_string()))
+        );
+        assert_eq!(
+            parse_assignment("MAILTO=''"),
+            Some(("MAILTO".to_string(), String::new()))
+        );
+        assert_eq!(parse_assignment("0 2 * * * /usr/bin/env A=1"), None);
+    }
+
+    #[test]
+    fn test_crontab_fixed_offset() {
+        let s =
+            "CRON_TZ=UTC-05:30\n0 9 * * * /usr/bin/report\nCRON_TZ=\n0 3 * * * /usr/bin/backup\n";
+        let crontab = Crontab::parse(s).unwrap();
+        let tasks: Vec<_> = crontab.cron().into_iter().collect();
+
+        assert!(crontab.env().is_empty());
+        assert_eq!(tasks[0].offset(), FixedOffset::east_opt(5 * 3600 + 30 * 60));
+        assert_eq!(tasks[1].offset(), None);
+        assert_eq!(crontab.to_string(), s);
+    }
+
+    #[cfg(not(feature = "chrono-tz"))]
+    #[test]
+    fn test_crontab_named_zone_kept() {
+        let crontab = Crontab::parse("CRON_TZ=Europe/Paris\n0 9 * * * /usr/bin/report\n").unwrap();
+
+        assert_eq!(crontab.var("CRON_TZ"), Some("Europe/Paris"));
+        assert_eq!(crontab.cron().into_iter().next().unwrap().offset(), None);
+    }
+
+    #[cfg(feature = "chrono-tz")]
+    #[test]
+    fn test_crontab_named_zone() {
+        let s = "CRON_TZ=Europe/Paris\n0 9 * * * /usr/bin/report\nCRON_TZ=\n0 3 * * * /usr/bin/backup\n";
+        let crontab = Crontab::parse(s).unwrap();
+        let tasks: Vec<_> = crontab.cron().into_iter().collect();
+
+        assert!(crontab.env().is_empty());
+        assert_eq!(tasks[0].timezone(), Some(chrono_tz::Europe::Paris));
+        assert_eq!(tasks[1].timezone(), None);
+        assert_eq!(crontab.to_string(), s);
+    }
+}
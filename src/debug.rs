@@ -0,0 +1,128 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, NaiveDateTime, TimeZone};
+
+use crate::task::CronTask;
+pub use crate::task::Rejection;
+
+/// Record of a `try_next_occurrence` search, see [`trace_next`].
+#[derive(Debug, Clone)]
+pub struct Trace<Tz: TimeZone> {
+    pub from: DateTime<Tz>,
+    pub result: Option<DateTime<Tz>>,
+    /// Wall clock readings examined and rejected, in search order, in the
+    /// zone the task's fields are read in.
+    pub rejected: Vec<(NaiveDateTime, Rejection)>,
+}
+
+/// Searches the next occurrence of `task` after `from` like
+/// [`CronTask::try_next_occurrence`], also recording each reading the
+/// search rejected along the way. The result is the one
+/// `try_next_occurrence` gives, so a trace can be attached to a report of
+/// a wrong occurrence and replayed step by step.
+pub fn trace_next<Tz: TimeZone>(task: &CronTask, from: DateTime<Tz>) -> Trace<Tz> {
+    let mut rejected = Vec::new();
+    let mut record = |reading, reason| rejected.push((reading, reason));
+    let result = task.next_occurrence_observed(from.clone(), None, &mut Some(&mut record));
+
+    Trace {
+        from,
+        result,
+        rejected,
+    }
+}
+
+impl<Tz: TimeZone> Display for Trace<Tz>
+where
+    Tz::Offset: Display,
+{
+    /// One line per step, such as `2025-02-28 13:00:00 hour`, between the
+    /// starting point and the result.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "from {}", self.from)?;
+        for (reading, reason) in &self.rejected {
+            writeln!(f, "{} {reason}", reading.format("%Y-%m-%d %H:%M:%S"))?;
+        }
+        match &self.result {
+            Some(result) => write!(f, "next {result}"),
+            None => write!(f, "next none"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Datelike, FixedOffset, Utc};
+
+    use super::*;
+    use crate::value::{CronValue, on};
+
+    fn make_datetime(year: i32, month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_trace_next() {
+        let task = CronTask::builder()
+            .minutes(on(0))
+            .hour(on(9))
+            .month_day(on(31))
+            .path("/usr/bin/report".to_string())
+            .build()
+            .unwrap();
+        let from = make_datetime(2025, 1, 31, 12);
+        let trace = trace_next(&task, from);
+
+        assert_eq!(trace.result, task.try_next_occurrence(from));
+        assert_eq!(trace.result, Some(make_datetime(2025, 3, 31, 9)));
+        assert_eq!(
+            trace
+                .rejected
+                .iter()
+                .map(|(reading, reason)| (reading.month(), reading.day(), *reason))
+                .collect::<Vec<_>>(),
+            [
+                (1, 31, Rejection::Hour),
+                (2, 1, Rejection::MonthDay),
+                (3, 1, Rejection::MonthDay),
+                (3, 31, Rejection::Hour),
+            ]
+        );
+        assert_eq!(
+            trace.to_string(),
+            "from 2025-01-31 12:00:00 UTC\n\
+             2025-01-31 12:00:00 hour\n\
+             2025-02-01 00:00:00 month day\n\
+             2025-03-01 00:00:00 month day\n\
+             2025-03-31 00:00:00 hour\n\
+             next 2025-03-31 09:00:00 UTC"
+        );
+    }
+
+    #[test]
+    fn test_trace_next_rules_and_zones() {
+        let excluded = CronTask::builder()
+            .minutes(CronValue::All)
+            .month_day(on(2))
+            .path(String::new())
+            .allow_commandless()
+            .build()
+            .unwrap();
+        let task = CronTask::builder()
+            .minutes(on(0))
+            .hour(on(0))
+            .exclude(excluded)
+            .offset(FixedOffset::east_opt(3600).unwrap())
+            .path("/usr/bin/sync".to_string())
+            .build()
+            .unwrap();
+        let from = make_datetime(2025, 6, 1, 0);
+        let trace = trace_next(&task, from);
+
+        assert_eq!(trace.result, task.try_next_occurrence(from));
+        assert!(trace.rejected.contains(&(
+            NaiveDateTime::parse_from_str("2025-06-02 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap(),
+            Rejection::Rule
+        )));
+    }
+}
@@ -0,0 +1,537 @@
+use crate::{task::CronTask, value::CronValue, year::YearValue};
+
+/// Language descriptions are rendered in. English is always available,
+/// other languages each come with their own feature.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Language {
+    #[default]
+    English,
+    #[cfg(feature = "describe-fr")]
+    French,
+}
+
+/// Phrases of a language, `{}` marking where values go.
+struct Table {
+    week_days: [&'static str; 7],
+    months: [&'static str; 12],
+    ordinals: [&'static str; 5],
+    and: &'static str,
+    range: &'static str,
+    at_startup: &'static str,
+    every_second: &'static str,
+    every_seconds: &'static str,
+    at_second: &'static str,
+    every_minute: &'static str,
+    every_minutes: &'static str,
+    at_minute: &'static str,
+    every_hours: &'static str,
+    at_time: &'static str,
+    past_every_hour: &'static str,
+    between: &'static str,
+    during_hours: &'static str,
+    on_month_days: &'static str,
+    on_last_day: &'static str,
+    on_nearest_weekday: &'static str,
+    week_day: &'static str,
+    week_day_range: &'static str,
+    on_week_days: &'static str,
+    nth_week_day: &'static str,
+    month: &'static str,
+    month_range: &'static str,
+    in_months: &'static str,
+    in_years: &'static str,
+}
+
+const ENGLISH: Table = Table {
+    week_days: [
+        "Monday",
+        "Tuesday",
+        "Wednesday",
+        "Thursday",
+        "Friday",
+        "Saturday",
+        "Sunday",
+    ],
+    months: [
+        "January",
+        "February",
+        "March",
+        "April",
+        "May",
+        "June",
+        "July",
+        "August",
+        "September",
+        "October",
+        "November",
+        "December",
+    ],
+    ordinals: ["first", "second", "third", "fourth", "fifth"],
+    and: "and",
+    range: "{} through {}",
+    at_startup: "at startup",
+    every_second: "every second",
+    every_seconds: "every {} seconds",
+    at_second: "at second {}",
+    every_minute: "every minute",
+    every_minutes: "every {} minutes",
+    at_minute: "at minute {}",
+    every_hours: "every {} hours",
+    at_time: "at {}",
+    past_every_hour: "{} past every hour",
+    between: "between {} and {}",
+    during_hours: "during hour {}",
+    on_month_days: "on day {} of the month",
+    on_last_day: "on the last day of the month",
+    on_nearest_weekday: "on the weekday nearest day {} of the month",
+    week_day: "{}",
+    week_day_range: "{} through {}",
+    on_week_days: "on {}",
+    nth_week_day: "the {} {}",
+    month: "{}",
+    month_range: "{} through {}",
+    in_months: "in {}",
+    in_years: "in {}",
+};
+
+#[cfg(feature = "describe-fr")]
+const FRENCH: Table = Table {
+    week_days: [
+        "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche",
+    ],
+    months: [
+        "janvier",
+        "février",
+        "mars",
+        "avril",
+        "mai",
+        "juin",
+        "juillet",
+        "août",
+        "septembre",
+        "octobre",
+        "novembre",
+        "décembre",
+    ],
+    ordinals: ["premier", "deuxième", "troisième", "quatrième", "cinquième"],
+    and: "et",
+    range: "{} à {}",
+    at_startup: "au démarrage",
+    every_second: "chaque seconde",
+    every_seconds: "toutes les {} secondes",
+    at_second: "à la seconde {}",
+    every_minute: "chaque minute",
+    every_minutes: "toutes les {} minutes",
+    at_minute: "à la minute {}",
+    every_hours: "toutes les {} heures",
+    at_time: "à {}",
+    past_every_hour: "{} de chaque heure",
+    between: "entre {} et {}",
+    during_hours: "pendant l'heure {}",
+    on_month_days: "le {} du mois",
+    on_last_day: "le dernier jour du mois",
+    on_nearest_weekday: "le jour ouvré le plus proche du {} du mois",
+    week_day: "le {}",
+    week_day_range: "du {} au {}",
+    on_week_days: "{}",
+    nth_week_day: "le {} {}",
+    month: "en {}",
+    month_range: "de {} à {}",
+    in_months: "{}",
+    in_years: "en {}",
+};
+
+impl Language {
+    fn table(&self) -> &'static Table {
+        match self {
+            Language::English => &ENGLISH,
+            #[cfg(feature = "describe-fr")]
+            Language::French => &FRENCH,
+        }
+    }
+}
+
+impl CronTask {
+    /// Describes the schedule in English, such as `At 09:00, on Monday
+    /// through Friday`.
+    pub fn describe(&self) -> String {
+        self.describe_in(Language::English)
+    }
+
+    pub fn describe_in(&self, language: Language) -> String {
+        let table = language.table();
+        if self.is_reboot() {
+            return capitalize(table.at_startup);
+        }
+
+        let mut parts = time_parts(self, table);
+        let days: Vec<String> = month_day_part(self.month_day(), table)
+            .into_iter()
+            .chain(week_day_part(self.week_day(), table))
+            .collect();
+        if self.matches_either_day() {
+            parts.push(days.join(&format!(" {} ", table.and)));
+        } else {
+            parts.extend(days);
+        }
+        parts.extend(month_part(self.month(), table));
+        if *self.year() != YearValue::All {
+            parts.push(fill(table.in_years, &[&self.year().to_string()]));
+        }
+
+        capitalize(&parts.join(", "))
+    }
+}
+
+fn time_parts(task: &CronTask, table: &Table) -> Vec<String> {
+    let minutes = matching(task.minute(), 0..=59);
+    let hours = matching(task.hour(), 0..=23);
+    let all_minutes = minutes.len() == 60;
+    let all_hours = hours.len() == 24;
+    let mut parts = Vec::new();
+
+    if let Some(second) = task.second() {
+        let seconds = matching(second, 0..=59);
+        parts.push(match (seconds.len(), step(&seconds, 60)) {
+            (60, _) => table.every_second.to_string(),
+            (_, Some(step)) => fill(table.every_seconds, &[&step.to_string()]),
+            _ => fill(table.at_second, &[&numbers(&seconds, table)]),
+        });
+        if all_minutes && all_hours {
+            return parts;
+        }
+    }
+
+    let minute_phrase = || match (all_minutes, step(&minutes, 60)) {
+        (true, _) => table.every_minute.to_string(),
+        (_, Some(step)) => fill(table.every_minutes, &[&step.to_string()]),
+        _ => fill(table.at_minute, &[&numbers(&minutes, table)]),
+    };
+
+    if all_hours {
+        let phrase = minute_phrase();
+        parts.push(if all_minutes || step(&minutes, 60).is_some() {
+            phrase
+        } else {
+            fill(table.past_every_hour, &[&phrase])
+        });
+    } else if minutes == [0]
+        && let Some(step) = step(&hours, 24)
+    {
+        parts.push(fill(table.every_hours, &[&step.to_string()]));
+    } else if minutes.len() * hours.len() <= 8 {
+        let times: Vec<String> = hours
+            .iter()
+            .flat_map(|hour| {
+                minutes
+                    .iter()
+                    .map(move |minute| format!("{hour:02}:{minute:02}"))
+            })
+            .collect();
+        parts.push(fill(table.at_time, &[&join(&times, table)]));
+    } else {
+        parts.push(minute_phrase());
+        parts.push(match runs(&hours)[..] {
+            [(start, end)] => fill(
+                table.between,
+                &[&format!("{start:02}:00"), &format!("{end:02}:59")],
+            ),
+            _ => fill(table.during_hours, &[&numbers(&hours, table)]),
+        });
+    }
+
+    parts
+}
+
+fn month_day_part(value: &CronValue, table: &Table) -> Option<String> {
+    match value {
+        CronValue::All => None,
+        CronValue::Last => Some(table.on_last_day.to_string()),
+        CronValue::NearestWeekday(day) => Some(fill(table.on_nearest_weekday, &[&day.to_string()])),
+        value => {
+            let days = matching(value, 1..=31);
+            (days.len() < 31).then(|| fill(table.on_month_days, &[&numbers(&days, table)]))
+        }
+    }
+}
+
+fn week_day_part(value: &CronValue, table: &Table) -> Option<String> {
+    let name = |day: u8| table.week_days[day as usize % 7];
+    let nth = |value: &CronValue| match value {
+        CronValue::Nth(day, n) => Some(fill(
+            table.nth_week_day,
+            &[
+                table.ordinals[(*n as usize).clamp(1, 5) - 1],
+                name(u8::from(day)),
+            ],
+        )),
+        _ => None,
+    };
+
+    let items = match value {
+        CronValue::All => return None,
+        CronValue::Nth(..) => vec![nth(value)?],
+        CronValue::List(list) if value.has_week_day_items() => list
+            .iter()
+            .flat_map(|item| match nth(item) {
+                Some(nth) => vec![nth],
+                None => names(
+                    &matching(item, 0..=6),
+                    name,
+                    table.week_day,
+                    table.week_day_range,
+                ),
+            })
+            .collect(),
+        value => {
+            let days = matching(value, 0..=6);
+            if days.len() == 7 {
+                return None;
+            }
+            names(&days, name, table.week_day, table.week_day_range)
+        }
+    };
+
+    Some(fill(table.on_week_days, &[&join(&items, table)]))
+}
+
+fn month_part(value: &CronValue, table: &Table) -> Option<String> {
+    let months = matching(value, 1..=12);
+    if months.len() == 12 {
+        return None;
+    }
+
+    let name = |month: u8| table.months[(month as usize + 11) % 12];
+    let items = names(&months, name, table.month, table.month_range);
+
+    Some(fill(table.in_months, &[&join(&items, table)]))
+}
+
+fn matching(value: &CronValue, domain: std::ops::RangeInclusive<u8>) -> Vec<u8> {
+    let first = *domain.start();
+    domain.filter(|v| value.matches_in(*v, first)).collect()
+}
+
+/// Step of `values` when they are every step-th value of a cycle of
+/// `size` starting at 0, as `*/15` minutes.
+fn step(values: &[u8], size: u8) -> Option<u8> {
+    let step = *values.get(1)?;
+
+    (values[0] == 0
+        && size.is_multiple_of(step)
+        && values.len() == (size / step) as usize
+        && values.windows(2).all(|w| w[1] - w[0] == step))
+    .then_some(step)
+}
+
+/// Consecutive values collapsed into inclusive runs.
+fn runs(values: &[u8]) -> Vec<(u8, u8)> {
+    let mut runs: Vec<(u8, u8)> = Vec::new();
+    for v in values {
+        match runs.last_mut() {
+            Some((_, end)) if *end + 1 == *v => *end = *v,
+            _ => runs.push((*v, *v)),
+        }
+    }
+    runs
+}
+
+fn numbers(values: &[u8], table: &Table) -> String {
+    let items = names(values, |v| v, "{}", table.range);
+    join(&items, table)
+}
+
+fn names<T: ToString>(
+    values: &[u8],
+    name: impl Fn(u8) -> T,
+    single: &str,
+    range: &str,
+) -> Vec<String> {
+    runs(values)
+        .into_iter()
+        .flat_map(|(start, end)| match end - start {
+            0 => vec![fill(single, &[&name(start).to_string()])],
+            1 => vec![
+                fill(single, &[&name(start).to_string()]),
+                fill(single, &[&name(end).to_string()]),
+            ],
+            _ => vec![fill(
+                range,
+                &[&name(start).to_string(), &name(end).to_string()],
+            )],
+        })
+        .collect()
+}
+
+/// Joins items as `a, b and c`.
+fn join(items: &[String], table: &Table) -> String {
+    match items {
+        [] => String::new(),
+        [item] => item.clone(),
+        [rest @ .., last] => format!("{} {} {last}", rest.join(", "), table.and),
+    }
+}
+
+fn fill(template: &str, values: &[&str]) -> String {
+    values
+        .iter()
+        .fold(template.to_string(), |s, value| s.replacen("{}", value, 1))
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Weekday;
+
+    use super::*;
+    use crate::task::DayMatch;
+    use crate::value::{every, last_day, nth, on, range};
+
+    fn task() -> crate::task::CronTaskBuilder {
+        CronTask::builder().path("/bin/true".to_string())
+    }
+
+    #[test]
+    fn test_describe() {
+        let cases = [
+            (task().build().unwrap(), "Every minute"),
+            (
+                task().minutes(every(15)).build().unwrap(),
+                "Every 15 minutes",
+            ),
+            (
+                task()
+                    .minutes(30)
+                    .hour(9)
+                    .week_day(range(0..4))
+                    .build()
+                    .unwrap(),
+                "At 09:30, on Monday through Friday",
+            ),
+            (
+                task().minutes(0).hour(on(9).or(17)).build().unwrap(),
+                "At 09:00 and 17:00",
+            ),
+            (
+                task().minutes(15).build().unwrap(),
+                "At minute 15 past every hour",
+            ),
+            (
+                task().minutes(0).hour(every(6)).build().unwrap(),
+                "Every 6 hours",
+            ),
+            (
+                task().minutes(every(5)).hour(range(9..17)).build().unwrap(),
+                "Every 5 minutes, between 09:00 and 17:59",
+            ),
+            (
+                task()
+                    .minutes(0)
+                    .hour(0)
+                    .month_day(last_day())
+                    .month(on(1).or(7))
+                    .build()
+                    .unwrap(),
+                "At 00:00, on the last day of the month, in January and July",
+            ),
+            (
+                task()
+                    .minutes(0)
+                    .hour(8)
+                    .week_day(nth(Weekday::Mon, 2))
+                    .build()
+                    .unwrap(),
+                "At 08:00, on the second Monday",
+            ),
+            (
+                task()
+                    .minutes(0)
+                    .hour(8)
+                    .week_day(CronValue::List(vec![nth(Weekday::Mon, 1), 4.into()]))
+                    .build()
+                    .unwrap(),
+                "At 08:00, on the first Monday and Friday",
+            ),
+            (
+                task()
+                    .minutes(0)
+                    .hour(0)
+                    .month_day(1)
+                    .week_day(0)
+                    .day_match(DayMatch::Any)
+                    .build()
+                    .unwrap(),
+                "At 00:00, on day 1 of the month and on Monday",
+            ),
+            (
+                task()
+                    .minutes(0)
+                    .hour(0)
+                    .month_day(1)
+                    .week_day(0)
+                    .build()
+                    .unwrap(),
+                "At 00:00, on day 1 of the month, on Monday",
+            ),
+            (
+                task().seconds(every(10)).build().unwrap(),
+                "Every 10 seconds",
+            ),
+            (CronTask::reboot("/bin/true"), "At startup"),
+        ];
+
+        for (task, expected) in cases {
+            assert_eq!(task.describe(), expected);
+        }
+    }
+
+    #[cfg(feature = "describe-fr")]
+    #[test]
+    fn test_describe_french() {
+        let cases = [
+            (
+                task().minutes(every(15)).build().unwrap(),
+                "Toutes les 15 minutes",
+            ),
+            (
+                task()
+                    .minutes(30)
+                    .hour(9)
+                    .week_day(range(0..4))
+                    .build()
+                    .unwrap(),
+                "À 09:30, du lundi au vendredi",
+            ),
+            (
+                task()
+                    .minutes(0)
+                    .hour(0)
+                    .month_day(1)
+                    .month(range(1..3))
+                    .build()
+                    .unwrap(),
+                "À 00:00, le 1 du mois, de janvier à mars",
+            ),
+            (
+                task()
+                    .minutes(0)
+                    .hour(8)
+                    .week_day(nth(Weekday::Mon, 2))
+                    .build()
+                    .unwrap(),
+                "À 08:00, le deuxième lundi",
+            ),
+        ];
+
+        for (task, expected) in cases {
+            assert_eq!(task.describe_in(Language::French), expected);
+        }
+    }
+}
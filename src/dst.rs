@@ -0,0 +1,236 @@
+use std::{fmt::Display, str::FromStr};
+
+use chrono::{DateTime, LocalResult, NaiveDateTime, TimeDelta, TimeZone, Timelike, Utc};
+
+use crate::error::{CronError, Result};
+
+/// How a task treats wall clock times that daylight saving transitions
+/// repeat or skip.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DstPolicy {
+    pub fold: FoldPolicy,
+    pub gap: GapPolicy,
+}
+
+/// Times repeated when clocks fall back, such as 02:30 in autumn.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FoldPolicy {
+    /// Runs at the first of the two instants, as cron daemons do.
+    #[default]
+    Earliest,
+    Latest,
+    Skip,
+}
+
+/// Times skipped when clocks spring forward, such as 02:30 in spring.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GapPolicy {
+    #[default]
+    Skip,
+    /// Runs once at the end of the gap, 03:00 for a 02:30 task when clocks
+    /// jump from 02:00 to 03:00.
+    ShiftForward,
+}
+
+impl Display for FoldPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            FoldPolicy::Earliest => "earliest",
+            FoldPolicy::Latest => "latest",
+            FoldPolicy::Skip => "skip",
+        })
+    }
+}
+
+impl FromStr for FoldPolicy {
+    type Err = CronError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "earliest" => Ok(FoldPolicy::Earliest),
+            "latest" => Ok(FoldPolicy::Latest),
+            "skip" => Ok(FoldPolicy::Skip),
+            _ => Err(CronError::InvalidExpression(format!(
+                "unknown fold policy `{s}`"
+            ))),
+        }
+    }
+}
+
+impl Display for GapPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            GapPolicy::Skip => "skip",
+            GapPolicy::ShiftForward => "shift-forward",
+        })
+    }
+}
+
+impl FromStr for GapPolicy {
+    type Err = CronError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "skip" => Ok(GapPolicy::Skip),
+            "shift-forward" => Ok(GapPolicy::ShiftForward),
+            _ => Err(CronError::InvalidExpression(format!(
+                "unknown gap policy `{s}`"
+            ))),
+        }
+    }
+}
+
+/// Transitions longer than this, two days, are not searched for.
+const MAX_GAP_MINUTES: usize = 2 * 24 * 60;
+
+impl DstPolicy {
+    /// Instant at which the wall clock reading `local` runs in `tz`, `None`
+    /// when the policy skips it.
+    pub(crate) fn resolve<Tz: TimeZone>(
+        &self,
+        tz: &Tz,
+        local: &NaiveDateTime,
+    ) -> Option<DateTime<Tz>> {
+        match tz.from_local_datetime(local) {
+            LocalResult::Single(date) => Some(date),
+            LocalResult::Ambiguous(earliest, latest) => match self.fold {
+                FoldPolicy::Earliest => Some(earliest),
+                FoldPolicy::Latest => Some(latest),
+                FoldPolicy::Skip => None,
+            },
+            LocalResult::None => match self.gap {
+                GapPolicy::Skip => None,
+                GapPolicy::ShiftForward => gap_end(tz, local),
+            },
+        }
+    }
+
+    /// Whether `date` is the instant its wall clock reading runs at: false
+    /// for one of the two readings of a fold.
+    pub(crate) fn keeps<Tz: TimeZone>(&self, date: &DateTime<Tz>) -> bool {
+        self.resolve(&date.timezone(), &date.naive_local())
+            .is_some_and(|kept| kept == *date)
+    }
+
+    /// Wall clock readings, one every `step`, of the gap ending at the slot
+    /// of `date` when the policy shifts them to it. Readings are given as
+    /// UTC dates so that their fields can be matched.
+    pub(crate) fn shifted_to<Tz: TimeZone>(
+        &self,
+        date: &DateTime<Tz>,
+        step: TimeDelta,
+    ) -> Vec<DateTime<Utc>> {
+        let tz = date.timezone();
+        let mut slot = date.naive_local().with_nanosecond(0).unwrap_or_default();
+        if step >= TimeDelta::minutes(1) {
+            slot = slot.with_second(0).unwrap_or(slot);
+        }
+
+        let mut shifted = Vec::new();
+        if self.gap == GapPolicy::ShiftForward {
+            let skipped =
+                |local: &NaiveDateTime| tz.from_local_datetime(local).earliest().is_none();
+            let mut local = slot;
+            while let Some(previous) = local.checked_sub_signed(step).filter(skipped) {
+                shifted.push(Utc.from_utc_datetime(&previous));
+                local = previous;
+            }
+        }
+
+        shifted
+    }
+}
+
+/// First instant after the gap `local` falls in.
+fn gap_end<Tz: TimeZone>(tz: &Tz, local: &NaiveDateTime) -> Option<DateTime<Tz>> {
+    let mut minute = local.with_nanosecond(0)?.with_second(0)?;
+
+    (0..MAX_GAP_MINUTES).find_map(|_| {
+        minute = minute.checked_add_signed(TimeDelta::minutes(1))?;
+        tz.from_local_datetime(&minute).earliest()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use chrono_tz::Europe::Paris;
+
+    use super::*;
+
+    fn local(month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, month, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    fn utc(month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.from_utc_datetime(&local(month, day, hour, minute))
+    }
+
+    #[test]
+    fn test_resolve_fold() {
+        // Paris falls back from 03:00 to 02:00 on October 26th.
+        let fold = local(10, 26, 2, 30);
+        let resolve = |fold_policy| {
+            DstPolicy {
+                fold: fold_policy,
+                ..Default::default()
+            }
+            .resolve(&Paris, &fold)
+            .map(|date| date.with_timezone(&Utc))
+        };
+
+        assert_eq!(resolve(FoldPolicy::Earliest), Some(utc(10, 26, 0, 30)));
+        assert_eq!(resolve(FoldPolicy::Latest), Some(utc(10, 26, 1, 30)));
+        assert_eq!(resolve(FoldPolicy::Skip), None);
+    }
+
+    #[test]
+    fn test_resolve_gap() {
+        // Paris springs forward from 02:00 to 03:00 on March 30th.
+        let gap = local(3, 30, 2, 30);
+        let shift = DstPolicy {
+            gap: GapPolicy::ShiftForward,
+            ..Default::default()
+        };
+
+        assert_eq!(DstPolicy::default().resolve(&Paris, &gap), None);
+        assert_eq!(
+            shift
+                .resolve(&Paris, &gap)
+                .map(|date| date.with_timezone(&Utc)),
+            Some(utc(3, 30, 1, 0))
+        );
+    }
+
+    #[test]
+    fn test_keeps_and_shifted_to() {
+        let latest = Paris.from_utc_datetime(&local(10, 26, 1, 30));
+        assert!(!DstPolicy::default().keeps(&latest));
+
+        let shift = DstPolicy {
+            gap: GapPolicy::ShiftForward,
+            ..Default::default()
+        };
+        let gap_end = Paris.from_utc_datetime(&local(3, 30, 1, 0));
+        let shifted = shift.shifted_to(&gap_end, TimeDelta::minutes(1));
+
+        assert_eq!(shifted.len(), 60);
+        assert_eq!(shifted[0], utc(3, 30, 2, 59));
+        assert!(
+            DstPolicy::default()
+                .shifted_to(&gap_end, TimeDelta::minutes(1))
+                .is_empty()
+        );
+        assert!(shift.shifted_to(&latest, TimeDelta::minutes(1)).is_empty());
+    }
+
+    #[test]
+    fn test_policy_names() {
+        assert_eq!("latest".parse::<FoldPolicy>().unwrap(), FoldPolicy::Latest);
+        assert_eq!(GapPolicy::ShiftForward.to_string(), "shift-forward");
+        assert!("never".parse::<GapPolicy>().is_err());
+    }
+}
@@ -0,0 +1,267 @@
+use crate::{
+    error::{CronError, Result},
+    task::CronTask,
+    value::{CronValue, every, range},
+};
+
+const WEEK_DAYS: [&str; 7] = [
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+/// What a phrase asked for, before it is turned into fields.
+#[derive(Default)]
+struct Phrase {
+    minutes: Option<u8>,
+    hours: Option<u8>,
+    times: Vec<(u8, u8)>,
+    week_days: Vec<CronValue>,
+    months: Vec<u8>,
+}
+
+impl CronTask {
+    /// Parses a schedule written in plain English into a task running
+    /// `path`, such as `every weekday at 9:30am`, `every 15 minutes`,
+    /// `on monday and friday at 18:00` or `daily at noon in june`. Phrases
+    /// naming days but no time run at midnight.
+    pub fn from_english(phrase: &str, path: impl Into<String>) -> Result<CronTask> {
+        let phrase = Phrase::parse(phrase)?;
+        let builder = CronTask::builder()
+            .path(path.into())
+            .week_day(list(phrase.week_days))
+            .month(list(
+                phrase.months.into_iter().map(CronValue::from).collect(),
+            ));
+
+        let builder = match (phrase.minutes, phrase.hours, &phrase.times[..]) {
+            (Some(_), Some(_), _) | (Some(_), _, [_, ..]) | (_, Some(_), [_, ..]) => {
+                return Err(unrecognized("intervals and times together"));
+            }
+            (Some(step), None, []) => builder.minutes(interval(step)),
+            (None, Some(step), []) => builder.minutes(0).hour(interval(step)),
+            (None, None, []) => builder.minutes(0).hour(0),
+            (None, None, [(_, minute), ..]) => {
+                if phrase.times.iter().any(|(_, m)| m != minute) {
+                    return Err(CronError::InvalidExpression(
+                        "times must share their minutes".to_string(),
+                    ));
+                }
+                let hours = phrase.times.iter().map(|(h, _)| CronValue::from(*h));
+
+                builder.minutes(*minute).hour(list(hours.collect()))
+            }
+        };
+
+        builder.build()
+    }
+}
+
+impl Phrase {
+    fn parse(s: &str) -> Result<Phrase> {
+        let s = s.to_lowercase().replace(',', " ");
+        let words: Vec<&str> = s.split_whitespace().collect();
+        if words.is_empty() {
+            return Err(CronError::InvalidExpression("empty schedule".to_string()));
+        }
+
+        let mut phrase = Phrase::default();
+        let mut i = 0;
+        while i < words.len() {
+            let word = words[i];
+            i += 1;
+
+            match word {
+                "on" | "in" | "and" | "the" | "day" | "daily" => {}
+                "every" | "each" => {
+                    let step = match words.get(i).and_then(|w| w.parse::<u8>().ok()) {
+                        Some(step) => {
+                            i += 1;
+                            step
+                        }
+                        None => 1,
+                    };
+                    match words.get(i) {
+                        Some(&("minute" | "minutes")) => phrase.minutes = Some(step_in(step, 60)?),
+                        Some(&("hour" | "hours")) => phrase.hours = Some(step_in(step, 24)?),
+                        Some(_) if step == 1 => continue,
+                        _ => return Err(unrecognized(word)),
+                    }
+                    i += 1;
+                }
+                "hourly" => phrase.hours = Some(1),
+                "weekday" | "weekdays" => phrase.week_days.push(range(0..4)),
+                "weekend" | "weekends" => phrase.week_days.push(range(5..6)),
+                "at" => loop {
+                    let (time, used) = time(&words[i..])
+                        .ok_or_else(|| unrecognized(words.get(i).copied().unwrap_or("at")))?;
+                    phrase.times.push(time);
+                    i += used;
+                    if words.get(i) != Some(&"and") || time_at(&words, i + 1).is_none() {
+                        break;
+                    }
+                    i += 1;
+                },
+                word => {
+                    if let Some(day) = find(&WEEK_DAYS, word.trim_end_matches('s')) {
+                        phrase.week_days.push(day.into());
+                    } else if let Some(month) = find(&MONTHS, word) {
+                        phrase.months.push(month + 1);
+                    } else {
+                        return Err(unrecognized(word));
+                    }
+                }
+            }
+        }
+
+        Ok(phrase)
+    }
+}
+
+fn time_at(words: &[&str], i: usize) -> Option<((u8, u8), usize)> {
+    time(words.get(i..)?)
+}
+
+/// Reads a time such as `9:30am`, `9 pm`, `17:00`, `noon` or `midnight`
+/// from the start of `words`, along with the number of words it took.
+fn time(words: &[&str]) -> Option<((u8, u8), usize)> {
+    let word = *words.first()?;
+    match word {
+        "noon" => return Some(((12, 0), 1)),
+        "midnight" => return Some(((0, 0), 1)),
+        _ => {}
+    }
+
+    let (clock, meridiem, used) = match (word.strip_suffix("am"), word.strip_suffix("pm")) {
+        (Some(clock), _) => (clock, Some(false), 1),
+        (_, Some(clock)) => (clock, Some(true), 1),
+        _ => match words.get(1) {
+            Some(&"am") => (word, Some(false), 2),
+            Some(&"pm") => (word, Some(true), 2),
+            _ => (word, None, 1),
+        },
+    };
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) if minute.len() == 2 => (hour.parse().ok()?, minute.parse().ok()?),
+        Some(_) => return None,
+        None if meridiem.is_some() => (clock.parse().ok()?, 0),
+        None => return None,
+    };
+    let hour: u8 = match meridiem {
+        Some(pm) if (1..=12).contains(&hour) => hour % 12 + if pm { 12 } else { 0 },
+        Some(_) => return None,
+        None => hour,
+    };
+
+    (hour < 24 && minute < 60).then_some(((hour, minute), used))
+}
+
+/// Index of `word` in `names`, either spelled out or as its first three
+/// letters.
+fn find(names: &[&str], word: &str) -> Option<u8> {
+    names
+        .iter()
+        .position(|name| *name == word || (word.len() == 3 && name.starts_with(word)))
+        .map(|i| i as u8)
+}
+
+fn step_in(step: u8, size: u8) -> Result<u8> {
+    if step == 0 || step >= size {
+        return Err(CronError::InvalidExpression(format!(
+            "invalid interval `{step}`"
+        )));
+    }
+    Ok(step)
+}
+
+fn interval(step: u8) -> CronValue {
+    match step {
+        1 => CronValue::All,
+        step => every(step),
+    }
+}
+
+fn list(mut values: Vec<CronValue>) -> CronValue {
+    match values.len() {
+        0 => CronValue::All,
+        1 => values.remove(0),
+        _ => CronValue::List(values),
+    }
+}
+
+fn unrecognized(word: &str) -> CronError {
+    CronError::InvalidExpression(format!("unrecognized `{word}`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(phrase: &str) -> String {
+        CronTask::from_english(phrase, "/bin/true")
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_from_english() {
+        assert_eq!(
+            schedule("every weekday at 9:30am"),
+            "30 9 * * 0-4 \"/bin/true\""
+        );
+        assert_eq!(schedule("every 15 minutes"), "*/15 * * * * \"/bin/true\"");
+        assert_eq!(schedule("Every minute"), "* * * * * \"/bin/true\"");
+        assert_eq!(schedule("every 2 hours"), "0 */2 * * * \"/bin/true\"");
+        assert_eq!(
+            schedule("on Monday and Friday at 6 pm"),
+            "0 18 * * 0,4 \"/bin/true\""
+        );
+        assert_eq!(
+            schedule("every day at 8:00 and 20:00"),
+            "0 8,20 * * * \"/bin/true\""
+        );
+        assert_eq!(
+            schedule("at noon on sundays in June, December"),
+            "0 12 * 6,12 6 \"/bin/true\""
+        );
+        assert_eq!(schedule("every weekend"), "0 0 * * 5-6 \"/bin/true\"");
+        assert_eq!(schedule("at 12am"), "0 0 * * * \"/bin/true\"");
+        assert_eq!(schedule("hourly"), "0 * * * * \"/bin/true\"");
+    }
+
+    #[test]
+    fn test_from_english_errors() {
+        let error = |phrase: &str| {
+            CronTask::from_english(phrase, "/bin/true")
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert!(error("every fortnight").contains("`fortnight`"));
+        assert!(error("at 25:00").contains("`25:00`"));
+        assert!(error("at 13pm").contains("`13pm`"));
+        assert!(error("every 15 minutes at 9:00").contains("intervals and times"));
+        assert!(error("at 9:00 and 17:30").contains("share their minutes"));
+        assert!(error("every 90 minutes").contains("`90`"));
+        assert!(error("").contains("empty"));
+    }
+}
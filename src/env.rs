@@ -0,0 +1,180 @@
+use crate::{
+    error::{CronError, Result},
+    parser::parse_bare_schedule,
+    task::CronTask,
+    zone::Zone,
+};
+
+impl CronTask {
+    /// Builds a task from `{prefix}_SCHEDULE` and `{prefix}_CMD`, plus the
+    /// optional `{prefix}_TZ`, `{prefix}_GROUP`, `{prefix}_SHARD_KEY` and
+    /// `{prefix}_SHARD_TOTAL` variables. The schedule may start with a
+    /// seconds field, and the time zone is read as a `CRON_TZ` value.
+    pub fn from_env(prefix: &str) -> Result<CronTask> {
+        Self::from_lookup(prefix, |key| std::env::var(key).ok())
+    }
+
+    fn from_lookup<F>(prefix: &str, lookup: F) -> Result<CronTask>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let var = |name: &str| lookup(&format!("{prefix}_{name}"));
+        let required = |name: &str| {
+            var(name).ok_or_else(|| CronError::MissingEnvVar(format!("{prefix}_{name}")))
+        };
+
+        let mut builder = parse_bare_schedule(&required("SCHEDULE")?)?.path(required("CMD")?);
+
+        if let Some(tz) = var("TZ") {
+            let zone = Zone::parse(&tz)
+                .ok_or_else(|| CronError::InvalidExpression(format!("unknown time zone `{tz}`")))?;
+            builder = zone.apply(builder);
+        }
+
+        if let Some(group) = var("GROUP") {
+            builder = builder.group(group);
+        }
+
+        if let Some(key) = var("SHARD_KEY") {
+            let total = required("SHARD_TOTAL")?
+                .parse()
+                .map_err(|_| CronError::InvalidShard)?;
+            builder = builder.shard(key, total);
+        }
+
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn lookup(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+
+        move |key| vars.get(key).cloned()
+    }
+
+    #[test]
+    fn test_from_lookup() {
+        let task = CronTask::from_lookup(
+            "BACKUP",
+            lookup(&[
+                ("BACKUP_SCHEDULE", "*/5 9-17 * * 1-5"),
+                ("BACKUP_CMD", "/usr/bin/backup"),
+                ("BACKUP_GROUP", "maintenance"),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(task.to_string(), "*/5 9-17 * * 0-4 \"/usr/bin/backup\"");
+        assert_eq!(task.group(), Some("maintenance"));
+        assert!(task.shard().is_none());
+    }
+
+    #[test]
+    fn test_from_lookup_with_shard() {
+        let task = CronTask::from_lookup(
+            "JOB",
+            lookup(&[
+                ("JOB_SCHEDULE", "0 0 31 12 0"),
+                ("JOB_CMD", "/usr/bin/report"),
+                ("JOB_SHARD_KEY", "reports"),
+                ("JOB_SHARD_TOTAL", "4"),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(task.shard().unwrap().total(), 4);
+    }
+
+    #[test]
+    fn test_from_lookup_with_seconds_and_offset() {
+        let task = CronTask::from_lookup(
+            "POLL",
+            lookup(&[
+                ("POLL_SCHEDULE", "*/15 * * * * *"),
+                ("POLL_CMD", "/usr/bin/poll"),
+                ("POLL_TZ", "UTC-05:30"),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            task.second().map(|s| s.to_string()).as_deref(),
+            Some("*/15")
+        );
+        assert_eq!(
+            task.offset(),
+            chrono::FixedOffset::east_opt(5 * 3600 + 30 * 60)
+        );
+    }
+
+    #[cfg(feature = "chrono-tz")]
+    #[test]
+    fn test_from_lookup_with_timezone() {
+        let task = CronTask::from_lookup(
+            "JOB",
+            lookup(&[
+                ("JOB_SCHEDULE", "0 3 * * *"),
+                ("JOB_CMD", "/usr/bin/report"),
+                ("JOB_TZ", "Europe/Paris"),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(task.timezone(), Some(chrono_tz::Europe::Paris));
+    }
+
+    #[test]
+    fn test_from_lookup_invalid_timezone() {
+        let err = CronTask::from_lookup(
+            "JOB",
+            lookup(&[
+                ("JOB_SCHEDULE", "0 3 * * *"),
+                ("JOB_CMD", "/usr/bin/report"),
+                ("JOB_TZ", "Nowhere/Town"),
+            ]),
+        );
+
+        assert!(matches!(err, Err(CronError::InvalidExpression(_))));
+    }
+
+    #[test]
+    fn test_from_lookup_missing_variables() {
+        let err = CronTask::from_lookup("JOB", lookup(&[("JOB_CMD", "/usr/bin/report")]));
+        assert!(matches!(err, Err(CronError::MissingEnvVar(v)) if v == "JOB_SCHEDULE"));
+
+        let err = CronTask::from_lookup("JOB", lookup(&[("JOB_SCHEDULE", "* * * * *")]));
+        assert!(matches!(err, Err(CronError::MissingEnvVar(v)) if v == "JOB_CMD"));
+
+        let err = CronTask::from_lookup(
+            "JOB",
+            lookup(&[
+                ("JOB_SCHEDULE", "* * * * *"),
+                ("JOB_CMD", "/usr/bin/report"),
+                ("JOB_SHARD_KEY", "reports"),
+            ]),
+        );
+        assert!(matches!(err, Err(CronError::MissingEnvVar(v)) if v == "JOB_SHARD_TOTAL"));
+    }
+
+    #[test]
+    fn test_from_lookup_invalid_schedule() {
+        let err = CronTask::from_lookup(
+            "JOB",
+            lookup(&[
+                ("JOB_SCHEDULE", "61 * * * *"),
+                ("JOB_CMD", "/usr/bin/report"),
+            ]),
+        );
+
+        assert!(matches!(err, Err(CronError::Correctable { .. })));
+    }
+}
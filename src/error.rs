@@ -1,9 +1,75 @@
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, string::String, vec::Vec};
+
 use thiserror::Error;
 
-pub type Result<T> = std::result::Result<T, CronError>;
+pub type Result<T> = core::result::Result<T, CronError>;
 
 #[derive(Debug, Error)]
 pub enum CronError {
     #[error("Invalid cron value.")]
     InvalidCronValue,
+    #[error("Invalid shard, total must be greater than zero.")]
+    InvalidShard,
+    #[error("Command {0:?} spans several lines.")]
+    MultilineCommand(String),
+    #[error("Missing owner.")]
+    MissingOwner,
+    #[error("Missing command.")]
+    MissingCommand,
+    #[error("Invalid cron expression: {0}.")]
+    InvalidExpression(String),
+    /// Invalid expression for which replacing `value` by `suggestion`
+    /// gives a valid one.
+    #[error("Invalid cron expression: {message}, try `{suggestion}` instead of `{value}`.")]
+    Correctable {
+        message: String,
+        value: String,
+        suggestion: String,
+    },
+    #[error("Missing environment variable {0}.")]
+    MissingEnvVar(String),
+    #[error("Invalid environment variable `{0}`.")]
+    InvalidEnvVar(String),
+    #[error("Invalid user `{0}`.")]
+    InvalidUser(String),
+    #[error("Unknown user or group `{0}`.")]
+    UnknownUser(String),
+    #[error("Missing value for placeholder `{0}`.")]
+    MissingPlaceholder(String),
+    #[error("Check `{0}` failed: {1}.")]
+    CheckFailed(String, String),
+    #[error("Line {line}: {source}")]
+    Line { line: usize, source: Box<CronError> },
+    #[error("Task `{name}`: {source}")]
+    Task {
+        name: String,
+        source: Box<CronError>,
+    },
+    #[error("Invalid config: {0}.")]
+    InvalidConfig(String),
+    #[error("Invalid run id `{0}`.")]
+    InvalidRunId(String),
+    #[error("Invalid output pattern: {0}.")]
+    InvalidPattern(String),
+    #[error("Invalid task pair: {0}.")]
+    InvalidPair(String),
+    #[error("State store failed: {0}.")]
+    StateStore(String),
+    #[error("Cannot convert {}.", .0.join(", "))]
+    Unconvertible(Vec<String>),
+    #[cfg(feature = "std")]
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+impl CronError {
+    /// Suggested replacement for the faulty value, when one is known.
+    pub fn suggestion(&self) -> Option<&str> {
+        match self {
+            CronError::Correctable { suggestion, .. } => Some(suggestion),
+            CronError::Line { source, .. } | CronError::Task { source, .. } => source.suggestion(),
+            _ => None,
+        }
+    }
 }
@@ -0,0 +1,144 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use chrono::{DateTime, Local};
+
+use crate::{run_id::RunId, skip::SkipReason, task::CronTask};
+
+/// Events kept in the history of an [`EventLog`], older ones being dropped.
+pub const HISTORY_LEN: usize = 1024;
+
+/// What a scheduler reports besides the runs it dispatches.
+#[derive(Debug, Clone)]
+pub enum SchedulerEvent {
+    /// A run the schedule called for did not happen.
+    Skipped {
+        task: CronTask,
+        date: DateTime<Local>,
+        reason: SkipReason,
+    },
+    /// The run `run_id` used up the rest of the task's budget for the
+    /// month of `date`, its next runs that month being skipped.
+    BudgetExhausted {
+        task: CronTask,
+        date: DateTime<Local>,
+        run_id: RunId,
+    },
+    /// The state store failed, the scheduler going on without catching up
+    /// or saving what it does until the store answers again.
+    Degraded { error: String },
+    /// The state store answers again after failing.
+    Recovered,
+}
+
+type Subscriber = Arc<dyn Fn(&SchedulerEvent) + Send + Sync>;
+
+/// Shared handle on the events of a scheduler: the latest ones are kept in
+/// a history, and subscribers are called with each as it happens, on the
+/// thread it happens on, that of the scheduler or of a run ending.
+#[derive(Clone, Default)]
+pub struct EventLog(Arc<Mutex<Log>>);
+
+#[derive(Default)]
+struct Log {
+    history: VecDeque<SchedulerEvent>,
+    subscribers: Vec<Subscriber>,
+}
+
+impl EventLog {
+    /// Calls `subscriber` with every event from now on.
+    pub fn subscribe(&self, subscriber: impl Fn(&SchedulerEvent) + Send + Sync + 'static) {
+        self.lock().subscribers.push(Arc::new(subscriber));
+    }
+
+    /// Latest events, oldest first, up to [`HISTORY_LEN`] of them.
+    pub fn history(&self) -> Vec<SchedulerEvent> {
+        self.lock().history.iter().cloned().collect()
+    }
+
+    /// Skipped runs in the history, oldest first.
+    pub fn skipped(&self) -> Vec<(CronTask, DateTime<Local>, SkipReason)> {
+        self.lock()
+            .history
+            .iter()
+            .filter_map(|event| match event {
+                SchedulerEvent::Skipped { task, date, reason } => {
+                    Some((task.clone(), *date, reason.clone()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub(crate) fn skip(&self, task: &CronTask, date: DateTime<Local>, reason: SkipReason) {
+        self.emit(SchedulerEvent::Skipped {
+            task: task.clone(),
+            date,
+            reason,
+        });
+    }
+
+    pub(crate) fn emit(&self, event: SchedulerEvent) {
+        let subscribers = {
+            let mut log = self.lock();
+            if log.history.len() == HISTORY_LEN {
+                log.history.pop_front();
+            }
+            log.history.push_back(event.clone());
+            log.subscribers.clone()
+        };
+        // Called unlocked, a subscriber may read the history.
+        for subscriber in subscribers {
+            subscriber(&event);
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Log> {
+        // The log is always left whole, a poisoned lock is still usable.
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl std::fmt::Debug for EventLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let log = self.lock();
+        f.debug_struct("EventLog")
+            .field("history", &log.history)
+            .field("subscribers", &log.subscribers.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn test_event_log() {
+        let log = EventLog::default();
+        let task = CronTask::builder().allow_commandless().build().unwrap();
+        let date = Local.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        let seen = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&seen);
+        let reader = log.clone();
+        log.subscribe(move |_| {
+            counter.fetch_add(1, Ordering::Relaxed);
+            // Subscribers may read the log they are called from.
+            assert!(!reader.history().is_empty());
+        });
+
+        for _ in 0..=HISTORY_LEN {
+            log.skip(&task, date, SkipReason::Paused);
+        }
+
+        assert_eq!(seen.load(Ordering::Relaxed), HISTORY_LEN + 1);
+        assert_eq!(log.history().len(), HISTORY_LEN);
+        assert_eq!(log.skipped()[0].2, SkipReason::Paused);
+    }
+}
@@ -0,0 +1,743 @@
+use std::{
+    collections::BTreeMap,
+    io::{self, ErrorKind, Read, Write},
+    process::{Child, Command, ExitStatus, Output, Stdio},
+    sync::{Arc, Mutex, MutexGuard},
+    thread,
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "group")]
+use crate::group::GroupPolicy;
+use crate::{
+    check::shell,
+    error::{CronError, Result},
+    run_id::RunId,
+    task::CronTask,
+};
+
+/// How often a command with a timeout is checked on.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// How long the output of a killed command is still read for, in case a
+/// process that left its group keeps the pipes open.
+const KILLED_OUTPUT_GRACE: Duration = Duration::from_millis(500);
+
+/// Outcome of running a task's command.
+#[derive(Debug, Clone)]
+pub enum RunResult {
+    /// The command ran to its end.
+    Finished(RunOutput),
+    /// The command ran past the task's timeout and was killed, the output
+    /// being what it printed until then.
+    TimedOut(RunOutput),
+}
+
+#[derive(Debug, Clone)]
+pub struct RunOutput {
+    /// Run the command belongs to, shared by its retried attempts.
+    pub run_id: RunId,
+    /// Process ID the command ran as, which led a process group of its
+    /// own on Unix.
+    pub pid: u32,
+    pub status: ExitStatus,
+    pub duration: Duration,
+    pub stdout: String,
+    pub stderr: String,
+    /// Whether the run succeeded by the task's criteria, see
+    /// [`CronTask::is_success`]. Never the case for a killed run.
+    pub success: bool,
+    /// Failed attempts made before this run under the task's retry
+    /// policy, oldest first.
+    pub retries: Vec<Attempt>,
+}
+
+/// Failed attempt that was retried, see [`RunOutput::retries`].
+#[derive(Debug, Clone)]
+pub enum Attempt {
+    Failed(RunResult),
+    /// The command could not be started.
+    Error(String),
+}
+
+impl RunResult {
+    pub fn output(&self) -> &RunOutput {
+        match self {
+            RunResult::Finished(output) | RunResult::TimedOut(output) => output,
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.output().success
+    }
+
+    fn with_retries(mut self, retries: Vec<Attempt>) -> Self {
+        match &mut self {
+            RunResult::Finished(output) | RunResult::TimedOut(output) => output.retries = retries,
+        }
+        self
+    }
+}
+
+/// Commands running under [`CronTask::execute_with`], whose PIDs and runs
+/// can be listed and which can be signalled on shutdown. Clones share the
+/// same commands.
+///
+/// ```ignore
+/// let children = Children::new();
+/// let scheduler = ThreadScheduler::run(cron, {
+///     let children = children.clone();
+///     move |task, _, permit| {
+///         let (task, children) = (task.clone(), children.clone());
+///         std::thread::spawn(move || {
+///             let _ = task.execute_run(&children, permit.run_id());
+///             drop(permit);
+///         });
+///     }
+/// });
+/// // On SIGTERM:
+/// scheduler.stop();
+/// children.shutdown(Duration::from_secs(10));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Children {
+    pids: Arc<Mutex<BTreeMap<u32, RunId>>>,
+}
+
+impl Children {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// PIDs of the running commands, in increasing order.
+    pub fn pids(&self) -> Vec<u32> {
+        self.lock().keys().copied().collect()
+    }
+
+    /// PIDs of the running commands with the run each belongs to, in
+    /// increasing order of PID.
+    pub fn runs(&self) -> Vec<(u32, RunId)> {
+        self.lock()
+            .iter()
+            .map(|(pid, run_id)| (*pid, *run_id))
+            .collect()
+    }
+
+    /// Sends SIGTERM to each running command and the processes it started.
+    /// Returns how many commands were signalled.
+    #[cfg(unix)]
+    pub fn terminate(&self) -> usize {
+        self.signal(libc::SIGTERM)
+    }
+
+    /// Terminates the running commands and waits up to `grace` for them to
+    /// end, then kills those still running. Their runs end as they would
+    /// have on their own, the commands being waited for there.
+    #[cfg(unix)]
+    pub fn shutdown(&self, grace: Duration) {
+        let deadline = Instant::now() + grace;
+        self.terminate();
+        while !self.lock().is_empty() && Instant::now() < deadline {
+            thread::sleep(POLL_INTERVAL);
+        }
+        self.signal(libc::SIGKILL);
+    }
+
+    /// Commands are only taken off the list before they are reaped, so a
+    /// PID signalled here is never one reused since.
+    #[cfg(unix)]
+    fn signal(&self, signal: libc::c_int) -> usize {
+        let pids = self.lock();
+        for pid in pids.keys() {
+            if let Ok(group) = libc::pid_t::try_from(*pid) {
+                // SAFETY: killpg has no memory safety requirements, the
+                // group was created for the command which is not waited for
+                // yet.
+                unsafe {
+                    libc::killpg(group, signal);
+                }
+            }
+        }
+        pids.len()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<u32, RunId>> {
+        // The map is always left whole, a poisoned lock is still usable.
+        self.pids.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Status of `child` if it ended, taking it off the list then.
+    fn try_wait(&self, child: &mut Child) -> io::Result<Option<ExitStatus>> {
+        let mut pids = self.lock();
+        let status = child.try_wait()?;
+        if status.is_some() {
+            pids.remove(&child.id());
+        }
+        Ok(status)
+    }
+
+    /// Polls `child` until it ends. The list is only locked while polling,
+    /// so a killed command slow to die does not hold up the others.
+    fn wait(&self, child: &mut Child) -> io::Result<ExitStatus> {
+        loop {
+            if let Some(status) = self.try_wait(child)? {
+                return Ok(status);
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    /// Kills `child`, which could not be polled, and takes it off the list
+    /// before reaping it: until then its PID cannot be reused.
+    fn abandon(&self, child: &mut Child) {
+        kill(child);
+        self.lock().remove(&child.id());
+        let _ = child.wait();
+    }
+}
+
+impl CronTask {
+    /// Runs the task's program with its arguments, or its path through the
+    /// shell when it has none, in the task's environment and directory, and
+    /// waits for it to end, capturing its output. The command runs as the
+    /// task's account, which needs the `run-as` feature. Only a missing
+    /// command or failing to start it is an error, a failed run is reported
+    /// in the result.
+    ///
+    /// A command running longer than the task's timeout is killed, along
+    /// with the processes it started on Unix. Failed runs are retried as
+    /// the task's retry policy says, this call sleeping in between, and the
+    /// last run is returned. When no attempt could start, the last error
+    /// is. A run that failed or could not start is then reported to the
+    /// notifier of the task's group, if any.
+    ///
+    /// The call blocks until the last attempt ends, so a scheduler hands it
+    /// to a thread of its own along with the run's permit:
+    ///
+    /// ```ignore
+    /// ThreadScheduler::run(cron, |task, _, permit| {
+    ///     let task = task.clone();
+    ///     std::thread::spawn(move || {
+    ///         match task.execute() {
+    ///             Ok(run) if !run.is_success() => eprintln!("{}", run.output().stderr),
+    ///             _ => {}
+    ///         }
+    ///         drop(permit);
+    ///     });
+    /// });
+    /// ```
+    pub fn execute(&self) -> Result<RunResult> {
+        self.execute_with(&Children::default())
+    }
+
+    /// Same as `execute`, listing the command in `children` while it runs.
+    pub fn execute_with(&self, children: &Children) -> Result<RunResult> {
+        self.execute_run(children, RunId::new())
+    }
+
+    /// Same as `execute_with`, running as `run_id` rather than a new run,
+    /// such as the one of a scheduler's [`crate::scheduler::RunPermit`].
+    pub fn execute_run(&self, children: &Children, run_id: RunId) -> Result<RunResult> {
+        let result = self.execute_retried(children, run_id);
+        #[cfg(feature = "group")]
+        if !result.as_ref().is_ok_and(RunResult::is_success)
+            && let Some(notifier) = self.group_policy().and_then(GroupPolicy::notifier)
+        {
+            notifier(self, &result);
+        }
+
+        result
+    }
+
+    fn execute_retried(&self, children: &Children, run_id: RunId) -> Result<RunResult> {
+        if self.path().as_os_str().is_empty() {
+            return Err(CronError::MissingCommand);
+        }
+        let Some(policy) = self.retry() else {
+            return self.execute_once(children, run_id);
+        };
+
+        let mut retries = Vec::new();
+        let mut attempt = 1;
+        loop {
+            let last = attempt >= policy.max_attempts;
+            match self.execute_once(children, run_id) {
+                Ok(run) if run.is_success() || last => return Ok(run.with_retries(retries)),
+                Err(error) if last => return Err(error),
+                Ok(run) => retries.push(Attempt::Failed(run)),
+                Err(error) => retries.push(Attempt::Error(error.to_string())),
+            }
+
+            thread::sleep(policy.delay(attempt));
+            attempt += 1;
+        }
+    }
+
+    fn execute_once(&self, children: &Children, run_id: RunId) -> Result<RunResult> {
+        let mut command = if self.args().is_empty() {
+            shell(&self.path().display().to_string())
+        } else {
+            let mut command = Command::new(self.path());
+            command.args(self.args());
+            command
+        };
+        command.envs(self.env());
+        if let Some(dir) = self.current_dir() {
+            command.current_dir(dir);
+        }
+        if let Some(run_as) = self.run_as() {
+            run_as.apply(&mut command)?;
+        }
+
+        let start = Instant::now();
+        let (pid, output, timed_out) =
+            output_within(&mut command, self.timeout(), self.stdin(), children, run_id)?;
+        let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+        let output = RunOutput {
+            run_id,
+            pid,
+            status: output.status,
+            duration: start.elapsed(),
+            success: !timed_out && self.is_success(output.status.success(), &stdout),
+            stdout,
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            retries: Vec::new(),
+        };
+
+        Ok(if timed_out {
+            RunResult::TimedOut(output)
+        } else {
+            RunResult::Finished(output)
+        })
+    }
+}
+
+/// Same as `Command::output`, writing `stdin` to the command and killing
+/// it once `timeout` is over, the command being listed in `children` under
+/// `run_id` until it is waited for. Also tells its PID and whether it was
+/// killed.
+fn output_within(
+    command: &mut Command,
+    timeout: Option<Duration>,
+    stdin: Option<&str>,
+    children: &Children,
+    run_id: RunId,
+) -> Result<(u32, Output, bool)> {
+    // In a group of its own, the command can be killed with its children.
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(command, 0);
+
+    let mut child = command
+        .stdin(match stdin {
+            Some(_) => Stdio::piped(),
+            None => Stdio::null(),
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    children.lock().insert(child.id(), run_id);
+    if let (Some(mut pipe), Some(stdin)) = (child.stdin.take(), stdin) {
+        let stdin = stdin.to_string();
+        // Written on another thread, a command may read its input only
+        // after filling its output pipes. The pipe closes once written.
+        thread::spawn(move || pipe.write_all(stdin.as_bytes()));
+    }
+    let stdout = Drain::new(child.stdout.take());
+    let stderr = Drain::new(child.stderr.take());
+
+    // Polled rather than waited for, so the command is taken off the list
+    // of children as it is reaped.
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    let polled = loop {
+        match children.try_wait(&mut child) {
+            Ok(Some(status)) => break Ok((status, false)),
+            Ok(None) => {}
+            Err(error) => break Err(error),
+        }
+        let now = Instant::now();
+        if deadline.is_some_and(|deadline| now >= deadline) {
+            kill(&mut child);
+            break children.wait(&mut child).map(|status| (status, true));
+        }
+        let wait = deadline.map_or(POLL_INTERVAL, |deadline| deadline - now);
+        thread::sleep(wait.min(POLL_INTERVAL));
+    };
+    let (status, timed_out) = match polled {
+        Ok(polled) => polled,
+        Err(error) => {
+            children.abandon(&mut child);
+            return Err(error.into());
+        }
+    };
+
+    let until = timed_out.then(|| Instant::now() + KILLED_OUTPUT_GRACE);
+    let output = Output {
+        status,
+        stdout: stdout.collect(until),
+        stderr: stderr.collect(until),
+    };
+    Ok((child.id(), output, timed_out))
+}
+
+/// A pipe drained on another thread, so a command filling one pipe does not
+/// block while the other is read.
+struct Drain {
+    bytes: Arc<Mutex<Vec<u8>>>,
+    thread: thread::JoinHandle<()>,
+}
+
+impl Drain {
+    fn new(pipe: Option<impl Read + Send + 'static>) -> Self {
+        let bytes = Arc::new(Mutex::new(Vec::new()));
+        let read = Arc::clone(&bytes);
+        let thread = thread::spawn(move || {
+            let Some(mut pipe) = pipe else {
+                return;
+            };
+            let mut buffer = [0; 8192];
+            loop {
+                match pipe.read(&mut buffer) {
+                    Ok(0) => return,
+                    Ok(n) => lock(&read).extend_from_slice(&buffer[..n]),
+                    Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                    Err(_) => return,
+                }
+            }
+        });
+
+        Self { bytes, thread }
+    }
+
+    /// What was read until the pipe closed, or until `until` when given,
+    /// the reading thread being left to end on its own then.
+    fn collect(self, until: Option<Instant>) -> Vec<u8> {
+        match until {
+            Some(until) => {
+                while !self.thread.is_finished() && Instant::now() < until {
+                    thread::sleep(POLL_INTERVAL);
+                }
+            }
+            None => {
+                let _ = self.thread.join();
+            }
+        }
+
+        std::mem::take(&mut *lock(&self.bytes))
+    }
+}
+
+fn lock(bytes: &Mutex<Vec<u8>>) -> MutexGuard<'_, Vec<u8>> {
+    // Bytes are only ever appended, a poisoned lock is still usable.
+    bytes.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Kills the command along with the processes it started on Unix, which
+/// share its process group.
+fn kill(child: &mut Child) {
+    #[cfg(unix)]
+    if let Ok(group) = libc::pid_t::try_from(child.id()) {
+        // SAFETY: killpg has no memory safety requirements, the group was
+        // created for the command and lives until it is waited for.
+        unsafe {
+            libc::killpg(group, libc::SIGKILL);
+        }
+    }
+    let _ = child.kill();
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::os::unix::process::CommandExt;
+
+    use super::*;
+    use crate::success::OutputMatch;
+
+    #[test]
+    fn test_execute() {
+        let task = CronTask::builder()
+            .path("echo synced; echo warning >&2".to_string())
+            .build()
+            .unwrap();
+        let run = task.execute().unwrap();
+        let output = run.output();
+
+        assert!(matches!(run, RunResult::Finished(_)));
+        assert!(run.is_success());
+        assert!(output.status.success());
+        assert_eq!(output.stdout, "synced\n");
+        assert_eq!(output.stderr, "warning\n");
+        // Each run gets an id of its own.
+        assert_ne!(task.execute().unwrap().output().run_id, output.run_id);
+    }
+
+    #[test]
+    fn test_execute_failures() {
+        let failing = CronTask::builder()
+            .path("exit 3".to_string())
+            .build()
+            .unwrap();
+        let run = failing.execute().unwrap();
+        assert!(!run.is_success());
+        assert_eq!(run.output().status.code(), Some(3));
+
+        let unexpected = CronTask::builder()
+            .path("echo nothing".to_string())
+            .success_output(OutputMatch::contains("synced"))
+            .build()
+            .unwrap();
+        assert!(!unexpected.execute().unwrap().is_success());
+
+        let schedule = CronTask::builder().allow_commandless().build().unwrap();
+        assert!(matches!(schedule.execute(), Err(CronError::MissingCommand)));
+    }
+
+    #[test]
+    fn test_execute_args() {
+        let task = CronTask::builder()
+            .program("printf")
+            .args(["%s|", "a b", "$HOME"])
+            .build()
+            .unwrap();
+
+        assert_eq!(task.execute().unwrap().output().stdout, "a b|$HOME|");
+    }
+
+    #[cfg(feature = "run-as")]
+    #[test]
+    fn test_execute_run_as() {
+        use crate::{error::CronError, run_as::RunAs};
+
+        let task = |run_as: RunAs| {
+            CronTask::builder()
+                .path("id -u".to_string())
+                .run_as(run_as)
+                .build()
+                .unwrap()
+        };
+
+        // Switching to our own account needs no privileges.
+        let uid = String::from_utf8(Command::new("id").arg("-u").output().unwrap().stdout).unwrap();
+        assert_eq!(
+            task(RunAs::new(uid.trim()))
+                .execute()
+                .unwrap()
+                .output()
+                .stdout,
+            uid
+        );
+        assert!(matches!(
+            task(RunAs::new("no-such-user")).execute(),
+            Err(CronError::UnknownUser(_))
+        ));
+    }
+
+    #[cfg(not(feature = "run-as"))]
+    #[test]
+    fn test_execute_run_as_unsupported() {
+        let task = CronTask::builder()
+            .path("id -u".to_string())
+            .run_as(crate::run_as::RunAs::new("root"))
+            .build()
+            .unwrap();
+
+        // Running the command as the current user instead would be silent.
+        assert!(matches!(
+            task.execute(),
+            Err(CronError::Io(e)) if e.kind() == std::io::ErrorKind::Unsupported
+        ));
+    }
+
+    #[test]
+    fn test_execute_stdin() {
+        let task = CronTask::builder()
+            .path("tr a-z A-Z".to_string())
+            .stdin("hello\nworld")
+            .build()
+            .unwrap();
+
+        assert_eq!(task.execute().unwrap().output().stdout, "HELLO\nWORLD");
+    }
+
+    #[test]
+    fn test_execute_timeout() {
+        let hung = CronTask::builder()
+            .path("echo started; sleep 5; echo done".to_string())
+            .timeout(Duration::from_millis(200))
+            .build()
+            .unwrap();
+
+        let run = hung.execute().unwrap();
+        assert!(matches!(run, RunResult::TimedOut(_)));
+        assert!(!run.is_success());
+        // The shell's `sleep` is killed too, or reading its output would
+        // wait for it.
+        assert!(run.output().duration < Duration::from_secs(3));
+        assert_eq!(run.output().stdout, "started\n");
+
+        let quick = CronTask::builder()
+            .path("echo done".to_string())
+            .timeout(Duration::from_secs(5))
+            .build()
+            .unwrap();
+        let run = quick.execute().unwrap();
+        assert!(matches!(run, RunResult::Finished(_)));
+        assert_eq!(run.output().stdout, "done\n");
+    }
+
+    #[test]
+    fn test_execute_timeout_kills_children() {
+        let task = CronTask::builder()
+            .program("sh")
+            .args(["-c", "sleep 100 & sleep 100"])
+            .timeout(Duration::from_millis(200))
+            .build()
+            .unwrap();
+
+        let start = Instant::now();
+        let run = task.execute().unwrap();
+        assert!(matches!(run, RunResult::TimedOut(_)));
+        // The background `sleep` holds the pipes too, it must be killed
+        // with the group for the output to end.
+        assert!(start.elapsed() < Duration::from_secs(3));
+    }
+
+    #[test]
+    fn test_children_shutdown() {
+        let run = |script: &str, grace| {
+            let children = Children::new();
+            let task = CronTask::builder()
+                .program("sh")
+                .args(["-c", script])
+                .build()
+                .unwrap();
+            let running = children.clone();
+            let run = thread::spawn(move || task.execute_with(&running));
+
+            let start = Instant::now();
+            while children.pids().is_empty() {
+                assert!(start.elapsed() < Duration::from_secs(5));
+                thread::sleep(POLL_INTERVAL);
+            }
+            let (pid, run_id) = children.runs()[0];
+            assert_eq!(children.pids(), [pid]);
+            children.shutdown(grace);
+
+            let run = run.join().unwrap().unwrap();
+            assert_eq!(run.output().pid, pid);
+            assert_eq!(run.output().run_id, run_id);
+            assert!(children.pids().is_empty());
+            start.elapsed()
+        };
+
+        // The background `sleep` gets the signal along with the shell.
+        assert!(run("sleep 100 & wait", Duration::from_secs(10)) < Duration::from_secs(3));
+        // Ignoring it, the commands are killed once the grace period ends.
+        assert!(
+            run("trap '' TERM; sleep 100", Duration::from_millis(200)) < Duration::from_secs(3)
+        );
+    }
+
+    #[test]
+    fn test_children_abandon() {
+        let children = Children::new();
+        let mut child = Command::new("sleep")
+            .arg("100")
+            .process_group(0)
+            .spawn()
+            .unwrap();
+        children.lock().insert(child.id(), RunId::new());
+
+        children.abandon(&mut child);
+        assert!(children.pids().is_empty());
+        assert!(child.try_wait().unwrap().is_some());
+    }
+
+    #[test]
+    fn test_execute_retries() {
+        use crate::retry::{Backoff, RetryPolicy};
+
+        let retry = |max_attempts| RetryPolicy {
+            max_attempts,
+            backoff: Backoff::Fixed(Duration::from_millis(10)),
+        };
+        let counter = std::env::temp_dir().join(format!("cron_dsl_retry_{}", std::process::id()));
+        // Fails until its third run.
+        let flaky = CronTask::builder()
+            .path(format!(
+                "n=$(($(cat {0} 2>/dev/null || echo 0) + 1)); echo $n > {0}; [ $n -ge 3 ]",
+                counter.display()
+            ))
+            .retry(retry(5))
+            .build()
+            .unwrap();
+
+        let run_id = RunId::new();
+        let run = flaky.execute_run(&Children::new(), run_id).unwrap();
+        std::fs::remove_file(&counter).unwrap();
+        assert!(run.is_success());
+        assert_eq!(run.output().run_id, run_id);
+        assert_eq!(run.output().retries.len(), 2);
+        // Retried attempts are part of the same run.
+        assert!(run.output().retries.iter().all(|attempt| matches!(
+            attempt,
+            Attempt::Failed(failed) if !failed.is_success() && failed.output().run_id == run_id
+        )));
+
+        let failing = CronTask::builder()
+            .path("exit 1".to_string())
+            .retry(retry(2))
+            .build()
+            .unwrap();
+        let run = failing.execute().unwrap();
+        assert!(!run.is_success());
+        assert_eq!(run.output().retries.len(), 1);
+    }
+
+    #[cfg(feature = "group")]
+    #[test]
+    fn test_group_notifier() {
+        use std::sync::{Arc, Mutex};
+
+        use crate::{cron::Cron, group::TaskGroup};
+
+        let failures = Arc::new(Mutex::new(Vec::new()));
+        let record = Arc::clone(&failures);
+        let mut cron = Cron::new();
+        for command in ["exit 2", "true"] {
+            cron.add_task(
+                CronTask::builder()
+                    .path(command.to_string())
+                    .group("backups")
+                    .build()
+                    .unwrap(),
+            );
+        }
+        cron.add_group(
+            TaskGroup::new("backups").with_notifier(move |task, result| {
+                let code = result.as_ref().unwrap().output().status.code();
+                record.lock().unwrap().push((task.command_line(), code));
+            }),
+        );
+
+        for task in &cron {
+            task.execute().unwrap();
+        }
+        assert_eq!(*failures.lock().unwrap(), [("exit 2".to_string(), Some(2))]);
+    }
+
+    #[test]
+    fn test_execute_env() {
+        let dir = std::env::temp_dir().canonicalize().unwrap();
+        let task = CronTask::builder()
+            .path("echo \"$REGION\" && pwd -P".to_string())
+            .env("REGION", "eu-west")
+            .current_dir(&dir)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            task.execute().unwrap().output().stdout,
+            format!("eu-west\n{}\n", dir.display())
+        );
+    }
+}
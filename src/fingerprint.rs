@@ -0,0 +1,331 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::{
+    dst::{FoldPolicy, GapPolicy},
+    error::{CronError, Result},
+    rule::RuleAction,
+    task::{CronTask, ScheduleKind},
+    value::{CronValue, fnv1a},
+    year::YearValue,
+};
+
+/// Version of the canonical encoding written by [`CronTask::canonical_bytes`],
+/// bumped whenever it changes. Older versions stay readable through
+/// [`CronTask::canonical_bytes_v`] so persisted fingerprints can be checked
+/// and then upgraded.
+pub const ENCODING_VERSION: u8 = 3;
+
+/// Hash of a schedule's canonical encoding, tagged with the encoding
+/// version, such as `v3:8c1e7f0a2b3d4c5e`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fingerprint {
+    pub version: u8,
+    pub hash: u64,
+}
+
+impl Fingerprint {
+    /// Whether `task` still has this fingerprint, computed with the version
+    /// it was taken with. `false` for versions this release does not know.
+    pub fn verify(&self, task: &CronTask) -> bool {
+        task.fingerprint_v(self.version) == Some(*self)
+    }
+
+    /// Whether the fingerprint was taken with an older encoding and should
+    /// be replaced by [`CronTask::fingerprint`] once verified.
+    pub fn is_outdated(&self) -> bool {
+        self.version < ENCODING_VERSION
+    }
+}
+
+impl Display for Fingerprint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "v{}:{:016x}", self.version, self.hash)
+    }
+}
+
+impl FromStr for Fingerprint {
+    type Err = CronError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || CronError::InvalidExpression(format!("invalid fingerprint `{s}`"));
+        let (version, hash) = s
+            .strip_prefix('v')
+            .and_then(|s| s.split_once(':'))
+            .ok_or_else(invalid)?;
+
+        Ok(Fingerprint {
+            version: version.parse().map_err(|_| invalid())?,
+            hash: u64::from_str_radix(hash, 16).map_err(|_| invalid())?,
+        })
+    }
+}
+
+impl CronTask {
+    /// Canonical encoding of when the task runs, in the current version.
+    /// Fields are encoded by the values they match rather than how they
+    /// are written, so `*/15` and `0,15,30,45` encode alike, and the
+    /// command and metadata are left out.
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        encode_v3(self)
+    }
+
+    /// Canonical encoding in a given version, `None` for unknown versions.
+    pub fn canonical_bytes_v(&self, version: u8) -> Option<Vec<u8>> {
+        match version {
+            1 => Some(encode_v1(self)),
+            2 => Some(encode_v2(self)),
+            3 => Some(encode_v3(self)),
+            _ => None,
+        }
+    }
+
+    pub fn fingerprint(&self) -> Fingerprint {
+        Fingerprint {
+            version: ENCODING_VERSION,
+            hash: fnv1a(&self.canonical_bytes()),
+        }
+    }
+
+    pub fn fingerprint_v(&self, version: u8) -> Option<Fingerprint> {
+        self.canonical_bytes_v(version).map(|bytes| Fingerprint {
+            version,
+            hash: fnv1a(&bytes),
+        })
+    }
+}
+
+/// Version 1, all integers little endian:
+///
+/// - version, then kind: 0 for calendar, 1 for `@reboot`
+/// - seconds: 0 when absent, else 1 and a 64 bit mask of matched seconds
+/// - minutes, hours, month days, months and week days (Monday = 0) as
+///   64 bit masks of matched values, month days and week days followed by
+///   a count and the sorted `L`, `W` and `#` items, tagged 1, 2 and 3
+/// - year: 0 for every year, else 1, a 16 bit length and its text
+/// - offset: 0 for none, else 1 and its 32 bit number of seconds east
+/// - time zone: 16 bit length and its name, empty for none
+/// - fold and gap policies, one byte each
+fn encode_v1(task: &CronTask) -> Vec<u8> {
+    let mut bytes = vec![1];
+    bytes.push(match task.kind() {
+        ScheduleKind::Calendar => 0,
+        ScheduleKind::Reboot => 1,
+    });
+
+    match task.second() {
+        Some(second) => {
+            bytes.push(1);
+            bytes.extend(mask(second, 0..=59));
+        }
+        None => bytes.push(0),
+    }
+    bytes.extend(mask(task.minute(), 0..=59));
+    bytes.extend(mask(task.hour(), 0..=23));
+    bytes.extend(mask(task.month_day(), 1..=31));
+    push_specials(&mut bytes, task.month_day());
+    bytes.extend(mask(task.month(), 1..=12));
+    bytes.extend(mask(task.week_day(), 0..=6));
+    push_specials(&mut bytes, task.week_day());
+
+    match task.year() {
+        YearValue::All => bytes.push(0),
+        year => {
+            bytes.push(1);
+            push_text(&mut bytes, &year.to_string());
+        }
+    }
+
+    match task.offset() {
+        Some(offset) => {
+            bytes.push(1);
+            bytes.extend(offset.local_minus_utc().to_le_bytes());
+        }
+        None => bytes.push(0),
+    }
+
+    #[cfg(feature = "chrono-tz")]
+    let timezone = task.timezone().map(|tz| tz.name()).unwrap_or_default();
+    #[cfg(not(feature = "chrono-tz"))]
+    let timezone = "";
+    push_text(&mut bytes, timezone);
+
+    let policy = task.dst_policy();
+    bytes.push(match policy.fold {
+        FoldPolicy::Earliest => 0,
+        FoldPolicy::Latest => 1,
+        FoldPolicy::Skip => 2,
+    });
+    bytes.push(match policy.gap {
+        GapPolicy::Skip => 0,
+        GapPolicy::ShiftForward => 1,
+    });
+
+    bytes
+}
+
+/// Version 2: version 1 with its version byte set to 2, followed by a 16
+/// bit count of rules, each encoded as its action, 0 to include and 1 to
+/// exclude, then the 16 bit length and version 2 encoding of its schedule.
+fn encode_v2(task: &CronTask) -> Vec<u8> {
+    encode_with_rules(task, 2, encode_v2)
+}
+
+/// Version 3: version 2 with its version byte set to 3 and rules encoded
+/// in version 3, followed by 1 when a date matching either day field is
+/// enough, else 0.
+fn encode_v3(task: &CronTask) -> Vec<u8> {
+    let mut bytes = encode_with_rules(task, 3, encode_v3);
+    bytes.push(task.matches_either_day().into());
+
+    bytes
+}
+
+fn encode_with_rules(task: &CronTask, version: u8, encode: fn(&CronTask) -> Vec<u8>) -> Vec<u8> {
+    let mut bytes = encode_v1(task);
+    bytes[0] = version;
+
+    bytes.extend((task.all_rules().count() as u16).to_le_bytes());
+    for rule in task.all_rules() {
+        bytes.push(match rule.action() {
+            RuleAction::Include => 0,
+            RuleAction::Exclude => 1,
+        });
+        let schedule = encode(rule.schedule());
+        bytes.extend((schedule.len() as u16).to_le_bytes());
+        bytes.extend(schedule);
+    }
+
+    bytes
+}
+
+fn mask(value: &CronValue, domain: std::ops::RangeInclusive<u8>) -> [u8; 8] {
+    let first = *domain.start();
+    domain
+        .filter(|v| value.matches_in(*v, first))
+        .fold(0u64, |mask, v| mask | 1 << v)
+        .to_le_bytes()
+}
+
+fn push_specials(bytes: &mut Vec<u8>, value: &CronValue) {
+    let mut specials = Vec::new();
+    collect_specials(value, &mut specials);
+    specials.sort();
+    specials.dedup();
+
+    bytes.push(specials.len() as u8);
+    bytes.extend(specials.into_iter().flatten());
+}
+
+fn collect_specials(value: &CronValue, specials: &mut Vec<Vec<u8>>) {
+    match value {
+        CronValue::List(values) => values.iter().for_each(|v| collect_specials(v, specials)),
+        CronValue::Last => specials.push(vec![1]),
+        CronValue::NearestWeekday(day) => specials.push(vec![2, *day]),
+        CronValue::Nth(week_day, n) => specials.push(vec![3, week_day.into(), *n]),
+        _ => {}
+    }
+}
+
+fn push_text(bytes: &mut Vec<u8>, text: &str) {
+    bytes.extend((text.len() as u16).to_le_bytes());
+    bytes.extend(text.as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "parser")]
+    fn task(s: &str) -> CronTask {
+        format!("{s} /bin/true").parse().unwrap()
+    }
+
+    #[cfg(feature = "parser")]
+    #[test]
+    fn test_equivalent_schedules() {
+        assert_eq!(
+            task("*/15 * * * *").fingerprint(),
+            task("0,15,30,45 * * * *").fingerprint()
+        );
+        assert_eq!(
+            task("0 9 * * 1-5").fingerprint(),
+            task("0 9 * * MON-FRI").fingerprint()
+        );
+        assert_ne!(
+            task("0 9 * * *").fingerprint(),
+            task("0 10 * * *").fingerprint()
+        );
+        assert_ne!(
+            task("0 9 L * *").fingerprint(),
+            task("0 9 * * *").fingerprint()
+        );
+
+        let both = crate::task::CronTaskBuilder::from_task(&task("0 0 13 * 5"))
+            .day_match(crate::task::DayMatch::All)
+            .build()
+            .unwrap();
+        assert_ne!(task("0 0 13 * 5").fingerprint(), both.fingerprint());
+        assert_eq!(task("0 0 13 * 5").fingerprint_v(2), both.fingerprint_v(2));
+    }
+
+    #[test]
+    fn test_canonical_bytes_stable() {
+        let task = CronTask::builder()
+            .minutes(30)
+            .hour(2)
+            .month_day(CronValue::Last)
+            .path("/bin/true".to_string())
+            .build()
+            .unwrap();
+
+        // Pinned: changing an encoding must add a new version instead.
+        let mut expected = vec![1, 0, 0];
+        expected.extend((1u64 << 30).to_le_bytes());
+        expected.extend((1u64 << 2).to_le_bytes());
+        expected.extend(0u64.to_le_bytes());
+        expected.extend([1, 1]);
+        expected.extend(0x1ffeu64.to_le_bytes());
+        expected.extend(0x7fu64.to_le_bytes());
+        expected.extend([0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(task.canonical_bytes_v(1).unwrap(), expected);
+        assert_eq!(
+            task.fingerprint_v(1).unwrap().to_string(),
+            "v1:f8b9c5fd6013449e"
+        );
+
+        expected[0] = 2;
+        expected.extend([0, 0]);
+        assert_eq!(task.canonical_bytes_v(2).unwrap(), expected);
+
+        expected[0] = 3;
+        expected.push(0);
+        assert_eq!(task.canonical_bytes(), expected);
+    }
+
+    #[test]
+    fn test_fingerprint_round_trip() {
+        let task = CronTask::builder()
+            .minutes(0)
+            .path("/bin/true".to_string())
+            .build()
+            .unwrap();
+        let fingerprint = task.fingerprint();
+        let parsed: Fingerprint = fingerprint.to_string().parse().unwrap();
+
+        assert_eq!(parsed, fingerprint);
+        assert!(parsed.verify(&task));
+        assert!(!parsed.is_outdated());
+
+        let legacy = task.fingerprint_v(1).unwrap();
+        assert!(legacy.is_outdated());
+        assert!(legacy.verify(&task));
+        assert!(
+            !Fingerprint {
+                version: 9,
+                hash: 0
+            }
+            .verify(&task)
+        );
+        assert!("1:00".parse::<Fingerprint>().is_err());
+    }
+}
@@ -0,0 +1,186 @@
+use std::{fmt::Display, str::FromStr, sync::Arc};
+
+use chrono::FixedOffset;
+
+use crate::{
+    error::{CronError, Result},
+    rule::ScheduleRule,
+};
+#[cfg(feature = "executor")]
+use crate::{executor::RunResult, task::CronTask};
+
+/// Called with a task of the group and the outcome of a run of it that
+/// failed or could not start, once retries are exhausted.
+#[cfg(feature = "executor")]
+pub type Notifier = Arc<dyn Fn(&CronTask, &Result<RunResult>) + Send + Sync>;
+
+#[derive(Debug, Clone)]
+pub struct TaskGroup {
+    name: String,
+    max_concurrent: Option<usize>,
+    overlap: OverlapPolicy,
+    enabled: bool,
+    policy: Arc<GroupPolicy>,
+}
+
+/// What a scheduler does with a run of a group already running as many
+/// tasks as it allows.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OverlapPolicy {
+    /// Holds the run back until one of the group's runs ends.
+    #[default]
+    Queue,
+    /// Skips the run, reporting it as an overlap.
+    Skip,
+}
+
+impl Display for OverlapPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            OverlapPolicy::Queue => "queue",
+            OverlapPolicy::Skip => "skip",
+        })
+    }
+}
+
+impl FromStr for OverlapPolicy {
+    type Err = CronError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "queue" => Ok(OverlapPolicy::Queue),
+            "skip" => Ok(OverlapPolicy::Skip),
+            _ => Err(CronError::InvalidExpression(format!(
+                "unknown overlap policy `{s}`"
+            ))),
+        }
+    }
+}
+
+/// Settings a group hands down to its tasks once registered in a
+/// [`crate::cron::Cron`]. A task's own time zone wins over the group's,
+/// and its own rules are evaluated after the group's calendar.
+#[derive(Clone, Default)]
+pub struct GroupPolicy {
+    offset: Option<FixedOffset>,
+    #[cfg(feature = "chrono-tz")]
+    timezone: Option<chrono_tz::Tz>,
+    calendar: Vec<ScheduleRule>,
+    #[cfg(feature = "executor")]
+    notifier: Option<Notifier>,
+}
+
+impl TaskGroup {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            max_concurrent: None,
+            overlap: OverlapPolicy::default(),
+            enabled: true,
+            policy: Arc::default(),
+        }
+    }
+
+    pub fn with_max_concurrent(mut self, max_concurrent: usize) -> Self {
+        self.max_concurrent = Some(max_concurrent);
+        self
+    }
+
+    pub fn with_overlap_policy(mut self, overlap: OverlapPolicy) -> Self {
+        self.overlap = overlap;
+        self
+    }
+
+    /// Reads the schedules of the group's tasks at a fixed offset.
+    pub fn with_offset(mut self, offset: FixedOffset) -> Self {
+        Arc::make_mut(&mut self.policy).offset = Some(offset);
+        self
+    }
+
+    /// Reads the schedules of the group's tasks in `timezone`, taking
+    /// precedence over the group's offset.
+    #[cfg(feature = "chrono-tz")]
+    pub fn with_timezone(mut self, timezone: chrono_tz::Tz) -> Self {
+        Arc::make_mut(&mut self.policy).timezone = Some(timezone);
+        self
+    }
+
+    /// Adds a rule to the calendar shared by the group's tasks, such as the
+    /// exclusion of public holidays.
+    pub fn with_calendar_rule(mut self, rule: ScheduleRule) -> Self {
+        Arc::make_mut(&mut self.policy).calendar.push(rule);
+        self
+    }
+
+    #[cfg(feature = "executor")]
+    pub fn with_notifier<F>(mut self, notifier: F) -> Self
+    where
+        F: Fn(&CronTask, &Result<RunResult>) + Send + Sync + 'static,
+    {
+        Arc::make_mut(&mut self.policy).notifier = Some(Arc::new(notifier));
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Maximum number of the group's tasks allowed to run at the same time.
+    pub fn max_concurrent(&self) -> Option<usize> {
+        self.max_concurrent
+    }
+
+    pub fn overlap_policy(&self) -> OverlapPolicy {
+        self.overlap
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn policy(&self) -> &GroupPolicy {
+        &self.policy
+    }
+
+    pub(crate) fn shared_policy(&self) -> Arc<GroupPolicy> {
+        Arc::clone(&self.policy)
+    }
+}
+
+impl GroupPolicy {
+    pub fn offset(&self) -> Option<FixedOffset> {
+        self.offset
+    }
+
+    #[cfg(feature = "chrono-tz")]
+    pub fn timezone(&self) -> Option<chrono_tz::Tz> {
+        self.timezone
+    }
+
+    /// Rules applied to the schedule of every task of the group, in order.
+    pub fn calendar(&self) -> &[ScheduleRule] {
+        &self.calendar
+    }
+
+    #[cfg(feature = "executor")]
+    pub fn notifier(&self) -> Option<&Notifier> {
+        self.notifier.as_ref()
+    }
+}
+
+impl std::fmt::Debug for GroupPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct("GroupPolicy");
+        debug.field("offset", &self.offset);
+        #[cfg(feature = "chrono-tz")]
+        debug.field("timezone", &self.timezone);
+        debug.field("calendar", &self.calendar);
+        #[cfg(feature = "executor")]
+        debug.field("notifier", &self.notifier.is_some());
+        debug.finish()
+    }
+}
@@ -0,0 +1,149 @@
+use std::ops::Range;
+
+use chrono::{DateTime, Local, Utc};
+
+use crate::{cron::Cron, value::fnv1a};
+
+/// Longest content line allowed before folding, in octets.
+const LINE_LIMIT: usize = 75;
+
+impl Cron {
+    /// Renders every occurrence of the tasks running here within `window` as
+    /// an iCalendar file, one `VEVENT` per run. Stamps use the window start
+    /// so that exporting the same window twice gives the same file.
+    pub fn export_ics(&self, window: Range<DateTime<Local>>) -> String {
+        let stamp = utc(window.start);
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            "PRODID:-//cron_dsl//EN".to_string(),
+        ];
+
+        for task in self
+            .into_iter()
+            .filter(|task| self.skip_reason(task).is_none())
+        {
+            let command = task.command_line();
+            for next in task.occurrences_between(window.clone()) {
+                let start = utc(next);
+                lines.push("BEGIN:VEVENT".to_string());
+                lines.push(format!(
+                    "UID:{:016x}-{start}@cron_dsl",
+                    fnv1a(command.as_bytes())
+                ));
+                lines.push(format!("DTSTAMP:{stamp}"));
+                lines.push(format!("DTSTART:{start}"));
+                lines.push(format!("SUMMARY:{}", escape(&command)));
+                if let Some(group) = task.group() {
+                    lines.push(format!("CATEGORIES:{}", escape(group)));
+                }
+                lines.push("END:VEVENT".to_string());
+            }
+        }
+
+        lines.push("END:VCALENDAR".to_string());
+        lines.iter().map(|line| fold(line) + "\r\n").collect()
+    }
+}
+
+fn utc(date: DateTime<Local>) -> String {
+    date.with_timezone(&Utc)
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Splits `line` into continuation lines of at most `LINE_LIMIT` octets,
+/// never inside a character.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut width = 0;
+
+    for c in line.chars() {
+        if width + c.len_utf8() > LINE_LIMIT {
+            folded.push_str("\r\n ");
+            // The leading space counts toward the continuation line.
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    #[cfg(feature = "group")]
+    use crate::group::TaskGroup;
+    use crate::task::CronTask;
+
+    #[test]
+    fn test_export_ics() {
+        let mut cron = Cron::new();
+        cron.add_task(
+            CronTask::builder()
+                .path("backup.sh, then upload".to_string())
+                .minutes(0)
+                .hour(3)
+                .group("maintenance")
+                .build()
+                .unwrap(),
+        );
+        cron.add_task(CronTask::reboot("/bin/true"));
+
+        let start = Local.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap();
+        let end = Local.with_ymd_and_hms(2025, 6, 3, 0, 0, 0).unwrap();
+        let ics = cron.export_ics(start..end);
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+        assert!(ics.contains(&format!(
+            "DTSTART:{}\r\n",
+            utc(Local.with_ymd_and_hms(2025, 6, 2, 3, 0, 0).unwrap())
+        )));
+        assert!(ics.contains("SUMMARY:backup.sh\\, then upload\r\n"));
+        assert!(ics.contains("CATEGORIES:maintenance\r\n"));
+    }
+
+    #[cfg(feature = "group")]
+    #[test]
+    fn test_export_ics_skips_disabled_groups() {
+        let mut cron = Cron::new();
+        cron.add_task(
+            CronTask::builder()
+                .path("/bin/true".to_string())
+                .group("maintenance")
+                .build()
+                .unwrap(),
+        );
+        let mut group = TaskGroup::new("maintenance");
+        group.set_enabled(false);
+        cron.add_group(group);
+
+        let start = Local.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap();
+        let ics = cron.export_ics(start..start + chrono::TimeDelta::hours(1));
+
+        assert!(!ics.contains("BEGIN:VEVENT"));
+    }
+
+    #[test]
+    fn test_fold() {
+        let line = "x".repeat(160);
+        let folded = fold(&line);
+
+        assert!(folded.split("\r\n").all(|part| part.len() <= LINE_LIMIT));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+        assert_eq!(fold("short"), "short");
+    }
+}
@@ -0,0 +1,119 @@
+//! Cron schedules written in Rust, matched against dates and searched for
+//! their next runs.
+//!
+//! Without the default `std` feature, only the `no_std` + `alloc` core is
+//! built: the field values of [`value`] and [`year`], and the schedules of
+//! [`schedule`] matched against plain dates, for targets with no time zone
+//! database or clock. Tasks, crons and everything built on them need
+//! `std`.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "budget")]
+pub mod budget;
+#[cfg(feature = "bundle")]
+pub mod bundle;
+#[cfg(feature = "capabilities")]
+pub mod capabilities;
+#[cfg(feature = "check")]
+pub mod check;
+#[cfg(feature = "clap")]
+pub mod cli;
+#[cfg(feature = "std")]
+mod command;
+#[cfg(any(feature = "toml", feature = "yaml"))]
+pub mod config;
+#[cfg(feature = "countdown")]
+pub mod countdown;
+#[cfg(feature = "std")]
+pub mod cron;
+#[cfg(feature = "crontab")]
+pub mod crontab;
+#[cfg(feature = "debug")]
+pub mod debug;
+#[cfg(feature = "describe")]
+pub mod describe;
+#[cfg(feature = "std")]
+pub mod dst;
+#[cfg(feature = "english")]
+pub mod english;
+#[cfg(feature = "env")]
+pub mod env;
+pub mod error;
+#[cfg(feature = "scheduler")]
+pub mod event;
+#[cfg(feature = "executor")]
+pub mod executor;
+#[cfg(feature = "fingerprint")]
+pub mod fingerprint;
+#[cfg(feature = "group")]
+pub mod group;
+#[cfg(feature = "ics")]
+pub mod ics;
+#[cfg(feature = "lint")]
+pub mod lint;
+#[cfg(feature = "owner")]
+pub mod owner;
+#[cfg(feature = "pair")]
+pub mod pair;
+#[cfg(feature = "parser")]
+pub mod parser;
+#[cfg(feature = "patch")]
+pub mod patch;
+#[cfg(feature = "preset")]
+pub mod preset;
+#[cfg(feature = "scheduler")]
+pub mod ready;
+#[cfg(feature = "clock")]
+pub mod realtime;
+#[cfg(feature = "retry")]
+pub mod retry;
+#[cfg(feature = "rrule")]
+pub mod rrule;
+#[cfg(feature = "std")]
+pub mod rule;
+#[cfg(any(feature = "parser", feature = "executor"))]
+pub mod run_as;
+#[cfg(feature = "run-id")]
+pub mod run_id;
+pub mod schedule;
+#[cfg(feature = "scheduler")]
+pub mod scheduler;
+#[cfg(feature = "serde")]
+pub mod serialize;
+#[cfg(feature = "shard")]
+pub mod shard;
+#[cfg(feature = "simulator")]
+pub mod simulator;
+#[cfg(feature = "std")]
+pub mod skip;
+#[cfg(feature = "scheduler")]
+pub mod state;
+#[cfg(feature = "success")]
+pub mod success;
+#[cfg(feature = "systemd")]
+pub mod systemd;
+#[cfg(feature = "std")]
+pub mod task;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+#[cfg(feature = "stream")]
+pub mod tick;
+#[cfg(feature = "time")]
+pub mod time_compat;
+#[cfg(feature = "tui")]
+pub mod tui;
+#[cfg(feature = "parser")]
+pub mod untrusted;
+#[cfg(feature = "crontab")]
+pub mod validate;
+pub mod value;
+#[cfg(feature = "std")]
+pub mod weekly;
+pub mod year;
+#[cfg(any(feature = "crontab", feature = "env"))]
+mod zone;
+
+#[cfg(feature = "capabilities")]
+pub use capabilities::capabilities;
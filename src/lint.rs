@@ -0,0 +1,218 @@
+use std::fmt::Display;
+
+use chrono::{Datelike, Days, Local, NaiveDate, NaiveTime, Offset, TimeDelta, TimeZone};
+
+use crate::{dst::GapPolicy, task::CronTask};
+
+/// Issues that leave a task valid but likely not doing what its author
+/// meant.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Lint {
+    /// Every fire time of `date` falls in the gap left when clocks spring
+    /// forward, so the task does not run that day. `suggestions` are the
+    /// same times moved out of the gap.
+    DstGap {
+        date: NaiveDate,
+        times: Vec<NaiveTime>,
+        suggestions: Vec<NaiveTime>,
+    },
+}
+
+impl Display for Lint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = |times: &[NaiveTime]| {
+            times
+                .iter()
+                .map(|time| time.format("%H:%M:%S").to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        match self {
+            Lint::DstGap {
+                date,
+                times,
+                suggestions,
+            } => write!(
+                f,
+                "Skipped on {date}: {} falls in the daylight saving gap, consider {} instead.",
+                join(times),
+                join(suggestions)
+            ),
+        }
+    }
+}
+
+impl CronTask {
+    /// Lints the schedule over `year`, in the zone its fields are read in.
+    pub fn lint(&self, year: i32) -> Vec<Lint> {
+        #[cfg(feature = "chrono-tz")]
+        if let Some(timezone) = self.timezone() {
+            return self.dst_gaps(&timezone, year);
+        }
+
+        match self.offset() {
+            Some(offset) => self.dst_gaps(&offset, year),
+            None => self.dst_gaps(&Local, year),
+        }
+    }
+
+    /// Days of `year` on which every fire time of the task is skipped by a
+    /// daylight saving transition of `tz`, none when the task shifts
+    /// skipped times forward.
+    pub fn dst_gaps<Tz: TimeZone>(&self, tz: &Tz, year: i32) -> Vec<Lint> {
+        let Some(first) = NaiveDate::from_ymd_opt(year, 1, 1) else {
+            return Vec::new();
+        };
+        if self.is_reboot()
+            || !self.year().matches(year)
+            || self.dst_policy().gap == GapPolicy::ShiftForward
+        {
+            return Vec::new();
+        }
+
+        first
+            .iter_days()
+            .take_while(|date| date.year() == year)
+            .filter(|date| self.matches_date(*date))
+            .filter_map(|date| self.dst_gap(tz, date))
+            .collect()
+    }
+
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        self.month().matches(date.month() as u8) && self.matches_day(date)
+    }
+
+    fn dst_gap<Tz: TimeZone>(&self, tz: &Tz, date: NaiveDate) -> Option<Lint> {
+        let offset_at = |date: NaiveDate| {
+            tz.from_local_datetime(&date.and_time(NaiveTime::MIN))
+                .earliest()
+                .map(|start| start.offset().fix().local_minus_utc())
+        };
+        let gap = offset_at(date.checked_add_days(Days::new(1))?)? - offset_at(date)?;
+        if gap <= 0 {
+            return None;
+        }
+
+        let times = self.fire_times();
+        let skipped = !times.is_empty()
+            && times.iter().all(|time| {
+                tz.from_local_datetime(&date.and_time(*time))
+                    .earliest()
+                    .is_none()
+            });
+        if !skipped {
+            return None;
+        }
+
+        let gap = TimeDelta::seconds(gap as i64);
+        let mut suggestions: Vec<NaiveTime> = times
+            .iter()
+            .flat_map(|time| [*time - gap, *time + gap])
+            .collect();
+        suggestions.sort();
+        suggestions.dedup();
+
+        Some(Lint::DstGap {
+            date,
+            times,
+            suggestions,
+        })
+    }
+
+    fn fire_times(&self) -> Vec<NaiveTime> {
+        let seconds: Vec<u8> = match self.second() {
+            Some(second) => (0..60).filter(|s| second.matches(*s)).collect(),
+            None => vec![0],
+        };
+
+        (0..24)
+            .filter(|hour| self.hour().matches(*hour))
+            .flat_map(|hour| {
+                (0..60)
+                    .filter(|minute| self.minute().matches(*minute))
+                    .map(move |minute| (hour, minute))
+            })
+            .flat_map(|(hour, minute)| {
+                seconds.iter().filter_map(move |second| {
+                    NaiveTime::from_hms_opt(hour as u32, minute as u32, *second as u32)
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::FixedOffset;
+    use chrono_tz::Europe::Paris;
+
+    use super::*;
+    use crate::{
+        dst::DstPolicy,
+        task::CronTaskBuilder,
+        value::{CronValue, on},
+    };
+
+    fn task(hour: impl Into<CronValue>) -> CronTask {
+        CronTask::builder()
+            .minutes(30)
+            .hour(hour)
+            .path("/usr/bin/backup".to_string())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_dst_gap() {
+        let lints = task(2).dst_gaps(&Paris, 2025);
+        let at = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+
+        assert_eq!(
+            lints,
+            vec![Lint::DstGap {
+                date: NaiveDate::from_ymd_opt(2025, 3, 30).unwrap(),
+                times: vec![at(2, 30)],
+                suggestions: vec![at(1, 30), at(3, 30)],
+            }]
+        );
+        assert_eq!(
+            lints[0].to_string(),
+            "Skipped on 2025-03-30: 02:30:00 falls in the daylight saving gap, \
+             consider 01:30:00, 03:30:00 instead."
+        );
+    }
+
+    #[test]
+    fn test_dst_gap_partial_day() {
+        // 03:30 still runs on the transition day.
+        assert!(task(on(2).or(3)).dst_gaps(&Paris, 2025).is_empty());
+        // The transition falls on a Sunday, never on a Monday.
+        let monday = CronTaskBuilder::from_task(&task(2))
+            .week_day(0)
+            .build()
+            .unwrap();
+        assert!(monday.dst_gaps(&Paris, 2025).is_empty());
+        assert!(task(1).dst_gaps(&Paris, 2025).is_empty());
+    }
+
+    #[test]
+    fn test_dst_gap_shifted() {
+        let shifted = CronTaskBuilder::from_task(&task(2))
+            .dst_policy(DstPolicy {
+                gap: GapPolicy::ShiftForward,
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+
+        assert!(shifted.dst_gaps(&Paris, 2025).is_empty());
+    }
+
+    #[test]
+    fn test_dst_gap_fixed_offset() {
+        let offset = FixedOffset::east_opt(3600).unwrap();
+
+        assert!(task(2).dst_gaps(&offset, 2025).is_empty());
+    }
+}
@@ -1,17 +1,21 @@
-use chrono::{Month, Weekday};
+use chrono::{Local, Month, Weekday};
 
-use crate::{
-    cron::Cron, task::CronTask, value::{from, interval, range}
+use cron_dsl::{
+    cron::Cron,
+    task::CronTask,
+    value::{from, interval, range},
 };
 
-pub mod cron;
-pub mod error;
-pub mod task;
-pub mod value;
-
 // const FORMAT_NO_FRAC: &str = "%Y-%m-%d %H:%M:%S";
 
 fn main() {
+    #[cfg(feature = "crontab")]
+    match &std::env::args().skip(1).collect::<Vec<_>>()[..] {
+        [command, dir] if command == "validate" => validate(dir, false),
+        [command, flag, dir] if command == "validate" && flag == "--strict" => validate(dir, true),
+        _ => {}
+    }
+
     let mut cron = Cron::new();
     let cron_task = CronTask::builder()
         .minutes(range(1..59))
@@ -24,7 +28,7 @@ fn main() {
     println!("{}", cron_task);
 
     cron.add_task(cron_task);
-    
+
     // let local_time = Local::now();
     // let date_str = "2025-11-24 15:43:07";
     // let date = Local.from_local_datetime(&NaiveDateTime::parse_from_str(&date_str, FORMAT_NO_FRAC).unwrap()).unwrap();
@@ -36,11 +40,14 @@ fn main() {
         // .hour(on(5).or(18))
         .week_day(from(Weekday::Mon, Weekday::Fri))
         .month(from(Month::April, Month::December))
+        .allow_commandless()
         .build()
         .unwrap();
 
     println!("{}", t);
-    println!("next {}", t.next_occurrence());
+    if let Some(next) = t.try_next_occurrence(Local::now()) {
+        println!("next {}", next);
+    }
 
     // let local_time = Local::now();
     // let date_str = "2025-11-24 15:43:07";
@@ -52,3 +59,26 @@ fn main() {
 
     // println!("match {}", matches);
 }
+
+/// `cron_dsl validate [--strict] <dir>`: prints a JSON report of the
+/// schedule files under `dir`, exiting with a failure status when any is
+/// invalid. `--strict` also requires every task to have an owner.
+#[cfg(feature = "crontab")]
+fn validate(dir: &str, strict: bool) -> ! {
+    let report = if strict {
+        Cron::validate_dir_strict(dir)
+    } else {
+        Cron::validate_dir(dir)
+    };
+
+    match report {
+        Ok(report) => {
+            println!("{}", report.to_json());
+            std::process::exit(if report.is_ok() { 0 } else { 1 });
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(2);
+        }
+    }
+}
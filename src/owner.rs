@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Local, TimeDelta};
+
+use crate::{cron::Cron, task::CronTask};
+
+/// Tasks of one owner, as listed by [`Cron::report_by_owner`].
+#[derive(Debug)]
+pub struct OwnerReport<'a> {
+    /// `None` gathers the tasks nobody owns.
+    pub owner: Option<&'a str>,
+    pub tasks: Vec<TaskSummary<'a>>,
+}
+
+#[derive(Debug)]
+pub struct TaskSummary<'a> {
+    pub task: &'a CronTask,
+    /// Runs within the week following the report date.
+    pub weekly_runs: usize,
+    pub next_run: Option<DateTime<Local>>,
+}
+
+impl OwnerReport<'_> {
+    pub fn weekly_runs(&self) -> usize {
+        self.tasks.iter().map(|summary| summary.weekly_runs).sum()
+    }
+}
+
+impl Cron {
+    /// Every task grouped by owner, with how often it runs and when it runs
+    /// next from `from`. Owners are sorted by name, unowned tasks come last.
+    pub fn report_by_owner(&self, from: DateTime<Local>) -> Vec<OwnerReport<'_>> {
+        let week = from..from.checked_add_signed(TimeDelta::weeks(1)).unwrap_or(from);
+        let mut owners: BTreeMap<(bool, Option<&str>), Vec<TaskSummary>> = BTreeMap::new();
+
+        for task in self {
+            owners
+                .entry((task.owner().is_none(), task.owner()))
+                .or_default()
+                .push(TaskSummary {
+                    task,
+                    weekly_runs: task.occurrences_between(week.clone()).count(),
+                    next_run: task.try_next_occurrence(from),
+                });
+        }
+
+        owners
+            .into_iter()
+            .map(|((_, owner), tasks)| OwnerReport { owner, tasks })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::value::on;
+
+    #[test]
+    fn test_report_by_owner() {
+        let task = |path: &str, hour: u8| {
+            CronTask::builder()
+                .path(path.to_string())
+                .minutes(0)
+                .hour(hour)
+        };
+        let mut cron = Cron::new();
+        cron.add_task(task("/usr/bin/report", 9).owner("web").build().unwrap());
+        cron.add_task(task("/usr/bin/orphan", 12).build().unwrap());
+        cron.add_task(task("/usr/bin/backup", 3).owner("ops").build().unwrap());
+        cron.add_task(
+            task("/usr/bin/vacuum", 4)
+                .week_day(on(6))
+                .owner("ops")
+                .build()
+                .unwrap(),
+        );
+
+        let from = Local.with_ymd_and_hms(2024, 6, 15, 10, 0, 0).unwrap();
+        let report = cron.report_by_owner(from);
+        let owners: Vec<Option<&str>> = report.iter().map(|owner| owner.owner).collect();
+
+        assert_eq!(owners, [Some("ops"), Some("web"), None]);
+        assert_eq!(report[0].tasks.len(), 2);
+        assert_eq!(report[0].weekly_runs(), 8);
+        assert_eq!(
+            report[1].tasks[0].next_run,
+            Some(Local.with_ymd_and_hms(2024, 6, 16, 9, 0, 0).unwrap())
+        );
+        assert_eq!(
+            report[2].tasks[0].task.path().to_str(),
+            Some("/usr/bin/orphan")
+        );
+    }
+}
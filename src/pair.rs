@@ -0,0 +1,184 @@
+use std::ops::Range;
+
+use chrono::{DateTime, Local, NaiveTime, Timelike};
+
+use crate::{
+    cron::Cron,
+    error::{CronError, Result},
+    task::{CronTask, CronTaskBuilder},
+};
+
+/// Two tasks opening and closing a window, such as starting a job at 23:30
+/// and stopping it at 01:30 the next day. Both are annotated with the pair
+/// name under `pair` and their role under `pair.role`.
+#[derive(Debug, Clone)]
+pub struct TaskPair {
+    name: String,
+    start: CronTask,
+    stop: CronTask,
+}
+
+impl TaskPair {
+    /// Tasks without a name are named after the pair, as `<name>:start`
+    /// and `<name>:stop`.
+    pub fn new(name: impl Into<String>, start: CronTask, stop: CronTask) -> Result<Self> {
+        let name = name.into();
+        let link = |task: &CronTask, role: &str| {
+            let builder = CronTaskBuilder::from_task(task)
+                .annotation("pair", &name)
+                .annotation("pair.role", role);
+
+            match task.name() {
+                Some(_) => builder,
+                None => builder.name(format!("{name}:{role}")),
+            }
+            .build()
+        };
+
+        Ok(Self {
+            start: link(&start, "start")?,
+            stop: link(&stop, "stop")?,
+            name,
+        })
+    }
+
+    /// A window opening every day at `start` and closing at `stop`, on the
+    /// next day when `stop` comes first.
+    pub fn daily(
+        name: impl Into<String>,
+        start: (NaiveTime, &str),
+        stop: (NaiveTime, &str),
+    ) -> Result<Self> {
+        let task = |(time, command): (NaiveTime, &str)| {
+            let builder = CronTask::builder()
+                .minutes(time.minute() as u8)
+                .hour(time.hour() as u8)
+                .command(command);
+
+            match time.second() {
+                0 => builder,
+                second => builder.seconds(second as u8),
+            }
+            .build()
+        };
+
+        Self::new(name, task(start)?, task(stop)?)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn start(&self) -> &CronTask {
+        &self.start
+    }
+
+    pub fn stop(&self) -> &CronTask {
+        &self.stop
+    }
+
+    pub fn into_tasks(self) -> (CronTask, CronTask) {
+        (self.start, self.stop)
+    }
+
+    /// Checks that over `window` every start is followed by a stop before
+    /// the next start. A stop before the first start is accepted, the
+    /// window may open while the pair is running.
+    pub fn validate(&self, window: Range<DateTime<Local>>) -> Result<()> {
+        let starts = self
+            .start
+            .occurrences_between(window.clone())
+            .map(|d| (d, true));
+        let stops = self.stop.occurrences_between(window).map(|d| (d, false));
+        let mut events: Vec<(DateTime<Local>, bool)> = starts.chain(stops).collect();
+        // Starts first, so a stop at the same date is caught.
+        events.sort_by_key(|(date, is_start)| (*date, !is_start));
+
+        let invalid = |reason: String| CronError::InvalidPair(format!("`{}` {reason}", self.name));
+        let mut running = None;
+        for (date, is_start) in events {
+            match (running, is_start) {
+                (Some(started), true) => {
+                    return Err(invalid(format!(
+                        "starts again at {date} without stopping since {started}"
+                    )));
+                }
+                (None, true) => running = Some(date),
+                (Some(started), false) if started == date => {
+                    return Err(invalid(format!("stops when it starts at {date}")));
+                }
+                (Some(_), false) => running = None,
+                (None, false) => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Cron {
+    /// Adds both tasks of `pair`, the start first.
+    pub fn add_pair(&mut self, pair: TaskPair) {
+        let (start, stop) = pair.into_tasks();
+        self.add_task(start);
+        self.add_task(stop);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeDelta, TimeZone};
+
+    use super::*;
+    use crate::value::{on, range};
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn window() -> Range<DateTime<Local>> {
+        let start = Local.with_ymd_and_hms(2025, 6, 2, 0, 0, 0).unwrap();
+        start..start + TimeDelta::weeks(4)
+    }
+
+    #[test]
+    fn test_daily_pair() {
+        let pair = TaskPair::daily(
+            "backup",
+            (time(23, 30), "/usr/bin/backup start"),
+            (time(1, 30), "/usr/bin/backup stop"),
+        )
+        .unwrap();
+
+        assert_eq!(pair.start().name(), Some("backup:start"));
+        assert_eq!(pair.stop().annotation("pair"), Some("backup"));
+        assert_eq!(pair.stop().annotation("pair.role"), Some("stop"));
+        assert_eq!(pair.stop().args(), ["stop"]);
+        assert!(pair.validate(window()).is_ok());
+
+        let mut cron = Cron::new();
+        cron.add_pair(pair);
+        assert_eq!(cron.into_iter().count(), 2);
+    }
+
+    #[test]
+    fn test_unpaired_schedules() {
+        let task = |week_days, hour| {
+            CronTask::builder()
+                .minutes(on(30))
+                .hour(on(hour))
+                .week_day(week_days)
+                .path("/usr/bin/job".to_string())
+                .build()
+                .unwrap()
+        };
+
+        // Stopping on weekdays only leaves Saturday's start running.
+        let pair = TaskPair::new("job", task(range(0..6), 23), task(range(0..4), 1)).unwrap();
+        let error = pair.validate(window()).unwrap_err().to_string();
+        assert!(error.starts_with("Invalid task pair: `job` starts again at"));
+
+        let pair = TaskPair::new("job", task(range(0..6), 23), task(range(0..6), 23)).unwrap();
+        assert!(pair.validate(window()).is_err());
+    }
+}
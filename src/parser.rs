@@ -0,0 +1,1477 @@
+use std::{borrow::Borrow, collections::HashMap, hash::Hash, str::FromStr};
+
+use crate::{
+    error::{CronError, Result},
+    task::{CronTask, CronTaskBuilder, DayMatch},
+    value::{CronValue, ValueKind},
+    year::YearValue,
+};
+
+/// Cron syntax an expression is written in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    /// Five fields, week days numbered from Sunday = 0.
+    #[default]
+    Unix,
+    /// Seconds first, then the five usual fields and an optional year. Week
+    /// days are numbered from Sunday = 1 and `?` stands for any day.
+    Quartz,
+    /// AWS EventBridge: the five usual fields and a year, optionally wrapped
+    /// in `cron(...)`. Week days are numbered from Sunday = 1 and `?` stands
+    /// for any day.
+    EventBridge,
+}
+
+/// Latest year EventBridge accepts, open-ended year steps stop there.
+const MAX_YEAR: i32 = 2199;
+
+/// How the fields of an expression are read.
+#[derive(Debug, Default, Clone, Copy)]
+struct Syntax<'a> {
+    dialect: Dialect,
+    locales: &'a [Locale],
+}
+
+impl Syntax<'_> {
+    fn week_days_from_one(&self) -> bool {
+        self.dialect != Dialect::Unix
+    }
+
+    /// Largest value of `field` once converted to crontab numbering.
+    fn max(&self, field: Field) -> u8 {
+        match field {
+            Field::WeekDay if self.week_days_from_one() => 6,
+            field => field.max(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Second,
+    Minute,
+    Hour,
+    MonthDay,
+    Month,
+    WeekDay,
+}
+
+impl Field {
+    pub const ALL: [Field; 5] = [
+        Field::Minute,
+        Field::Hour,
+        Field::MonthDay,
+        Field::Month,
+        Field::WeekDay,
+    ];
+
+    /// Smallest value accepted in crontab syntax.
+    pub fn min(&self) -> u8 {
+        match self {
+            Field::Second | Field::Minute | Field::Hour | Field::WeekDay => 0,
+            Field::MonthDay | Field::Month => 1,
+        }
+    }
+
+    /// Largest value accepted in crontab syntax, `7` being an alias for
+    /// Sunday in the week day field.
+    pub fn max(&self) -> u8 {
+        match self {
+            Field::Second | Field::Minute => 59,
+            Field::Hour => 23,
+            Field::MonthDay => 31,
+            Field::Month => 12,
+            Field::WeekDay => 7,
+        }
+    }
+}
+
+impl CronTask {
+    /// Parses a crontab line such as `*/5 9-17 * * 1-5 /usr/bin/backup`.
+    /// Everything after the fifth field is the command, a line with none
+    /// giving a schedule-only task.
+    pub fn parse(line: &str) -> Result<CronTask> {
+        line.parse()
+    }
+
+    /// Parses a 6-field line whose first field holds the seconds, such as
+    /// `*/15 * * * * * /usr/bin/poll`.
+    pub fn parse_with_seconds(line: &str) -> Result<CronTask> {
+        let (fields, rest) = split_fields(line, 1)?;
+        let second = parse_field(fields[0], Field::Second)?;
+
+        parse_line(rest)?.seconds(second).build()
+    }
+
+    /// Parses a system crontab line, as found in `/etc/crontab`, whose
+    /// schedule is followed by the user the command runs as, such as
+    /// `0 3 * * * backup /usr/bin/backup`.
+    pub fn parse_system(line: &str) -> Result<CronTask> {
+        parse_system_line(line)?.build()
+    }
+
+    /// Parses a crontab line like [`CronTask::parse`], also accepting the
+    /// day and month names of the given locales.
+    pub fn parse_localized(line: &str, locales: &[Locale]) -> Result<CronTask> {
+        let syntax = Syntax {
+            locales,
+            ..Syntax::default()
+        };
+
+        parse_line_with(line, syntax)?.build()
+    }
+
+    /// Parses a union of schedules separated by `;` or newlines, such as
+    /// `0 9 * * MON-FRI; 0 10 * * SAT /usr/bin/report`, into one task. The
+    /// command follows the last schedule, the others being included as
+    /// rules. Separators are only looked for after the five fields of a
+    /// schedule, so the command may hold `;` of its own.
+    pub fn parse_union(s: &str) -> Result<CronTask> {
+        let is_separator = |c: char| c == ';' || c.is_whitespace();
+        let mut schedules = Vec::new();
+        let mut rest = s.trim_start_matches(is_separator);
+
+        let mut builder = loop {
+            if rest.is_empty() {
+                break schedules
+                    .pop()
+                    .ok_or_else(|| CronError::InvalidExpression("no schedule".to_string()))?;
+            }
+            if rest.starts_with('@') {
+                break parse_line(rest)?;
+            }
+
+            let (fields, tail) = union_fields(rest)?;
+            let tail = tail.trim_start_matches(|c: char| c.is_whitespace() && c != '\n');
+            if tail.is_empty() || tail.starts_with([';', '\n']) {
+                schedules.push(schedule(&fields, Syntax::default())?);
+                rest = tail.trim_start_matches(is_separator);
+            } else {
+                break parse_line(rest)?;
+            }
+        };
+
+        for schedule in schedules {
+            builder = builder.include(schedule.build()?);
+        }
+
+        builder.build()
+    }
+
+    /// Parses a crontab line whose schedule fields hold `{name}`
+    /// placeholders, such as `{minute} {hour} * * * /usr/bin/backup`,
+    /// filling them from `values`. The command is left as is.
+    pub fn parse_template<K, V>(template: &str, values: &HashMap<K, V>) -> Result<CronTask>
+    where
+        K: Borrow<str> + Hash + Eq,
+        V: AsRef<str>,
+    {
+        let (fields, command) = split_fields(template, 5)?;
+        let fields = fields
+            .into_iter()
+            .map(|field| fill(field, values))
+            .collect::<Result<Vec<_>>>()?;
+
+        format!("{} {command}", fields.join(" ")).parse()
+    }
+
+    /// Parses a line written in `dialect`, such as Quartz'
+    /// `0 15 10 ? * 6#3 2025 /usr/bin/report` or EventBridge's
+    /// `cron(0 12 * * ? *) /usr/bin/report`. Everything after the schedule
+    /// is the command.
+    pub fn parse_with(dialect: Dialect, line: &str) -> Result<CronTask> {
+        let syntax = Syntax {
+            dialect,
+            ..Syntax::default()
+        };
+
+        parse_line_with(line, syntax)?.build()
+    }
+}
+
+impl FromStr for CronTask {
+    type Err = CronError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        parse_line(s)?.build()
+    }
+}
+
+/// Parses a crontab line into a builder, leaving room for metadata before
+/// the task is built.
+pub(crate) fn parse_line(line: &str) -> Result<CronTaskBuilder> {
+    parse_line_with(line, Syntax::default())
+}
+
+/// Same as `parse_line` for system crontab lines.
+pub(crate) fn parse_system_line(line: &str) -> Result<CronTaskBuilder> {
+    let count = if line.trim_start().starts_with('@') {
+        1
+    } else {
+        5
+    };
+    let (fields, rest) = split_fields(line, count)?;
+    let (user, command) = split_fields(rest, 1)?;
+    // Without one, a user crontab line would read its command as the user.
+    if command.is_empty() {
+        return Err(CronError::InvalidExpression(
+            "expected a user and a command".to_string(),
+        ));
+    }
+
+    Ok(parse_line(&format!("{} {command}", fields.join(" ")))?.run_as(user[0].parse()?))
+}
+
+fn parse_line_with(line: &str, syntax: Syntax) -> Result<CronTaskBuilder> {
+    match syntax.dialect {
+        Dialect::Unix => {
+            if line.trim_start().starts_with('@') {
+                let (fields, command) = split_fields(line, 1)?;
+                let task = nickname(fields[0], "")?;
+
+                return Ok(with_command(CronTaskBuilder::from_task(&task), command));
+            }
+
+            let (fields, command) = split_fields(line, 5)?;
+
+            Ok(with_command(schedule(&fields, syntax)?, command))
+        }
+        Dialect::Quartz => {
+            let (fields, rest) = split_fields(line, 6)?;
+            let second = parse_list(fields[0], syntax, |_| Some(Field::Second))?;
+
+            // The year is optional: a seventh field is only one if it reads
+            // as a year.
+            let (year, command) = match split_fields(rest, 1) {
+                Ok((year, command)) => match parse_year(year[0]) {
+                    Ok(year) => (year, command),
+                    Err(_) => (YearValue::All, rest),
+                },
+                Err(_) => (YearValue::All, rest),
+            };
+
+            let builder = schedule(&fields[1..], syntax)?.seconds(second).year(year);
+
+            Ok(with_command(builder, command))
+        }
+        Dialect::EventBridge => {
+            let (fields, command) = match line.trim_start().strip_prefix("cron(") {
+                Some(inner) => {
+                    let (expr, command) = inner.split_once(')').ok_or_else(|| {
+                        CronError::InvalidExpression("unclosed `cron(`".to_string())
+                    })?;
+                    let (fields, rest) = split_fields(expr, 6)?;
+
+                    if !rest.is_empty() {
+                        return Err(CronError::InvalidExpression(
+                            "expected 6 fields, found more".to_string(),
+                        ));
+                    }
+                    (fields, command.trim())
+                }
+                None => split_fields(line, 6)?,
+            };
+
+            let builder = schedule(&fields[..5], syntax)?.year(parse_year(fields[5])?);
+
+            Ok(with_command(builder, command))
+        }
+    }
+}
+
+/// Sets the command of a crontab line. As in cron, what follows its first
+/// `%` is the command's standard input, further ones standing for line
+/// breaks, and `\%` is a literal `%`.
+fn with_command(builder: CronTaskBuilder, command: &str) -> CronTaskBuilder {
+    let mut parts = vec![String::new()];
+    let mut chars = command.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.next_if_eq(&'%').is_some() => parts.last_mut().unwrap().push('%'),
+            '%' => parts.push(String::new()),
+            c => parts.last_mut().unwrap().push(c),
+        }
+    }
+
+    let builder = builder.path(parts[0].trim_end().to_string());
+    match parts.len() {
+        1 => builder,
+        _ => builder.stdin(parts[1..].join("\n")),
+    }
+}
+
+/// Builds the five usual schedule fields, in order. Expressions are often
+/// schedules alone, such as rules, so the command may be left out.
+fn schedule(fields: &[&str], syntax: Syntax) -> Result<CronTaskBuilder> {
+    let [minute, hour, month_day, month, week_day] = parse_fields(fields, syntax)?;
+    // Cron runs a line restricting both day fields on either of them, while
+    // Quartz and EventBridge expect `?` in one of them. As in Vixie cron, a
+    // field starting with `*` does not restrict: `*/2` week days are a list
+    // once parsed, yet the line still needs both fields to match.
+    let starred = |field: &str| field.starts_with('*');
+    let day_match = if syntax.dialect == Dialect::Unix && !starred(fields[2]) && !starred(fields[4])
+    {
+        DayMatch::Any
+    } else {
+        DayMatch::All
+    };
+
+    Ok(CronTask::builder()
+        .allow_commandless()
+        .day_match(day_match)
+        .minutes(minute)
+        .hour(hour)
+        .month_day(month_day)
+        .month(month)
+        .week_day(week_day))
+}
+
+/// Expands the `@reboot`, `@hourly`, `@daily`, `@weekly`, `@monthly` and
+/// `@yearly` shortcuts along with their `@midnight` and `@annually` aliases.
+fn nickname(name: &str, command: &str) -> Result<CronTask> {
+    let preset = match name {
+        "@reboot" => CronTask::reboot,
+        "@hourly" => CronTask::hourly,
+        "@daily" | "@midnight" => CronTask::daily,
+        "@weekly" => CronTask::weekly,
+        "@monthly" => CronTask::monthly,
+        "@yearly" | "@annually" => CronTask::yearly,
+        _ => {
+            return Err(CronError::InvalidExpression(format!(
+                "unknown nickname `{name}`"
+            )));
+        }
+    };
+
+    Ok(preset(command))
+}
+
+/// Splits the first `count` whitespace separated fields from the rest of
+/// the line, which is returned trimmed.
+pub(crate) fn split_fields(line: &str, count: usize) -> Result<(Vec<&str>, &str)> {
+    let mut fields = Vec::with_capacity(count);
+    let mut rest = line.trim_start();
+
+    while fields.len() < count {
+        if rest.is_empty() {
+            return Err(CronError::InvalidExpression(format!(
+                "expected {count} fields, found {}",
+                fields.len()
+            )));
+        }
+
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        fields.push(&rest[..end]);
+        rest = rest[end..].trim_start();
+    }
+
+    Ok((fields, rest.trim_end()))
+}
+
+/// Parses a schedule with no command: five fields, six with a leading
+/// seconds field, or a nickname such as `@daily`.
+#[cfg(any(feature = "env", feature = "serde"))]
+pub(crate) fn parse_bare_schedule(schedule: &str) -> Result<CronTaskBuilder> {
+    match schedule.split_whitespace().count() {
+        6 => {
+            let (fields, rest) = split_fields(schedule, 1)?;
+            Ok(parse_line(rest)?.seconds(parse_field(fields[0], Field::Second)?))
+        }
+        1 | 5 => parse_line(schedule),
+        count => Err(CronError::InvalidExpression(format!(
+            "expected 5 or 6 schedule fields, found {count}"
+        ))),
+    }
+}
+
+/// Parses the five schedule fields of a crontab line, in order.
+pub fn parse_schedule(expr: &str) -> Result<[CronValue; 5]> {
+    let fields = split_exact(expr, 5)?;
+
+    parse_fields(&fields, Syntax::default())
+}
+
+/// Replaces the `{name}` placeholders of `s` with their value.
+fn fill<K, V>(s: &str, values: &HashMap<K, V>) -> Result<String>
+where
+    K: Borrow<str> + Hash + Eq,
+    V: AsRef<str>,
+{
+    let mut filled = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(start) = rest.find('{') {
+        let (name, after) = rest[start + 1..].split_once('}').ok_or_else(|| {
+            CronError::InvalidExpression(format!("unclosed placeholder in `{s}`"))
+        })?;
+        let value = values
+            .get(name)
+            .ok_or_else(|| CronError::MissingPlaceholder(name.to_string()))?;
+
+        filled.push_str(&rest[..start]);
+        filled.push_str(value.as_ref());
+        rest = after;
+    }
+    filled.push_str(rest);
+
+    Ok(filled)
+}
+
+/// Splits an expression into exactly `count` whitespace separated fields.
+/// Splits the five fields of a schedule off a union, a field ending at
+/// whitespace or at the `;` closing the schedule.
+fn union_fields(s: &str) -> Result<(Vec<&str>, &str)> {
+    let mut fields = Vec::new();
+    let mut rest = s;
+
+    while fields.len() < 5 {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() && c != '\n');
+        let end = rest
+            .find(|c: char| c == ';' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        if end == 0 {
+            return Err(CronError::InvalidExpression(format!(
+                "expected 5 fields, found {}",
+                fields.len()
+            )));
+        }
+        fields.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+
+    Ok((fields, rest))
+}
+
+fn split_exact(expr: &str, count: usize) -> Result<Vec<&str>> {
+    let fields: Vec<&str> = expr.split_whitespace().collect();
+
+    if fields.len() != count {
+        return Err(CronError::InvalidExpression(format!(
+            "expected {count} fields, found {}",
+            fields.len()
+        )));
+    }
+
+    Ok(fields)
+}
+
+fn parse_fields(fields: &[&str], syntax: Syntax) -> Result<[CronValue; 5]> {
+    let mut values = Field::ALL
+        .iter()
+        .zip(fields)
+        .map(|(field, s)| parse_list(s, syntax, |_| Some(*field)));
+
+    let values = [
+        values.next().unwrap()?,
+        values.next().unwrap()?,
+        values.next().unwrap()?,
+        values.next().unwrap()?,
+        values.next().unwrap()?,
+    ];
+
+    let [_, _, month_day, month, week_day] = &values;
+    if matches!(week_day, CronValue::All)
+        && !month_day.has_month_day_items()
+        && !occurs(month_day, month)
+    {
+        return Err(CronError::Correctable {
+            message: format!(
+                "MonthDay `{}` never occurs in Month `{}`",
+                fields[2], fields[3]
+            ),
+            value: fields[2].to_string(),
+            suggestion: "L".to_string(),
+        });
+    }
+
+    Ok(values)
+}
+
+/// Whether some day of `month_day` exists in some month of `month`, leap
+/// years included.
+fn occurs(month_day: &CronValue, month: &CronValue) -> bool {
+    const DAYS: [u8; 12] = [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+
+    (1..=12)
+        .filter(|m| month.matches_in(*m, 1))
+        .any(|m| (1..=DAYS[usize::from(m - 1)]).any(|day| month_day.matches_in(day, 1)))
+}
+
+/// Parses a single crontab field into the DSL representation. Week days use
+/// crontab numbering (Sunday is 0 or 7) and are translated to the DSL one
+/// (Monday is 0). Three letter day and month names are accepted in their
+/// respective fields.
+pub fn parse_field(s: &str, field: Field) -> Result<CronValue> {
+    parse_field_localized(s, field, &[])
+}
+
+/// Parses a single field like [`parse_field`], also accepting the day and
+/// month names of the given locales.
+pub fn parse_field_localized(s: &str, field: Field, locales: &[Locale]) -> Result<CronValue> {
+    let syntax = Syntax {
+        locales,
+        ..Syntax::default()
+    };
+
+    parse_list(s, syntax, |_| Some(field))
+}
+
+/// Parses a Quartz or EventBridge year field, such as `2025-2030` or
+/// `2025/2`.
+pub fn parse_year(s: &str) -> Result<YearValue> {
+    let mut items = s
+        .split(',')
+        .map(parse_year_item)
+        .collect::<Result<Vec<_>>>()?;
+
+    if items.len() == 1 {
+        Ok(items.remove(0))
+    } else {
+        Ok(YearValue::List(items))
+    }
+}
+
+fn parse_year_item(item: &str) -> Result<YearValue> {
+    let invalid = || CronError::InvalidExpression(format!("invalid year value `{item}`"));
+    let year = |s: &str| s.parse::<i32>().map_err(|_| invalid());
+
+    let (base, step) = match item.split_once('/') {
+        Some((base, step)) => {
+            let step = step.parse::<u16>().ok().filter(|step| *step > 0);
+            (base, Some(step.ok_or_else(invalid)?))
+        }
+        None => (item, None),
+    };
+
+    let base = match base.split_once('-') {
+        _ if base == "*" => YearValue::All,
+        Some((start, end)) => {
+            let (start, end) = (year(start)?, year(end)?);
+
+            if start > end {
+                return Err(invalid());
+            }
+            YearValue::Range(start..=end)
+        }
+        None if step.is_some() => YearValue::Range(year(base)?..=MAX_YEAR),
+        None => YearValue::Value(year(base)?),
+    };
+
+    Ok(match step {
+        Some(step) => base.every(step),
+        None => base,
+    })
+}
+
+impl FromStr for CronValue {
+    type Err = CronError;
+
+    /// Parses a field without knowing which one it belongs to. Items using
+    /// day or month names are read as such, numbers are kept as is.
+    fn from_str(s: &str) -> Result<Self> {
+        parse_list(s, Syntax::default(), |item| infer_field(item, &[]))
+    }
+}
+
+fn parse_list<F>(s: &str, syntax: Syntax, field_of: F) -> Result<CronValue>
+where
+    F: Fn(&str) -> Option<Field>,
+{
+    let mut items = s
+        .split(',')
+        .map(|item| parse_item(item, field_of(item), syntax))
+        .collect::<Result<Vec<_>>>()?;
+
+    if items.len() == 1 {
+        Ok(items.remove(0))
+    } else {
+        Ok(CronValue::List(items))
+    }
+}
+
+fn parse_item(item: &str, field: Option<Field>, syntax: Syntax) -> Result<CronValue> {
+    if item == "?"
+        && syntax.dialect != Dialect::Unix
+        && matches!(field, Some(Field::MonthDay | Field::WeekDay))
+    {
+        return Ok(CronValue::All);
+    }
+
+    if let Some(rest) = item.strip_prefix('H') {
+        return parse_hashed(item, rest, field, syntax);
+    }
+
+    if field == Some(Field::MonthDay) {
+        if item.eq_ignore_ascii_case("L") {
+            return Ok(CronValue::Last);
+        }
+        if let Some(day) = item.strip_suffix(['W', 'w']) {
+            return Ok(CronValue::NearestWeekday(parse_number(day, field, syntax)?));
+        }
+    }
+
+    if let (Some(Field::WeekDay), Some((day, n))) = (field, item.split_once('#')) {
+        let day = parse_number(day, field, syntax)?;
+        let n = n
+            .parse::<u8>()
+            .ok()
+            .filter(|n| (1..=5).contains(n))
+            .ok_or_else(|| invalid(item, field))?;
+
+        return Ok(CronValue::Nth(((day + 6) % 7).into(), n));
+    }
+
+    let (base, step) = match item.split_once('/') {
+        Some((base, step)) => (base, Some(parse_step(step, field)?)),
+        None => (item, None),
+    };
+
+    let bounds = match base {
+        "*" => None,
+        _ => match base.split_once('-') {
+            Some((start, end)) => {
+                let start = parse_number(start, field, syntax)?;
+                let end = parse_number(end, field, syntax)?;
+
+                if start > end {
+                    return Err(invalid(item, field));
+                }
+                Some((start, end))
+            }
+            None => {
+                let v = parse_number(base, field, syntax)?;
+
+                match (step, field) {
+                    (Some(_), Some(field)) => Some((v, syntax.max(field))),
+                    (Some(_), None) => return Err(invalid(item, field)),
+                    (None, _) => Some((v, v)),
+                }
+            }
+        },
+    };
+
+    if field == Some(Field::WeekDay) {
+        return Ok(week_day_value(bounds, step));
+    }
+
+    let base = match bounds {
+        None => CronValue::All,
+        Some((start, end)) if start == end => start.into(),
+        Some((start, end)) => CronValue::Range(start..end),
+    };
+
+    Ok(match step {
+        Some(step) => CronValue::Interval(Box::new(base), ValueKind::Number(step)),
+        None => base,
+    })
+}
+
+/// Parses what follows `H`: optional `(start-end)` bounds, then an optional
+/// `/step`.
+fn parse_hashed(item: &str, rest: &str, field: Option<Field>, syntax: Syntax) -> Result<CronValue> {
+    let (bounds, rest) = match rest.strip_prefix('(') {
+        Some(inner) => {
+            let (range, rest) = inner.split_once(')').ok_or_else(|| invalid(item, field))?;
+            let (start, end) = range.split_once('-').ok_or_else(|| invalid(item, field))?;
+            let start = parse_number(start, field, syntax)?;
+            let end = parse_number(end, field, syntax)?;
+
+            if start > end {
+                return Err(invalid(item, field));
+            }
+            (Some((start, end)), rest)
+        }
+        None => (None, rest),
+    };
+
+    let step = match rest {
+        "" => None,
+        _ => match rest.strip_prefix('/') {
+            Some(step) => Some(parse_step(step, field)?),
+            None => return Err(invalid(item, field)),
+        },
+    };
+
+    // Bounded week days are translated to the DSL numbering, which cannot
+    // hold a range starting on Sunday.
+    let bounds = match (field, bounds) {
+        (Some(Field::WeekDay), Some((0, _))) => return Err(invalid(item, field)),
+        (Some(Field::WeekDay), Some((start, end))) => Some((start - 1, end - 1)),
+        (_, bounds) => bounds,
+    };
+
+    Ok(CronValue::Hashed(bounds, step))
+}
+
+fn week_day_value(bounds: Option<(u8, u8)>, step: Option<u8>) -> CronValue {
+    let to_dsl = |day: u8| (day + 6) % 7;
+
+    match (bounds, step) {
+        (None, None) => CronValue::All,
+        (Some((start, end)), None) if start == end => to_dsl(start).into(),
+        (Some((start, end)), None) if start > 0 => CronValue::Range(start - 1..end - 1),
+        // Without Sunday the range shifts as a whole and keeps its step.
+        (Some((start, end)), Some(step)) if start > 0 && start < end => CronValue::Interval(
+            Box::new(CronValue::Range(start - 1..end - 1)),
+            ValueKind::Number(step),
+        ),
+        (bounds, step) => {
+            let (start, end) = bounds.unwrap_or((0, 6));
+            let mut days: Vec<u8> = (start..=end)
+                .step_by(step.unwrap_or(1) as usize)
+                .map(to_dsl)
+                .collect();
+            days.sort();
+            days.dedup();
+
+            if days.len() == 1 {
+                days[0].into()
+            } else {
+                CronValue::List(days.into_iter().map(CronValue::from).collect())
+            }
+        }
+    }
+}
+
+/// Day and month names of a language, accepted by the parser in addition to
+/// the English abbreviations. Names are compared case-insensitively.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Locale {
+    week_days: [String; 7],
+    months: [String; 12],
+}
+
+impl Locale {
+    /// Week days start on Sunday as in crontab, months on January.
+    pub fn new(week_days: [&str; 7], months: [&str; 12]) -> Self {
+        Self {
+            week_days: week_days.map(str::to_lowercase),
+            months: months.map(str::to_lowercase),
+        }
+    }
+
+    pub fn french() -> Self {
+        Self::new(
+            ["dim", "lun", "mar", "mer", "jeu", "ven", "sam"],
+            [
+                "janv", "févr", "mars", "avr", "mai", "juin", "juil", "août", "sept", "oct", "nov",
+                "déc",
+            ],
+        )
+    }
+
+    pub fn german() -> Self {
+        Self::new(
+            ["so", "mo", "di", "mi", "do", "fr", "sa"],
+            [
+                "jan", "feb", "mär", "apr", "mai", "jun", "jul", "aug", "sep", "okt", "nov", "dez",
+            ],
+        )
+    }
+
+    pub fn spanish() -> Self {
+        Self::new(
+            ["dom", "lun", "mar", "mié", "jue", "vie", "sáb"],
+            [
+                "ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sep", "oct", "nov", "dic",
+            ],
+        )
+    }
+}
+
+const WEEK_DAYS: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
+fn name_index(names: &[&str], s: &str) -> Option<u8> {
+    names
+        .iter()
+        .position(|name| name.eq_ignore_ascii_case(s))
+        .map(|i| i as u8)
+}
+
+fn week_day_index(s: &str, locales: &[Locale]) -> Option<u8> {
+    name_index(&WEEK_DAYS, s).or_else(|| {
+        locales
+            .iter()
+            .find_map(|l| localized_index(&l.week_days, s))
+    })
+}
+
+fn month_index(s: &str, locales: &[Locale]) -> Option<u8> {
+    name_index(&MONTHS, s).or_else(|| locales.iter().find_map(|l| localized_index(&l.months, s)))
+}
+
+fn localized_index(names: &[String], s: &str) -> Option<u8> {
+    let s = s.to_lowercase();
+    names.iter().position(|name| *name == s).map(|i| i as u8)
+}
+
+fn infer_field(item: &str, locales: &[Locale]) -> Option<Field> {
+    let atoms = || item.split(['-', '/']);
+
+    if atoms().any(|atom| week_day_index(atom, locales).is_some()) {
+        Some(Field::WeekDay)
+    } else if atoms().any(|atom| month_index(atom, locales).is_some()) {
+        Some(Field::Month)
+    } else {
+        None
+    }
+}
+
+fn parse_number(s: &str, field: Option<Field>, syntax: Syntax) -> Result<u8> {
+    let name = match field {
+        Some(Field::WeekDay) => week_day_index(s, syntax.locales),
+        Some(Field::Month) => month_index(s, syntax.locales).map(|i| i + 1),
+        _ => None,
+    };
+    if let Some(v) = name {
+        return Ok(v);
+    }
+
+    let v = s.parse::<u32>().map_err(|_| invalid(s, field))?;
+    let Some(field) = field else {
+        return u8::try_from(v).map_err(|_| invalid(s, None));
+    };
+    let from_one = field == Field::WeekDay && syntax.week_days_from_one();
+    let (min, max) = if from_one {
+        (1, 7)
+    } else {
+        (field.min(), field.max())
+    };
+
+    if !(u32::from(min)..=u32::from(max)).contains(&v) {
+        // Sunday is 0 in crontab numbering and 1 in dialects counting from
+        // one, the name reads the same in both.
+        let suggestion = if from_one && v == 0 {
+            "SUN".to_string()
+        } else {
+            v.clamp(min.into(), max.into()).to_string()
+        };
+        return Err(CronError::Correctable {
+            message: format!("{field:?} must be within {min}-{max}"),
+            value: s.to_string(),
+            suggestion,
+        });
+    }
+
+    Ok(v as u8 - u8::from(from_one))
+}
+
+fn parse_step(s: &str, field: Option<Field>) -> Result<u8> {
+    s.parse::<u8>()
+        .ok()
+        .filter(|v| *v > 0 && field.is_none_or(|field| *v <= field.max()))
+        .ok_or_else(|| invalid(s, field))
+}
+
+fn invalid(s: &str, field: Option<Field>) -> CronError {
+    match field {
+        Some(field) => CronError::InvalidExpression(format!("invalid {field:?} value `{s}`")),
+        None => CronError::InvalidExpression(format!("invalid value `{s}`")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run_as::RunAs;
+
+    #[test]
+    fn test_parse_field_all() {
+        assert!(matches!(
+            parse_field("*", Field::Minute).unwrap(),
+            CronValue::All
+        ));
+    }
+
+    #[test]
+    fn test_parse_field_value() {
+        let value = parse_field("30", Field::Minute).unwrap();
+
+        assert_eq!(value.to_string(), "30");
+        assert!(value.matches(30));
+    }
+
+    #[test]
+    fn test_parse_field_range() {
+        let value = parse_field("9-17", Field::Hour).unwrap();
+
+        assert_eq!(value.to_string(), "9-17");
+        assert!(value.matches(9));
+        assert!(value.matches(17));
+        assert!(!value.matches(18));
+    }
+
+    #[test]
+    fn test_parse_field_interval() {
+        assert_eq!(
+            parse_field("*/5", Field::Minute).unwrap().to_string(),
+            "*/5"
+        );
+        assert_eq!(
+            parse_field("10-30/5", Field::Minute).unwrap().to_string(),
+            "10-30/5"
+        );
+        assert_eq!(
+            parse_field("10/5", Field::Minute).unwrap().to_string(),
+            "10-59/5"
+        );
+    }
+
+    #[test]
+    fn test_parse_field_list() {
+        let value = parse_field("1,5,10-12", Field::Minute).unwrap();
+
+        assert_eq!(value.to_string(), "1,5,10-12");
+        assert!(value.matches(11));
+        assert!(!value.matches(6));
+    }
+
+    #[test]
+    fn test_parse_field_out_of_bounds() {
+        assert!(parse_field("60", Field::Minute).is_err());
+        assert!(parse_field("0", Field::MonthDay).is_err());
+        assert!(parse_field("13", Field::Month).is_err());
+        assert!(parse_field("8", Field::WeekDay).is_err());
+    }
+
+    #[test]
+    fn test_parse_field_malformed() {
+        assert!(parse_field("", Field::Minute).is_err());
+        assert!(parse_field("a", Field::Minute).is_err());
+        assert!(parse_field("5-1", Field::Minute).is_err());
+        assert!(parse_field("*/0", Field::Minute).is_err());
+        assert!(parse_field("1,,2", Field::Minute).is_err());
+    }
+
+    #[test]
+    fn test_parse_week_day_numbering() {
+        assert_eq!(
+            parse_field("1-5", Field::WeekDay).unwrap().to_string(),
+            "0-4"
+        );
+        assert_eq!(parse_field("0", Field::WeekDay).unwrap().to_string(), "6");
+        assert_eq!(parse_field("7", Field::WeekDay).unwrap().to_string(), "6");
+        assert_eq!(
+            parse_field("0-1", Field::WeekDay).unwrap().to_string(),
+            "0,6"
+        );
+        assert_eq!(
+            parse_field("*/2", Field::WeekDay).unwrap().to_string(),
+            "1,3,5,6"
+        );
+    }
+
+    #[test]
+    fn test_parse_field_names() {
+        assert_eq!(
+            parse_field("MON-FRI", Field::WeekDay).unwrap().to_string(),
+            "0-4"
+        );
+        assert_eq!(parse_field("sun", Field::WeekDay).unwrap().to_string(), "6");
+        assert_eq!(
+            parse_field("JAN-MAR,DEC", Field::Month)
+                .unwrap()
+                .to_string(),
+            "1-3,12"
+        );
+        assert!(parse_field("MON", Field::Month).is_err());
+        assert!(parse_field("LUN", Field::WeekDay).is_err());
+    }
+
+    #[test]
+    fn test_parse_last_day() {
+        assert!(matches!(
+            parse_field("L", Field::MonthDay).unwrap(),
+            CronValue::Last
+        ));
+        assert_eq!(
+            parse_field("1,L", Field::MonthDay).unwrap().to_string(),
+            "1,L"
+        );
+        assert!(parse_field("L", Field::Hour).is_err());
+    }
+
+    #[test]
+    fn test_parse_nearest_weekday() {
+        let value = parse_field("15W", Field::MonthDay).unwrap();
+
+        assert!(matches!(value, CronValue::NearestWeekday(15)));
+        assert_eq!(value.to_string(), "15W");
+        assert!(parse_field("0W", Field::MonthDay).is_err());
+        assert!(parse_field("32W", Field::MonthDay).is_err());
+        assert!(parse_field("W", Field::MonthDay).is_err());
+        assert!(parse_field("15W", Field::Hour).is_err());
+    }
+
+    #[test]
+    fn test_parse_nth_week_day() {
+        assert_eq!(
+            parse_field("MON#2", Field::WeekDay).unwrap().to_string(),
+            "0#2"
+        );
+        assert_eq!(
+            parse_field("0#1", Field::WeekDay).unwrap().to_string(),
+            "6#1"
+        );
+        assert!(parse_field("MON#6", Field::WeekDay).is_err());
+        assert!(parse_field("MON#0", Field::WeekDay).is_err());
+        assert!(parse_field("1#2", Field::MonthDay).is_err());
+        assert!(parse_field("JAN", Field::Minute).is_err());
+    }
+
+    #[test]
+    fn test_cron_value_from_str() {
+        let value: CronValue = "1-10/2".parse().unwrap();
+
+        assert!(matches!(
+            &value,
+            CronValue::Interval(base, ValueKind::Number(2)) if matches!(**base, CronValue::Range(_))
+        ));
+        assert_eq!(value.to_string(), "1-10/2");
+        assert_eq!("*/15".parse::<CronValue>().unwrap().to_string(), "*/15");
+        assert_eq!("42".parse::<CronValue>().unwrap().to_string(), "42");
+    }
+
+    #[test]
+    fn test_cron_value_from_str_with_names() {
+        let value: CronValue = "1,5,MON-FRI".parse().unwrap();
+
+        assert_eq!(value.to_string(), "1,5,0-4");
+        assert_eq!("JUN-AUG".parse::<CronValue>().unwrap().to_string(), "6-8");
+    }
+
+    #[test]
+    fn test_cron_value_from_str_invalid() {
+        assert!("".parse::<CronValue>().is_err());
+        assert!("5/2".parse::<CronValue>().is_err());
+        assert!("*/0".parse::<CronValue>().is_err());
+        assert!("MON-JAN".parse::<CronValue>().is_err());
+        assert!("256".parse::<CronValue>().is_err());
+    }
+
+    #[test]
+    fn test_parse_schedule() {
+        let fields = parse_schedule("*/5 9-17 * * 1-5").unwrap();
+        let display: Vec<String> = fields.iter().map(|v| v.to_string()).collect();
+
+        assert_eq!(display, ["*/5", "9-17", "*", "*", "0-4"]);
+    }
+
+    #[test]
+    fn test_parse_schedule_field_count() {
+        assert!(parse_schedule("* * * *").is_err());
+        assert!(parse_schedule("* * * * * *").is_err());
+    }
+
+    #[test]
+    fn test_split_fields() {
+        let (fields, rest) = split_fields("  0 5   * * 1 /usr/bin/backup --full  ", 5).unwrap();
+
+        assert_eq!(fields, ["0", "5", "*", "*", "1"]);
+        assert_eq!(rest, "/usr/bin/backup --full");
+        assert!(split_fields("0 5 *", 5).is_err());
+    }
+
+    #[test]
+    fn test_parse_task() {
+        let task = CronTask::parse("*/5 9-17 * * 1-5 /usr/bin/backup").unwrap();
+
+        assert_eq!(task.to_string(), "*/5 9-17 * * 0-4 \"/usr/bin/backup\"");
+    }
+
+    #[test]
+    fn test_parse_task_from_str() {
+        let task: CronTask = "30 2 1 * * /usr/bin/report --monthly".parse().unwrap();
+
+        assert_eq!(task.to_string(), "30 2 1 * * \"/usr/bin/report --monthly\"");
+    }
+
+    #[test]
+    fn test_parse_task_without_command() {
+        let task = CronTask::parse("0 0 * * *").unwrap();
+
+        assert_eq!(task.to_string(), "0 0 * * * \"\"");
+    }
+
+    #[test]
+    fn test_parse_task_matches() {
+        use chrono::{Local, TimeZone};
+
+        let task = CronTask::parse("*/15 9-17 * * 1-5 /usr/bin/backup").unwrap();
+
+        // 2024-06-14 is a Friday, 2024-06-15 a Saturday.
+        assert!(task.matches(Local.with_ymd_and_hms(2024, 6, 14, 9, 45, 0).unwrap()));
+        assert!(!task.matches(Local.with_ymd_and_hms(2024, 6, 14, 9, 50, 0).unwrap()));
+        assert!(!task.matches(Local.with_ymd_and_hms(2024, 6, 15, 9, 45, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_parse_task_day_fields() {
+        use chrono::{Local, TimeZone};
+
+        let at = |day: u32| Local.with_ymd_and_hms(2024, 6, day, 0, 0, 0).unwrap();
+
+        // The first of the month or any Monday, 2024-06-01 being a Saturday.
+        let task = CronTask::parse("0 0 1 * 1 /bin/true").unwrap();
+        assert!(task.matches(at(1)));
+        assert!(task.matches(at(3)));
+        assert!(!task.matches(at(4)));
+        assert_eq!(task.try_next_occurrence(at(1)), Some(at(3)));
+
+        // The 1st, the 15th, or any Friday.
+        let task = CronTask::parse("0 0 1,15 * 5 /bin/true").unwrap();
+        let runs: Vec<_> =
+            std::iter::successors(Some(at(1)), |from| task.try_next_occurrence(*from))
+                .skip(1)
+                .take(5)
+                .collect();
+        assert_eq!(runs, [at(7), at(14), at(15), at(21), at(28)]);
+
+        // Vixie cron sees a starred field as unrestricted and needs both to
+        // match: the 1st when it is a Sunday, Tuesday, Thursday or Saturday.
+        let at = |month: u32, day: u32| Local.with_ymd_and_hms(2025, month, day, 0, 0, 0).unwrap();
+        let task = CronTask::parse("0 0 1 * */2 /bin/true").unwrap();
+        assert!(!task.matches_either_day());
+        assert!(!task.matches(at(6, 3)));
+        assert!(!task.matches(at(9, 1)));
+        assert!(task.matches(at(6, 1)));
+        assert_eq!(task.try_next_occurrence(at(6, 1)), Some(at(7, 1)));
+    }
+
+    #[test]
+    fn test_parse_with_seconds() {
+        let task = CronTask::parse_with_seconds("*/15 * 9-17 * * 1-5 /usr/bin/poll").unwrap();
+
+        assert_eq!(task.second().unwrap().to_string(), "*/15");
+        assert_eq!(task.to_string(), "*/15 * 9-17 * * 0-4 \"/usr/bin/poll\"");
+        assert!(CronTask::parse_with_seconds("60 * * * * * /usr/bin/poll").is_err());
+        assert!(CronTask::parse_with_seconds("* * * * * ").is_err());
+    }
+
+    #[test]
+    fn test_parse_nicknames() {
+        let line = |s: &str| CronTask::parse(s).unwrap().to_string();
+
+        assert_eq!(line("@hourly /bin/true"), "0 * * * * \"/bin/true\"");
+        assert_eq!(line("@daily /bin/true"), "0 0 * * * \"/bin/true\"");
+        assert_eq!(line("@midnight /bin/true"), "0 0 * * * \"/bin/true\"");
+        assert_eq!(line("@weekly /bin/true"), "0 0 * * Sun \"/bin/true\"");
+        assert_eq!(line("@monthly /bin/true"), "0 0 1 * * \"/bin/true\"");
+        assert_eq!(line("@yearly /bin/true"), "0 0 1 1 * \"/bin/true\"");
+        assert_eq!(line("@annually /bin/true"), "0 0 1 1 * \"/bin/true\"");
+    }
+
+    #[test]
+    fn test_parse_reboot() {
+        let task = CronTask::parse("@reboot /usr/bin/warmup --fast").unwrap();
+
+        assert!(task.is_reboot());
+        assert_eq!(task.path(), std::path::Path::new("/usr/bin/warmup --fast"));
+    }
+
+    #[test]
+    fn test_parse_unknown_nickname() {
+        assert!(CronTask::parse("@fortnightly /bin/true").is_err());
+        assert!(CronTask::parse("@").is_err());
+    }
+
+    #[test]
+    fn test_parse_task_invalid() {
+        assert!(CronTask::parse("").is_err());
+        assert!(CronTask::parse("* * * /usr/bin/backup").is_err());
+        assert!(CronTask::parse("* 24 * * * /usr/bin/backup").is_err());
+    }
+
+    #[test]
+    fn test_parse_system() {
+        let task = CronTask::parse_system("0 3 * * * backup:disk /usr/bin/backup --all").unwrap();
+        #[cfg(feature = "crontab")]
+        assert_eq!(task.to_crontab_line(), "0 3 * * * /usr/bin/backup --all");
+        assert_eq!(
+            task.run_as(),
+            Some(&RunAs::new("backup").with_group("disk"))
+        );
+
+        let reboot = CronTask::parse_system("@reboot root /usr/bin/warmup").unwrap();
+        assert!(reboot.is_reboot());
+        assert_eq!(reboot.run_as().map(RunAs::user), Some("root"));
+
+        assert!(CronTask::parse_system("0 3 * * * root").is_err());
+    }
+
+    #[test]
+    fn test_parse_suggestions() {
+        let suggestion = |dialect: Dialect, line: &str| {
+            CronTask::parse_with(dialect, line)
+                .unwrap_err()
+                .suggestion()
+                .map(str::to_string)
+        };
+
+        assert_eq!(
+            suggestion(Dialect::Unix, "75 24 * * * /bin/true").as_deref(),
+            Some("59")
+        );
+        assert_eq!(
+            suggestion(Dialect::Unix, "0 9 0 * * /bin/true").as_deref(),
+            Some("1")
+        );
+        assert_eq!(
+            suggestion(Dialect::Quartz, "0 0 0 ? * 0").as_deref(),
+            Some("SUN")
+        );
+        assert_eq!(suggestion(Dialect::Unix, "0 9 x * * /bin/true"), None);
+
+        let error = CronTask::parse("0 0 30,31 2 * /bin/true").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid cron expression: MonthDay `30,31` never occurs in Month `2`, try `L` instead of `30,31`."
+        );
+        assert!(CronTask::parse("0 0 29 2 * /bin/true").is_ok());
+        assert!(CronTask::parse("0 0 30 2 1 /bin/true").is_ok());
+    }
+
+    #[test]
+    fn test_parse_localized_names() {
+        let locales = [Locale::french(), Locale::german()];
+
+        assert_eq!(
+            parse_field_localized("lun-ven", Field::WeekDay, &locales)
+                .unwrap()
+                .to_string(),
+            "0-4"
+        );
+        assert_eq!(
+            parse_field_localized("Mo", Field::WeekDay, &locales)
+                .unwrap()
+                .to_string(),
+            "0"
+        );
+        assert_eq!(
+            parse_field_localized("FÉVR,Dez", Field::Month, &locales)
+                .unwrap()
+                .to_string(),
+            "2,12"
+        );
+        assert_eq!(
+            parse_field_localized("MAR", Field::Month, &locales)
+                .unwrap()
+                .to_string(),
+            "3"
+        );
+        assert!(parse_field_localized("lunes", Field::WeekDay, &locales).is_err());
+    }
+
+    #[test]
+    fn test_parse_localized_task() {
+        let task =
+            CronTask::parse_localized("0 8 * * sáb /usr/bin/report", &[Locale::spanish()]).unwrap();
+
+        assert_eq!(task.week_day().to_string(), "5");
+        assert!(CronTask::parse("0 8 * * sáb /usr/bin/report").is_err());
+    }
+
+    #[test]
+    fn test_parse_quartz() {
+        let task =
+            CronTask::parse_with(Dialect::Quartz, "0 15 10 ? * 6#3 /usr/bin/report").unwrap();
+
+        assert_eq!(task.second().unwrap().to_string(), "0");
+        assert_eq!(task.month_day().to_string(), "*");
+        assert_eq!(task.week_day().to_string(), "4#3");
+        assert_eq!(task.year(), &YearValue::All);
+        assert_eq!(task.path(), std::path::Path::new("/usr/bin/report"));
+
+        let task =
+            CronTask::parse_with(Dialect::Quartz, "0 0 12 * * 2-6 2025/2 /usr/bin/report").unwrap();
+
+        assert_eq!(task.week_day().to_string(), "0-4");
+        assert_eq!(task.year().to_string(), "2025-2199/2");
+        assert_eq!(task.path(), std::path::Path::new("/usr/bin/report"));
+    }
+
+    #[test]
+    fn test_parse_quartz_week_days() {
+        let week_day = |expr: &str| {
+            CronTask::parse_with(Dialect::Quartz, &format!("0 0 0 ? * {expr}"))
+                .unwrap()
+                .week_day()
+                .to_string()
+        };
+
+        assert_eq!(week_day("1"), "6");
+        assert_eq!(week_day("7"), "5");
+        assert_eq!(week_day("MON"), "0");
+        assert_eq!(week_day("2/2"), "0-5/2");
+        assert!(CronTask::parse_with(Dialect::Quartz, "0 0 0 ? * 0").is_err());
+    }
+
+    #[test]
+    fn test_parse_event_bridge() {
+        let task = CronTask::parse_with(
+            Dialect::EventBridge,
+            "cron(0 18 ? * MON-FRI *) /usr/bin/report",
+        )
+        .unwrap();
+
+        assert_eq!(task.minute().to_string(), "0");
+        assert_eq!(task.hour().to_string(), "18");
+        assert_eq!(task.week_day().to_string(), "0-4");
+        assert!(task.second().is_none());
+        assert_eq!(task.path(), std::path::Path::new("/usr/bin/report"));
+
+        let task =
+            CronTask::parse_with(Dialect::EventBridge, "0/15 * L * ? 2025-2026 /bin/true").unwrap();
+        assert_eq!(task.year().to_string(), "2025-2026");
+
+        assert!(CronTask::parse_with(Dialect::EventBridge, "cron(0 18 ? * MON-FRI)").is_err());
+        assert!(CronTask::parse_with(Dialect::EventBridge, "cron(0 18 ? * * * *)").is_err());
+        assert!(CronTask::parse_with(Dialect::EventBridge, "cron(0 18 ? * * *").is_err());
+    }
+
+    #[test]
+    fn test_parse_unix_rejects_question_mark() {
+        assert!(CronTask::parse_with(Dialect::Unix, "0 0 ? * * /bin/true").is_err());
+        assert!(CronTask::parse_with(Dialect::Unix, "0 0 1 * * /bin/true").is_ok());
+    }
+
+    #[test]
+    fn test_parse_year() {
+        assert_eq!(parse_year("*").unwrap(), YearValue::All);
+        assert_eq!(parse_year("2025").unwrap(), YearValue::Value(2025));
+        assert_eq!(
+            parse_year("2025-2030,2040").unwrap().to_string(),
+            "2025-2030,2040"
+        );
+        assert_eq!(parse_year("*/4").unwrap().to_string(), "*/4");
+        assert!(parse_year("2030-2025").is_err());
+        assert!(parse_year("*/0").is_err());
+        assert!(parse_year("soon").is_err());
+    }
+
+    #[test]
+    fn test_parse_named_steps() {
+        let field = |s: &str, field: Field| parse_field(s, field).unwrap().to_string();
+
+        assert_eq!(field("JAN-DEC/3", Field::Month), "1-12/3");
+        assert_eq!(field("MON-FRI/2", Field::WeekDay), "0-4/2");
+        assert_eq!(field("sun-sat/3", Field::WeekDay), "2,5,6");
+        assert_eq!(field("1-7/3", Field::WeekDay), "0-6/3");
+        assert_eq!(field("FEB/4", Field::Month), "2-12/4");
+        assert_eq!(field("MAR-OCT/2,DEC", Field::Month), "3-10/2,12");
+        let quartz =
+            CronTask::parse_with(Dialect::Quartz, "0 0 9 ? JAN-DEC/3 MON-FRI/2 /bin/true").unwrap();
+        assert_eq!(quartz.month().to_string(), "1-12/3");
+        assert_eq!(quartz.week_day().to_string(), "0-4/2");
+        let weekdays = parse_field("MON-FRI/2", Field::WeekDay).unwrap();
+        assert_eq!(
+            (0..7)
+                .filter(|day| weekdays.matches(*day))
+                .collect::<Vec<_>>(),
+            [0, 2, 4]
+        );
+        assert!(parse_field("FRI-MON/2", Field::WeekDay).is_err());
+        assert!(parse_field("JAN-DEC/13", Field::Month).is_err());
+    }
+
+    #[test]
+    fn test_parse_hashed() {
+        let field = |s: &str, field: Field| parse_field(s, field).unwrap().to_string();
+
+        assert_eq!(field("H", Field::Minute), "H");
+        assert_eq!(field("H/15", Field::Minute), "H/15");
+        assert_eq!(field("H(0-29)/10", Field::Minute), "H(0-29)/10");
+        assert_eq!(field("H(1-5)", Field::WeekDay), "H(0-4)");
+        assert!(parse_field("H(0-5)", Field::WeekDay).is_err());
+        assert!(parse_field("H(5-1)", Field::Minute).is_err());
+        assert!(parse_field("H/0", Field::Minute).is_err());
+        assert!(parse_field("H(0-29", Field::Minute).is_err());
+        assert!(parse_field("Hx", Field::Minute).is_err());
+    }
+
+    #[test]
+    fn test_parse_hashed_task() {
+        let task = CronTask::parse("H/15 H * * * /usr/bin/poll").unwrap();
+        let again = CronTask::parse("H/15 H * * * /usr/bin/poll").unwrap();
+        let keyed = parse_line("H/15 H * * * /usr/bin/poll")
+            .unwrap()
+            .hash_key("poll")
+            .build()
+            .unwrap();
+
+        assert_eq!(task.to_string(), again.to_string());
+        assert!(!task.to_string().contains('H'));
+        assert!(!keyed.to_string().contains('H'));
+    }
+
+    #[test]
+    fn test_parse_union() {
+        use chrono::{Local, TimeZone};
+
+        let task =
+            CronTask::parse_union("0 9 * * MON-FRI; 0 10 * * SAT /usr/bin/report --full %weekly")
+                .unwrap();
+
+        assert_eq!(task.to_string(), "0 10 * * 5 \"/usr/bin/report --full\"");
+        assert_eq!(task.stdin(), Some("weekly"));
+        assert_eq!(task.rules().len(), 1);
+        assert_eq!(task.rules()[0].schedule().to_string(), "0 9 * * 0-4 \"\"");
+
+        // 2024-06-07 is a Friday.
+        let friday = Local.with_ymd_and_hms(2024, 6, 7, 0, 0, 0).unwrap();
+        let runs: Vec<_> = task.upcoming(friday).take(3).collect();
+        assert_eq!(
+            runs,
+            [
+                Local.with_ymd_and_hms(2024, 6, 7, 9, 0, 0).unwrap(),
+                Local.with_ymd_and_hms(2024, 6, 8, 10, 0, 0).unwrap(),
+                Local.with_ymd_and_hms(2024, 6, 10, 9, 0, 0).unwrap(),
+            ]
+        );
+
+        let task = CronTask::parse_union("0 9 * * 1-5\n0 10 * * 6\n/usr/bin/report");
+        assert!(task.is_err());
+
+        let task = CronTask::parse_union("0 9 * * 1-5\n0 10 * * 6 /usr/bin/report\n").unwrap();
+        assert_eq!(task.rules().len(), 1);
+
+        assert!(CronTask::parse_union("").is_err());
+        assert!(CronTask::parse_union("0 9 * *; 0 10 * * 6 /bin/true").is_err());
+
+        let task = CronTask::parse_union("0 0 * * 1 a;b").unwrap();
+        assert_eq!(task.command_line(), "a;b");
+        assert!(task.rules().is_empty());
+
+        let task = CronTask::parse_union("0 9 * * 1-5;0 10 * * 6 cd /srv; make; true").unwrap();
+        assert_eq!(task.command_line(), "cd /srv; make; true");
+        assert_eq!(task.rules().len(), 1);
+    }
+
+    #[test]
+    fn test_parse_template() {
+        let values = HashMap::from([("minute", "30"), ("hour", "2"), ("days", "1-5")]);
+
+        let task =
+            CronTask::parse_template("{minute} {hour} * * {days} echo ${HOME}", &values).unwrap();
+        assert_eq!(task.to_string(), "30 2 * * 0-4 \"echo ${HOME}\"");
+
+        let task = CronTask::parse_template("*/{minute} * * * * /bin/true", &values).unwrap();
+        assert_eq!(task.minute().to_string(), "*/30");
+    }
+
+    #[test]
+    fn test_parse_template_errors() {
+        let values = HashMap::from([("minute".to_string(), "75".to_string())]);
+
+        assert!(matches!(
+            CronTask::parse_template("{minute} {hour} * * * /bin/true", &values),
+            Err(CronError::MissingPlaceholder(name)) if name == "hour"
+        ));
+        assert!(CronTask::parse_template("{minute} * * * * /bin/true", &values).is_err());
+        assert!(CronTask::parse_template("{minute * * * * /bin/true", &values).is_err());
+    }
+}
@@ -0,0 +1,220 @@
+use crate::value::CronValue;
+
+#[derive(Debug, Clone, Default)]
+pub enum FieldPatch {
+    #[default]
+    Keep,
+    Set(CronValue),
+    Clear,
+}
+
+impl FieldPatch {
+    pub fn apply(self, value: &mut CronValue) {
+        match self {
+            FieldPatch::Keep => {}
+            FieldPatch::Set(v) => *value = v,
+            FieldPatch::Clear => *value = CronValue::default(),
+        }
+    }
+
+    /// Same as `apply` for a field that can be left out, clearing turning
+    /// it off.
+    pub fn apply_optional(self, value: &mut Option<CronValue>) {
+        match self {
+            FieldPatch::Keep => {}
+            FieldPatch::Set(v) => *value = Some(v),
+            FieldPatch::Clear => *value = None,
+        }
+    }
+
+    fn or(self, other: FieldPatch) -> FieldPatch {
+        match other {
+            FieldPatch::Keep => self,
+            v => v,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SchedulePatch {
+    pub(crate) second: FieldPatch,
+    pub(crate) minute: FieldPatch,
+    pub(crate) hour: FieldPatch,
+    pub(crate) month_day: FieldPatch,
+    pub(crate) month: FieldPatch,
+    pub(crate) week_day: FieldPatch,
+}
+
+impl SchedulePatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn seconds<T: Into<CronValue>>(mut self, value: T) -> Self {
+        self.second = FieldPatch::Set(value.into());
+        self
+    }
+
+    pub fn minutes<T: Into<CronValue>>(mut self, value: T) -> Self {
+        self.minute = FieldPatch::Set(value.into());
+        self
+    }
+
+    pub fn hour<T: Into<CronValue>>(mut self, value: T) -> Self {
+        self.hour = FieldPatch::Set(value.into());
+        self
+    }
+
+    pub fn month_day<T: Into<CronValue>>(mut self, value: T) -> Self {
+        self.month_day = FieldPatch::Set(value.into());
+        self
+    }
+
+    pub fn month<T: Into<CronValue>>(mut self, value: T) -> Self {
+        self.month = FieldPatch::Set(value.into());
+        self
+    }
+
+    pub fn week_day<T: Into<CronValue>>(mut self, value: T) -> Self {
+        self.week_day = FieldPatch::Set(value.into());
+        self
+    }
+
+    /// Removes the seconds field, the task running once a minute again.
+    pub fn clear_seconds(mut self) -> Self {
+        self.second = FieldPatch::Clear;
+        self
+    }
+
+    pub fn clear_minutes(mut self) -> Self {
+        self.minute = FieldPatch::Clear;
+        self
+    }
+
+    pub fn clear_hour(mut self) -> Self {
+        self.hour = FieldPatch::Clear;
+        self
+    }
+
+    pub fn clear_month_day(mut self) -> Self {
+        self.month_day = FieldPatch::Clear;
+        self
+    }
+
+    pub fn clear_month(mut self) -> Self {
+        self.month = FieldPatch::Clear;
+        self
+    }
+
+    pub fn clear_week_day(mut self) -> Self {
+        self.week_day = FieldPatch::Clear;
+        self
+    }
+
+    /// Layers `other` on top of `self`: fields set or cleared in `other` win.
+    pub fn merge(self, other: SchedulePatch) -> Self {
+        Self {
+            second: self.second.or(other.second),
+            minute: self.minute.or(other.minute),
+            hour: self.hour.or(other.hour),
+            month_day: self.month_day.or(other.month_day),
+            month: self.month.or(other.month),
+            week_day: self.week_day.or(other.week_day),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        task::CronTask,
+        value::{all, on},
+    };
+
+    fn make_task() -> CronTask {
+        CronTask::builder()
+            .minutes(on(0))
+            .hour(on(3))
+            .month_day(all())
+            .month(all())
+            .week_day(all())
+            .path("/usr/bin/backup".to_string())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_field_patch_apply() {
+        let mut value = CronValue::from(5);
+
+        FieldPatch::Keep.apply(&mut value);
+        assert_eq!(value.to_string(), "5");
+
+        FieldPatch::Set(10.into()).apply(&mut value);
+        assert_eq!(value.to_string(), "10");
+
+        FieldPatch::Clear.apply(&mut value);
+        assert_eq!(value.to_string(), "*");
+
+        let mut second = None;
+        FieldPatch::Set(30.into()).apply_optional(&mut second);
+        assert_eq!(
+            second.as_ref().map(|s| s.to_string()).as_deref(),
+            Some("30")
+        );
+        FieldPatch::Clear.apply_optional(&mut second);
+        assert!(second.is_none());
+    }
+
+    #[test]
+    fn test_apply_patch_overrides_single_field() {
+        let mut task = make_task();
+
+        task.apply_patch(SchedulePatch::new().hour(on(5))).unwrap();
+
+        assert_eq!(task.to_string(), "0 5 * * * \"/usr/bin/backup\"");
+    }
+
+    #[test]
+    fn test_apply_patch_clears_field() {
+        let mut task = make_task();
+
+        task.apply_patch(SchedulePatch::new().clear_minutes())
+            .unwrap();
+
+        assert_eq!(task.to_string(), "* 3 * * * \"/usr/bin/backup\"");
+    }
+
+    #[test]
+    fn test_apply_patch_toggles_seconds() {
+        let mut task = make_task();
+
+        task.apply_patch(SchedulePatch::new().seconds(30)).unwrap();
+        assert_eq!(task.to_string(), "30 0 3 * * * \"/usr/bin/backup\"");
+
+        task.apply_patch(SchedulePatch::new().clear_seconds())
+            .unwrap();
+        assert_eq!(task.to_string(), "0 3 * * * \"/usr/bin/backup\"");
+    }
+
+    #[test]
+    fn test_apply_invalid_patch_leaves_task_untouched() {
+        let mut task = make_task();
+
+        assert!(task.apply_patch(SchedulePatch::new().hour(24)).is_err());
+        assert_eq!(task.to_string(), "0 3 * * * \"/usr/bin/backup\"");
+    }
+
+    #[test]
+    fn test_merge_patches() {
+        let global = SchedulePatch::new().minutes(15).hour(2);
+        let env = SchedulePatch::new().hour(4);
+        let mut task = make_task();
+
+        task.apply_patch(global.merge(env)).unwrap();
+
+        assert_eq!(task.to_string(), "15 4 * * * \"/usr/bin/backup\"");
+    }
+}
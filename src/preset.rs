@@ -0,0 +1,137 @@
+use std::path::PathBuf;
+
+use crate::{
+    task::{CronTask, CronTaskBuilder},
+    value::{every, on, range},
+};
+
+/// A named, ready-made schedule.
+///
+/// ```ignore
+/// let task = preset::NIGHTLY_2AM.task("/usr/bin/backup");
+/// let task = preset::WEEKDAYS_9AM.builder().name("standup").path(path).build()?;
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Preset {
+    name: &'static str,
+    apply: fn(CronTaskBuilder) -> CronTaskBuilder,
+}
+
+/// `* * * * *`
+pub const EVERY_MINUTE: Preset = Preset {
+    name: "every minute",
+    apply: |builder| builder,
+};
+
+/// `*/5 * * * *`
+pub const EVERY_5_MINUTES: Preset = Preset {
+    name: "every 5 minutes",
+    apply: |builder| builder.minutes(every(5)),
+};
+
+/// `0 * * * *`
+pub const HOURLY_ON_THE_HOUR: Preset = Preset {
+    name: "hourly on the hour",
+    apply: |builder| builder.minutes(on(0)),
+};
+
+/// `0 2 * * *`
+pub const NIGHTLY_2AM: Preset = Preset {
+    name: "nightly at 2am",
+    apply: |builder| builder.minutes(on(0)).hour(on(2)),
+};
+
+/// `0 9 * * 1-5`, Monday to Friday.
+pub const WEEKDAYS_9AM: Preset = Preset {
+    name: "weekdays at 9am",
+    apply: |builder| builder.minutes(on(0)).hour(on(9)).week_day(range(0..4)),
+};
+
+/// `0 0 1 * *`
+pub const FIRST_OF_MONTH_MIDNIGHT: Preset = Preset {
+    name: "first of the month at midnight",
+    apply: |builder| builder.minutes(on(0)).hour(on(0)).month_day(on(1)),
+};
+
+pub const PRESETS: [Preset; 6] = [
+    EVERY_MINUTE,
+    EVERY_5_MINUTES,
+    HOURLY_ON_THE_HOUR,
+    NIGHTLY_2AM,
+    WEEKDAYS_9AM,
+    FIRST_OF_MONTH_MIDNIGHT,
+];
+
+impl Preset {
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// A builder holding the schedule, for the rest of the task to be set.
+    pub fn builder(&self) -> CronTaskBuilder {
+        (self.apply)(CronTask::builder())
+    }
+
+    pub fn task(&self, path: impl Into<PathBuf>) -> CronTask {
+        CronTask::from(self.builder().program(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Local, TimeZone};
+
+    use super::*;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2025, 5, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_presets() {
+        let lines: Vec<String> = PRESETS
+            .iter()
+            .map(|preset| preset.task("/bin/true").to_string())
+            .collect();
+
+        assert_eq!(
+            lines,
+            [
+                "* * * * * \"/bin/true\"",
+                "*/5 * * * * \"/bin/true\"",
+                "0 * * * * \"/bin/true\"",
+                "0 2 * * * \"/bin/true\"",
+                "0 9 * * 0-4 \"/bin/true\"",
+                "0 0 1 * * \"/bin/true\"",
+            ]
+        );
+        assert!(
+            PRESETS
+                .iter()
+                .all(|preset| preset.builder().allow_commandless().build().is_ok())
+        );
+    }
+
+    #[test]
+    fn test_preset_occurrences() {
+        // Friday, May 30th 2025.
+        let from = at(30, 10, 2);
+        let next = |preset: Preset| preset.task("/bin/true").try_next_occurrence(from);
+
+        assert_eq!(next(EVERY_MINUTE), Some(at(30, 10, 3)));
+        assert_eq!(next(EVERY_5_MINUTES), Some(at(30, 10, 5)));
+        assert_eq!(next(HOURLY_ON_THE_HOUR), Some(at(30, 11, 0)));
+        assert_eq!(next(NIGHTLY_2AM), Some(at(31, 2, 0)));
+        assert_eq!(
+            next(WEEKDAYS_9AM),
+            Some(Local.with_ymd_and_hms(2025, 6, 2, 9, 0, 0).unwrap())
+        );
+        assert_eq!(
+            next(FIRST_OF_MONTH_MIDNIGHT),
+            Some(Local.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap())
+        );
+        assert_eq!(WEEKDAYS_9AM.name(), "weekdays at 9am");
+    }
+}
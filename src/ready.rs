@@ -0,0 +1,92 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        Arc, Mutex, MutexGuard,
+        atomic::{AtomicBool, Ordering},
+    },
+    task::{Context, Poll, Waker},
+};
+
+/// Whether a scheduler is done warming up and schedules runs, shared with
+/// its handle. Health checks read it with [`Readiness::is_ready`], or await
+/// it to know when a starting scheduler gets there.
+#[derive(Debug, Clone, Default)]
+pub struct Readiness(Arc<Ready>);
+
+#[derive(Debug, Default)]
+struct Ready {
+    ready: AtomicBool,
+    wakers: Mutex<Vec<Waker>>,
+}
+
+impl Readiness {
+    pub fn is_ready(&self) -> bool {
+        self.0.ready.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn set(&self) {
+        self.0.ready.store(true, Ordering::Release);
+        for waker in self.wakers().drain(..) {
+            waker.wake();
+        }
+    }
+
+    fn wakers(&self) -> MutexGuard<'_, Vec<Waker>> {
+        // Wakers are only pushed and drained, a poisoned lock is still
+        // usable.
+        self.0.wakers.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Future for Readiness {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.is_ready() {
+            return Poll::Ready(());
+        }
+        let mut wakers = self.wakers();
+        // Checked again under the lock, `set` draining the wakers only
+        // after setting the flag.
+        if self.is_ready() {
+            return Poll::Ready(());
+        }
+        if !wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+            wakers.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::task::Wake;
+
+    use super::*;
+
+    struct Flag(AtomicBool);
+
+    impl Wake for Flag {
+        fn wake(self: Arc<Self>) {
+            self.0.store(true, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn test_readiness() {
+        let mut readiness = Readiness::default();
+        let flag = Arc::new(Flag(AtomicBool::new(false)));
+        let waker = Waker::from(Arc::clone(&flag));
+        let mut cx = Context::from_waker(&waker);
+
+        assert!(!readiness.is_ready());
+        assert_eq!(Pin::new(&mut readiness).poll(&mut cx), Poll::Pending);
+
+        let handle = readiness.clone();
+        std::thread::spawn(move || handle.set()).join().unwrap();
+        assert!(flag.0.load(Ordering::Relaxed));
+        assert!(readiness.is_ready());
+        assert_eq!(Pin::new(&mut readiness).poll(&mut cx), Poll::Ready(()));
+    }
+}
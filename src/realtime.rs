@@ -0,0 +1,185 @@
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use chrono::Local;
+use std::sync::Mutex;
+
+use chrono::{DateTime, TimeDelta, TimeZone};
+
+use crate::{cron::Cron, task::CronTask};
+
+/// Source of the current date. Passing one in keeps schedules usable where
+/// there is no system clock, such as `wasm32-unknown-unknown` in a browser.
+pub trait Clock {
+    type Tz: TimeZone;
+
+    fn now(&self) -> DateTime<Self::Tz>;
+}
+
+/// The system clock, read in the local time zone.
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+impl Clock for SystemClock {
+    type Tz = Local;
+
+    fn now(&self) -> DateTime<Local> {
+        Local::now()
+    }
+}
+
+/// A clock stopped at a given date, such as one handed over by JavaScript.
+#[derive(Debug, Clone)]
+pub struct FixedClock<Tz: TimeZone>(pub DateTime<Tz>);
+
+impl<Tz: TimeZone> Clock for FixedClock<Tz> {
+    type Tz = Tz;
+
+    fn now(&self) -> DateTime<Tz> {
+        self.0.clone()
+    }
+}
+
+/// A clock that only moves when told to, for deterministic tests of what
+/// runs next. It can be shared by reference with the code under test.
+#[derive(Debug)]
+pub struct ManualClock<Tz: TimeZone> {
+    now: Mutex<DateTime<Tz>>,
+}
+
+impl<Tz: TimeZone> ManualClock<Tz> {
+    pub fn new(start: DateTime<Tz>) -> Self {
+        Self {
+            now: Mutex::new(start),
+        }
+    }
+
+    pub fn set(&self, date: DateTime<Tz>) {
+        *self.lock() = date;
+    }
+
+    pub fn advance(&self, delta: TimeDelta) {
+        let mut now = self.lock();
+        *now = now.clone() + delta;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, DateTime<Tz>> {
+        // The date is always left whole, a poisoned lock is still usable.
+        self.now.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<Tz: TimeZone> Clock for ManualClock<Tz> {
+    type Tz = Tz;
+
+    fn now(&self) -> DateTime<Tz> {
+        self.lock().clone()
+    }
+}
+
+impl CronTask {
+    /// Next occurrence after the current date of `clock`.
+    pub fn next_from_clock<C: Clock>(&self, clock: &C) -> Option<DateTime<C::Tz>> {
+        self.try_next_occurrence(clock.now())
+    }
+}
+
+impl Cron {
+    /// Next event after the current date of `clock`.
+    pub fn next_event_from_clock<C: Clock>(
+        &self,
+        clock: &C,
+    ) -> Option<(DateTime<C::Tz>, &CronTask)> {
+        self.next_event(clock.now())
+    }
+}
+
+/// Conveniences reading the wall clock. The rest of the crate takes the
+/// current date as a parameter and stays deterministic.
+pub trait Realtime {
+    type Event;
+
+    /// Next event after the current local time.
+    fn next_from_now(&self) -> Option<Self::Event>;
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+impl Realtime for CronTask {
+    type Event = DateTime<Local>;
+
+    fn next_from_now(&self) -> Option<DateTime<Local>> {
+        self.next_from_clock(&SystemClock)
+    }
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+impl<'a> Realtime for &'a Cron {
+    type Event = (DateTime<Local>, &'a CronTask);
+
+    fn next_from_now(&self) -> Option<Self::Event> {
+        self.next_event_from_clock(&SystemClock)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+    use crate::value::every;
+
+    #[test]
+    fn test_next_from_now() {
+        let task = CronTask::builder().allow_commandless().build().unwrap();
+        let before = Local::now();
+        let next = task.next_from_now().unwrap();
+
+        assert!(next > before);
+
+        let mut cron = Cron::new();
+        cron.add_task(task);
+        assert!(
+            (&cron)
+                .next_from_now()
+                .is_some_and(|(date, _)| date > before)
+        );
+    }
+
+    #[test]
+    fn test_fixed_clock() {
+        let clock = FixedClock(Utc.with_ymd_and_hms(2025, 6, 1, 8, 15, 0).unwrap());
+        let task = CronTask::builder()
+            .minutes(30)
+            .hour(8)
+            .path("/usr/bin/report".to_string())
+            .build()
+            .unwrap();
+
+        let next = Utc.with_ymd_and_hms(2025, 6, 1, 8, 30, 0).unwrap();
+        assert_eq!(task.next_from_clock(&clock), Some(next));
+
+        let mut cron = Cron::new();
+        cron.add_task(task);
+        assert_eq!(
+            cron.next_event_from_clock(&clock).map(|(date, _)| date),
+            Some(next)
+        );
+    }
+
+    #[test]
+    fn test_manual_clock() {
+        let at = |hour, minute| Utc.with_ymd_and_hms(2025, 6, 1, hour, minute, 0).unwrap();
+        let clock = ManualClock::new(at(8, 0));
+        let task = CronTask::builder()
+            .minutes(every(20))
+            .path("/usr/bin/poll".to_string())
+            .build()
+            .unwrap();
+
+        assert_eq!(task.next_from_clock(&clock), Some(at(8, 20)));
+        clock.advance(TimeDelta::minutes(25));
+        assert_eq!(task.next_from_clock(&clock), Some(at(8, 40)));
+        clock.set(at(9, 50));
+        assert_eq!(task.next_from_clock(&clock), Some(at(10, 0)));
+    }
+}
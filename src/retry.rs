@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+/// How the executor runs a failed command again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Runs made at most, the first one included.
+    pub max_attempts: u32,
+    pub backoff: Backoff,
+}
+
+/// Wait between a failed attempt and the next one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backoff {
+    Fixed(Duration),
+    /// Starts at `initial` and doubles after each failure, up to `max`.
+    Exponential {
+        initial: Duration,
+        max: Duration,
+    },
+}
+
+impl RetryPolicy {
+    /// Wait after the `attempt`th run failed, counting from 1.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        match self.backoff {
+            Backoff::Fixed(delay) => delay,
+            Backoff::Exponential { initial, max } => 2u32
+                .checked_pow(attempt.saturating_sub(1))
+                .and_then(|factor| initial.checked_mul(factor))
+                .map_or(max, |delay| delay.min(max)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay() {
+        let fixed = RetryPolicy {
+            max_attempts: 3,
+            backoff: Backoff::Fixed(Duration::from_secs(30)),
+        };
+        assert_eq!(fixed.delay(1), Duration::from_secs(30));
+        assert_eq!(fixed.delay(2), Duration::from_secs(30));
+
+        let exponential = RetryPolicy {
+            max_attempts: 40,
+            backoff: Backoff::Exponential {
+                initial: Duration::from_secs(1),
+                max: Duration::from_secs(60),
+            },
+        };
+        let delays: Vec<u64> = (1..=8)
+            .map(|attempt| exponential.delay(attempt).as_secs())
+            .collect();
+        assert_eq!(delays, [1, 2, 4, 8, 16, 32, 60, 60]);
+        assert_eq!(exponential.delay(39), Duration::from_secs(60));
+    }
+}
@@ -0,0 +1,561 @@
+use crate::{
+    error::{CronError, Result},
+    task::CronTask,
+    value::CronValue,
+    year::YearValue,
+};
+
+const WEEK_DAYS: [&str; 7] = ["MO", "TU", "WE", "TH", "FR", "SA", "SU"];
+
+impl CronTask {
+    /// Converts the schedule into an RFC 5545 recurrence rule, such as
+    /// `FREQ=WEEKLY;BYDAY=MO;BYHOUR=5;BYMINUTE=0`. Without a seconds field,
+    /// seconds are left to the event's start. Parts with no rule
+    /// equivalent are all listed in the error.
+    pub fn to_rrule(&self) -> Result<String> {
+        let mut unconvertible = Vec::new();
+
+        if self.is_reboot() {
+            return Err(CronError::Unconvertible(vec!["@reboot".to_string()]));
+        }
+        if self.year() != &YearValue::All {
+            unconvertible.push(format!("year `{}`", self.year()));
+        }
+        let nearest = |v: &CronValue| matches!(v, CronValue::NearestWeekday(_));
+        if nearest(self.month_day())
+            || matches!(self.month_day(), CronValue::List(list) if list.iter().any(nearest))
+        {
+            unconvertible.push(format!("month day `{}`", self.month_day()));
+        }
+        // Rules keep the dates matching both day parts.
+        if self.matches_either_day() {
+            unconvertible.push(format!(
+                "month day `{}` or week day `{}`",
+                self.month_day(),
+                self.week_day()
+            ));
+        }
+
+        if !unconvertible.is_empty() {
+            return Err(CronError::Unconvertible(unconvertible));
+        }
+
+        let (freq, interval, free) = self.frequency();
+        let mut parts = vec![format!("FREQ={freq}")];
+        if let Some(interval) = interval {
+            parts.push(format!("INTERVAL={interval}"));
+        }
+
+        let by = [
+            ("BYMONTH", Level::Month, list(self.month(), 1..=12)),
+            ("BYMONTHDAY", Level::Day, month_days(self.month_day())),
+            ("BYDAY", Level::Day, week_days(self.week_day())),
+            ("BYHOUR", Level::Hour, list(self.hour(), 0..=23)),
+            ("BYMINUTE", Level::Minute, list(self.minute(), 0..=59)),
+            (
+                "BYSECOND",
+                Level::Second,
+                self.second().and_then(|second| list(second, 0..=59)),
+            ),
+        ];
+
+        for (name, level, values) in by {
+            if let Some(values) = values
+                && Some(level) != free
+            {
+                parts.push(format!("{name}={values}"));
+            }
+        }
+
+        Ok(parts.join(";"))
+    }
+
+    /// Parses an RFC 5545 recurrence rule, with or without the `RRULE:`
+    /// prefix, into a task running `path`. Time parts the rule would take
+    /// from the event's start default to zero; date parts must be given.
+    /// Parts with no task equivalent are all listed in the error.
+    pub fn from_rrule(rule: &str, path: impl Into<String>) -> Result<CronTask> {
+        let rule = rule.trim();
+        let rule = rule.strip_prefix("RRULE:").unwrap_or(rule);
+
+        let mut freq = None;
+        let mut interval = 1;
+        let mut by = Vec::new();
+        let mut unconvertible = Vec::new();
+
+        for part in rule.split(';').filter(|part| !part.is_empty()) {
+            let Some((name, value)) = part.split_once('=') else {
+                return Err(invalid_rule(part));
+            };
+
+            match name.to_ascii_uppercase().as_str() {
+                "FREQ" => freq = Some(Level::from_freq(value).ok_or_else(|| invalid_rule(part))?),
+                "INTERVAL" => {
+                    interval = value
+                        .parse::<u8>()
+                        .ok()
+                        .filter(|v| *v > 0)
+                        .ok_or_else(|| invalid_rule(part))?;
+                }
+                "BYMONTH" => by.push((Level::Month, numbers(value, 1, 12, part)?)),
+                "BYMONTHDAY" => by.push((Level::Day, month_days_value(value, part)?)),
+                "BYDAY" => by.push((Level::WeekDay, week_days_value(value, part)?)),
+                "BYHOUR" => by.push((Level::Hour, numbers(value, 0, 23, part)?)),
+                "BYMINUTE" => by.push((Level::Minute, numbers(value, 0, 59, part)?)),
+                "BYSECOND" => by.push((Level::Second, numbers(value, 0, 59, part)?)),
+                _ => unconvertible.push(format!("`{part}`")),
+            }
+        }
+
+        let freq = freq.ok_or_else(|| invalid_rule(rule))?;
+        let given = |level: Level| by.iter().any(|(l, _)| *l == level);
+        let mut builder = CronTask::builder().path(path.into());
+
+        // The repeating level covers its whole range, finer levels not given
+        // start at zero as the event's start would.
+        let step = |size: u8| match interval {
+            1 => Some(CronValue::All),
+            n if size.is_multiple_of(n) => Some(crate::value::every(n)),
+            _ => None,
+        };
+        let repeating = match freq {
+            Level::Second => step(60),
+            Level::Minute => step(60),
+            Level::Hour => step(24),
+            Level::Month => step(12).map(|v| v.phase(1)),
+            _ if interval == 1 => Some(CronValue::All),
+            _ => None,
+        };
+        let Some(repeating) = repeating else {
+            unconvertible.push(format!("`INTERVAL={interval}`"));
+            return Err(CronError::Unconvertible(unconvertible));
+        };
+
+        match freq {
+            Level::Second => builder = builder.seconds(repeating),
+            Level::Minute => builder = builder.minutes(repeating),
+            Level::Hour => builder = builder.minutes(0).hour(repeating),
+            _ => {
+                builder = builder.minutes(0).hour(0);
+                if freq == Level::Month {
+                    builder = builder.month(repeating);
+                }
+            }
+        }
+
+        let needs_day = match freq {
+            Level::WeekDay => !given(Level::WeekDay),
+            Level::Month => !given(Level::Day) && !given(Level::WeekDay),
+            Level::Year => !given(Level::Month) || (!given(Level::Day) && !given(Level::WeekDay)),
+            _ => false,
+        };
+        if needs_day {
+            unconvertible.push(format!("{} dates taken from the event start", freq.name()));
+        }
+
+        if !unconvertible.is_empty() {
+            return Err(CronError::Unconvertible(unconvertible));
+        }
+
+        for (level, value) in by {
+            builder = match level {
+                Level::Month => builder.month(value),
+                Level::Day => builder.month_day(value),
+                Level::WeekDay => builder.week_day(value),
+                Level::Hour => builder.hour(value),
+                Level::Minute => builder.minutes(value),
+                Level::Second => builder.seconds(value),
+                Level::Year => builder,
+            };
+        }
+
+        builder.build()
+    }
+
+    /// The rule frequency, its interval, and the level it covers on its
+    /// own: the finest field repeating over its whole range.
+    fn frequency(&self) -> (&'static str, Option<u8>, Option<Level>) {
+        let repeating = |value: &CronValue, size: u8| match value {
+            CronValue::All => Some(None),
+            CronValue::Interval(base, step)
+                if matches!(**base, CronValue::All) && size.is_multiple_of(u8::from(step)) =>
+            {
+                Some(Some(u8::from(step)))
+            }
+            _ => None,
+        };
+
+        if let Some(interval) = self.second().and_then(|second| repeating(second, 60)) {
+            return ("SECONDLY", interval, Some(Level::Second));
+        }
+        if let Some(interval) = repeating(self.minute(), 60) {
+            return ("MINUTELY", interval, Some(Level::Minute));
+        }
+        if let Some(interval) = repeating(self.hour(), 24) {
+            return ("HOURLY", interval, Some(Level::Hour));
+        }
+
+        let nth = self.week_day().has_week_day_items();
+        let only_week_days = matches!(self.month_day(), CronValue::All)
+            && matches!(self.month(), CronValue::All)
+            && !matches!(self.week_day(), CronValue::All);
+
+        match (nth, only_week_days) {
+            (true, _) => ("MONTHLY", None, None),
+            (false, true) => ("WEEKLY", None, None),
+            (false, false) => ("DAILY", None, None),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Level {
+    Year,
+    Month,
+    WeekDay,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+impl Level {
+    fn from_freq(freq: &str) -> Option<Level> {
+        Some(match freq.to_ascii_uppercase().as_str() {
+            "YEARLY" => Level::Year,
+            "MONTHLY" => Level::Month,
+            "WEEKLY" => Level::WeekDay,
+            "DAILY" => Level::Day,
+            "HOURLY" => Level::Hour,
+            "MINUTELY" => Level::Minute,
+            "SECONDLY" => Level::Second,
+            _ => return None,
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Level::Year => "yearly",
+            Level::Month => "monthly",
+            Level::WeekDay => "weekly",
+            Level::Day => "daily",
+            Level::Hour => "hourly",
+            Level::Minute => "minutely",
+            Level::Second => "secondly",
+        }
+    }
+}
+
+fn numbers(s: &str, min: u8, max: u8, part: &str) -> Result<CronValue> {
+    let values = s
+        .split(',')
+        .map(|v| {
+            v.parse::<u8>()
+                .ok()
+                .filter(|v| (min..=max).contains(v))
+                .map(CronValue::from)
+                .ok_or_else(|| invalid_rule(part))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(single_or_list(values))
+}
+
+fn month_days_value(s: &str, part: &str) -> Result<CronValue> {
+    let values = s
+        .split(',')
+        .map(|v| match v {
+            "-1" => Ok(CronValue::Last),
+            v if v.starts_with('-') => Err(CronError::Unconvertible(vec![format!("`{part}`")])),
+            v => numbers(v, 1, 31, part),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(single_or_list(values))
+}
+
+fn week_days_value(s: &str, part: &str) -> Result<CronValue> {
+    let values = s
+        .split(',')
+        .map(|v| {
+            let split = v.len().saturating_sub(2);
+            let (n, day) = v
+                .split_at_checked(split)
+                .ok_or_else(|| invalid_rule(part))?;
+            let day = WEEK_DAYS
+                .iter()
+                .position(|d| d.eq_ignore_ascii_case(day))
+                .ok_or_else(|| invalid_rule(part))? as u8;
+
+            match n {
+                "" => Ok(day.into()),
+                n if n.starts_with('-') => Err(CronError::Unconvertible(vec![format!("`{part}`")])),
+                n => match n.trim_start_matches('+').parse::<u8>() {
+                    Ok(n) if (1..=5).contains(&n) => Ok(CronValue::Nth(day.into(), n)),
+                    _ => Err(invalid_rule(part)),
+                },
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(single_or_list(values))
+}
+
+fn single_or_list(mut values: Vec<CronValue>) -> CronValue {
+    match values.len() {
+        1 => values.remove(0),
+        _ => CronValue::List(values),
+    }
+}
+
+fn invalid_rule(part: &str) -> CronError {
+    CronError::InvalidExpression(format!("invalid recurrence rule part `{part}`"))
+}
+
+/// Comma separated values of `domain` matched by `value`, `None` when
+/// every value matches.
+fn list(value: &CronValue, domain: std::ops::RangeInclusive<u8>) -> Option<String> {
+    if matches!(value, CronValue::All) {
+        return None;
+    }
+
+    let first = *domain.start();
+    let values: Vec<String> = domain
+        .filter(|v| value.matches_in(*v, first))
+        .map(|v| v.to_string())
+        .collect();
+    Some(values.join(","))
+}
+
+fn month_days(value: &CronValue) -> Option<String> {
+    match value {
+        CronValue::Last => Some("-1".to_string()),
+        CronValue::List(items) if value.has_month_day_items() => Some(
+            items
+                .iter()
+                .filter_map(month_days)
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        value => list(value, 1..=31),
+    }
+}
+
+fn week_days(value: &CronValue) -> Option<String> {
+    match value {
+        CronValue::Nth(day, n) => Some(format!("{n}{}", WEEK_DAYS[u8::from(day) as usize % 7])),
+        CronValue::All => None,
+        CronValue::List(items) if value.has_week_day_items() => Some(
+            items
+                .iter()
+                .filter_map(week_days)
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        value => Some(
+            (0..7)
+                .filter(|d| value.matches(*d))
+                .map(|d| WEEK_DAYS[d as usize])
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Weekday;
+
+    use super::*;
+    use crate::task::DayMatch;
+    use crate::value::{every, last_day, nearest_weekday, nth, on};
+
+    #[test]
+    fn test_to_rrule() {
+        let rule = |task: CronTask| task.to_rrule().unwrap();
+
+        assert_eq!(
+            rule(
+                CronTask::builder()
+                    .minutes(on(0))
+                    .hour(on(5))
+                    .week_day(Weekday::Mon)
+                    .allow_commandless()
+                    .build()
+                    .unwrap()
+            ),
+            "FREQ=WEEKLY;BYDAY=MO;BYHOUR=5;BYMINUTE=0"
+        );
+        assert_eq!(
+            rule(CronTask::daily("/bin/true")),
+            "FREQ=DAILY;BYHOUR=0;BYMINUTE=0"
+        );
+        assert_eq!(
+            rule(
+                CronTask::builder()
+                    .minutes(every(15u8))
+                    .allow_commandless()
+                    .build()
+                    .unwrap()
+            ),
+            "FREQ=MINUTELY;INTERVAL=15"
+        );
+        assert_eq!(
+            rule(
+                CronTask::builder()
+                    .minutes(on(30))
+                    .hour(CronValue::Range(9..17))
+                    .allow_commandless()
+                    .build()
+                    .unwrap()
+            ),
+            "FREQ=DAILY;BYHOUR=9,10,11,12,13,14,15,16,17;BYMINUTE=30"
+        );
+        assert_eq!(
+            rule(
+                CronTask::builder()
+                    .minutes(on(0))
+                    .allow_commandless()
+                    .build()
+                    .unwrap()
+            ),
+            "FREQ=HOURLY;BYMINUTE=0"
+        );
+    }
+
+    #[test]
+    fn test_to_rrule_special_days() {
+        let last = CronTask::builder()
+            .minutes(on(0))
+            .hour(on(0))
+            .month_day(last_day())
+            .allow_commandless()
+            .build()
+            .unwrap();
+        assert_eq!(
+            last.to_rrule().unwrap(),
+            "FREQ=DAILY;BYMONTHDAY=-1;BYHOUR=0;BYMINUTE=0"
+        );
+
+        let second_monday = CronTask::builder()
+            .minutes(on(0))
+            .hour(on(9))
+            .week_day(nth(Weekday::Mon, 2))
+            .allow_commandless()
+            .build()
+            .unwrap();
+        assert_eq!(
+            second_monday.to_rrule().unwrap(),
+            "FREQ=MONTHLY;BYDAY=2MO;BYHOUR=9;BYMINUTE=0"
+        );
+
+        let mixed = CronTask::builder()
+            .minutes(on(0))
+            .hour(on(9))
+            .week_day(CronValue::List(vec![
+                nth(Weekday::Mon, 2),
+                Weekday::Fri.into(),
+            ]))
+            .allow_commandless()
+            .build()
+            .unwrap();
+        assert_eq!(
+            mixed.to_rrule().unwrap(),
+            "FREQ=MONTHLY;BYDAY=2MO,FR;BYHOUR=9;BYMINUTE=0"
+        );
+
+        // Steps that do not divide the hour restart every hour in cron.
+        let uneven = CronTask::builder()
+            .minutes(every(25u8))
+            .allow_commandless()
+            .build()
+            .unwrap();
+        assert_eq!(uneven.to_rrule().unwrap(), "FREQ=HOURLY;BYMINUTE=0,25,50");
+    }
+
+    #[test]
+    fn test_to_rrule_unconvertible() {
+        let task = CronTask::builder()
+            .month_day(nearest_weekday(15))
+            .year(2025)
+            .allow_commandless()
+            .build()
+            .unwrap();
+
+        let error = task.to_rrule().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Cannot convert year `2025`, month day `15W`."
+        );
+        assert!(CronTask::reboot("/bin/true").to_rrule().is_err());
+
+        let either = CronTask::builder()
+            .month_day(13)
+            .week_day(Weekday::Fri)
+            .day_match(DayMatch::Any)
+            .allow_commandless()
+            .build()
+            .unwrap();
+        assert!(either.to_rrule().is_err());
+    }
+
+    #[test]
+    fn test_from_rrule() {
+        let parse = |rule: &str| CronTask::from_rrule(rule, "/bin/true").unwrap().to_string();
+
+        assert_eq!(
+            parse("RRULE:FREQ=WEEKLY;BYDAY=MO;BYHOUR=5"),
+            "0 5 * * 0 \"/bin/true\""
+        );
+        assert_eq!(
+            parse("FREQ=MINUTELY;INTERVAL=15"),
+            "*/15 * * * * \"/bin/true\""
+        );
+        assert_eq!(
+            parse("FREQ=DAILY;BYHOUR=9,17;BYMINUTE=30"),
+            "30 9,17 * * * \"/bin/true\""
+        );
+        assert_eq!(
+            parse("FREQ=MONTHLY;BYDAY=2MO,FR;BYHOUR=9"),
+            "0 9 * * 0#2,4 \"/bin/true\""
+        );
+        assert_eq!(
+            parse("FREQ=MONTHLY;INTERVAL=3;BYMONTHDAY=-1"),
+            "0 0 L 1/3 * \"/bin/true\""
+        );
+        assert_eq!(
+            parse("FREQ=SECONDLY;INTERVAL=10"),
+            "*/10 * * * * * \"/bin/true\""
+        );
+
+        // Both day parts must match, as in a task built with the DSL.
+        let friday_13th = CronTask::from_rrule("FREQ=MONTHLY;BYMONTHDAY=13;BYDAY=FR", "/bin/true");
+        assert!(!friday_13th.unwrap().matches_either_day());
+    }
+
+    #[test]
+    fn test_from_rrule_unconvertible() {
+        let error = CronTask::from_rrule("FREQ=DAILY;INTERVAL=2;COUNT=5", "/bin/true").unwrap_err();
+        assert_eq!(error.to_string(), "Cannot convert `COUNT=5`, `INTERVAL=2`.");
+
+        let error = CronTask::from_rrule("FREQ=WEEKLY", "/bin/true").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Cannot convert weekly dates taken from the event start."
+        );
+
+        assert!(CronTask::from_rrule("FREQ=MONTHLY;BYDAY=-1FR", "/bin/true").is_err());
+        assert!(CronTask::from_rrule("FREQ=MONTHLY;BYMONTHDAY=-2", "/bin/true").is_err());
+        assert!(CronTask::from_rrule("FREQ=FORTNIGHTLY", "/bin/true").is_err());
+        assert!(CronTask::from_rrule("BYHOUR=5", "/bin/true").is_err());
+        assert!(CronTask::from_rrule("FREQ=DAILY;BYHOUR=24", "/bin/true").is_err());
+    }
+
+    #[test]
+    fn test_rrule_round_trip() {
+        let rule = "FREQ=WEEKLY;BYDAY=MO,WE,FR;BYHOUR=6;BYMINUTE=15";
+        let task = CronTask::from_rrule(rule, "/bin/true").unwrap();
+
+        assert_eq!(task.to_rrule().unwrap(), rule);
+    }
+}
@@ -0,0 +1,40 @@
+use crate::task::CronTask;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleAction {
+    Include,
+    Exclude,
+}
+
+/// Schedule adding runs to a task or removing them. A task's rules are
+/// evaluated in order on top of its own schedule, the last one matching a
+/// date deciding whether the task runs then.
+#[derive(Debug, Clone)]
+pub struct ScheduleRule {
+    action: RuleAction,
+    schedule: CronTask,
+}
+
+impl ScheduleRule {
+    /// Only the schedule fields of `schedule` are used, it is read in the
+    /// time zone of the task the rule belongs to.
+    pub fn new(action: RuleAction, schedule: CronTask) -> Self {
+        Self { action, schedule }
+    }
+
+    pub fn include(schedule: CronTask) -> Self {
+        Self::new(RuleAction::Include, schedule)
+    }
+
+    pub fn exclude(schedule: CronTask) -> Self {
+        Self::new(RuleAction::Exclude, schedule)
+    }
+
+    pub fn action(&self) -> RuleAction {
+        self.action
+    }
+
+    pub fn schedule(&self) -> &CronTask {
+        &self.schedule
+    }
+}
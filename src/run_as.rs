@@ -0,0 +1,158 @@
+use std::{fmt::Display, str::FromStr};
+
+#[cfg(feature = "executor")]
+use std::process::Command;
+
+use crate::error::{CronError, Result};
+
+/// Account a task's command runs as, written `user` or `user:group`. Users
+/// and groups are names or numeric ids.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunAs {
+    user: String,
+    group: Option<String>,
+}
+
+impl RunAs {
+    pub fn new(user: impl Into<String>) -> Self {
+        Self {
+            user: user.into(),
+            group: None,
+        }
+    }
+
+    /// Runs under `group` rather than the user's primary group.
+    pub fn with_group(mut self, group: impl Into<String>) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+
+    pub fn user(&self) -> &str {
+        &self.user
+    }
+
+    pub fn group(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+
+    pub fn verify(&self) -> Result<()> {
+        // Names end up in a whitespace separated crontab column.
+        let valid = |name: &str| {
+            !name.is_empty() && !name.contains(|c: char| c.is_whitespace() || c == ':')
+        };
+
+        if valid(&self.user) && self.group.as_deref().is_none_or(valid) {
+            Ok(())
+        } else {
+            Err(CronError::InvalidUser(self.to_string()))
+        }
+    }
+
+    /// Makes `command` switch to the account before it execs, which needs
+    /// the privileges to do so. Names are looked up in `/etc/passwd` and
+    /// `/etc/group`.
+    #[cfg(all(feature = "run-as", unix))]
+    pub(crate) fn apply(&self, command: &mut Command) -> Result<()> {
+        use std::{fs, os::unix::process::CommandExt};
+
+        let passwd = fs::read_to_string("/etc/passwd")?;
+        let (uid, primary) = lookup(&passwd, &self.user)
+            .and_then(|entry| Some((entry[0], *entry.get(1)?)))
+            .ok_or_else(|| CronError::UnknownUser(self.user.clone()))?;
+        let gid = match &self.group {
+            Some(group) => lookup(&fs::read_to_string("/etc/group")?, group)
+                .map(|entry| entry[0])
+                .ok_or_else(|| CronError::UnknownUser(group.clone()))?,
+            None => primary,
+        };
+
+        // The group goes first, a process no longer root cannot change it.
+        command.gid(gid).uid(uid);
+        Ok(())
+    }
+
+    #[cfg(all(feature = "run-as", not(unix)))]
+    pub(crate) fn apply(&self, _command: &mut Command) -> Result<()> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "running as another user needs a Unix system",
+        )
+        .into())
+    }
+
+    /// Refuses to run the command as the current user instead.
+    #[cfg(all(feature = "executor", not(feature = "run-as")))]
+    pub(crate) fn apply(&self, _command: &mut Command) -> Result<()> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            format!("running as `{self}` needs the `run-as` feature"),
+        )
+        .into())
+    }
+}
+
+/// Numeric ids following the name of the `/etc/passwd` or `/etc/group`
+/// entry for `name`, which may also be the entry's id.
+#[cfg(all(feature = "run-as", unix))]
+fn lookup(database: &str, name: &str) -> Option<Vec<u32>> {
+    database
+        .lines()
+        .map(|line| line.split(':').collect::<Vec<_>>())
+        .filter(|fields| fields.len() > 2)
+        .find(|fields| fields[0] == name || fields[2] == name)
+        .map(|fields| fields[2..].iter().map_while(|id| id.parse().ok()).collect())
+        .filter(|ids: &Vec<u32>| !ids.is_empty())
+}
+
+impl Display for RunAs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.group {
+            Some(group) => write!(f, "{}:{group}", self.user),
+            None => write!(f, "{}", self.user),
+        }
+    }
+}
+
+impl FromStr for RunAs {
+    type Err = CronError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let run_as = match s.split_once(':') {
+            Some((user, group)) => RunAs::new(user).with_group(group),
+            None => RunAs::new(s),
+        };
+        run_as.verify()?;
+
+        Ok(run_as)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_run_as() {
+        let run_as: RunAs = "deploy:www-data".parse().unwrap();
+        assert_eq!(run_as.user(), "deploy");
+        assert_eq!(run_as.group(), Some("www-data"));
+        assert_eq!(run_as.to_string(), "deploy:www-data");
+
+        assert_eq!("root".parse::<RunAs>().unwrap(), RunAs::new("root"));
+        assert!("".parse::<RunAs>().is_err());
+        assert!("deploy:".parse::<RunAs>().is_err());
+        assert!("a:b:c".parse::<RunAs>().is_err());
+        assert!(RunAs::new("my user").verify().is_err());
+    }
+
+    #[cfg(all(feature = "run-as", unix))]
+    #[test]
+    fn test_lookup() {
+        let passwd = "root:x:0:0:root:/root:/bin/sh\ndeploy:x:1001:1002::/home/deploy:/bin/sh\n";
+
+        assert_eq!(lookup(passwd, "deploy"), Some(vec![1001, 1002]));
+        assert_eq!(lookup(passwd, "1001"), Some(vec![1001, 1002]));
+        assert_eq!(lookup(passwd, "nobody"), None);
+        assert_eq!(lookup("www-data:x:33:\n", "www-data"), Some(vec![33]));
+    }
+}
@@ -0,0 +1,145 @@
+use std::{
+    collections::hash_map::RandomState,
+    fmt::Display,
+    hash::{BuildHasher, Hasher},
+    str::FromStr,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::error::CronError;
+
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// ULID identifying a single run: 48 bits of millisecond timestamp followed
+/// by 80 random bits, rendered as 26 Crockford base32 characters. Ids sort
+/// by creation time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RunId(u128);
+
+impl RunId {
+    pub fn new() -> Self {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64);
+
+        Self::from_parts(millis, random_bits())
+    }
+
+    pub fn from_parts(millis: u64, random: u128) -> Self {
+        let millis = (millis as u128) & ((1 << 48) - 1);
+        let random = random & ((1 << 80) - 1);
+
+        Self((millis << 80) | random)
+    }
+
+    pub fn timestamp_millis(&self) -> u64 {
+        (self.0 >> 80) as u64
+    }
+
+    pub fn as_u128(&self) -> u128 {
+        self.0
+    }
+}
+
+impl Default for RunId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for RunId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let encoded: String = (0..26)
+            .rev()
+            .map(|i| ALPHABET[((self.0 >> (i * 5)) & 0x1f) as usize] as char)
+            .collect();
+
+        write!(f, "{encoded}")
+    }
+}
+
+impl FromStr for RunId {
+    type Err = CronError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || CronError::InvalidRunId(s.to_string());
+
+        if s.len() != 26 || s.as_bytes()[0] > b'7' {
+            return Err(invalid());
+        }
+
+        s.bytes()
+            .try_fold(0u128, |acc, c| {
+                ALPHABET
+                    .iter()
+                    .position(|a| *a == c.to_ascii_uppercase())
+                    .map(|v| (acc << 5) | v as u128)
+            })
+            .map(RunId)
+            .ok_or_else(invalid)
+    }
+}
+
+fn random_bits() -> u128 {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let state = RandomState::new();
+    let word = || {
+        let mut hasher = state.build_hasher();
+        hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+        hasher.finish() as u128
+    };
+
+    (word() << 64) | word()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_id_round_trip() {
+        let id = RunId::new();
+        let encoded = id.to_string();
+
+        assert_eq!(encoded.len(), 26);
+        assert_eq!(encoded.parse::<RunId>().unwrap(), id);
+    }
+
+    #[test]
+    fn test_run_id_unique() {
+        let a = RunId::new();
+        let b = RunId::new();
+
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_run_id_sorts_by_time() {
+        let earlier = RunId::from_parts(1_000, u128::MAX);
+        let later = RunId::from_parts(1_001, 0);
+
+        assert!(earlier < later);
+        assert!(earlier.to_string() < later.to_string());
+        assert_eq!(later.timestamp_millis(), 1_001);
+    }
+
+    #[test]
+    fn test_run_id_known_encoding() {
+        let id = RunId::from_parts(0, 0);
+
+        assert_eq!(id.to_string(), "00000000000000000000000000");
+        assert_eq!(
+            RunId::from_parts(1, 1).to_string(),
+            "00000000010000000000000001"
+        );
+    }
+
+    #[test]
+    fn test_run_id_parse_invalid() {
+        assert!("".parse::<RunId>().is_err());
+        assert!("0000000000000000000000000U".parse::<RunId>().is_err());
+        assert!("80000000000000000000000000".parse::<RunId>().is_err());
+    }
+}
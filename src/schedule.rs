@@ -0,0 +1,411 @@
+//! The scheduling core, `no_std` + `alloc`: schedule fields matched against
+//! plain wall clock readings, with no time zone or clock involved. Tasks
+//! build on it with time zones, DST policies and rules, see
+//! `CronTask::schedule`.
+
+#[cfg(not(feature = "std"))]
+use alloc::format;
+use core::{fmt::Display, str::FromStr};
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::{
+    error::{CronError, Result},
+    value::CronValue,
+    year::YearValue,
+};
+
+/// How the month day and week day fields combine once both are restricted.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DayMatch {
+    /// A date must match both fields.
+    #[default]
+    All,
+    /// A date matching either field is enough, as cron reads its lines:
+    /// `0 0 1 * 1` runs on the first of the month and on every Monday.
+    Any,
+}
+
+impl DayMatch {
+    /// Whether a date matching either field is enough, [`DayMatch::Any`]
+    /// being asked for and both fields restricted.
+    pub fn is_either(self, month_day: &CronValue, week_day: &CronValue) -> bool {
+        self == DayMatch::Any && month_day.is_restricted() && week_day.is_restricted()
+    }
+}
+
+impl Display for DayMatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            DayMatch::All => "all",
+            DayMatch::Any => "any",
+        })
+    }
+}
+
+impl FromStr for DayMatch {
+    type Err = CronError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "all" => Ok(DayMatch::All),
+            "any" => Ok(DayMatch::Any),
+            _ => Err(CronError::InvalidExpression(format!(
+                "unknown day match `{s}`"
+            ))),
+        }
+    }
+}
+
+/// Whether `date` matches the day fields, combined as `day_match` asks.
+pub(crate) fn matches_day(
+    month_day: &CronValue,
+    week_day: &CronValue,
+    day_match: DayMatch,
+    date: NaiveDate,
+) -> bool {
+    let month_day_matches = month_day.matches_month_day(date);
+    let week_day_matches = week_day.matches_week_day(date.weekday() as u8, date.day() as u8);
+
+    if day_match.is_either(month_day, week_day) {
+        month_day_matches || week_day_matches
+    } else {
+        month_day_matches && week_day_matches
+    }
+}
+
+/// A wall clock reading to the second, with no time zone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PlainDateTime {
+    year: i32,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+}
+
+impl PlainDateTime {
+    /// `None` for a date or time that does not exist, such as February 30
+    /// or 24:00.
+    pub fn new(year: i32, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> Option<Self> {
+        NaiveDate::from_ymd_opt(year, month as u32, day as u32)?;
+        if hour > 23 || minute > 59 || second > 59 {
+            return None;
+        }
+
+        Some(Self {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        })
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    fn date(&self) -> NaiveDate {
+        NaiveDate::from_ymd_opt(self.year, self.month as u32, self.day as u32)
+            .expect("plain dates are valid")
+    }
+}
+
+impl Display for PlainDateTime {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
+
+/// The fields of a schedule, with crontab numbering: week days from
+/// Monday = 0 and months from 1. Without a seconds field, it runs at
+/// second 0 of the matching minutes.
+#[derive(Debug, Clone, Default)]
+pub struct Schedule {
+    pub second: Option<CronValue>,
+    pub minute: CronValue,
+    pub hour: CronValue,
+    pub month_day: CronValue,
+    pub month: CronValue,
+    pub week_day: CronValue,
+    pub year: YearValue,
+    pub day_match: DayMatch,
+}
+
+impl Schedule {
+    /// Fails on values out of their field's bounds, or items meant for
+    /// another field, such as `L` in the hour field.
+    pub fn verify(&self) -> Result<()> {
+        self.year.verify()?;
+
+        if let Some(second) = &self.second {
+            second.verify(0, 60)?;
+        }
+        self.minute.verify(0, 60)?;
+        self.hour.verify(0, 24)?;
+        self.month_day.verify(1, 32)?;
+        self.month.verify(1, 13)?;
+        self.week_day.verify(0, 7)?;
+
+        let others = [&self.minute, &self.hour, &self.month];
+        if others
+            .into_iter()
+            .chain(&self.second)
+            .any(|value| value.has_month_day_items() || value.has_week_day_items())
+            || self.month_day.has_week_day_items()
+            || self.week_day.has_month_day_items()
+        {
+            return Err(CronError::InvalidCronValue);
+        }
+
+        Ok(())
+    }
+
+    pub fn matches(&self, date: &PlainDateTime) -> bool {
+        self.year.matches(date.year)
+            && self.month.matches_in(date.month, 1)
+            && matches_day(&self.month_day, &self.week_day, self.day_match, date.date())
+            && self.hour.matches(date.hour)
+            && self.minute.matches(date.minute)
+            && match &self.second {
+                Some(second) => second.matches(date.second),
+                None => date.second == 0,
+            }
+    }
+
+    /// First matching reading after `from`, `None` when there is none
+    /// within the 400 years the calendar takes to repeat.
+    pub fn next_after(&self, from: &PlainDateTime) -> Option<PlainDateTime> {
+        let zero = CronValue::from(0);
+        let second = self.second.as_ref().unwrap_or(&zero);
+        let PlainDateTime {
+            mut year,
+            mut month,
+            mut day,
+            mut hour,
+            mut minute,
+            second: mut sec,
+        } = *from;
+        sec += 1;
+        let mut years_searched = 0;
+
+        loop {
+            // Carries the fields gone past their last value.
+            if sec > 59 {
+                (minute, sec) = (minute + 1, 0);
+            }
+            if minute > 59 {
+                (hour, minute) = (hour + 1, 0);
+            }
+            if hour > 23 {
+                (day, hour) = (day + 1, 0);
+            }
+            if NaiveDate::from_ymd_opt(year, month as u32, day as u32).is_none() {
+                (month, day) = (month + 1, 1);
+            }
+            if month > 12 {
+                (year, month) = (year.checked_add(1)?, 1);
+                years_searched += 1;
+            }
+
+            match self.year.next_value(year) {
+                Some(next) if next != year => {
+                    (year, month, day, hour, minute, sec) = (next, 1, 1, 0, 0, 0);
+                    years_searched += 1;
+                }
+                Some(_) => {}
+                None => return None,
+            }
+            if years_searched > 400 {
+                return None;
+            }
+
+            if !self.month.matches_in(month, 1) {
+                (month, day, hour, minute, sec) = (month + 1, 1, 0, 0, 0);
+                continue;
+            }
+            let date = NaiveDate::from_ymd_opt(year, month as u32, day as u32)?;
+            if !matches_day(&self.month_day, &self.week_day, self.day_match, date) {
+                (day, hour, minute, sec) = (day + 1, 0, 0, 0);
+                continue;
+            }
+
+            match self.hour.next_value(hour, 23) {
+                Some(next) if next != hour => (hour, minute, sec) = (next, 0, 0),
+                Some(_) => {}
+                None => {
+                    (day, hour, minute, sec) = (day + 1, 0, 0, 0);
+                    continue;
+                }
+            }
+            match self.minute.next_value(minute, 59) {
+                Some(next) if next != minute => (minute, sec) = (next, 0),
+                Some(_) => {}
+                None => {
+                    (hour, minute, sec) = (hour + 1, 0, 0);
+                    continue;
+                }
+            }
+            match second.next_value(sec, 59) {
+                Some(next) => sec = next,
+                None => {
+                    (minute, sec) = (minute + 1, 0);
+                    continue;
+                }
+            }
+
+            return PlainDateTime::new(year, month, day, hour, minute, sec);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Weekday;
+
+    use super::*;
+    use crate::value::{every, last_day, nth, on, range};
+
+    fn at(year: i32, month: u8, day: u8, hour: u8, minute: u8) -> PlainDateTime {
+        PlainDateTime::new(year, month, day, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_plain_date_time() {
+        assert!(PlainDateTime::new(2025, 2, 29, 0, 0, 0).is_none());
+        assert!(PlainDateTime::new(2024, 2, 29, 0, 0, 0).is_some());
+        assert!(PlainDateTime::new(2025, 6, 1, 24, 0, 0).is_none());
+        assert_eq!(at(2025, 6, 1, 9, 5).to_string(), "2025-06-01 09:05:00");
+        assert!(at(2025, 6, 1, 9, 5) < at(2025, 6, 1, 10, 0));
+    }
+
+    #[test]
+    fn test_schedule_next_after() {
+        let schedule = Schedule {
+            minute: on(30).into(),
+            hour: range(9..17),
+            week_day: Weekday::Mon.into(),
+            ..Schedule::default()
+        };
+        assert!(schedule.verify().is_ok());
+
+        // 2025-06-01 is a Sunday.
+        let next = schedule.next_after(&at(2025, 6, 1, 12, 0)).unwrap();
+        assert_eq!(next, at(2025, 6, 2, 9, 30));
+        assert!(schedule.matches(&next));
+        assert_eq!(schedule.next_after(&next), Some(at(2025, 6, 2, 10, 30)));
+        // Ranges take their end in.
+        assert_eq!(
+            schedule.next_after(&at(2025, 6, 2, 17, 30)),
+            Some(at(2025, 6, 9, 9, 30))
+        );
+    }
+
+    #[test]
+    fn test_schedule_days() {
+        let last = Schedule {
+            minute: on(0).into(),
+            hour: on(0).into(),
+            month_day: last_day(),
+            ..Schedule::default()
+        };
+        assert_eq!(
+            last.next_after(&at(2024, 2, 1, 0, 0)),
+            Some(at(2024, 2, 29, 0, 0))
+        );
+        assert_eq!(
+            last.next_after(&at(2023, 12, 31, 0, 0)),
+            Some(at(2024, 1, 31, 0, 0))
+        );
+
+        let second_monday = Schedule {
+            minute: on(0).into(),
+            hour: on(0).into(),
+            week_day: nth(Weekday::Mon, 2),
+            ..Schedule::default()
+        };
+        assert_eq!(
+            second_monday.next_after(&at(2025, 6, 1, 0, 0)),
+            Some(at(2025, 6, 9, 0, 0))
+        );
+
+        // The first of the month or a Monday, as cron reads `0 0 1 * 1`.
+        let either = Schedule {
+            minute: on(0).into(),
+            hour: on(0).into(),
+            month_day: on(1).into(),
+            week_day: Weekday::Mon.into(),
+            day_match: DayMatch::Any,
+            ..Schedule::default()
+        };
+        assert_eq!(
+            either.next_after(&at(2025, 6, 2, 0, 0)),
+            Some(at(2025, 6, 9, 0, 0))
+        );
+        assert_eq!(
+            either.next_after(&at(2025, 6, 30, 0, 0)),
+            Some(at(2025, 7, 1, 0, 0))
+        );
+    }
+
+    #[test]
+    fn test_schedule_seconds_and_years() {
+        let schedule = Schedule {
+            second: Some(every(20)),
+            year: 2030.into(),
+            ..Schedule::default()
+        };
+        assert_eq!(
+            schedule.next_after(&at(2025, 6, 1, 0, 0)),
+            PlainDateTime::new(2030, 1, 1, 0, 0, 0)
+        );
+        assert_eq!(
+            schedule.next_after(&PlainDateTime::new(2030, 1, 1, 0, 0, 40).unwrap()),
+            PlainDateTime::new(2030, 1, 1, 0, 1, 0)
+        );
+        assert_eq!(schedule.next_after(&at(2031, 1, 1, 0, 0)), None);
+
+        // February 30 never comes.
+        let never = Schedule {
+            month_day: on(30).into(),
+            month: on(2).into(),
+            ..Schedule::default()
+        };
+        assert_eq!(never.next_after(&at(2025, 1, 1, 0, 0)), None);
+        assert!(
+            Schedule {
+                hour: on(24).into(),
+                ..Schedule::default()
+            }
+            .verify()
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,2255 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Display,
+    ops::Range,
+    str::FromStr,
+    sync::{
+        Arc, Condvar, Mutex, MutexGuard,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
+#[cfg(feature = "tokio")]
+use std::{future::Future, pin::Pin};
+
+use chrono::{DateTime, Local, TimeDelta};
+#[cfg(feature = "tokio")]
+use tokio::{
+    sync::{Notify, Semaphore},
+    time::{Instant, timeout_at},
+};
+
+#[cfg(feature = "bundle")]
+use crate::bundle::Bundle;
+#[cfg(feature = "budget")]
+use crate::event::SchedulerEvent;
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use crate::realtime::SystemClock;
+use crate::{
+    cron::{Cron, SharedCron},
+    error::{CronError, Result},
+    event::EventLog,
+    group::OverlapPolicy,
+    ready::Readiness,
+    realtime::Clock,
+    run_id::RunId,
+    skip::SkipReason,
+    state::{Persistence, SchedulerState, StateStore},
+    task::CronTask,
+};
+
+/// Runs the tasks of a [`Cron`] on a thread of its own, calling `dispatch`
+/// with each due task, its scheduled date and the permit of the run.
+/// `@reboot` tasks are dispatched once on start, with the start date.
+/// Dispatching happens on the scheduler thread, so long runs should be
+/// handed off along with their permit.
+///
+/// Given a [`SharedCron`], the scheduler reads it on each wake-up and is
+/// woken up by changes made through other handles, so pausing a task or
+/// adding one applies to the running scheduler.
+///
+/// On start, the scheduler works out the next run of every task, sharing
+/// the tasks between the available cores, and only then schedules them;
+/// [`ThreadScheduler::ready`] tells the two apart. A wake-up then only
+/// looks again at the tasks that fell due.
+///
+/// A run of a group with a concurrency limit waits while as many permits
+/// of the group as it allows are held, or is skipped under
+/// [`OverlapPolicy::Skip`]. Runs the schedule calls for but that do not
+/// happen are reported to the [`EventLog`] with the reason why.
+///
+/// ```ignore
+/// let scheduler = ThreadScheduler::run(cron, |task, _, permit| {
+///     let task = task.clone();
+///     std::thread::spawn(move || {
+///         let _ = task.execute();
+///         drop(permit);
+///     });
+/// });
+/// // ...
+/// scheduler.stop();
+/// ```
+#[derive(Debug)]
+pub struct ThreadScheduler {
+    shared: Arc<Shared>,
+    metrics: FiringMetrics,
+    events: EventLog,
+    degraded: Arc<AtomicBool>,
+    ready: Readiness,
+    thread: JoinHandle<()>,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    state: Mutex<State>,
+    wake: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    stopped: bool,
+    /// Permits held per group.
+    running: HashMap<String, usize>,
+    /// Permits given back so far.
+    released: u64,
+    /// Changes made to the shared cron so far.
+    changes: u64,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        // The state is always left whole, a poisoned lock is still usable.
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Slot a run takes in its group's concurrency limit, see
+/// [`ThreadScheduler`] and [`PollingScheduler`], along with the id of the
+/// run. Dropping it ends the run for the scheduler, which records the time
+/// it took against the task's budget if it has one, taking the cron's
+/// write lock to do so.
+#[derive(Debug)]
+pub struct RunPermit {
+    run_id: RunId,
+    group: Option<String>,
+    shared: Arc<Shared>,
+    #[cfg(feature = "budget")]
+    budget: Option<(BudgetedRun, std::time::Instant)>,
+}
+
+impl RunPermit {
+    /// Id of the run, to hand down to its command with
+    /// [`CronTask::execute_run`] and to tag what it reports.
+    pub fn run_id(&self) -> RunId {
+        self.run_id
+    }
+
+    /// Records the run time of budgeted tasks once the permit is dropped.
+    /// Targets without a monotonic clock, such as wasm, record nothing.
+    #[cfg(feature = "budget")]
+    fn track(&mut self, run: Option<BudgetedRun>) {
+        let measured = !cfg!(all(target_arch = "wasm32", target_os = "unknown"));
+        self.budget = run
+            .filter(|_| measured)
+            .map(|run| (run, std::time::Instant::now()));
+    }
+}
+
+impl Drop for RunPermit {
+    fn drop(&mut self) {
+        #[cfg(feature = "budget")]
+        if let Some((run, started)) = self.budget.take() {
+            run.record(self.run_id, started.elapsed());
+        }
+        let Some(group) = self.group.take() else {
+            return;
+        };
+        let mut state = self.shared.lock();
+        if let Some(running) = state.running.get_mut(&group) {
+            *running = running.saturating_sub(1);
+        }
+        state.released += 1;
+        self.shared.wake.notify_all();
+    }
+}
+
+impl ThreadScheduler {
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    pub fn run<F>(cron: impl Into<SharedCron>, dispatch: F) -> Self
+    where
+        F: FnMut(&CronTask, DateTime<Local>, RunPermit) + Send + 'static,
+    {
+        Self::run_with_clock(cron, SystemClock, dispatch)
+    }
+
+    pub fn run_with_clock<C, F>(cron: impl Into<SharedCron>, clock: C, dispatch: F) -> Self
+    where
+        C: Clock<Tz = Local> + Send + 'static,
+        F: FnMut(&CronTask, DateTime<Local>, RunPermit) + Send + 'static,
+    {
+        Self::run_with_mode(cron, clock, FiringMode::default(), dispatch)
+    }
+
+    pub fn run_with_mode<C, F>(
+        cron: impl Into<SharedCron>,
+        clock: C,
+        mode: FiringMode,
+        dispatch: F,
+    ) -> Self
+    where
+        C: Clock<Tz = Local> + Send + 'static,
+        F: FnMut(&CronTask, DateTime<Local>, RunPermit) + Send + 'static,
+    {
+        Self::builder(cron, clock).firing_mode(mode).run(dispatch)
+    }
+
+    /// Scheduler with more options than the `run` functions take, such as
+    /// a state store.
+    pub fn builder<C>(cron: impl Into<SharedCron>, clock: C) -> ThreadSchedulerBuilder<C>
+    where
+        C: Clock<Tz = Local> + Send + 'static,
+    {
+        ThreadSchedulerBuilder {
+            cron: cron.into(),
+            clock,
+            mode: FiringMode::default(),
+            store: None,
+            catch_up: None,
+        }
+    }
+
+    /// Scheduler set up as `bundle` says, but for its state store, which
+    /// the caller opens from [`Bundle::state`] and passes on to
+    /// [`ThreadSchedulerBuilder::state_store`].
+    #[cfg(feature = "bundle")]
+    pub fn import_bundle<C>(bundle: Bundle, clock: C) -> ThreadSchedulerBuilder<C>
+    where
+        C: Clock<Tz = Local> + Send + 'static,
+    {
+        ThreadSchedulerBuilder {
+            cron: bundle.cron.into(),
+            clock,
+            mode: bundle.firing_mode,
+            store: None,
+            catch_up: bundle.catch_up,
+        }
+    }
+
+    /// Lateness of the runs fired so far.
+    pub fn metrics(&self) -> FiringMetrics {
+        self.metrics.clone()
+    }
+
+    /// Runs skipped so far, and the ones to come as they are.
+    pub fn events(&self) -> EventLog {
+        self.events.clone()
+    }
+
+    /// Whether the state store failed the last time it was used, the
+    /// scheduler then running without it.
+    pub fn is_degraded(&self) -> bool {
+        self.degraded.load(Ordering::Relaxed)
+    }
+
+    /// Whether the scheduler is done working out the next run of every
+    /// task and schedules them, or is still starting.
+    pub fn ready(&self) -> Readiness {
+        self.ready.clone()
+    }
+
+    /// Wakes the scheduler thread up and waits for it to end, after the
+    /// dispatch in progress if any. Dropping the handle instead leaves the
+    /// thread running.
+    pub fn stop(self) {
+        self.shared.lock().stopped = true;
+        self.shared.wake.notify_all();
+
+        // A panic in `dispatch` already ended the thread.
+        let _ = self.thread.join();
+    }
+
+    /// Whether the thread ended, which happens once no task has a next run.
+    /// With a lookahead, that is only once no task runs on a schedule. With
+    /// a cron still shared with other handles, the thread instead waits for
+    /// tasks to be added.
+    pub fn is_finished(&self) -> bool {
+        self.thread.is_finished()
+    }
+}
+
+/// Options of a [`ThreadScheduler`], see [`ThreadScheduler::builder`].
+pub struct ThreadSchedulerBuilder<C> {
+    cron: SharedCron,
+    clock: C,
+    mode: FiringMode,
+    store: Option<Box<dyn StateStore>>,
+    catch_up: Option<CatchUpPolicy>,
+}
+
+impl<C: Clock<Tz = Local> + Send + 'static> ThreadSchedulerBuilder<C> {
+    pub fn firing_mode(mut self, mode: FiringMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Saves the [`SchedulerState`] to `store` on each wake-up. The
+    /// scheduler keeps running while the store fails, see
+    /// [`ThreadScheduler::is_degraded`].
+    pub fn state_store(mut self, store: impl StateStore + 'static) -> Self {
+        self.store = Some(Box::new(store));
+        self
+    }
+
+    /// Dispatches on start the runs missed since the last wake-up saved in
+    /// the state store, as `policy` says. Without a store or a saved state,
+    /// or with the store failing, nothing is caught up on.
+    pub fn catch_up(mut self, policy: CatchUpPolicy) -> Self {
+        self.catch_up = Some(policy);
+        self
+    }
+
+    /// The setup of the scheduler as a [`Bundle`], with the cron as it is
+    /// now and the reference of the state store.
+    #[cfg(feature = "bundle")]
+    pub fn export_bundle(&self) -> Bundle {
+        Bundle {
+            cron: self.cron.read().clone(),
+            firing_mode: self.mode,
+            catch_up: self.catch_up,
+            state: self.store.as_ref().and_then(|store| store.reference()),
+        }
+    }
+
+    pub fn run<F>(self, mut dispatch: F) -> ThreadScheduler
+    where
+        F: FnMut(&CronTask, DateTime<Local>, RunPermit) + Send + 'static,
+    {
+        let Self {
+            cron,
+            clock,
+            mode,
+            store,
+            catch_up,
+        } = self;
+        let shared = Arc::new(Shared::default());
+        let signal = Arc::clone(&shared);
+        let metrics = FiringMetrics::default();
+        let recorder = metrics.clone();
+        let events = EventLog::default();
+        let log = events.clone();
+        let degraded = Arc::new(AtomicBool::new(false));
+        let mut store =
+            store.map(|store| Persistence::new(store, events.clone(), Arc::clone(&degraded)));
+        let ready = Readiness::default();
+        let warmed = ready.clone();
+
+        let watched = Arc::downgrade(&shared);
+        cron.watch(move || match watched.upgrade() {
+            Some(shared) => {
+                shared.lock().changes += 1;
+                shared.wake.notify_all();
+                true
+            }
+            None => false,
+        });
+
+        let thread = std::thread::spawn(move || {
+            let mut after = clock.now();
+            let mut changes = signal.lock().changes;
+            let saved = store.as_mut().and_then(Persistence::load);
+            let mut waiting: VecDeque<_> = {
+                let cron = cron.read();
+                skip_reboots(&cron, after, &log);
+                // The runs of the last wake-up were handled, those after it
+                // were missed.
+                let missed = match (saved.and_then(|state| state.last_wake), catch_up) {
+                    (Some(last), Some(policy)) => {
+                        let since = last + TimeDelta::nanoseconds(1);
+                        catch_up_runs(&cron, since..after, after, policy, &log)
+                    }
+                    _ => Vec::new(),
+                };
+                cron.reboot_tasks()
+                    .map(|task| (task.clone(), after))
+                    .chain(missed.into_iter().map(|(date, task)| (task.clone(), date)))
+                    .collect()
+            };
+            let mut runs = NextRuns::warm_up(&cron.read(), after);
+            warmed.set();
+            let mut released = 0;
+            dispatch_waiting(&cron, &signal, &log, &mut waiting, &mut dispatch);
+            if let Some(store) = &mut store {
+                store.save(&runs.state(after));
+            }
+
+            loop {
+                let next = runs.next_wake();
+                if next.is_none() && waiting.is_empty() && !cron.is_shared() {
+                    return;
+                }
+
+                // Sleeps until the next run is due, also waking up when a
+                // permit comes back while runs wait for one, or when the
+                // cron changes.
+                let state = signal.lock();
+                let keep_waiting = |state: &mut State| {
+                    !state.stopped
+                        && (waiting.is_empty() || state.released == released)
+                        && state.changes == changes
+                };
+                let (state, timed_out) = match next {
+                    Some((date, _)) => {
+                        let wait = mode.wait(date - clock.now());
+                        let (state, result) = signal
+                            .wake
+                            .wait_timeout_while(state, wait, keep_waiting)
+                            .unwrap_or_else(|e| e.into_inner());
+                        (state, result.timed_out())
+                    }
+                    None => (
+                        signal
+                            .wake
+                            .wait_while(state, keep_waiting)
+                            .unwrap_or_else(|e| e.into_inner()),
+                        false,
+                    ),
+                };
+                if state.stopped {
+                    return;
+                }
+                let changed = state.changes != changes;
+                released = state.released;
+                changes = state.changes;
+                drop(state);
+
+                if let (Some((date, due)), true) = (next, timed_out) {
+                    let cron = cron.read();
+                    if due {
+                        let due = cron.get_all_due_at(date);
+                        if !due.is_empty() {
+                            recorder.record(clock.now() - date);
+                        }
+                        waiting.extend(due.into_iter().map(|task| (task.clone(), date)));
+                        for (task, reason) in cron.get_all_skipped_due_at(date) {
+                            log.skip(task, date, reason);
+                        }
+                    }
+                    runs.advance(&cron, date);
+                    after = date;
+                }
+                if changed {
+                    runs = NextRuns::warm_up(&cron.read(), after);
+                }
+                dispatch_waiting(&cron, &signal, &log, &mut waiting, &mut dispatch);
+                if let (Some(store), true) = (&mut store, timed_out) {
+                    store.save(&runs.state(after));
+                }
+            }
+        });
+
+        ThreadScheduler {
+            shared,
+            metrics,
+            events,
+            degraded,
+            ready,
+            thread,
+        }
+    }
+}
+
+/// How precisely a scheduler wakes up for a run.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FiringMode {
+    /// At the scheduled instant, to the precision of the host timer.
+    #[default]
+    Exact,
+    /// After a whole number of seconds, rounding the wait up: runs are
+    /// never early but may be up to a second late, in exchange for timers
+    /// without sub-second precision.
+    Coarse,
+}
+
+impl Display for FiringMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            FiringMode::Exact => "exact",
+            FiringMode::Coarse => "coarse",
+        })
+    }
+}
+
+impl FromStr for FiringMode {
+    type Err = CronError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "exact" => Ok(FiringMode::Exact),
+            "coarse" => Ok(FiringMode::Coarse),
+            _ => Err(CronError::InvalidExpression(format!(
+                "unknown firing mode `{s}`"
+            ))),
+        }
+    }
+}
+
+impl FiringMode {
+    /// Time to sleep for a run `delta` away.
+    fn wait(self, delta: TimeDelta) -> Duration {
+        let wait = delta.to_std().unwrap_or_default();
+        match self {
+            Self::Exact => wait,
+            Self::Coarse if wait.subsec_nanos() > 0 => Duration::from_secs(wait.as_secs() + 1),
+            Self::Coarse => wait,
+        }
+    }
+}
+
+/// Lateness of fired runs, measured on the scheduler's clock between the
+/// scheduled date and the wake-up for it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FiringAccuracy {
+    firings: u64,
+    total: TimeDelta,
+    max: TimeDelta,
+}
+
+impl FiringAccuracy {
+    /// Number of wake-ups for due runs.
+    pub fn firings(&self) -> u64 {
+        self.firings
+    }
+
+    pub fn max_lateness(&self) -> TimeDelta {
+        self.max
+    }
+
+    pub fn mean_lateness(&self) -> Option<TimeDelta> {
+        let firings = i64::try_from(self.firings).ok().filter(|n| *n > 0)?;
+        Some(TimeDelta::nanoseconds(
+            self.total.num_nanoseconds()? / firings,
+        ))
+    }
+}
+
+/// Shared handle on the [`FiringAccuracy`] of a scheduler, readable while
+/// the scheduler runs.
+#[derive(Debug, Default, Clone)]
+pub struct FiringMetrics(Arc<Mutex<FiringAccuracy>>);
+
+impl FiringMetrics {
+    pub fn accuracy(&self) -> FiringAccuracy {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Runs woken up early, as with a clock behind the timer, count as on
+    /// time.
+    fn record(&self, lateness: TimeDelta) {
+        let lateness = lateness.max(TimeDelta::zero());
+        let mut accuracy = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        accuracy.firings += 1;
+        accuracy.total += lateness;
+        accuracy.max = accuracy.max.max(lateness);
+    }
+}
+
+/// Least tasks worked out on a thread of their own when warming up.
+const WARM_UP_CHUNK: usize = 256;
+
+/// Next run of each task of a cron, in its order, so that a wake-up only
+/// looks again at the schedules of the tasks that fell due. A task with
+/// nothing inside the lookahead is looked at again at the end of the
+/// horizon, the scheduler idling until then.
+#[derive(Debug)]
+struct NextRuns {
+    dates: Vec<Option<DateTime<Local>>>,
+    /// Earliest end of the lookahead a task had no run inside of.
+    horizon: Option<DateTime<Local>>,
+}
+
+impl NextRuns {
+    /// Works out the next run after `after` of every task of `cron`,
+    /// sharing the tasks between the available cores.
+    fn warm_up(cron: &Cron, after: DateTime<Local>) -> Self {
+        let tasks: Vec<&CronTask> = cron.into_iter().collect();
+        let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+        let chunk = tasks.len().div_ceil(threads).max(WARM_UP_CHUNK);
+        let next_runs = |tasks: &[&CronTask]| -> Vec<_> {
+            tasks
+                .iter()
+                .map(|task| cron.next_run_of(task, after))
+                .collect()
+        };
+        let dates = if tasks.len() <= chunk {
+            next_runs(&tasks)
+        } else {
+            std::thread::scope(|scope| {
+                let handles: Vec<_> = tasks
+                    .chunks(chunk)
+                    .map(|tasks| scope.spawn(move || next_runs(tasks)))
+                    .collect();
+                handles
+                    .into_iter()
+                    .flat_map(|handle| {
+                        handle
+                            .join()
+                            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                    })
+                    .collect()
+            })
+        };
+        let mut runs = Self {
+            dates,
+            horizon: None,
+        };
+        for (task, date) in tasks.into_iter().zip(runs.dates.clone()) {
+            runs.note_idle(cron, task, date, after);
+        }
+        runs
+    }
+
+    /// Date the scheduler next wakes up at, and whether runs are due or
+    /// skipped then rather than the lookahead running out.
+    fn next_wake(&self) -> Option<(DateTime<Local>, bool)> {
+        let due = self.dates.iter().flatten().min().copied();
+        match (due, self.horizon) {
+            (Some(date), Some(horizon)) if horizon < date => Some((horizon, false)),
+            (Some(date), _) => Some((date, true)),
+            (None, horizon) => horizon.map(|horizon| (horizon, false)),
+        }
+    }
+
+    /// Moves on the tasks due at `date`, and the idle ones once `date`
+    /// reaches the end of their lookahead.
+    fn advance(&mut self, cron: &Cron, date: DateTime<Local>) {
+        let idle_over = self.horizon.is_some_and(|horizon| horizon <= date);
+        if idle_over {
+            self.horizon = None;
+        }
+        for (index, task) in cron.into_iter().enumerate() {
+            let Some(next) = self.dates.get_mut(index) else {
+                break;
+            };
+            let stale = match *next {
+                Some(next) => next <= date,
+                None => idle_over,
+            };
+            if stale {
+                *next = cron.next_run_of(task, date);
+                let next = *next;
+                self.note_idle(cron, task, next, date);
+            }
+        }
+    }
+
+    /// Idles until the end of the lookahead for a task with no run inside
+    /// it, as long as it runs on a schedule.
+    fn note_idle(
+        &mut self,
+        cron: &Cron,
+        task: &CronTask,
+        next: Option<DateTime<Local>>,
+        from: DateTime<Local>,
+    ) {
+        if next.is_some() || task.is_reboot() {
+            return;
+        }
+        let end = cron
+            .lookahead()
+            .and_then(|lookahead| from.checked_add_signed(lookahead));
+        self.horizon = match (self.horizon, end) {
+            (Some(horizon), Some(end)) => Some(horizon.min(end)),
+            (horizon, end) => horizon.or(end),
+        };
+    }
+
+    fn state(&self, last_wake: DateTime<Local>) -> SchedulerState {
+        SchedulerState {
+            last_wake: Some(last_wake),
+            next_runs: self.dates.clone(),
+        }
+    }
+}
+
+/// Reports the `@reboot` tasks that do not run on start.
+fn skip_reboots(cron: &Cron, start: DateTime<Local>, events: &EventLog) {
+    for task in cron.into_iter().filter(|task| task.is_reboot()) {
+        if let Some(reason) = cron.skip_reason(task) {
+            events.skip(task, start, reason);
+        }
+    }
+}
+
+/// Dispatches the waiting runs whose group has a permit to spare, in
+/// order, leaving the others waiting or skipping them as their group's
+/// overlap policy says. The cron is not locked while `dispatch` runs, so
+/// that it may change the cron.
+fn dispatch_waiting<F>(
+    cron: &SharedCron,
+    shared: &Arc<Shared>,
+    events: &EventLog,
+    waiting: &mut VecDeque<(CronTask, DateTime<Local>)>,
+    dispatch: &mut F,
+) where
+    F: FnMut(&CronTask, DateTime<Local>, RunPermit),
+{
+    let mut blocked = VecDeque::new();
+
+    while let Some((task, date)) = waiting.pop_front() {
+        let permit = try_permit(&cron.read(), shared, &task);
+        match permit {
+            #[cfg_attr(not(feature = "budget"), allow(unused_mut))]
+            Ok(mut permit) => {
+                #[cfg(feature = "budget")]
+                permit.track(BudgetedRun::new(cron, events, &task, date));
+                dispatch(&task, date, permit)
+            }
+            Err(OverlapPolicy::Skip) => events.skip(&task, date, SkipReason::Overlap),
+            Err(OverlapPolicy::Queue) => blocked.push_back((task, date)),
+        }
+    }
+
+    *waiting = blocked;
+}
+
+/// Takes a permit for a run of `task`, unless its group already holds as
+/// many as its concurrency limit allows, in which case the group's overlap
+/// policy comes back.
+fn try_permit(
+    cron: &Cron,
+    shared: &Arc<Shared>,
+    task: &CronTask,
+) -> std::result::Result<RunPermit, OverlapPolicy> {
+    let limited = task.group().and_then(|name| {
+        let group = cron.group(name)?;
+        Some((name, group.max_concurrent()?, group.overlap_policy()))
+    });
+    let group = match limited {
+        Some((name, limit, overlap)) => {
+            let mut state = shared.lock();
+            let running = state.running.entry(name.to_string()).or_default();
+            if *running >= limit.max(1) {
+                return Err(overlap);
+            }
+            *running += 1;
+            Some(name.to_string())
+        }
+        None => None,
+    };
+
+    Ok(RunPermit {
+        run_id: RunId::new(),
+        group,
+        shared: Arc::clone(shared),
+        #[cfg(feature = "budget")]
+        budget: None,
+    })
+}
+
+/// Run of a task with a budget, whose run time goes to the cron's ledger
+/// once it ends.
+#[cfg(feature = "budget")]
+#[derive(Debug)]
+struct BudgetedRun {
+    cron: SharedCron,
+    events: EventLog,
+    task: CronTask,
+    date: DateTime<Local>,
+}
+
+#[cfg(feature = "budget")]
+impl BudgetedRun {
+    fn new(
+        cron: &SharedCron,
+        events: &EventLog,
+        task: &CronTask,
+        date: DateTime<Local>,
+    ) -> Option<Self> {
+        task.budget()?;
+        Some(Self {
+            cron: cron.clone(),
+            events: events.clone(),
+            task: task.clone(),
+            date,
+        })
+    }
+
+    /// Records `runtime` in the month of the run, reporting the budget
+    /// running out.
+    fn record(self, run_id: RunId, runtime: Duration) {
+        let runtime = TimeDelta::from_std(runtime).unwrap_or(TimeDelta::MAX);
+        let exhausted = {
+            let mut cron = self.cron.write();
+            let before = cron.ledger().is_exhausted(&self.task, self.date);
+            cron.ledger_mut()
+                .record(&self.task.budget_key(), self.date, runtime);
+            !before && cron.ledger().is_exhausted(&self.task, self.date)
+        };
+        if exhausted {
+            self.events.emit(SchedulerEvent::BudgetExhausted {
+                task: self.task,
+                date: self.date,
+                run_id,
+            });
+        }
+    }
+}
+
+/// Runs of `cron` due within `window`, filtered by `policy` at `now`, in
+/// the order they fell due. Runs left out are reported to `events`.
+fn catch_up_runs<'a>(
+    cron: &'a Cron,
+    window: Range<DateTime<Local>>,
+    now: DateTime<Local>,
+    policy: CatchUpPolicy,
+    events: &EventLog,
+) -> Vec<(DateTime<Local>, &'a CronTask)> {
+    let mut runs: Vec<(DateTime<Local>, &CronTask)> = Vec::new();
+    for task in cron.into_iter().filter(|task| !task.is_reboot()) {
+        let mut missed: Vec<_> = task.occurrences_between(window.clone()).collect();
+        let kept = match policy {
+            CatchUpPolicy::All => missed.len(),
+            CatchUpPolicy::Latest => missed.len().min(1),
+            CatchUpPolicy::Within(lateness) => missed
+                .iter()
+                .rev()
+                .take_while(|date| now - **date <= lateness)
+                .count(),
+        };
+        let reason = match policy {
+            CatchUpPolicy::Latest => SkipReason::Superseded,
+            _ => SkipReason::MissedBeyondGrace,
+        };
+        for date in missed.drain(..missed.len() - kept) {
+            events.skip(task, date, reason.clone());
+        }
+        for date in missed {
+            match cron.skip_reason_at(task, date) {
+                Some(reason) => events.skip(task, date, reason),
+                None => runs.push((date, task)),
+            }
+        }
+    }
+    runs.sort_by_key(|(date, _)| *date);
+    runs
+}
+
+/// Which of the runs due since the last wake a [`PollingScheduler`] keeps,
+/// or a [`ThreadScheduler`] catches up on when started again.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CatchUpPolicy {
+    /// Every run, however late.
+    #[default]
+    All,
+    /// Only the most recent run of each task.
+    Latest,
+    /// Runs at most this late, older ones being dropped.
+    Within(TimeDelta),
+}
+
+/// Written `all`, `latest`, or `within` followed by a number of seconds,
+/// such as `within 300s`.
+impl Display for CatchUpPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CatchUpPolicy::All => f.write_str("all"),
+            CatchUpPolicy::Latest => f.write_str("latest"),
+            CatchUpPolicy::Within(lateness) => write!(f, "within {}s", lateness.num_seconds()),
+        }
+    }
+}
+
+impl FromStr for CatchUpPolicy {
+    type Err = CronError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let unknown = || CronError::InvalidExpression(format!("unknown catch-up policy `{s}`"));
+        match s {
+            "all" => Ok(CatchUpPolicy::All),
+            "latest" => Ok(CatchUpPolicy::Latest),
+            _ => {
+                let seconds = s
+                    .strip_prefix("within ")
+                    .and_then(|lateness| lateness.strip_suffix('s'))
+                    .and_then(|seconds| seconds.parse().ok())
+                    .ok_or_else(unknown)?;
+                TimeDelta::try_seconds(seconds)
+                    .filter(|lateness| *lateness >= TimeDelta::zero())
+                    .map(CatchUpPolicy::Within)
+                    .ok_or_else(unknown)
+            }
+        }
+    }
+}
+
+/// Fallback for targets without precise long sleeps, such as wasm or some
+/// embedded ones: the host wakes it every `interval` with a coarse timer
+/// and it hands back everything that fell due since the previous wake,
+/// along with `@reboot` tasks on the first one.
+///
+/// Group concurrency limits apply as with [`ThreadScheduler`]: a run
+/// whose group has no permit to spare is held back until a later poll
+/// finds one given back, or skipped under [`OverlapPolicy::Skip`]. Runs
+/// dropped by the catch-up policy or skipped by the cron are reported to
+/// the [`EventLog`]. The cron is read on each poll, so changes made
+/// through a [`SharedCron`] apply from the next one.
+///
+/// ```ignore
+/// let mut scheduler = PollingScheduler::new(cron, clock, TimeDelta::minutes(1));
+/// // On each tick of the host timer:
+/// for (date, task, permit) in scheduler.poll() {
+///     run(&task, date);
+///     drop(permit);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct PollingScheduler<C: Clock<Tz = Local>> {
+    cron: SharedCron,
+    clock: C,
+    interval: TimeDelta,
+    policy: CatchUpPolicy,
+    last: DateTime<Local>,
+    rebooted: bool,
+    /// Runs held back for a permit.
+    waiting: Vec<(DateTime<Local>, CronTask)>,
+    shared: Arc<Shared>,
+    events: EventLog,
+}
+
+impl<C: Clock<Tz = Local>> PollingScheduler<C> {
+    /// Starts counting due runs from the current date of `clock`.
+    pub fn new(cron: impl Into<SharedCron>, clock: C, interval: TimeDelta) -> Self {
+        Self {
+            cron: cron.into(),
+            last: clock.now(),
+            clock,
+            interval,
+            policy: CatchUpPolicy::default(),
+            rebooted: false,
+            waiting: Vec::new(),
+            shared: Arc::default(),
+            events: EventLog::default(),
+        }
+    }
+
+    pub fn with_policy(mut self, policy: CatchUpPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// How often the host should call `poll`.
+    pub fn interval(&self) -> TimeDelta {
+        self.interval
+    }
+
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    pub fn cron(&self) -> &SharedCron {
+        &self.cron
+    }
+
+    /// Runs skipped so far, and the ones to come as they are.
+    pub fn events(&self) -> EventLog {
+        self.events.clone()
+    }
+
+    /// Runs due since the previous call, filtered by the catch-up policy,
+    /// in the order they fell due, after the runs held back so far.
+    pub fn poll(&mut self) -> Vec<(DateTime<Local>, CronTask, RunPermit)> {
+        let now = self.clock.now();
+        let window = self.last..now;
+        let start = self.last;
+        self.last = now;
+
+        let cron = self.cron.read();
+        let mut reboots = Vec::new();
+        if !std::mem::replace(&mut self.rebooted, true) {
+            reboots = cron.reboot_tasks().map(|task| (start, task)).collect();
+            skip_reboots(&cron, start, &self.events);
+        }
+
+        let runs = catch_up_runs(&cron, window, now, self.policy, &self.events);
+
+        let due: Vec<_> = std::mem::take(&mut self.waiting)
+            .into_iter()
+            .chain(
+                reboots
+                    .into_iter()
+                    .chain(runs)
+                    .map(|(date, task)| (date, task.clone())),
+            )
+            .collect();
+
+        let mut ready = Vec::new();
+        for (date, task) in due {
+            match try_permit(&cron, &self.shared, &task) {
+                #[cfg_attr(not(feature = "budget"), allow(unused_mut))]
+                Ok(mut permit) => {
+                    #[cfg(feature = "budget")]
+                    permit.track(BudgetedRun::new(&self.cron, &self.events, &task, date));
+                    ready.push((date, task, permit))
+                }
+                Err(OverlapPolicy::Skip) => self.events.skip(&task, date, SkipReason::Overlap),
+                Err(OverlapPolicy::Queue) => self.waiting.push((date, task)),
+            }
+        }
+        ready
+    }
+}
+
+/// Semaphore and overlap policy of each group with a concurrency limit.
+#[cfg(feature = "tokio")]
+type Limits = HashMap<String, (Arc<Semaphore>, OverlapPolicy)>;
+
+#[cfg(feature = "tokio")]
+type Handler = Arc<
+    dyn Fn(CronTask, DateTime<Local>, RunId) -> Pin<Box<dyn Future<Output = ()> + Send>>
+        + Send
+        + Sync,
+>;
+
+/// Runs the tasks of a [`Cron`] on a Tokio runtime, calling an async
+/// handler for each run, and once on start for `@reboot` tasks, with the
+/// id of the run. Handlers are registered by task name; tasks without one go to the fallback
+/// handler, or are left alone. A run of a group with a concurrency limit
+/// waits for one of the group's runs to end when it reaches the limit, or
+/// is skipped under [`OverlapPolicy::Skip`]. As with [`ThreadScheduler`],
+/// a [`SharedCron`] is read on each wake-up and changes to it wake the
+/// scheduler up, and skipped runs are reported to the [`EventLog`].
+///
+/// ```ignore
+/// TokioScheduler::new(cron)
+///     .handler("backup", |task, date, run_id| async move {
+///         backup(date, run_id).await
+///     })
+///     .run()
+///     .await;
+/// ```
+#[cfg(feature = "tokio")]
+pub struct TokioScheduler<C: Clock<Tz = Local>> {
+    cron: SharedCron,
+    clock: C,
+    handlers: HashMap<String, Handler>,
+    fallback: Option<Handler>,
+    mode: FiringMode,
+    metrics: FiringMetrics,
+    events: EventLog,
+    ready: Readiness,
+}
+
+#[cfg(all(
+    feature = "tokio",
+    not(all(target_arch = "wasm32", target_os = "unknown"))
+))]
+impl TokioScheduler<SystemClock> {
+    pub fn new(cron: impl Into<SharedCron>) -> Self {
+        Self::with_clock(cron, SystemClock)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<C: Clock<Tz = Local>> TokioScheduler<C> {
+    pub fn with_clock(cron: impl Into<SharedCron>, clock: C) -> Self {
+        Self {
+            cron: cron.into(),
+            clock,
+            handlers: HashMap::new(),
+            fallback: None,
+            mode: FiringMode::default(),
+            metrics: FiringMetrics::default(),
+            events: EventLog::default(),
+            ready: Readiness::default(),
+        }
+    }
+
+    pub fn firing_mode(mut self, mode: FiringMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Calls `handler` with the task, its scheduled date and the id of the
+    /// run whenever the task named `name` is due.
+    pub fn handler<F, Fut>(mut self, name: &str, handler: F) -> Self
+    where
+        F: Fn(CronTask, DateTime<Local>, RunId) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.handlers.insert(name.to_string(), boxed(handler));
+        self
+    }
+
+    /// Handler for the tasks no named handler was registered for.
+    pub fn fallback<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(CronTask, DateTime<Local>, RunId) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.fallback = Some(boxed(handler));
+        self
+    }
+
+    pub fn cron(&self) -> &SharedCron {
+        &self.cron
+    }
+
+    /// Lateness of the runs fired so far, the handle staying readable once
+    /// `run` took the scheduler.
+    pub fn metrics(&self) -> FiringMetrics {
+        self.metrics.clone()
+    }
+
+    /// Runs skipped so far, and the ones to come as they are.
+    pub fn events(&self) -> EventLog {
+        self.events.clone()
+    }
+
+    /// Whether `run` is done working out the next run of every task and
+    /// schedules them, the handle staying readable once `run` took the
+    /// scheduler.
+    pub fn ready(&self) -> Readiness {
+        self.ready.clone()
+    }
+
+    /// Sleeps until each run is due and spawns its handler, without
+    /// waiting for it to finish. Returns once no task has a next run, or
+    /// with a lookahead, once no task runs on a schedule. With a cron still
+    /// shared with other handles, it instead waits for tasks to be added.
+    pub async fn run(self) {
+        let changed = Arc::new(Notify::new());
+        let watched = Arc::downgrade(&changed);
+        self.cron.watch(move || match watched.upgrade() {
+            Some(changed) => {
+                changed.notify_one();
+                true
+            }
+            None => false,
+        });
+
+        // Limits are taken on start, groups added later run unlimited.
+        let limits: Limits = self
+            .cron
+            .read()
+            .groups()
+            .iter()
+            .filter_map(|group| {
+                let limit = Arc::new(Semaphore::new(group.max_concurrent()?.max(1)));
+                Some((group.name().to_string(), (limit, group.overlap_policy())))
+            })
+            .collect();
+        let mut after = self.clock.now();
+
+        let reboots: Vec<CronTask> = {
+            let cron = self.cron.read();
+            skip_reboots(&cron, after, &self.events);
+            cron.reboot_tasks().cloned().collect()
+        };
+        let mut runs = self.warm_up(after).await;
+        self.ready.set();
+        for task in reboots {
+            self.spawn(&limits, task, after);
+        }
+        loop {
+            let Some((date, due)) = runs.next_wake() else {
+                if !self.cron.is_shared() {
+                    return;
+                }
+                changed.notified().await;
+                runs = self.warm_up(after).await;
+                continue;
+            };
+            let wait = self.mode.wait(date - self.clock.now());
+            if timeout_at(Instant::now() + wait, changed.notified())
+                .await
+                .is_ok()
+            {
+                runs = self.warm_up(after).await;
+                continue;
+            }
+
+            let tasks: Vec<CronTask> = {
+                let cron = self.cron.read();
+                let tasks = if due {
+                    for (task, reason) in cron.get_all_skipped_due_at(date) {
+                        self.events.skip(task, date, reason);
+                    }
+                    cron.get_all_due_at(date).into_iter().cloned().collect()
+                } else {
+                    Vec::new()
+                };
+                runs.advance(&cron, date);
+                tasks
+            };
+            if !tasks.is_empty() {
+                self.metrics.record(self.clock.now() - date);
+            }
+            for task in tasks {
+                self.spawn(&limits, task, date);
+            }
+            after = date;
+        }
+    }
+
+    /// Works out the next run of every task off the runtime's threads.
+    async fn warm_up(&self, after: DateTime<Local>) -> NextRuns {
+        let cron = self.cron.clone();
+        tokio::task::spawn_blocking(move || NextRuns::warm_up(&cron.read(), after))
+            .await
+            .unwrap_or_else(|error| std::panic::resume_unwind(error.into_panic()))
+    }
+
+    fn spawn(&self, limits: &Limits, task: CronTask, date: DateTime<Local>) {
+        let Some(handler) = self.handler_for(&task) else {
+            return;
+        };
+        let limit = task.group().and_then(|name| limits.get(name));
+        let (held, queued) = match limit {
+            Some((limit, OverlapPolicy::Skip)) => match Arc::clone(limit).try_acquire_owned() {
+                Ok(permit) => (Some(permit), None),
+                Err(_) => {
+                    self.events.skip(&task, date, SkipReason::Overlap);
+                    return;
+                }
+            },
+            Some((limit, OverlapPolicy::Queue)) => (None, Some(Arc::clone(limit))),
+            None => (None, None),
+        };
+
+        let run_id = RunId::new();
+        #[cfg(feature = "budget")]
+        let budgeted = BudgetedRun::new(&self.cron, &self.events, &task, date);
+        let run = handler(task, date, run_id);
+        tokio::spawn(async move {
+            let _permit = match queued {
+                Some(limit) => limit.acquire_owned().await.ok(),
+                None => held,
+            };
+            #[cfg(feature = "budget")]
+            let started = Instant::now();
+            run.await;
+            #[cfg(feature = "budget")]
+            if let Some(budgeted) = budgeted {
+                budgeted.record(run_id, started.elapsed());
+            }
+        });
+    }
+
+    fn handler_for(&self, task: &CronTask) -> Option<&Handler> {
+        task.name()
+            .and_then(|name| self.handlers.get(name))
+            .or(self.fallback.as_ref())
+    }
+}
+
+#[cfg(feature = "tokio")]
+fn boxed<F, Fut>(handler: F) -> Handler
+where
+    F: Fn(CronTask, DateTime<Local>, RunId) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    Arc::new(move |task, date, run_id| Box::pin(handler(task, date, run_id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            mpsc,
+        },
+        time::Duration,
+    };
+
+    use chrono::{TimeDelta, TimeZone, Timelike};
+
+    use super::*;
+    #[cfg(feature = "budget")]
+    use crate::budget::RuntimeBudget;
+    use crate::{
+        error::CronError,
+        event::SchedulerEvent,
+        group::TaskGroup,
+        realtime::{FixedClock, ManualClock},
+        state::MemoryStore,
+        value::{every, on},
+    };
+
+    fn cron() -> Cron {
+        let mut cron = Cron::new();
+        cron.add_task(
+            CronTask::builder()
+                .minutes(every(10))
+                .name("poll")
+                .path("/usr/bin/poll".to_string())
+                .build()
+                .unwrap(),
+        );
+        cron.add_task(
+            CronTask::builder()
+                .minutes(on(15))
+                .path("/usr/bin/report".to_string())
+                .build()
+                .unwrap(),
+        );
+        cron
+    }
+
+    #[test]
+    fn test_thread_scheduler() {
+        let start =
+            Local.with_ymd_and_hms(2025, 6, 1, 12, 10, 0).unwrap() - TimeDelta::milliseconds(50);
+        let (sender, receiver) = mpsc::channel();
+
+        let scheduler =
+            ThreadScheduler::run_with_clock(cron(), FixedClock(start), move |task, date, _| {
+                sender
+                    .send((task.name().map(str::to_string), date.minute()))
+                    .unwrap();
+            });
+
+        assert_eq!(
+            receiver.recv_timeout(Duration::from_secs(5)),
+            Ok((Some("poll".to_string()), 10))
+        );
+        // The next run is five minutes away on the clock, stopping must not
+        // wait for it.
+        scheduler.stop();
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_thread_scheduler_shared_cron() {
+        let start =
+            Local.with_ymd_and_hms(2025, 6, 1, 12, 10, 0).unwrap() - TimeDelta::milliseconds(50);
+        let shared = SharedCron::default();
+        let (sender, receiver) = mpsc::channel();
+
+        let scheduler = ThreadScheduler::run_with_clock(
+            shared.clone(),
+            FixedClock(start),
+            move |task, _, _| {
+                sender.send(task.name().map(str::to_string)).unwrap();
+            },
+        );
+
+        // Nothing to run yet, the scheduler waits for tasks instead of
+        // ending.
+        assert!(receiver.recv_timeout(Duration::from_millis(200)).is_err());
+        assert!(!scheduler.is_finished());
+
+        let (skips, skipped) = mpsc::channel();
+        let skips = Mutex::new(skips);
+        scheduler.events().subscribe(move |event| {
+            let SchedulerEvent::Skipped { task, reason, .. } = event else {
+                return;
+            };
+            let _ = skips
+                .lock()
+                .unwrap()
+                .send((task.name().map(str::to_string), reason.clone()));
+        });
+        {
+            let mut tasks = shared.write();
+            *tasks = cron();
+            tasks.set_task_paused("poll", true);
+        }
+        // The paused run is skipped, the next one is minutes away.
+        assert_eq!(
+            skipped.recv_timeout(Duration::from_secs(5)),
+            Ok((Some("poll".to_string()), SkipReason::Paused))
+        );
+        assert!(receiver.recv_timeout(Duration::from_millis(200)).is_err());
+
+        shared.write().add_task(
+            CronTask::builder()
+                .seconds(every(1))
+                .name("tick")
+                .path("/usr/bin/tick".to_string())
+                .build()
+                .unwrap(),
+        );
+        assert_eq!(
+            receiver.recv_timeout(Duration::from_secs(5)),
+            Ok(Some("tick".to_string()))
+        );
+        scheduler.stop();
+    }
+
+    #[test]
+    fn test_thread_scheduler_skips() {
+        let start =
+            Local.with_ymd_and_hms(2025, 6, 1, 12, 10, 0).unwrap() - TimeDelta::milliseconds(500);
+        let mut cron = cron();
+        cron.set_task_paused("poll", true);
+        let (sender, receiver) = mpsc::channel();
+
+        let scheduler = ThreadScheduler::run_with_clock(cron, FixedClock(start), |_, _, _| {});
+        let sender = Mutex::new(sender);
+        scheduler.events().subscribe(move |event| {
+            let SchedulerEvent::Skipped { task, date, reason } = event else {
+                return;
+            };
+            let _ = sender.lock().unwrap().send((
+                task.name().map(str::to_string),
+                date.minute(),
+                reason.clone(),
+            ));
+        });
+
+        // The paused run is reported when it falls due, rather than left
+        // out.
+        assert_eq!(
+            receiver.recv_timeout(Duration::from_secs(5)),
+            Ok((Some("poll".to_string()), 10, SkipReason::Paused))
+        );
+        scheduler.stop();
+    }
+
+    /// Reads `start` once, then a date far past every run, so the scheduler
+    /// catches up on the runs in between without sleeping.
+    struct CatchUpClock {
+        start: DateTime<Local>,
+        read: AtomicBool,
+    }
+
+    impl Clock for CatchUpClock {
+        type Tz = Local;
+
+        fn now(&self) -> DateTime<Local> {
+            if self.read.swap(true, Ordering::Relaxed) {
+                self.start + TimeDelta::days(1)
+            } else {
+                self.start
+            }
+        }
+    }
+
+    #[test]
+    fn test_thread_scheduler_slots() {
+        let mut cron = Cron::new();
+        cron.add_task(
+            CronTask::builder()
+                .seconds(every(15))
+                .name("poll")
+                .path("/usr/bin/poll".to_string())
+                .build()
+                .unwrap(),
+        );
+        cron.add_task(
+            CronTask::builder()
+                .name("report")
+                .path("/usr/bin/report".to_string())
+                .build()
+                .unwrap(),
+        );
+        let clock = CatchUpClock {
+            start: Local.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap() - TimeDelta::seconds(1),
+            read: AtomicBool::new(false),
+        };
+        let (sender, receiver) = mpsc::channel();
+
+        let scheduler = ThreadScheduler::run_with_clock(cron, clock, move |task, date, _| {
+            let _ = sender.send((
+                task.name().unwrap().to_string(),
+                date.minute(),
+                date.second(),
+            ));
+        });
+        let runs: Vec<_> = (0..7)
+            .map(|_| receiver.recv_timeout(Duration::from_secs(5)).unwrap())
+            .collect();
+        let metrics = scheduler.metrics();
+        scheduler.stop();
+
+        // The minute task runs once a minute, not at each of the seconds
+        // task's runs.
+        let run = |name: &str, minute, second| (name.to_string(), minute, second);
+        assert_eq!(
+            runs,
+            [
+                run("poll", 0, 0),
+                run("report", 0, 0),
+                run("poll", 0, 15),
+                run("poll", 0, 30),
+                run("poll", 0, 45),
+                run("poll", 1, 0),
+                run("report", 1, 0),
+            ]
+        );
+        // Each wake-up came a day late on the clock.
+        let accuracy = metrics.accuracy();
+        assert!(accuracy.firings() >= 5);
+        assert!(accuracy.max_lateness() > TimeDelta::hours(23));
+        assert!(accuracy.mean_lateness().unwrap() > TimeDelta::hours(23));
+    }
+
+    #[cfg(feature = "budget")]
+    fn budgeted_cron() -> Cron {
+        let mut cron = Cron::new();
+        cron.add_task(
+            CronTask::builder()
+                .seconds(every(1))
+                .name("batch")
+                .path("/usr/bin/batch".to_string())
+                .budget(RuntimeBudget::monthly(TimeDelta::milliseconds(1)))
+                .build()
+                .unwrap(),
+        );
+        cron
+    }
+
+    #[cfg(feature = "budget")]
+    #[test]
+    fn test_thread_scheduler_budget() {
+        let clock = CatchUpClock {
+            start: Local.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap(),
+            read: AtomicBool::new(false),
+        };
+        let shared = SharedCron::new(budgeted_cron());
+        let (sender, receiver) = mpsc::channel();
+
+        let scheduler =
+            ThreadScheduler::run_with_clock(shared.clone(), clock, move |_, _, permit| {
+                let _ = sender.send(permit.run_id());
+                std::thread::sleep(Duration::from_millis(5));
+                drop(permit);
+            });
+        let events = scheduler.events();
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while events.skipped().is_empty() && std::time::Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        scheduler.stop();
+
+        // The first run used up the budget, the next one is skipped.
+        let first = receiver.recv().unwrap();
+        let history = events.history();
+        assert!(matches!(
+            &history[0],
+            SchedulerEvent::BudgetExhausted { task, run_id, .. }
+                if task.name() == Some("batch") && *run_id == first
+        ));
+        assert!(matches!(
+            &history[1],
+            SchedulerEvent::Skipped {
+                reason: SkipReason::BudgetExhausted,
+                ..
+            }
+        ));
+        assert!(shared.read().ledger().used("batch", 2025, 6) >= TimeDelta::milliseconds(5));
+    }
+
+    #[test]
+    fn test_firing_mode_wait() {
+        let delta = TimeDelta::milliseconds(1500);
+        assert_eq!(FiringMode::Exact.wait(delta), Duration::from_millis(1500));
+        assert_eq!(FiringMode::Coarse.wait(delta), Duration::from_secs(2));
+        assert_eq!(
+            FiringMode::Coarse.wait(TimeDelta::seconds(3)),
+            Duration::from_secs(3)
+        );
+        assert_eq!(FiringMode::Coarse.wait(-delta), Duration::ZERO);
+        assert_eq!(FiringAccuracy::default().mean_lateness(), None);
+    }
+
+    #[test]
+    fn test_thread_scheduler_reboot() {
+        let start = Local.with_ymd_and_hms(2025, 6, 1, 12, 0, 30).unwrap();
+        let mut cron = cron();
+        cron.add_task(CronTask::reboot("/usr/bin/warm-up"));
+        let (sender, receiver) = mpsc::channel();
+
+        let scheduler =
+            ThreadScheduler::run_with_clock(cron, FixedClock(start), move |task, date, _| {
+                sender.send((task.path().to_path_buf(), date)).unwrap();
+            });
+
+        assert_eq!(
+            receiver.recv_timeout(Duration::from_secs(5)),
+            Ok((std::path::PathBuf::from("/usr/bin/warm-up"), start))
+        );
+        scheduler.stop();
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_thread_scheduler_catch_up() {
+        let start = Local.with_ymd_and_hms(2025, 6, 1, 12, 20, 30).unwrap();
+        let store = MemoryStore::new();
+        store
+            .clone()
+            .save(&SchedulerState {
+                last_wake: Some(Local.with_ymd_and_hms(2025, 6, 1, 11, 55, 0).unwrap()),
+                ..SchedulerState::default()
+            })
+            .unwrap();
+        let (sender, receiver) = mpsc::channel();
+
+        let scheduler = ThreadScheduler::builder(cron(), FixedClock(start))
+            .state_store(store.clone())
+            .catch_up(CatchUpPolicy::Latest)
+            .run(move |task, date, _| {
+                sender
+                    .send((task.path().to_path_buf(), date.minute()))
+                    .unwrap();
+            });
+
+        // The latest missed run of each task, in the order they fell due.
+        for expected in [("/usr/bin/report", 15), ("/usr/bin/poll", 20)] {
+            assert_eq!(
+                receiver.recv_timeout(Duration::from_secs(5)),
+                Ok((std::path::PathBuf::from(expected.0), expected.1))
+            );
+        }
+        let events = scheduler.events();
+        scheduler.stop();
+        assert!(receiver.try_recv().is_err());
+        let superseded: Vec<_> = events
+            .skipped()
+            .into_iter()
+            .map(|(_, date, reason)| (date.minute(), reason))
+            .collect();
+        assert_eq!(
+            superseded,
+            [(0, SkipReason::Superseded), (10, SkipReason::Superseded)]
+        );
+        // The next start catches up from this one.
+        assert_eq!(store.state().and_then(|state| state.last_wake), Some(start));
+    }
+
+    /// Store failing while `down` is set.
+    struct FlakyStore {
+        memory: MemoryStore,
+        down: Arc<AtomicBool>,
+    }
+
+    impl StateStore for FlakyStore {
+        fn load(&mut self) -> crate::error::Result<Option<SchedulerState>> {
+            self.check()?;
+            self.memory.load()
+        }
+
+        fn save(&mut self, state: &SchedulerState) -> crate::error::Result<()> {
+            self.check()?;
+            self.memory.save(state)
+        }
+    }
+
+    impl FlakyStore {
+        fn check(&self) -> crate::error::Result<()> {
+            if self.down.load(Ordering::Relaxed) {
+                return Err(CronError::StateStore("connection refused".to_string()));
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_thread_scheduler_degraded() {
+        let mut cron = Cron::new();
+        cron.add_task(
+            CronTask::builder()
+                .seconds(every(1))
+                .path("/usr/bin/tick".to_string())
+                .build()
+                .unwrap(),
+        );
+        let memory = MemoryStore::new();
+        let down = Arc::new(AtomicBool::new(true));
+        let store = FlakyStore {
+            memory: memory.clone(),
+            down: Arc::clone(&down),
+        };
+        let (sender, receiver) = mpsc::channel();
+
+        let scheduler = ThreadScheduler::builder(cron, SystemClock)
+            .state_store(store)
+            .catch_up(CatchUpPolicy::All)
+            .run(move |_, _, _| {
+                let _ = sender.send(());
+            });
+
+        // Runs go on while the store fails.
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(5)), Ok(()));
+        assert!(scheduler.is_degraded());
+        assert_eq!(memory.state(), None);
+
+        down.store(false, Ordering::Relaxed);
+        // The save after the first of these runs reaches the store.
+        for _ in 0..2 {
+            assert_eq!(receiver.recv_timeout(Duration::from_secs(5)), Ok(()));
+        }
+        assert!(!scheduler.is_degraded());
+        assert!(memory.state().is_some());
+        let history = scheduler.events().history();
+        assert!(matches!(
+            history.as_slice(),
+            [SchedulerEvent::Degraded { .. }, SchedulerEvent::Recovered]
+        ));
+        scheduler.stop();
+    }
+
+    #[test]
+    fn test_thread_scheduler_ready() {
+        let start = Local.with_ymd_and_hms(2025, 6, 1, 12, 5, 0).unwrap();
+        let store = MemoryStore::new();
+
+        let scheduler = ThreadScheduler::builder(cron(), FixedClock(start))
+            .state_store(store.clone())
+            .run(|_, _, _| {});
+        let ready = scheduler.ready();
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while !ready.is_ready() && std::time::Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(ready.is_ready());
+        scheduler.stop();
+
+        // The next runs worked out on start are saved along with it.
+        let at = |minute| Some(Local.with_ymd_and_hms(2025, 6, 1, 12, minute, 0).unwrap());
+        assert_eq!(
+            store.state(),
+            Some(SchedulerState {
+                last_wake: Some(start),
+                next_runs: vec![at(10), at(15)],
+            })
+        );
+    }
+
+    #[test]
+    fn test_next_runs() {
+        let start = Local.with_ymd_and_hms(2025, 6, 1, 12, 0, 30).unwrap();
+        let mut cron = Cron::new();
+        // Enough tasks to be shared between threads.
+        for minute in 0..4 * WARM_UP_CHUNK {
+            cron.add_task(
+                CronTask::builder()
+                    .minutes(on((minute % 60) as u8))
+                    .allow_commandless()
+                    .build()
+                    .unwrap(),
+            );
+        }
+
+        let mut runs = NextRuns::warm_up(&cron, start);
+        let expected: Vec<_> = cron
+            .into_iter()
+            .map(|task| cron.next_run_of(task, start))
+            .collect();
+        assert_eq!(runs.dates, expected);
+
+        let date = Local.with_ymd_and_hms(2025, 6, 1, 12, 1, 0).unwrap();
+        assert_eq!(runs.next_wake(), Some((date, true)));
+        runs.advance(&cron, date);
+        assert_eq!(runs.dates[1], Some(date + TimeDelta::hours(1)));
+        assert_eq!(runs.dates[2], expected[2]);
+        assert_eq!(runs.next_wake(), Some((date + TimeDelta::minutes(1), true)));
+    }
+
+    #[test]
+    fn test_thread_scheduler_beyond_lookahead() {
+        let clock = CatchUpClock {
+            start: Local.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap(),
+            read: AtomicBool::new(false),
+        };
+        let cron = cron().with_lookahead(TimeDelta::minutes(1));
+        let (sender, receiver) = mpsc::channel();
+
+        let scheduler = ThreadScheduler::run_with_clock(cron, clock, move |task, date, _| {
+            let _ = sender.send((task.name().map(str::to_string), date.minute()));
+        });
+
+        // Nothing runs within a minute of the start, the scheduler idles
+        // until the horizon moves up to the first run.
+        assert_eq!(
+            receiver.recv_timeout(Duration::from_secs(5)),
+            Ok((Some("poll".to_string()), 10))
+        );
+        assert!(!scheduler.is_finished());
+        scheduler.stop();
+    }
+
+    fn grouped_cron(max_concurrent: usize) -> Cron {
+        let mut cron = Cron::new();
+        for name in ["dump", "vacuum"] {
+            cron.add_task(
+                CronTask::builder()
+                    .minutes(every(10))
+                    .name(name)
+                    .group("db")
+                    .path(format!("/usr/bin/{name}"))
+                    .build()
+                    .unwrap(),
+            );
+        }
+        cron.add_group(TaskGroup::new("db").with_max_concurrent(max_concurrent));
+        cron
+    }
+
+    #[test]
+    fn test_thread_scheduler_group_limit() {
+        let start =
+            Local.with_ymd_and_hms(2025, 6, 1, 12, 10, 0).unwrap() - TimeDelta::milliseconds(50);
+        let (sender, receiver) = mpsc::channel();
+
+        let scheduler = ThreadScheduler::run_with_clock(
+            grouped_cron(1),
+            FixedClock(start),
+            move |task, _, permit| {
+                sender
+                    .send((task.name().unwrap().to_string(), permit))
+                    .unwrap();
+            },
+        );
+
+        let (first, permit) = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(first, "dump");
+        // The group allows one run at a time, the other waits for the
+        // permit to come back.
+        assert!(receiver.recv_timeout(Duration::from_millis(200)).is_err());
+        let first_id = permit.run_id();
+        drop(permit);
+        let (second, permit) = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(second, "vacuum");
+        assert_ne!(permit.run_id(), first_id);
+        scheduler.stop();
+    }
+
+    #[test]
+    fn test_polling_scheduler() {
+        let start = Local.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        let mut scheduler =
+            PollingScheduler::new(cron(), ManualClock::new(start), TimeDelta::hours(1));
+        let runs = |scheduler: &mut PollingScheduler<ManualClock<Local>>| {
+            scheduler
+                .poll()
+                .into_iter()
+                .map(|(date, task, _)| (date.minute(), task.path().display().to_string()))
+                .collect::<Vec<_>>()
+        };
+
+        scheduler.clock().advance(TimeDelta::minutes(30));
+        assert_eq!(
+            runs(&mut scheduler),
+            [
+                (0, "/usr/bin/poll".to_string()),
+                (10, "/usr/bin/poll".to_string()),
+                (15, "/usr/bin/report".to_string()),
+                (20, "/usr/bin/poll".to_string()),
+            ]
+        );
+        assert!(runs(&mut scheduler).is_empty());
+
+        let mut scheduler = scheduler.with_policy(CatchUpPolicy::Latest);
+        scheduler.clock().advance(TimeDelta::hours(1));
+        assert_eq!(
+            runs(&mut scheduler),
+            [
+                (15, "/usr/bin/report".to_string()),
+                (20, "/usr/bin/poll".to_string()),
+            ]
+        );
+
+        let mut scheduler = scheduler.with_policy(CatchUpPolicy::Within(TimeDelta::minutes(12)));
+        scheduler.clock().advance(TimeDelta::hours(1));
+        assert_eq!(runs(&mut scheduler), [(20, "/usr/bin/poll".to_string())]);
+    }
+
+    #[test]
+    fn test_polling_scheduler_skips() {
+        let start = Local.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        let mut scheduler =
+            PollingScheduler::new(cron(), ManualClock::new(start), TimeDelta::hours(1))
+                .with_policy(CatchUpPolicy::Within(TimeDelta::minutes(12)));
+        let skipped = |scheduler: &PollingScheduler<ManualClock<Local>>| {
+            scheduler
+                .events()
+                .skipped()
+                .into_iter()
+                .map(|(task, date, reason)| {
+                    (task.path().display().to_string(), date.minute(), reason)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        scheduler.clock().advance(TimeDelta::minutes(30));
+        assert_eq!(scheduler.poll().len(), 1);
+        let missed = |path: &str, minute| (path.to_string(), minute, SkipReason::MissedBeyondGrace);
+        assert_eq!(
+            skipped(&scheduler),
+            [
+                missed("/usr/bin/poll", 0),
+                missed("/usr/bin/poll", 10),
+                missed("/usr/bin/report", 15),
+            ]
+        );
+
+        let mut scheduler = scheduler.with_policy(CatchUpPolicy::Latest);
+        scheduler.cron().write().set_task_paused("poll", true);
+        scheduler.clock().advance(TimeDelta::minutes(30));
+        assert!(scheduler.poll().is_empty());
+        assert_eq!(
+            skipped(&scheduler)[3..],
+            [
+                ("/usr/bin/poll".to_string(), 30, SkipReason::Superseded),
+                ("/usr/bin/poll".to_string(), 40, SkipReason::Superseded),
+                ("/usr/bin/poll".to_string(), 50, SkipReason::Paused),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_polling_scheduler_reboot() {
+        let start = Local.with_ymd_and_hms(2025, 6, 1, 12, 0, 30).unwrap();
+        let mut cron = cron();
+        cron.add_task(CronTask::reboot("/usr/bin/warm-up"));
+        let mut scheduler =
+            PollingScheduler::new(cron, ManualClock::new(start), TimeDelta::minutes(1));
+        let paths = |scheduler: &mut PollingScheduler<ManualClock<Local>>| {
+            scheduler
+                .poll()
+                .into_iter()
+                .map(|(date, task, _)| (date, task.path().display().to_string()))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            paths(&mut scheduler),
+            [(start, "/usr/bin/warm-up".to_string())]
+        );
+        scheduler.clock().advance(TimeDelta::minutes(10));
+        assert_eq!(
+            paths(&mut scheduler),
+            [(
+                Local.with_ymd_and_hms(2025, 6, 1, 12, 10, 0).unwrap(),
+                "/usr/bin/poll".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_polling_scheduler_shared_cron() {
+        let start = Local.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        let shared = SharedCron::new(cron());
+        let mut scheduler =
+            PollingScheduler::new(shared.clone(), ManualClock::new(start), TimeDelta::hours(1));
+
+        shared.write().set_task_paused("poll", true);
+        scheduler.clock().advance(TimeDelta::minutes(30));
+        let runs = scheduler.poll();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].1.path(), std::path::Path::new("/usr/bin/report"));
+    }
+
+    #[test]
+    fn test_polling_scheduler_group_limit() {
+        let start = Local.with_ymd_and_hms(2025, 6, 1, 12, 5, 0).unwrap();
+        let mut scheduler = PollingScheduler::new(
+            grouped_cron(1),
+            ManualClock::new(start),
+            TimeDelta::minutes(1),
+        );
+
+        scheduler.clock().advance(TimeDelta::minutes(10));
+        let mut runs = scheduler.poll();
+        assert_eq!(runs.len(), 1);
+        let (_, task, permit) = runs.remove(0);
+        assert_eq!(task.name(), Some("dump"));
+
+        // The other run is held back while the permit is out.
+        assert!(scheduler.poll().is_empty());
+        drop(permit);
+        let runs = scheduler.poll();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].1.name(), Some("vacuum"));
+    }
+
+    #[test]
+    fn test_polling_scheduler_overlap() {
+        let start = Local.with_ymd_and_hms(2025, 6, 1, 12, 5, 0).unwrap();
+        let mut cron = grouped_cron(1);
+        cron.add_group(
+            TaskGroup::new("db")
+                .with_max_concurrent(1)
+                .with_overlap_policy(OverlapPolicy::Skip),
+        );
+        let mut scheduler =
+            PollingScheduler::new(cron, ManualClock::new(start), TimeDelta::minutes(1));
+
+        scheduler.clock().advance(TimeDelta::minutes(10));
+        let runs = scheduler.poll();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].1.name(), Some("dump"));
+
+        // The other run is skipped rather than held back.
+        let skipped = scheduler.events().skipped();
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].0.name(), Some("vacuum"));
+        assert_eq!(skipped[0].2, SkipReason::Overlap);
+        drop(runs);
+        assert!(scheduler.poll().is_empty());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test(start_paused = true)]
+    async fn test_run_handlers() {
+        let start = Local.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let fallback = sender.clone();
+
+        let scheduler = TokioScheduler::with_clock(cron(), FixedClock(start))
+            .handler("poll", move |task, date, run_id| {
+                let sender = sender.clone();
+                async move {
+                    sender
+                        .send((task.name().map(str::to_string), date, run_id))
+                        .unwrap();
+                }
+            })
+            .fallback(move |task, date, run_id| {
+                let sender = fallback.clone();
+                async move {
+                    sender
+                        .send((task.name().map(str::to_string), date, run_id))
+                        .unwrap();
+                }
+            });
+        let run = tokio::spawn(scheduler.run());
+
+        let mut runs = Vec::new();
+        let mut run_ids = std::collections::HashSet::new();
+        for _ in 0..3 {
+            let (name, date, run_id) = receiver.recv().await.unwrap();
+            runs.push((name, date.minute()));
+            run_ids.insert(run_id);
+        }
+        run.abort();
+        assert_eq!(run_ids.len(), 3);
+
+        assert_eq!(
+            runs,
+            [
+                (Some("poll".to_string()), 10),
+                (None, 15),
+                (Some("poll".to_string()), 20)
+            ]
+        );
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test(start_paused = true)]
+    async fn test_run_ready() {
+        let start = Local.with_ymd_and_hms(2025, 6, 1, 12, 5, 0).unwrap();
+        let scheduler = TokioScheduler::with_clock(cron(), FixedClock(start));
+        let ready = scheduler.ready();
+        assert!(!ready.is_ready());
+
+        let run = tokio::spawn(scheduler.run());
+        ready.clone().await;
+        assert!(ready.is_ready());
+        run.abort();
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test(start_paused = true)]
+    async fn test_run_reboot() {
+        let start = Local.with_ymd_and_hms(2025, 6, 1, 12, 0, 30).unwrap();
+        let mut cron = cron();
+        cron.add_task(CronTask::reboot("/usr/bin/warm-up"));
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+
+        let scheduler =
+            TokioScheduler::with_clock(cron, FixedClock(start)).fallback(move |task, date, _| {
+                let sender = sender.clone();
+                async move {
+                    sender
+                        .send((task.path().display().to_string(), date))
+                        .unwrap();
+                }
+            });
+        let run = tokio::spawn(scheduler.run());
+
+        assert_eq!(
+            receiver.recv().await.unwrap(),
+            ("/usr/bin/warm-up".to_string(), start)
+        );
+        assert_eq!(receiver.recv().await.unwrap().0, "/usr/bin/poll");
+        run.abort();
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test(start_paused = true)]
+    async fn test_run_beyond_lookahead() {
+        let start = Local.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        let cron = cron().with_lookahead(TimeDelta::minutes(1));
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+
+        let scheduler =
+            TokioScheduler::with_clock(cron, FixedClock(start)).fallback(move |task, date, _| {
+                let sender = sender.clone();
+                async move {
+                    sender
+                        .send((task.path().display().to_string(), date.minute()))
+                        .unwrap();
+                }
+            });
+        let run = tokio::spawn(scheduler.run());
+
+        assert_eq!(
+            receiver.recv().await.unwrap(),
+            ("/usr/bin/poll".to_string(), 10)
+        );
+        run.abort();
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test(start_paused = true)]
+    async fn test_run_shared_cron() {
+        let start = Local.with_ymd_and_hms(2025, 6, 1, 12, 5, 0).unwrap();
+        let shared = SharedCron::new(cron());
+        shared.write().set_task_paused("poll", true);
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+
+        let scheduler = TokioScheduler::with_clock(shared.clone(), FixedClock(start)).fallback(
+            move |task, date, _| {
+                let sender = sender.clone();
+                async move {
+                    sender
+                        .send((task.path().display().to_string(), date.minute()))
+                        .unwrap();
+                }
+            },
+        );
+        let run = tokio::spawn(scheduler.run());
+        tokio::task::yield_now().await;
+
+        // Resuming wakes the scheduler up, which was sleeping until the
+        // report at 15 minutes past.
+        shared.write().set_task_paused("poll", false);
+        assert_eq!(
+            receiver.recv().await.unwrap(),
+            ("/usr/bin/poll".to_string(), 10)
+        );
+        run.abort();
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test(start_paused = true)]
+    async fn test_run_coarse() {
+        let start =
+            Local.with_ymd_and_hms(2025, 6, 1, 12, 9, 59).unwrap() + TimeDelta::milliseconds(500);
+        let clock = ManualClock::new(start);
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+
+        let scheduler = TokioScheduler::with_clock(cron(), clock)
+            .firing_mode(FiringMode::Coarse)
+            .fallback(move |_, _, _| {
+                let sender = sender.clone();
+                async move {
+                    sender.send(Instant::now()).unwrap();
+                }
+            });
+        let metrics = scheduler.metrics();
+        let started = Instant::now();
+        let run = tokio::spawn(scheduler.run());
+
+        // Half a second away, the run waits for a whole one.
+        let fired = receiver.recv().await.unwrap();
+        run.abort();
+        assert_eq!(fired - started, Duration::from_secs(1));
+        assert_eq!(metrics.accuracy().firings(), 1);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test(start_paused = true)]
+    async fn test_run_group_limit() {
+        let start = Local.with_ymd_and_hms(2025, 6, 1, 12, 5, 0).unwrap();
+        let runs = |max_concurrent| async move {
+            let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+            let scheduler =
+                TokioScheduler::with_clock(grouped_cron(max_concurrent), FixedClock(start))
+                    .fallback(move |task, _, _| {
+                        let sender = sender.clone();
+                        async move {
+                            let name = task.name().unwrap().to_string();
+                            sender.send(format!("{name} started")).unwrap();
+                            tokio::time::sleep(Duration::from_secs(60)).await;
+                            sender.send(format!("{name} ended")).unwrap();
+                        }
+                    });
+            let run = tokio::spawn(scheduler.run());
+
+            let mut events = Vec::new();
+            for _ in 0..4 {
+                events.push(receiver.recv().await.unwrap());
+            }
+            run.abort();
+            events
+        };
+
+        assert_eq!(
+            runs(1).await,
+            [
+                "dump started",
+                "dump ended",
+                "vacuum started",
+                "vacuum ended"
+            ]
+        );
+        assert_eq!(
+            runs(2).await,
+            [
+                "dump started",
+                "vacuum started",
+                "dump ended",
+                "vacuum ended"
+            ]
+        );
+    }
+
+    #[cfg(all(feature = "tokio", feature = "budget"))]
+    #[tokio::test(start_paused = true)]
+    async fn test_run_budget() {
+        let start = Local.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+
+        let scheduler = TokioScheduler::with_clock(budgeted_cron(), FixedClock(start))
+            .fallback(|_, _, _| tokio::time::sleep(Duration::from_millis(5)));
+        let shared = scheduler.cron().clone();
+        scheduler.events().subscribe(move |event| {
+            let _ = sender.send(event.clone());
+        });
+        let run = tokio::spawn(scheduler.run());
+
+        let exhausted = receiver.recv().await.unwrap();
+        let skipped = receiver.recv().await.unwrap();
+        run.abort();
+        assert!(matches!(
+            exhausted,
+            SchedulerEvent::BudgetExhausted { date, .. } if date.second() == 1
+        ));
+        assert!(matches!(
+            skipped,
+            SchedulerEvent::Skipped { date, reason: SkipReason::BudgetExhausted, .. }
+                if date.second() == 2
+        ));
+        assert_eq!(
+            shared.read().ledger().used("batch", 2025, 6),
+            TimeDelta::milliseconds(5)
+        );
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test(start_paused = true)]
+    async fn test_run_overlap() {
+        let start = Local.with_ymd_and_hms(2025, 6, 1, 12, 5, 0).unwrap();
+        let mut cron = grouped_cron(1);
+        cron.add_group(
+            TaskGroup::new("db")
+                .with_max_concurrent(1)
+                .with_overlap_policy(OverlapPolicy::Skip),
+        );
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+
+        let scheduler =
+            TokioScheduler::with_clock(cron, FixedClock(start)).fallback(move |task, _, _| {
+                let sender = sender.clone();
+                async move {
+                    sender.send(task.name().unwrap().to_string()).unwrap();
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                }
+            });
+        let events = scheduler.events();
+        let run = tokio::spawn(scheduler.run());
+
+        assert_eq!(receiver.recv().await.unwrap(), "dump");
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        run.abort();
+        let skipped = events.skipped();
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].0.name(), Some("vacuum"));
+        assert_eq!(skipped[0].2, SkipReason::Overlap);
+    }
+}
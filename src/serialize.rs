@@ -0,0 +1,726 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use chrono::{FixedOffset, TimeDelta};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error as _};
+
+use crate::{
+    cron::Cron,
+    dst::{DstPolicy, FoldPolicy, GapPolicy},
+    error::{CronError, Result},
+    group::{OverlapPolicy, TaskGroup},
+    parser::{parse_bare_schedule, parse_year},
+    rule::{RuleAction, ScheduleRule},
+    success::OutputMatch,
+    task::{CronTask, DayMatch},
+    value::CronValue,
+    year::YearValue,
+};
+
+/// Values are stored as the field strings they display as.
+impl Serialize for CronValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for CronValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+impl Serialize for YearValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for YearValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        parse_year(&String::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+/// A task as stored: its crontab schedule, with a leading seconds field if
+/// any, and the metadata the schedule cannot carry.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "CronTask"))]
+struct TaskRepr {
+    /// Crontab schedule such as `*/5 * * * *`, six fields with seconds, or
+    /// a nickname such as `@daily`.
+    schedule: String,
+    #[serde(default)]
+    command: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    env: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    current_dir: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    stdin: Option<String>,
+    /// `user` or `user:group`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    run_as: Option<String>,
+    /// In seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timeout: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    /// `all` when a date must match both day fields of the schedule, which
+    /// cron reads as either of them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    day_match: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    year: Option<YearValue>,
+    /// Such as `+05:30`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    offset: Option<String>,
+    /// Such as `Europe/Paris`.
+    #[cfg(feature = "chrono-tz")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timezone: Option<String>,
+    /// `earliest`, `latest` or `skip`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dst_fold: Option<String>,
+    /// `skip` or `shift-forward`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dst_gap: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    rules: Vec<RuleRepr>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    group: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    owner: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    annotations: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    priority: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    shard: Option<ShardRepr>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    check: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    success_output: Option<OutputRepr>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    comments: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "Shard"))]
+struct ShardRepr {
+    key: String,
+    total: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "OutputMatch"))]
+#[serde(rename_all = "snake_case")]
+enum OutputRepr {
+    Contains(String),
+    Regex(String),
+}
+
+/// A rule as its action and bare schedule, such as
+/// `{"exclude": "* * 25 12 *"}`.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "ScheduleRule"))]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum RuleRepr {
+    Include(String),
+    Exclude(String),
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "TaskGroup"))]
+pub(crate) struct GroupRepr {
+    name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_concurrent: Option<usize>,
+    #[serde(default = "enabled")]
+    enabled: bool,
+    /// `queue` or `skip`, the former when left out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    overlap: Option<String>,
+    /// Such as `+05:30`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    offset: Option<String>,
+    /// Such as `Europe/Paris`.
+    #[cfg(feature = "chrono-tz")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timezone: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    calendar: Vec<RuleRepr>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "Cron"))]
+struct CronRepr {
+    #[serde(default)]
+    tasks: Vec<CronTask>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    groups: Vec<GroupRepr>,
+    /// In seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    lookahead: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    instance: Option<u32>,
+}
+
+/// Parses an offset written as `+05:30`.
+pub(crate) fn parse_offset(s: &str) -> Result<FixedOffset> {
+    s.parse()
+        .map_err(|_| CronError::InvalidExpression(format!("invalid offset `{s}`")))
+}
+
+/// Parses a time zone name such as `Europe/Paris`.
+#[cfg(feature = "chrono-tz")]
+pub(crate) fn parse_timezone(s: &str) -> Result<chrono_tz::Tz> {
+    s.parse()
+        .map_err(|_| CronError::InvalidExpression(format!("unknown time zone `{s}`")))
+}
+
+pub(crate) fn parse_dst_policy(fold: Option<&str>, gap: Option<&str>) -> Result<DstPolicy> {
+    Ok(DstPolicy {
+        fold: fold.map(str::parse).transpose()?.unwrap_or_default(),
+        gap: gap.map(str::parse).transpose()?.unwrap_or_default(),
+    })
+}
+
+pub(crate) fn enabled() -> bool {
+    true
+}
+
+fn is_zero(n: &i32) -> bool {
+    *n == 0
+}
+
+impl From<&TaskGroup> for GroupRepr {
+    fn from(group: &TaskGroup) -> Self {
+        let policy = group.policy();
+
+        GroupRepr {
+            name: group.name().to_string(),
+            max_concurrent: group.max_concurrent(),
+            enabled: group.is_enabled(),
+            overlap: Some(group.overlap_policy())
+                .filter(|overlap| *overlap != OverlapPolicy::Queue)
+                .map(|overlap| overlap.to_string()),
+            offset: policy.offset().map(|offset| offset.to_string()),
+            #[cfg(feature = "chrono-tz")]
+            timezone: policy
+                .timezone()
+                .map(|timezone| timezone.name().to_string()),
+            calendar: policy.calendar().iter().map(RuleRepr::from).collect(),
+        }
+    }
+}
+
+impl GroupRepr {
+    pub(crate) fn into_group(self) -> Result<TaskGroup> {
+        let mut group = TaskGroup::new(self.name);
+        if let Some(max_concurrent) = self.max_concurrent {
+            group = group.with_max_concurrent(max_concurrent);
+        }
+        if let Some(overlap) = self.overlap {
+            group = group.with_overlap_policy(overlap.parse()?);
+        }
+        if let Some(offset) = self.offset {
+            group = group.with_offset(parse_offset(&offset)?);
+        }
+        #[cfg(feature = "chrono-tz")]
+        if let Some(timezone) = self.timezone {
+            group = group.with_timezone(parse_timezone(&timezone)?);
+        }
+        for rule in self.calendar {
+            group = group.with_calendar_rule(rule.into_rule()?);
+        }
+        group.set_enabled(self.enabled);
+
+        Ok(group)
+    }
+}
+
+impl Serialize for CronTask {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let command = self.command_line();
+        let schedule = self.crontab_schedule();
+
+        TaskRepr {
+            schedule,
+            command,
+            env: self.env().clone(),
+            current_dir: self
+                .current_dir()
+                .map(|dir| dir.to_string_lossy().into_owned()),
+            stdin: self.stdin().map(str::to_string),
+            run_as: self.run_as().map(|run_as| run_as.to_string()),
+            timeout: self.timeout().map(|timeout| timeout.as_secs()),
+            name: self.name().map(str::to_string),
+            day_match: Some(self.day_match())
+                .filter(|day_match| {
+                    *day_match == DayMatch::All
+                        && self.month_day().is_restricted()
+                        && self.week_day().is_restricted()
+                })
+                .map(|day_match| day_match.to_string()),
+            year: Some(self.year().clone()).filter(|year| *year != YearValue::All),
+            offset: self.own_offset().map(|offset| offset.to_string()),
+            #[cfg(feature = "chrono-tz")]
+            timezone: self
+                .own_timezone()
+                .map(|timezone| timezone.name().to_string()),
+            dst_fold: Some(self.dst_policy().fold)
+                .filter(|fold| *fold != FoldPolicy::default())
+                .map(|fold| fold.to_string()),
+            dst_gap: Some(self.dst_policy().gap)
+                .filter(|gap| *gap != GapPolicy::default())
+                .map(|gap| gap.to_string()),
+            rules: self.rules().iter().map(RuleRepr::from).collect(),
+            group: self.group().map(str::to_string),
+            owner: self.owner().map(str::to_string),
+            annotations: self.annotations().clone(),
+            priority: self.priority(),
+            shard: self.shard().map(|shard| ShardRepr {
+                key: shard.key().to_string(),
+                total: shard.total(),
+            }),
+            check: self.check().map(str::to_string),
+            success_output: self.success_output().map(|criteria| match criteria {
+                OutputMatch::Contains(needle) => OutputRepr::Contains(needle.clone()),
+                #[cfg(feature = "regex")]
+                OutputMatch::Regex(regex) => OutputRepr::Regex(regex.as_str().to_string()),
+            }),
+            comments: self.comments().to_vec(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CronTask {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        TaskRepr::deserialize(deserializer)?
+            .into_task()
+            .map_err(D::Error::custom)
+    }
+}
+
+impl TaskRepr {
+    fn into_task(self) -> Result<CronTask> {
+        let mut builder = parse_bare_schedule(&self.schedule)?
+            .path(self.command)
+            .priority(self.priority);
+
+        if let Some(name) = self.name {
+            builder = builder.name(name);
+        }
+        if let Some(day_match) = self.day_match {
+            builder = builder.day_match(day_match.parse()?);
+        }
+        if let Some(year) = self.year {
+            builder = builder.year(year);
+        }
+        if let Some(offset) = self.offset {
+            builder = builder.offset(parse_offset(&offset)?);
+        }
+        #[cfg(feature = "chrono-tz")]
+        if let Some(timezone) = self.timezone {
+            builder = builder.timezone(parse_timezone(&timezone)?);
+        }
+        builder = builder.dst_policy(parse_dst_policy(
+            self.dst_fold.as_deref(),
+            self.dst_gap.as_deref(),
+        )?);
+        if let Some(group) = self.group {
+            builder = builder.group(group);
+        }
+        if let Some(owner) = self.owner {
+            builder = builder.owner(owner);
+        }
+        for (key, value) in self.annotations {
+            builder = builder.annotation(key, value);
+        }
+        for (key, value) in self.env {
+            builder = builder.env(key, value);
+        }
+        if let Some(dir) = self.current_dir {
+            builder = builder.current_dir(dir);
+        }
+        if let Some(stdin) = self.stdin {
+            builder = builder.stdin(stdin);
+        }
+        if let Some(run_as) = self.run_as {
+            builder = builder.run_as(run_as.parse()?);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(Duration::from_secs(timeout));
+        }
+        if let Some(shard) = self.shard {
+            builder = builder.shard(shard.key, shard.total);
+        }
+        if let Some(check) = self.check {
+            builder = builder.check(check);
+        }
+        if let Some(criteria) = self.success_output {
+            builder = builder.success_output(criteria.into_match()?);
+        }
+        for rule in self.rules {
+            builder = builder.rule(rule.into_rule()?);
+        }
+
+        self.comments
+            .into_iter()
+            .fold(builder, |builder, comment| builder.comment(comment))
+            .build()
+    }
+}
+
+impl From<&ScheduleRule> for RuleRepr {
+    fn from(rule: &ScheduleRule) -> Self {
+        let schedule = rule.schedule().crontab_schedule();
+
+        match rule.action() {
+            RuleAction::Include => RuleRepr::Include(schedule),
+            RuleAction::Exclude => RuleRepr::Exclude(schedule),
+        }
+    }
+}
+
+impl RuleRepr {
+    pub(crate) fn into_rule(self) -> Result<ScheduleRule> {
+        let (action, schedule) = match self {
+            RuleRepr::Include(schedule) => (RuleAction::Include, schedule),
+            RuleRepr::Exclude(schedule) => (RuleAction::Exclude, schedule),
+        };
+
+        Ok(ScheduleRule::new(
+            action,
+            parse_bare_schedule(&schedule)?.build()?,
+        ))
+    }
+}
+
+impl OutputRepr {
+    fn into_match(self) -> Result<OutputMatch> {
+        match self {
+            OutputRepr::Contains(needle) => Ok(OutputMatch::contains(needle)),
+            #[cfg(feature = "regex")]
+            OutputRepr::Regex(pattern) => OutputMatch::regex(&pattern),
+            #[cfg(not(feature = "regex"))]
+            OutputRepr::Regex(pattern) => Err(CronError::InvalidPattern(format!(
+                "`{pattern}` needs the regex feature"
+            ))),
+        }
+    }
+}
+
+impl Serialize for Cron {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        CronRepr {
+            tasks: self.into_iter().cloned().collect(),
+            groups: self.groups().iter().map(GroupRepr::from).collect(),
+            lookahead: self.lookahead().map(|lookahead| lookahead.num_seconds()),
+            instance: self.instance(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Cron {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let repr = CronRepr::deserialize(deserializer)?;
+        let mut cron = Cron::new();
+
+        for task in repr.tasks {
+            cron.add_task(task);
+        }
+        for group in repr.groups {
+            cron.add_group(group.into_group().map_err(D::Error::custom)?);
+        }
+        if let Some(seconds) = repr.lookahead {
+            let lookahead = TimeDelta::try_seconds(seconds)
+                .ok_or_else(|| D::Error::custom(format!("lookahead of {seconds}s is too long")))?;
+            cron = cron.with_lookahead(lookahead);
+        }
+        if let Some(instance) = repr.instance {
+            cron = cron.with_instance(instance);
+        }
+
+        Ok(cron)
+    }
+}
+
+#[cfg(feature = "schemars")]
+mod schema {
+    use std::borrow::Cow;
+
+    use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
+
+    use super::{CronRepr, TaskRepr};
+    use crate::{cron::Cron, task::CronTask, value::CronValue, year::YearValue};
+
+    impl JsonSchema for CronValue {
+        fn schema_name() -> Cow<'static, str> {
+            "CronValue".into()
+        }
+
+        fn json_schema(_: &mut SchemaGenerator) -> Schema {
+            json_schema!({
+                "type": "string",
+                "description": "A cron field, such as `*/5`, `1-5` or `MON,WED`."
+            })
+        }
+    }
+
+    impl JsonSchema for YearValue {
+        fn schema_name() -> Cow<'static, str> {
+            "YearValue".into()
+        }
+
+        fn json_schema(_: &mut SchemaGenerator) -> Schema {
+            json_schema!({
+                "type": "string",
+                "description": "A year field, such as `2025` or `2025-2030/2`."
+            })
+        }
+    }
+
+    impl JsonSchema for CronTask {
+        fn schema_name() -> Cow<'static, str> {
+            TaskRepr::schema_name()
+        }
+
+        fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+            TaskRepr::json_schema(generator)
+        }
+    }
+
+    impl JsonSchema for Cron {
+        fn schema_name() -> Cow<'static, str> {
+            CronRepr::schema_name()
+        }
+
+        fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+            CronRepr::json_schema(generator)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        run_as::RunAs,
+        value::{every, range},
+    };
+
+    #[test]
+    fn test_cron_value_serde() {
+        let value = range(1..5).every(2);
+        let json = serde_json::to_string(&value).unwrap();
+
+        assert_eq!(json, "\"1-5/2\"");
+        assert_eq!(
+            serde_json::from_str::<CronValue>(&json)
+                .unwrap()
+                .to_string(),
+            value.to_string()
+        );
+        assert!(serde_json::from_str::<CronValue>("\"1-\"").is_err());
+    }
+
+    #[test]
+    fn test_task_serde() {
+        let task = CronTask::parse("*/15 9-17 * * 1-5 /usr/bin/poll --quiet").unwrap();
+        let json = serde_json::to_string(&task).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"schedule":"*/15 9-17 * * 1-5","command":"/usr/bin/poll --quiet"}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<CronTask>(&json).unwrap().to_string(),
+            task.to_string()
+        );
+    }
+
+    #[test]
+    fn test_task_serde_day_match() {
+        let task = CronTask::builder()
+            .minutes(0)
+            .hour(0)
+            .month_day(13)
+            .week_day(chrono::Weekday::Fri)
+            .path("/usr/bin/report".to_string())
+            .build()
+            .unwrap();
+        let json = serde_json::to_string(&task).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"schedule":"0 0 13 * 5","command":"/usr/bin/report","day_match":"all"}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<CronTask>(&json).unwrap().day_match(),
+            DayMatch::All
+        );
+
+        let parsed = CronTask::parse("0 0 13 * 5 /usr/bin/report").unwrap();
+        let json = serde_json::to_string(&parsed).unwrap();
+        assert!(!json.contains("day_match"));
+        assert_eq!(
+            serde_json::from_str::<CronTask>(&json).unwrap().day_match(),
+            DayMatch::Any
+        );
+    }
+
+    #[test]
+    fn test_task_serde_metadata() {
+        let task = CronTask::builder()
+            .seconds(every(10))
+            .year(2030)
+            .offset(FixedOffset::east_opt(5 * 3600 + 30 * 60).unwrap())
+            .dst_policy(DstPolicy {
+                fold: FoldPolicy::Latest,
+                gap: GapPolicy::ShiftForward,
+            })
+            .exclude("* * 25 12 *".parse::<CronTask>().unwrap())
+            .path("/bin/true".to_string())
+            .name("tick")
+            .group("maintenance")
+            .owner("ops@example.com")
+            .annotation("deploy.id", "42")
+            .env("REGION", "eu-west")
+            .current_dir("/srv/app")
+            .stdin("yes\n")
+            .run_as(RunAs::new("deploy").with_group("www"))
+            .timeout(Duration::from_secs(90))
+            .priority(5)
+            .shard("billing", 4)
+            .success_output(OutputMatch::contains("done"))
+            .comment("# nightly")
+            .build()
+            .unwrap();
+        let json = serde_json::to_value(&task).unwrap();
+
+        assert_eq!(json["schedule"], "*/10 * * * * *");
+        assert_eq!(json["year"], "2030");
+        assert_eq!(json["offset"], "+05:30");
+        assert_eq!(json["dst_fold"], "latest");
+        assert_eq!(json["dst_gap"], "shift-forward");
+        assert_eq!(json["rules"][0]["exclude"], "* * 25 12 *");
+        assert_eq!(json["annotations"]["deploy.id"], "42");
+        assert_eq!(json["env"]["REGION"], "eu-west");
+        assert_eq!(json["current_dir"], "/srv/app");
+        assert_eq!(json["stdin"], "yes\n");
+        assert_eq!(json["run_as"], "deploy:www");
+        assert_eq!(json["timeout"], 90);
+        assert_eq!(json["priority"], 5);
+        assert_eq!(json["shard"]["total"], 4);
+        assert_eq!(json["success_output"]["contains"], "done");
+
+        let back: CronTask = serde_json::from_value(json).unwrap();
+        assert_eq!(back.to_string(), task.to_string());
+        assert_eq!(back.offset(), task.offset());
+        assert_eq!(back.dst_policy(), task.dst_policy());
+        #[cfg(feature = "fingerprint")]
+        assert_eq!(back.fingerprint(), task.fingerprint());
+        assert_eq!(back.name(), Some("tick"));
+        assert_eq!(back.group(), Some("maintenance"));
+        assert_eq!(back.owner(), Some("ops@example.com"));
+        assert_eq!(back.annotations(), task.annotations());
+        assert_eq!(back.env(), task.env());
+        assert_eq!(back.current_dir(), task.current_dir());
+        assert_eq!(back.stdin(), task.stdin());
+        assert_eq!(back.run_as(), task.run_as());
+        assert_eq!(back.timeout(), task.timeout());
+        assert_eq!(back.priority(), 5);
+        assert_eq!(back.shard().map(|shard| shard.key()), Some("billing"));
+        assert_eq!(back.comments(), task.comments());
+    }
+
+    #[cfg(feature = "chrono-tz")]
+    #[test]
+    fn test_task_serde_timezone() {
+        let task = CronTask::builder()
+            .minutes(0)
+            .hour(3)
+            .timezone(chrono_tz::Europe::Paris)
+            .path("/bin/true".to_string())
+            .build()
+            .unwrap();
+        let json = serde_json::to_value(&task).unwrap();
+
+        assert_eq!(json["timezone"], "Europe/Paris");
+        let back: CronTask = serde_json::from_value(json).unwrap();
+        assert_eq!(back.timezone(), task.timezone());
+        assert!(
+            serde_json::from_str::<CronTask>(r#"{"schedule":"0 3 * * *","timezone":"Nowhere"}"#)
+                .is_err()
+        );
+    }
+    #[test]
+    fn test_task_serde_validates() {
+        let invalid = [
+            r#"{"schedule":"61 * * * *"}"#,
+            r#"{"schedule":"* * * *"}"#,
+            r#"{"schedule":"* * * * *","shard":{"key":"a","total":0}}"#,
+        ];
+
+        for json in invalid {
+            assert!(serde_json::from_str::<CronTask>(json).is_err(), "{json}");
+        }
+    }
+
+    #[test]
+    fn test_cron_serde() {
+        let mut cron = Cron::new()
+            .with_lookahead(TimeDelta::hours(1))
+            .with_instance(2);
+        cron.add_task(CronTask::parse("@reboot /usr/bin/warm").unwrap());
+        cron.add_task(CronTask::parse("0 3 * * * /usr/bin/backup").unwrap());
+        let mut group = TaskGroup::new("maintenance")
+            .with_max_concurrent(1)
+            .with_overlap_policy(OverlapPolicy::Skip)
+            .with_offset(FixedOffset::east_opt(3600).unwrap())
+            .with_calendar_rule(ScheduleRule::exclude(
+                CronTask::parse("* * 25 12 *").unwrap(),
+            ));
+        group.set_enabled(false);
+        cron.add_group(group);
+
+        let json = serde_json::to_string(&cron).unwrap();
+        let back: Cron = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(serde_json::to_string(&back).unwrap(), json);
+        assert_eq!(back.lookahead(), Some(TimeDelta::hours(1)));
+        assert!(!back.group("maintenance").unwrap().is_enabled());
+        assert_eq!(
+            back.group("maintenance").unwrap().overlap_policy(),
+            OverlapPolicy::Skip
+        );
+        assert!(json.contains(r#""calendar":[{"exclude":"* * 25 12 *"}]"#));
+        assert_eq!(back.into_iter().count(), 2);
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn test_json_schema() {
+        let schema = serde_json::to_value(schemars::schema_for!(Cron)).unwrap();
+        let task = &schema["$defs"]["CronTask"];
+
+        assert_eq!(schema["title"], "Cron");
+        assert_eq!(task["required"], serde_json::json!(["schedule"]));
+        assert_eq!(task["properties"]["schedule"]["type"], "string");
+        assert_eq!(
+            task["properties"]["shard"]["anyOf"][0]["$ref"],
+            "#/$defs/Shard"
+        );
+    }
+}
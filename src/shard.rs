@@ -0,0 +1,72 @@
+use crate::{
+    error::{CronError, Result},
+    value::fnv1a,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shard {
+    key: String,
+    total: u32,
+}
+
+impl Shard {
+    pub fn new(key: impl Into<String>, total: u32) -> Self {
+        Self {
+            key: key.into(),
+            total,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn total(&self) -> u32 {
+        self.total
+    }
+
+    /// Index of the instance owning this shard, stable across processes and
+    /// releases so every replica agrees on it.
+    pub fn index(&self) -> u32 {
+        (fnv1a(self.key.as_bytes()) % self.total as u64) as u32
+    }
+
+    pub fn is_owned_by(&self, instance: u32) -> bool {
+        self.index() == instance
+    }
+
+    pub fn verify(&self) -> Result<()> {
+        if self.total > 0 {
+            Ok(())
+        } else {
+            Err(CronError::InvalidShard)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shard_index_is_stable() {
+        let shard = Shard::new("billing", 4);
+
+        assert_eq!(shard.index(), Shard::new("billing", 4).index());
+        assert_eq!(shard.index(), (fnv1a(b"billing") % 4) as u32);
+    }
+
+    #[test]
+    fn test_shard_owned_by_exactly_one_instance() {
+        let shard = Shard::new("reports", 3);
+        let owners = (0..3).filter(|i| shard.is_owned_by(*i)).count();
+
+        assert_eq!(owners, 1);
+    }
+
+    #[test]
+    fn test_shard_verify() {
+        assert!(Shard::new("key", 1).verify().is_ok());
+        assert!(Shard::new("key", 0).verify().is_err());
+    }
+}
@@ -0,0 +1,155 @@
+use std::ops::Range;
+
+use chrono::{DateTime, Local, TimeDelta};
+
+use crate::{
+    cron::Cron,
+    realtime::{Clock, ManualClock},
+    task::CronTask,
+};
+
+/// Instant at which tasks would fire, in the order they would start.
+#[derive(Debug, Clone)]
+pub struct Firing<'a> {
+    pub date: DateTime<Local>,
+    pub tasks: Vec<&'a CronTask>,
+}
+
+/// Replays a schedule over a window on a virtual clock, recording what
+/// would fire and when without running anything. Iterating advances the
+/// clock from one firing to the next.
+///
+/// ```ignore
+/// let firings: Vec<Firing> = Simulator::new(&cron, start..end).collect();
+/// ```
+#[derive(Debug)]
+pub struct Simulator<'a> {
+    cron: &'a Cron,
+    clock: ManualClock<Local>,
+    end: DateTime<Local>,
+}
+
+impl<'a> Simulator<'a> {
+    pub fn new(cron: &'a Cron, window: Range<DateTime<Local>>) -> Self {
+        // Firings are searched strictly after the clock.
+        let start = window
+            .start
+            .checked_sub_signed(TimeDelta::nanoseconds(1))
+            .unwrap_or(window.start);
+
+        Self {
+            cron,
+            clock: ManualClock::new(start),
+            end: window.end,
+        }
+    }
+
+    /// Current date of the virtual clock.
+    pub fn now(&self) -> DateTime<Local> {
+        self.clock.now()
+    }
+
+    /// Number of runs of each task over the rest of the window, in the
+    /// order tasks were added.
+    pub fn run_counts(self) -> Vec<(&'a CronTask, usize)> {
+        let cron = self.cron;
+        let firings: Vec<Firing> = self.collect();
+
+        cron.into_iter()
+            .map(|task| {
+                let runs = firings
+                    .iter()
+                    .filter(|firing| firing.tasks.iter().any(|t| std::ptr::eq(*t, task)))
+                    .count();
+                (task, runs)
+            })
+            .collect()
+    }
+}
+
+impl<'a> Iterator for Simulator<'a> {
+    type Item = Firing<'a>;
+
+    fn next(&mut self) -> Option<Firing<'a>> {
+        let now = self.clock.now();
+        let date = self
+            .cron
+            .into_iter()
+            .filter_map(|task| {
+                task.try_next_occurrence(now)
+                    .filter(|date| self.cron.skip_reason_at(task, *date).is_none())
+            })
+            .min()
+            .filter(|date| *date < self.end)?;
+
+        self.clock.set(date);
+        Some(Firing {
+            date,
+            tasks: self.cron.get_all_due_at(date),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Timelike};
+
+    use super::*;
+    use crate::value::{every, on};
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2025, 6, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn cron() -> Cron {
+        let mut cron = Cron::new();
+        cron.add_task(
+            CronTask::builder()
+                .minutes(every(30))
+                .path("/usr/bin/poll".to_string())
+                .build()
+                .unwrap(),
+        );
+        cron.add_task(
+            CronTask::builder()
+                .minutes(on(0))
+                .hour(on(3))
+                .path("/usr/bin/backup".to_string())
+                .build()
+                .unwrap(),
+        );
+        cron
+    }
+
+    #[test]
+    fn test_simulate() {
+        let cron = cron();
+        let mut simulator = Simulator::new(&cron, at(1, 2, 30)..at(1, 4, 0));
+
+        let first = simulator.next().unwrap();
+        assert_eq!(first.date, at(1, 2, 30));
+        assert_eq!(first.tasks.len(), 1);
+
+        let second = simulator.next().unwrap();
+        assert_eq!(second.date, at(1, 3, 0));
+        assert_eq!(second.tasks.len(), 2);
+        assert_eq!(simulator.now(), at(1, 3, 0));
+
+        assert_eq!(simulator.next().map(|f| f.date.minute()), Some(30));
+        assert!(simulator.next().is_none());
+    }
+
+    #[test]
+    fn test_run_counts() {
+        let cron = cron();
+        let counts: Vec<usize> = Simulator::new(&cron, at(1, 0, 0)..at(8, 0, 0))
+            .run_counts()
+            .into_iter()
+            .map(|(_, runs)| runs)
+            .collect();
+
+        assert_eq!(counts, [7 * 48, 7]);
+    }
+}
@@ -0,0 +1,69 @@
+use std::fmt::Display;
+
+/// Why a task whose schedule matches does not run on this instance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkipReason {
+    /// The task belongs to a disabled group.
+    GroupDisabled(String),
+    /// The task was paused by name.
+    Paused,
+    /// The task's shard is owned by another instance.
+    NotOwner { owner: u32 },
+    /// The task used up its run time for the month.
+    BudgetExhausted,
+    /// The run falls within a blackout window of the cron.
+    Blackout,
+    /// The task's group was already running as many tasks as it allows,
+    /// and skips runs rather than queueing them.
+    Overlap,
+    /// The run fell due longer ago than the catch-up policy allows.
+    MissedBeyondGrace,
+    /// A later run of the task fell due since, which the catch-up policy
+    /// keeps instead.
+    Superseded,
+}
+
+impl Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SkipReason::GroupDisabled(group) => write!(f, "group `{group}` is disabled"),
+            SkipReason::Paused => write!(f, "task is paused"),
+            SkipReason::NotOwner { owner } => write!(f, "shard is owned by instance {owner}"),
+            SkipReason::BudgetExhausted => write!(f, "monthly run time budget is used up"),
+            SkipReason::Blackout => write!(f, "run falls within a blackout"),
+            SkipReason::Overlap => write!(f, "group is already running as many tasks as it allows"),
+            SkipReason::MissedBeyondGrace => write!(f, "run was missed for too long to catch up"),
+            SkipReason::Superseded => write!(f, "a later run was caught up instead"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skip_reason_display() {
+        assert_eq!(
+            SkipReason::GroupDisabled("reports".to_string()).to_string(),
+            "group `reports` is disabled"
+        );
+        assert_eq!(SkipReason::Paused.to_string(), "task is paused");
+        assert_eq!(
+            SkipReason::NotOwner { owner: 2 }.to_string(),
+            "shard is owned by instance 2"
+        );
+        assert_eq!(
+            SkipReason::BudgetExhausted.to_string(),
+            "monthly run time budget is used up"
+        );
+        assert_eq!(
+            SkipReason::Blackout.to_string(),
+            "run falls within a blackout"
+        );
+        assert_eq!(
+            SkipReason::MissedBeyondGrace.to_string(),
+            "run was missed for too long to catch up"
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use std::sync::{
+    Arc, Mutex, MutexGuard,
+    atomic::{AtomicBool, Ordering},
+};
+
+use chrono::{DateTime, Local};
+
+use crate::{
+    error::Result,
+    event::{EventLog, SchedulerEvent},
+};
+
+/// What a scheduler keeps across restarts.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchedulerState {
+    /// Last date the scheduler woke up at, the runs due until then, that
+    /// one included, being dispatched or skipped.
+    pub last_wake: Option<DateTime<Local>>,
+    /// Next run of each task of the cron as of the last wake-up, in the
+    /// order of the cron, `None` for a task with no run inside the
+    /// lookahead or none at all.
+    pub next_runs: Vec<Option<DateTime<Local>>>,
+}
+
+/// Where a scheduler keeps its [`SchedulerState`], such as a file or a
+/// database. Errors of the store's own are reported as
+/// [`crate::error::CronError::StateStore`].
+pub trait StateStore: Send {
+    /// The saved state, `None` when nothing was saved yet.
+    fn load(&mut self) -> Result<Option<SchedulerState>>;
+
+    fn save(&mut self, state: &SchedulerState) -> Result<()>;
+
+    /// Where the state is kept, such as a file path or a database URL,
+    /// for bundles to tell the host importing them which store to open.
+    /// `None` for a store that cannot be found again, as in memory.
+    fn reference(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Store keeping the state in memory, shared between its clones, so that a
+/// scheduler started again in the same process picks up where the last one
+/// stopped.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStore(Arc<Mutex<Option<SchedulerState>>>);
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn state(&self) -> Option<SchedulerState> {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Option<SchedulerState>> {
+        // The state is only ever replaced whole, a poisoned lock is still
+        // usable.
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl StateStore for MemoryStore {
+    fn load(&mut self) -> Result<Option<SchedulerState>> {
+        Ok(self.state())
+    }
+
+    fn save(&mut self, state: &SchedulerState) -> Result<()> {
+        *self.lock() = Some(state.clone());
+        Ok(())
+    }
+}
+
+/// Store of a running scheduler, which keeps scheduling while the store
+/// fails: without the saved state, there is nothing to catch up on, and
+/// what happens meanwhile is not saved. Each save tries the store again,
+/// the scheduler reporting it going down and coming back.
+pub(crate) struct Persistence {
+    store: Box<dyn StateStore>,
+    events: EventLog,
+    degraded: Arc<AtomicBool>,
+}
+
+impl Persistence {
+    pub(crate) fn new(
+        store: Box<dyn StateStore>,
+        events: EventLog,
+        degraded: Arc<AtomicBool>,
+    ) -> Self {
+        Self {
+            store,
+            events,
+            degraded,
+        }
+    }
+
+    /// The saved state, `None` when there is none or the store fails.
+    pub(crate) fn load(&mut self) -> Option<SchedulerState> {
+        let loaded = self.store.load();
+        self.report(loaded.as_ref().map(|_| ()).map_err(ToString::to_string));
+        loaded.ok().flatten()
+    }
+
+    pub(crate) fn save(&mut self, state: &SchedulerState) {
+        let saved = self.store.save(state);
+        self.report(saved.map_err(|error| error.to_string()));
+    }
+
+    fn report(&self, result: std::result::Result<(), String>) {
+        let degraded = result.is_err();
+        if self.degraded.swap(degraded, Ordering::Relaxed) == degraded {
+            return;
+        }
+        self.events.emit(match result {
+            Ok(()) => SchedulerEvent::Recovered,
+            Err(error) => SchedulerEvent::Degraded { error },
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::error::CronError;
+
+    /// Store failing while `down` is set, keeping the state in memory
+    /// otherwise.
+    struct FlakyStore {
+        memory: MemoryStore,
+        down: Arc<AtomicBool>,
+    }
+
+    impl StateStore for FlakyStore {
+        fn load(&mut self) -> Result<Option<SchedulerState>> {
+            if self.down.load(Ordering::Relaxed) {
+                return Err(CronError::StateStore("connection refused".to_string()));
+            }
+            self.memory.load()
+        }
+
+        fn save(&mut self, state: &SchedulerState) -> Result<()> {
+            if self.down.load(Ordering::Relaxed) {
+                return Err(CronError::StateStore("connection refused".to_string()));
+            }
+            self.memory.save(state)
+        }
+    }
+
+    #[test]
+    fn test_persistence_degrades_and_recovers() {
+        let down = Arc::new(AtomicBool::new(true));
+        let memory = MemoryStore::new();
+        let store = FlakyStore {
+            memory: memory.clone(),
+            down: Arc::clone(&down),
+        };
+        let events = EventLog::default();
+        let degraded = Arc::new(AtomicBool::new(false));
+        let mut persistence =
+            Persistence::new(Box::new(store), events.clone(), Arc::clone(&degraded));
+        let state = SchedulerState {
+            last_wake: Some(Local.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap()),
+            ..SchedulerState::default()
+        };
+
+        assert_eq!(persistence.load(), None);
+        persistence.save(&state);
+        assert!(degraded.load(Ordering::Relaxed));
+        assert_eq!(memory.state(), None);
+
+        down.store(false, Ordering::Relaxed);
+        persistence.save(&state);
+        assert!(!degraded.load(Ordering::Relaxed));
+        assert_eq!(persistence.load(), Some(state));
+
+        // Reported once per change.
+        let history = events.history();
+        assert_eq!(history.len(), 2);
+        assert!(matches!(
+            &history[0],
+            SchedulerEvent::Degraded { error } if error.contains("connection refused")
+        ));
+        assert!(matches!(history[1], SchedulerEvent::Recovered));
+    }
+}
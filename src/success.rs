@@ -0,0 +1,55 @@
+#[cfg(feature = "regex")]
+use crate::error::{CronError, Result};
+
+/// Output a run must produce on stdout to count as successful, on top of a
+/// zero exit status.
+#[derive(Debug, Clone)]
+pub enum OutputMatch {
+    Contains(String),
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+}
+
+impl OutputMatch {
+    pub fn contains(needle: impl Into<String>) -> Self {
+        Self::Contains(needle.into())
+    }
+
+    #[cfg(feature = "regex")]
+    pub fn regex(pattern: &str) -> Result<Self> {
+        regex::Regex::new(pattern)
+            .map(Self::Regex)
+            .map_err(|e| CronError::InvalidPattern(e.to_string()))
+    }
+
+    pub fn is_match(&self, output: &str) -> bool {
+        match self {
+            Self::Contains(needle) => output.contains(needle.as_str()),
+            #[cfg(feature = "regex")]
+            Self::Regex(regex) => regex.is_match(output),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains() {
+        let criteria = OutputMatch::contains("backup complete");
+
+        assert!(criteria.is_match("starting\nbackup complete\n"));
+        assert!(!criteria.is_match("backup failed"));
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_regex() {
+        let criteria = OutputMatch::regex(r"^synced \d+ files$").unwrap();
+
+        assert!(criteria.is_match("synced 42 files"));
+        assert!(!criteria.is_match("synced some files"));
+        assert!(OutputMatch::regex("(").is_err());
+    }
+}
@@ -0,0 +1,625 @@
+use chrono::FixedOffset;
+
+use crate::{
+    error::{CronError, Result},
+    task::CronTask,
+    value::CronValue,
+    year::YearValue,
+};
+
+const WEEK_DAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// Latest year systemd accepts, open-ended year steps stop there.
+const MAX_YEAR: i32 = 2199;
+
+/// Expansions of the shorthand calendar events.
+const SHORTHANDS: [(&str, &str); 9] = [
+    ("minutely", "*-*-* *:*:00"),
+    ("hourly", "*-*-* *:00:00"),
+    ("daily", "*-*-* 00:00:00"),
+    ("weekly", "Mon *-*-* 00:00:00"),
+    ("monthly", "*-*-01 00:00:00"),
+    ("quarterly", "*-01,04,07,10-01 00:00:00"),
+    ("semiannually", "*-01,07-01 00:00:00"),
+    ("yearly", "*-01-01 00:00:00"),
+    ("annually", "*-01-01 00:00:00"),
+];
+
+/// A `.timer` unit and the `.service` unit it starts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemdUnits {
+    pub timer: String,
+    pub service: String,
+}
+
+impl CronTask {
+    /// Translates the task into systemd units, the schedule becoming an
+    /// `OnCalendar=` expression. Fails on values systemd calendar events
+    /// cannot express, such as nearest week days.
+    pub fn to_systemd_units(&self) -> Result<SystemdUnits> {
+        let trigger = if self.is_reboot() {
+            "OnBootSec=0".to_string()
+        } else {
+            format!("OnCalendar={}", self.on_calendar()?)
+        };
+        let command = self.command_line();
+
+        let timer = format!(
+            "[Unit]\nDescription=Timer for {command}\n\n\
+             [Timer]\n{trigger}\n\n\
+             [Install]\nWantedBy=timers.target\n"
+        );
+        let mut service = format!(
+            "[Unit]\nDescription={command}\n\n\
+             [Service]\nType=oneshot\nExecStart=/bin/sh -c \"{}\"\n",
+            escape(&command)
+        );
+        if let Some(stdin) = self.stdin() {
+            service.push_str("StandardInput=data\n");
+            // Escapes and specifiers are resolved in the text as well.
+            for line in stdin.split('\n') {
+                let line = line.replace('\\', "\\\\").replace('%', "%%");
+                service.push_str(&format!("StandardInputText={line}\n"));
+            }
+        }
+
+        Ok(SystemdUnits { timer, service })
+    }
+
+    /// Renders the schedule as a systemd calendar event, such as
+    /// `Mon..Fri *-*-* 09:00:00`.
+    pub fn on_calendar(&self) -> Result<String> {
+        let (week_days, nth) = week_day_spec(self.week_day())?;
+        let month_day = match (nth, self.month_day()) {
+            (Some((start, end)), CronValue::All) => format!("-{start:02}..{end:02}"),
+            (Some(_), _) => return Err(unsupported(self.week_day())),
+            // systemd keeps the dates matching both.
+            (None, _) if self.matches_either_day() => return Err(unsupported(self.week_day())),
+            (None, CronValue::Last) => "~01".to_string(),
+            (None, value) => format!("-{}", field(value, 1, 31)?),
+        };
+
+        let date = format!(
+            "{}-{}{month_day}",
+            year(self.year()),
+            field(self.month(), 1, 12)?
+        );
+        let time = format!(
+            "{}:{}:{}",
+            field(self.hour(), 0, 23)?,
+            field(self.minute(), 0, 59)?,
+            match self.second() {
+                Some(second) => field(second, 0, 59)?,
+                None => "00".to_string(),
+            }
+        );
+
+        let zone = self.offset().map(time_zone).transpose()?;
+        // systemd reads the same zone names as chrono-tz.
+        #[cfg(feature = "chrono-tz")]
+        let zone = self.timezone().map(|tz| tz.name().to_string()).or(zone);
+        let time = match zone {
+            Some(zone) => format!("{time} {zone}"),
+            None => time,
+        };
+
+        Ok(match week_days {
+            Some(week_days) => format!("{week_days} {date} {time}"),
+            None => format!("{date} {time}"),
+        })
+    }
+}
+
+impl CronTask {
+    /// Parses a systemd calendar event such as `Mon..Fri *-*-* 08:00:00`,
+    /// with or without the `OnCalendar=` prefix, into a task running `path`.
+    /// Constructs with no task equivalent, such as named time zones without
+    /// the `chrono-tz` feature or days counted from the end of the month
+    /// other than the last one, are reported as errors.
+    pub fn from_on_calendar(s: &str, path: impl Into<String>) -> Result<CronTask> {
+        let s = s.trim();
+        let s = s.strip_prefix("OnCalendar=").unwrap_or(s).trim();
+        if s.is_empty() {
+            return Err(CronError::InvalidExpression(
+                "empty calendar event".to_string(),
+            ));
+        }
+        let s = SHORTHANDS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s))
+            .map_or(s, |(_, expansion)| expansion);
+
+        let mut tokens = s.split_whitespace().peekable();
+        let mut builder = CronTask::builder().path(path.into());
+
+        if let Some(week_days) = tokens.next_if(|t| t.starts_with(char::is_alphabetic)) {
+            builder = builder.week_day(parse_week_days(week_days)?);
+        }
+        if let Some(date) = tokens.next_if(|t| t.contains(['-', '~'])) {
+            let (year, month, month_day) = parse_date(date)?;
+            builder = builder.year(year).month(month).month_day(month_day);
+        }
+
+        let (hour, minute, second) = match tokens.next_if(|t| t.contains(':')) {
+            Some(time) => parse_time(time)?,
+            None => (CronValue::from(0), CronValue::from(0), None),
+        };
+        builder = builder.hour(hour).minutes(minute);
+        if let Some(second) = second {
+            builder = builder.seconds(second);
+        }
+
+        if let Some(zone) = tokens.next() {
+            builder = match parse_time_zone(zone) {
+                Some(offset) => builder.offset(offset),
+                #[cfg(feature = "chrono-tz")]
+                None => builder.timezone(zone.parse().map_err(|_| unsupported_calendar(zone))?),
+                #[cfg(not(feature = "chrono-tz"))]
+                None => return Err(unsupported_calendar(zone)),
+            };
+        }
+        if let Some(rest) = tokens.next() {
+            return Err(unsupported_calendar(rest));
+        }
+
+        builder.build()
+    }
+}
+
+fn parse_week_days(s: &str) -> Result<CronValue> {
+    let day = |name: &str| {
+        WEEK_DAYS
+            .iter()
+            .position(|day| {
+                name.get(..3)
+                    .is_some_and(|name| day.eq_ignore_ascii_case(name))
+            })
+            .map(|i| i as u8)
+            .ok_or_else(|| unsupported_calendar(s))
+    };
+
+    let items = s
+        .split(',')
+        .map(
+            |item| match item.split_once("..").or_else(|| item.split_once('-')) {
+                Some((start, end)) => {
+                    let (start, end) = (day(start)?, day(end)?);
+                    if start < end {
+                        Ok(CronValue::Range(start..end))
+                    } else {
+                        Err(unsupported_calendar(s))
+                    }
+                }
+                None => day(item).map(CronValue::from),
+            },
+        )
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(single_or_list(items))
+}
+
+/// Splits `[year-]month-day` or `[[year-]month]~01` into its parts.
+fn parse_date(s: &str) -> Result<(YearValue, CronValue, CronValue)> {
+    let (parts, month_day) = match s.split_once('~') {
+        Some((parts, "01" | "1")) => (parts, CronValue::Last),
+        Some(_) => return Err(unsupported_calendar(s)),
+        None => match s.rsplit_once('-') {
+            Some((parts, day)) => (parts, parse_calendar_value(day)?),
+            None => return Err(unsupported_calendar(s)),
+        },
+    };
+
+    let (year, month) = match parts.split_once('-') {
+        Some((year, month)) => (parse_calendar_year(year)?, month),
+        None => (YearValue::All, parts),
+    };
+
+    Ok((year, parse_calendar_value(month)?, month_day))
+}
+
+fn parse_time(s: &str) -> Result<(CronValue, CronValue, Option<CronValue>)> {
+    let parts: Vec<&str> = s.split(':').collect();
+
+    match parts[..] {
+        [hour, minute] => Ok((
+            parse_calendar_value(hour)?,
+            parse_calendar_value(minute)?,
+            None,
+        )),
+        [hour, minute, second] => {
+            let second = match second {
+                "00" | "0" => None,
+                second => Some(parse_calendar_value(second)?),
+            };
+            Ok((
+                parse_calendar_value(hour)?,
+                parse_calendar_value(minute)?,
+                second,
+            ))
+        }
+        _ => Err(unsupported_calendar(s)),
+    }
+}
+
+fn parse_calendar_value(s: &str) -> Result<CronValue> {
+    let number = |n: &str| n.parse::<u8>().map_err(|_| unsupported_calendar(s));
+
+    let items = s
+        .split(',')
+        .map(|item| {
+            let (base, step) = match item.split_once('/') {
+                Some((base, step)) => (base, Some(number(step)?)),
+                None => (item, None),
+            };
+            let base = match base.split_once("..") {
+                _ if base == "*" => CronValue::All,
+                Some((start, end)) => CronValue::Range(number(start)?..number(end)?),
+                None => number(base)?.into(),
+            };
+
+            Ok(match step {
+                Some(0) => return Err(unsupported_calendar(s)),
+                Some(step) => CronValue::Interval(Box::new(base), step.into()),
+                None => base,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(single_or_list(items))
+}
+
+fn parse_calendar_year(s: &str) -> Result<YearValue> {
+    let number = |n: &str| n.parse::<i32>().map_err(|_| unsupported_calendar(s));
+
+    let mut items = s
+        .split(',')
+        .map(|item| {
+            let (base, step) = match item.split_once('/') {
+                Some((base, step)) => (base, Some(step)),
+                None => (item, None),
+            };
+            let base = match (base.split_once(".."), step) {
+                _ if base == "*" => YearValue::All,
+                (Some((start, end)), _) => YearValue::Range(number(start)?..=number(end)?),
+                (None, Some(_)) => YearValue::Range(number(base)?..=MAX_YEAR),
+                (None, None) => YearValue::Value(number(base)?),
+            };
+
+            Ok(match step {
+                Some(step) => base.every(step.parse().map_err(|_| unsupported_calendar(s))?),
+                None => base,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(match items.len() {
+        1 => items.remove(0),
+        _ => YearValue::List(items),
+    })
+}
+
+fn single_or_list(mut items: Vec<CronValue>) -> CronValue {
+    match items.len() {
+        1 => items.remove(0),
+        _ => CronValue::List(items),
+    }
+}
+
+fn unsupported_calendar(s: &str) -> CronError {
+    CronError::InvalidExpression(format!("`{s}` has no task equivalent"))
+}
+
+/// Week day names, and the day of month range an nth week day stands for.
+type WeekDaySpec = (Option<String>, Option<(u8, u8)>);
+
+fn week_day_spec(value: &CronValue) -> Result<WeekDaySpec> {
+    let name = |day: u8| WEEK_DAYS[day as usize % 7];
+
+    Ok(match value {
+        CronValue::All => (None, None),
+        CronValue::Value(v) => (Some(name(u8::from(v)).to_string()), None),
+        CronValue::Range(r) => (Some(format!("{}..{}", name(r.start), name(r.end))), None),
+        CronValue::Nth(day, n) => {
+            let start = (n - 1) * 7 + 1;
+            (
+                Some(name(u8::from(day)).to_string()),
+                Some((start, start + 6)),
+            )
+        }
+        value if value.has_week_day_items() => return Err(unsupported(value)),
+        value => {
+            let days: Vec<&str> = (0..7).filter(|d| value.matches(*d)).map(name).collect();
+            (Some(days.join(",")), None)
+        }
+    })
+}
+
+fn field(value: &CronValue, min: u8, max: u8) -> Result<String> {
+    Ok(match value {
+        CronValue::All => "*".to_string(),
+        CronValue::Value(v) => format!("{:02}", u8::from(v)),
+        CronValue::Range(r) => format!("{:02}..{:02}", r.start, r.end),
+        CronValue::Interval(base, step) if matches!(**base, CronValue::All) => {
+            format!("{min:02}/{}", u8::from(step))
+        }
+        CronValue::Interval(base, step) if matches!(**base, CronValue::Value(_)) => {
+            format!("{}/{}", field(base, min, max)?, u8::from(step))
+        }
+        CronValue::List(list) => list
+            .iter()
+            .map(|v| field(v, min, max))
+            .collect::<Result<Vec<_>>>()?
+            .join(","),
+        CronValue::Interval(..) => (min..=max)
+            .filter(|v| value.matches_in(*v, min))
+            .map(|v| format!("{v:02}"))
+            .collect::<Vec<_>>()
+            .join(","),
+        value => return Err(unsupported(value)),
+    })
+}
+
+fn year(value: &YearValue) -> String {
+    match value {
+        YearValue::All => "*".to_string(),
+        YearValue::Value(v) => v.to_string(),
+        YearValue::Range(r) => format!("{}..{}", r.start(), r.end()),
+        YearValue::List(list) => list.iter().map(year).collect::<Vec<_>>().join(","),
+        YearValue::Interval(base, step) => match base.min_value() {
+            Some(start) => format!("{start}/{step}"),
+            None => format!("0/{step}"),
+        },
+    }
+}
+
+/// Escapes a command for a double quoted systemd `ExecStart=` argument.
+fn escape(command: &str) -> String {
+    command
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('%', "%%")
+        .replace('$', "$$")
+        .replace('\n', "\\n")
+}
+
+/// Names `offset` as systemd expects it. Only whole hours have a name, the
+/// `Etc/GMT` zones counting hours west of UTC.
+fn time_zone(offset: FixedOffset) -> Result<String> {
+    let seconds = offset.local_minus_utc();
+
+    match (seconds, seconds % 3600) {
+        (0, _) => Ok("UTC".to_string()),
+        (_, 0) => Ok(format!("Etc/GMT{:+}", -seconds / 3600)),
+        _ => Err(CronError::Unconvertible(vec![format!(
+            "the UTC{offset} offset"
+        )])),
+    }
+}
+
+fn parse_time_zone(s: &str) -> Option<FixedOffset> {
+    if s.eq_ignore_ascii_case("UTC") || s.eq_ignore_ascii_case("Etc/UTC") {
+        return FixedOffset::east_opt(0);
+    }
+
+    let hours = s.strip_prefix("Etc/GMT")?;
+    if !hours.starts_with(['+', '-']) {
+        return None;
+    }
+
+    FixedOffset::west_opt(hours.parse::<i32>().ok()? * 3600)
+}
+
+fn unsupported(value: &CronValue) -> CronError {
+    CronError::InvalidExpression(format!("`{value}` has no systemd calendar equivalent"))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Weekday;
+
+    use super::*;
+    use crate::task::DayMatch;
+    use crate::value::{every, last_day, nearest_weekday, nth, on};
+
+    #[test]
+    fn test_on_calendar() {
+        let calendar = |task: CronTask| task.on_calendar().unwrap();
+
+        assert_eq!(calendar(CronTask::daily("/bin/true")), "*-*-* 00:00:00");
+        assert_eq!(
+            calendar(
+                CronTask::builder()
+                    .minutes(every(15u8))
+                    .hour(CronValue::Range(9..17))
+                    .week_day(CronValue::Range(0..4))
+                    .allow_commandless()
+                    .build()
+                    .unwrap()
+            ),
+            "Mon..Fri *-*-* 09..17:00/15:00"
+        );
+        assert_eq!(
+            calendar(
+                CronTask::builder()
+                    .minutes(on(30))
+                    .hour(on(6))
+                    .month_day(on(1).or(15))
+                    .month(every(3u8))
+                    .year(2025..=2027)
+                    .allow_commandless()
+                    .build()
+                    .unwrap()
+            ),
+            "2025..2027-01/3-01,15 06:30:00"
+        );
+    }
+
+    #[test]
+    fn test_on_calendar_special_days() {
+        let last = CronTask::builder()
+            .minutes(on(0))
+            .hour(on(0))
+            .month_day(last_day())
+            .allow_commandless()
+            .build()
+            .unwrap();
+        assert_eq!(last.on_calendar().unwrap(), "*-*~01 00:00:00");
+
+        let second_monday = CronTask::builder()
+            .minutes(on(0))
+            .hour(on(9))
+            .week_day(nth(Weekday::Mon, 2))
+            .allow_commandless()
+            .build()
+            .unwrap();
+        assert_eq!(
+            second_monday.on_calendar().unwrap(),
+            "Mon *-*-08..14 09:00:00"
+        );
+
+        let nearest = CronTask::builder()
+            .month_day(nearest_weekday(15))
+            .allow_commandless()
+            .build()
+            .unwrap();
+        assert!(nearest.on_calendar().is_err());
+    }
+
+    #[test]
+    fn test_to_systemd_units() {
+        let task = CronTask::builder()
+            .minutes(on(0))
+            .hour(on(2))
+            .path("echo \"50%\" $HOME".to_string())
+            .build()
+            .unwrap();
+        let units = task.to_systemd_units().unwrap();
+
+        assert!(units.timer.contains("OnCalendar=*-*-* 02:00:00\n"));
+        assert!(units.timer.contains("WantedBy=timers.target"));
+        assert!(
+            units
+                .service
+                .contains("ExecStart=/bin/sh -c \"echo \\\"50%%\\\" $$HOME\"\n")
+        );
+
+        let reboot = CronTask::reboot("/usr/bin/warmup")
+            .to_systemd_units()
+            .unwrap();
+        assert!(reboot.timer.contains("OnBootSec=0\n"));
+
+        let stdin = CronTask::builder()
+            .path("mail joe".to_string())
+            .stdin("Hi,\n100%")
+            .build()
+            .unwrap()
+            .to_systemd_units()
+            .unwrap();
+        assert!(
+            stdin
+                .service
+                .ends_with("StandardInput=data\nStandardInputText=Hi,\nStandardInputText=100%%\n")
+        );
+
+        let multiline = CronTask::daily("cd /srv\nmake").to_systemd_units().unwrap();
+        assert!(
+            multiline
+                .service
+                .contains("ExecStart=/bin/sh -c \"cd /srv\\nmake\"\n")
+        );
+    }
+
+    #[test]
+    fn test_from_on_calendar() {
+        let parse = |s: &str| {
+            CronTask::from_on_calendar(s, "/bin/true")
+                .unwrap()
+                .to_string()
+        };
+
+        assert_eq!(
+            parse("OnCalendar=Mon..Fri *-*-* 08:00:00"),
+            "0 8 * * 0-4 \"/bin/true\""
+        );
+        assert_eq!(parse("Sat,Sun 12:30"), "30 12 * * 5,6 \"/bin/true\"");
+        assert_eq!(parse("*-*-01 00/6:00"), "0 0/6 1 * * \"/bin/true\"");
+        assert_eq!(parse("2025-02~01 23:00"), "0 23 L 2 * 2025 \"/bin/true\"");
+        assert_eq!(parse("*:0/15:30"), "30 0/15 * * * * \"/bin/true\"");
+        assert_eq!(parse("weekly"), "0 0 * * 0 \"/bin/true\"");
+        assert_eq!(parse("quarterly"), "0 0 1 1,4,7,10 * \"/bin/true\"");
+    }
+
+    #[test]
+    fn test_from_on_calendar_unsupported() {
+        let parse = |s: &str| CronTask::from_on_calendar(s, "/bin/true");
+
+        assert!(parse("*-*~03 00:00").is_err());
+        #[cfg(not(feature = "chrono-tz"))]
+        assert!(parse("*-*-* 00:00:00 Europe/Paris").is_err());
+        assert!(parse("*-*-* 00:00:00.5").is_err());
+        assert!(parse("Fri..Mon 00:00").is_err());
+        assert!(parse("Someday").is_err());
+        assert!(parse("*-*-* 25:00").is_err());
+        assert!(parse("a€ *-*-* 00:00").is_err());
+        assert!(parse("Mo€ 00:00").is_err());
+        assert!(parse("").is_err());
+        assert!(parse("   ").is_err());
+        assert!(parse("OnCalendar=").is_err());
+
+        // Cron runs this line on the 13th and on Fridays, systemd on both.
+        let either = CronTask::builder()
+            .month_day(13)
+            .week_day(Weekday::Fri)
+            .day_match(DayMatch::Any)
+            .allow_commandless()
+            .build()
+            .unwrap();
+        assert!(either.on_calendar().is_err());
+    }
+
+    #[test]
+    fn test_on_calendar_round_trip() {
+        for s in [
+            "Mon..Fri *-*-* 09..17:00/15:00",
+            "*-*~01 00:00:00",
+            "Fri *-*-13 00:00:00",
+        ] {
+            let task = CronTask::from_on_calendar(s, "/bin/true").unwrap();
+            assert_eq!(task.on_calendar().unwrap(), s);
+        }
+    }
+
+    #[test]
+    fn test_on_calendar_offset() {
+        let task = |hours: i32, minutes: i32| {
+            CronTask::builder()
+                .minutes(0)
+                .hour(9)
+                .offset(FixedOffset::east_opt(hours * 3600 + minutes * 60).unwrap())
+                .path("/bin/true".to_string())
+                .build()
+                .unwrap()
+        };
+
+        assert_eq!(task(0, 0).on_calendar().unwrap(), "*-*-* 09:00:00 UTC");
+        assert_eq!(
+            task(2, 0).on_calendar().unwrap(),
+            "*-*-* 09:00:00 Etc/GMT-2"
+        );
+        assert!(task(5, 30).on_calendar().is_err());
+
+        let parsed = CronTask::from_on_calendar("*-*-* 09:00:00 Etc/GMT-2", "/bin/true").unwrap();
+        assert_eq!(parsed.offset(), task(2, 0).offset());
+    }
+
+    #[cfg(feature = "chrono-tz")]
+    #[test]
+    fn test_on_calendar_timezone() {
+        let s = "*-*-* 03:00:00 Europe/Paris";
+        let task = CronTask::from_on_calendar(s, "/bin/true").unwrap();
+
+        assert_eq!(task.timezone(), Some(chrono_tz::Europe::Paris));
+        assert_eq!(task.on_calendar().unwrap(), s);
+        assert!(CronTask::from_on_calendar("*-*-* 03:00:00 Mars/Olympus", "/bin/true").is_err());
+    }
+}
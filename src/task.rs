@@ -1,26 +1,185 @@
-use std::{fmt::Display, path::PathBuf};
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    mem,
+    ops::Range,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
-use chrono::{DateTime, Datelike, Local, NaiveDate, Timelike, TimeZone};
+use chrono::{
+    DateTime, Datelike, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeDelta, TimeZone,
+    Timelike, Weekday,
+};
 
-use crate::{error::Result, value::CronValue};
+#[cfg(feature = "budget")]
+use crate::budget::RuntimeBudget;
+pub use crate::schedule::DayMatch;
+use crate::{
+    command,
+    dst::DstPolicy,
+    error::{CronError, Result},
+    rule::{RuleAction, ScheduleRule},
+    schedule::{self, Schedule},
+    value::{CronValue, fnv1a},
+    year::YearValue,
+};
+#[cfg(feature = "group")]
+use std::sync::Arc;
 
-#[derive(Debug, Default)]
+#[cfg(feature = "group")]
+use crate::group::GroupPolicy;
+#[cfg(feature = "patch")]
+use crate::patch::SchedulePatch;
+#[cfg(feature = "retry")]
+use crate::retry::RetryPolicy;
+#[cfg(any(feature = "parser", feature = "executor"))]
+use crate::run_as::RunAs;
+#[cfg(feature = "shard")]
+use crate::shard::Shard;
+#[cfg(feature = "success")]
+use crate::success::OutputMatch;
+
+/// Candidates excluded by rules that a search skips before giving up.
+const MAX_RULE_CANDIDATES: usize = 100_000;
+
+/// Why the search for the next occurrence moved past a wall clock reading.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+    Year,
+    Month,
+    MonthDay,
+    WeekDay,
+    Hour,
+    Minute,
+    Second,
+    /// The reading matches but does not come after the starting point.
+    NotAfter,
+    /// The reading matches but the DST policy skips it, or it does not
+    /// exist in the time zone.
+    Dst,
+    /// The schedule matches but a rule excludes the date.
+    Rule,
+}
+
+impl Display for Rejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self {
+            Rejection::Year => "year",
+            Rejection::Month => "month",
+            Rejection::MonthDay => "month day",
+            Rejection::WeekDay => "week day",
+            Rejection::Hour => "hour",
+            Rejection::Minute => "minute",
+            Rejection::Second => "second",
+            Rejection::NotAfter => "not after start",
+            Rejection::Dst => "dst",
+            Rejection::Rule => "rule",
+        };
+        write!(f, "{reason}")
+    }
+}
+
+/// Receives the wall clock readings a search rejects, see
+/// `debug::trace_next`.
+pub(crate) type Observer<'a> = Option<&'a mut dyn FnMut(NaiveDateTime, Rejection)>;
+
+/// Reports the reading made of the given fields to `observe`, unless it is
+/// only a carry to the next unit, such as a 32nd day or a 24th hour.
+fn reject(observe: &mut Observer, reason: Rejection, year: i32, fields: [u8; 5]) {
+    let Some(observe) = observe else {
+        return;
+    };
+    let [month, day, hour, min, sec] = fields.map(u32::from);
+
+    if let Some(reading) =
+        NaiveDate::from_ymd_opt(year, month, day).and_then(|date| date.and_hms_opt(hour, min, sec))
+    {
+        observe(reading, reason);
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleKind {
+    /// Runs whenever the date matches the schedule fields.
+    #[default]
+    Calendar,
+    /// `@reboot`: runs once when the scheduler starts, the schedule fields
+    /// are ignored.
+    Reboot,
+}
+
+#[derive(Debug, Default, Clone)]
 pub struct CronTask {
+    kind: ScheduleKind,
+    day_match: DayMatch,
+    second: Option<CronValue>,
     minute: CronValue,
     hour: CronValue,
     month_day: CronValue,
     month: CronValue,
     week_day: CronValue,
     path: PathBuf,
+    args: Vec<String>,
+    env: BTreeMap<String, String>,
+    current_dir: Option<PathBuf>,
+    stdin: Option<String>,
+    #[cfg(any(feature = "parser", feature = "executor"))]
+    run_as: Option<RunAs>,
+    timeout: Option<Duration>,
+    #[cfg(feature = "retry")]
+    retry: Option<RetryPolicy>,
+    name: Option<String>,
+    #[cfg(feature = "shard")]
+    shard: Option<Shard>,
+    comments: Vec<String>,
+    group: Option<String>,
+    /// Policy of the group once registered in a `Cron`.
+    #[cfg(feature = "group")]
+    group_policy: Option<Arc<GroupPolicy>>,
+    owner: Option<String>,
+    annotations: BTreeMap<String, String>,
+    check: Option<String>,
+    #[cfg(feature = "success")]
+    success_output: Option<OutputMatch>,
+    year: YearValue,
+    offset: Option<FixedOffset>,
+    #[cfg(feature = "chrono-tz")]
+    timezone: Option<chrono_tz::Tz>,
+    dst: DstPolicy,
+    rules: Vec<ScheduleRule>,
+    #[cfg(feature = "budget")]
+    budget: Option<RuntimeBudget>,
+    priority: i32,
 }
 
 impl Display for CronTask {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{} {} {} {} {} {:?}",
-            self.minute, self.hour, self.month_day, self.month, self.week_day, self.path
-        )
+        if let Some(offset) = self.offset() {
+            write!(f, "UTC{offset} ")?;
+        }
+        #[cfg(feature = "chrono-tz")]
+        if let Some(timezone) = self.timezone() {
+            write!(f, "{timezone} ")?;
+        }
+        if let (ScheduleKind::Calendar, Some(second)) = (self.kind, &self.second) {
+            write!(f, "{second} ")?;
+        }
+
+        match self.kind {
+            ScheduleKind::Calendar => {
+                write!(
+                    f,
+                    "{} {} {} {} {} ",
+                    self.minute, self.hour, self.month_day, self.month, self.week_day
+                )?;
+                if self.year != YearValue::All {
+                    write!(f, "{} ", self.year)?;
+                }
+                write!(f, "{:?}", self.command_line())
+            }
+            ScheduleKind::Reboot => write!(f, "@reboot {:?}", self.command_line()),
+        }
     }
 }
 
@@ -34,12 +193,45 @@ impl CronTask {
         path: PathBuf,
     ) -> Self {
         CronTask {
+            kind: ScheduleKind::Calendar,
+            day_match: DayMatch::All,
+            second: None,
             minute,
             hour,
             month_day,
             month,
             week_day,
             path,
+            args: Vec::new(),
+            env: BTreeMap::new(),
+            current_dir: None,
+            stdin: None,
+            #[cfg(any(feature = "parser", feature = "executor"))]
+            run_as: None,
+            timeout: None,
+            #[cfg(feature = "retry")]
+            retry: None,
+            name: None,
+            #[cfg(feature = "shard")]
+            shard: None,
+            comments: Vec::new(),
+            group: None,
+            #[cfg(feature = "group")]
+            group_policy: None,
+            owner: None,
+            annotations: BTreeMap::new(),
+            check: None,
+            #[cfg(feature = "success")]
+            success_output: None,
+            year: YearValue::All,
+            offset: None,
+            #[cfg(feature = "chrono-tz")]
+            timezone: None,
+            dst: DstPolicy::default(),
+            rules: Vec::new(),
+            #[cfg(feature = "budget")]
+            budget: None,
+            priority: 0,
         }
     }
 
@@ -47,130 +239,1231 @@ impl CronTask {
         CronTaskBuilder::default()
     }
 
+    /// `@reboot`: runs once at scheduler startup.
+    pub fn reboot(path: impl Into<PathBuf>) -> Self {
+        let mut task = Self::preset(
+            CronValue::All,
+            CronValue::All,
+            CronValue::All,
+            CronValue::All,
+            path,
+        );
+        task.kind = ScheduleKind::Reboot;
+        task
+    }
+
+    /// `@hourly`: at minute 0 of every hour.
+    pub fn hourly(path: impl Into<PathBuf>) -> Self {
+        Self::preset(
+            0.into(),
+            CronValue::All,
+            CronValue::All,
+            CronValue::All,
+            path,
+        )
+    }
+
+    /// `@daily`: every day at midnight.
+    pub fn daily(path: impl Into<PathBuf>) -> Self {
+        Self::preset(0.into(), 0.into(), CronValue::All, CronValue::All, path)
+    }
+
+    /// `@weekly`: every Sunday at midnight.
+    pub fn weekly(path: impl Into<PathBuf>) -> Self {
+        let mut task = Self::daily(path);
+        task.week_day = Weekday::Sun.into();
+        task
+    }
+
+    /// `@monthly`: the first day of every month at midnight.
+    pub fn monthly(path: impl Into<PathBuf>) -> Self {
+        Self::preset(0.into(), 0.into(), 1.into(), CronValue::All, path)
+    }
+
+    /// `@yearly`: every January 1st at midnight.
+    pub fn yearly(path: impl Into<PathBuf>) -> Self {
+        Self::preset(0.into(), 0.into(), 1.into(), 1.into(), path)
+    }
+
+    fn preset(
+        minute: CronValue,
+        hour: CronValue,
+        month_day: CronValue,
+        month: CronValue,
+        path: impl Into<PathBuf>,
+    ) -> Self {
+        CronTask::new(minute, hour, month_day, month, CronValue::All, path.into())
+    }
+
     fn verify(&self) -> Result<()> {
-        self.minute.verify(0, 60)?;
-        self.hour.verify(0, 24)?;
-        self.month_day.verify(0, 31)?;
-        self.month.verify(0, 12)?;
-        self.week_day.verify(0, 6)?;
-        
+        self.schedule().verify()?;
+
+        #[cfg(feature = "shard")]
+        if let Some(shard) = &self.shard {
+            shard.verify()?;
+        }
+        #[cfg(any(feature = "parser", feature = "executor"))]
+        if let Some(run_as) = &self.run_as {
+            run_as.verify()?;
+        }
+
+        // A line break would end the crontab line and start another entry.
+        let command = self.command_line();
+        if command.contains(['\n', '\r']) {
+            return Err(CronError::MultilineCommand(command));
+        }
+
+        // Variables are exported as `NAME=value` crontab lines.
+        for (name, value) in &self.env {
+            let mut chars = name.chars();
+            let is_identifier = chars
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+            if !is_identifier || value.contains(['\n', '\r']) {
+                return Err(CronError::InvalidEnvVar(name.clone()));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn kind(&self) -> ScheduleKind {
+        self.kind
+    }
+
+    pub fn is_reboot(&self) -> bool {
+        self.kind == ScheduleKind::Reboot
+    }
+
+    pub fn day_match(&self) -> DayMatch {
+        self.day_match
+    }
+
+    /// Whether a date matching either day field is enough, the task asking
+    /// for [`DayMatch::Any`] and restricting both.
+    pub fn matches_either_day(&self) -> bool {
+        self.day_match.is_either(&self.month_day, &self.week_day)
+    }
+
+    /// The schedule fields alone, for the `no_std` core of
+    /// [`crate::schedule`].
+    pub fn schedule(&self) -> Schedule {
+        Schedule {
+            second: self.second.clone(),
+            minute: self.minute.clone(),
+            hour: self.hour.clone(),
+            month_day: self.month_day.clone(),
+            month: self.month.clone(),
+            week_day: self.week_day.clone(),
+            year: self.year.clone(),
+            day_match: self.day_match,
+        }
+    }
+
+    /// Seconds field, `None` when the task fires at second 0 of matching
+    /// minutes.
+    pub fn second(&self) -> Option<&CronValue> {
+        self.second.as_ref()
+    }
+
+    pub fn year(&self) -> &YearValue {
+        &self.year
+    }
+
+    /// Fixed offset the schedule fields are read in, `None` for the local
+    /// time zone. Without a zone of its own, the task reads them in its
+    /// group's.
+    pub fn offset(&self) -> Option<FixedOffset> {
+        #[cfg(feature = "group")]
+        if !self.has_own_zone() {
+            return self
+                .group_policy
+                .as_ref()
+                .and_then(|policy| policy.offset());
+        }
+        self.offset
+    }
+
+    /// Time zone the schedule fields are read in, taking precedence over
+    /// `offset`.
+    #[cfg(feature = "chrono-tz")]
+    pub fn timezone(&self) -> Option<chrono_tz::Tz> {
+        #[cfg(feature = "group")]
+        if !self.has_own_zone() {
+            return self
+                .group_policy
+                .as_ref()
+                .and_then(|policy| policy.timezone());
+        }
+        self.timezone
+    }
+
+    /// The task's own offset, leaving its group's out.
+    #[cfg(feature = "serde")]
+    pub(crate) fn own_offset(&self) -> Option<FixedOffset> {
+        self.offset
+    }
+
+    #[cfg(all(feature = "serde", feature = "chrono-tz"))]
+    pub(crate) fn own_timezone(&self) -> Option<chrono_tz::Tz> {
+        self.timezone
+    }
+
+    #[cfg(feature = "group")]
+    fn has_own_zone(&self) -> bool {
+        #[cfg(feature = "chrono-tz")]
+        if self.timezone.is_some() {
+            return true;
+        }
+        self.offset.is_some()
+    }
+
+    pub fn dst_policy(&self) -> DstPolicy {
+        self.dst
+    }
+
+    /// Include and exclude rules applied on top of the schedule, in order.
+    /// The calendar of the task's group comes before them.
+    pub fn rules(&self) -> &[ScheduleRule] {
+        &self.rules
+    }
+
+    /// The group's calendar followed by the task's own rules.
+    pub(crate) fn all_rules(&self) -> impl Iterator<Item = &ScheduleRule> {
+        #[cfg(feature = "group")]
+        let calendar = self
+            .group_policy
+            .iter()
+            .flat_map(|policy| policy.calendar());
+        #[cfg(not(feature = "group"))]
+        let calendar = std::iter::empty();
+
+        calendar.chain(&self.rules)
+    }
+
+    pub(crate) fn has_rules(&self) -> bool {
+        self.all_rules().next().is_some()
+    }
+
+    #[cfg(feature = "budget")]
+    pub fn budget(&self) -> Option<RuntimeBudget> {
+        self.budget
+    }
+
+    /// Key the run time of the task is recorded under: its name, or its
+    /// command when it has none.
+    #[cfg(feature = "budget")]
+    pub fn budget_key(&self) -> String {
+        self.name.clone().unwrap_or_else(|| self.command_line())
+    }
+
+    pub fn minute(&self) -> &CronValue {
+        &self.minute
+    }
+
+    pub fn hour(&self) -> &CronValue {
+        &self.hour
+    }
+
+    pub fn month_day(&self) -> &CronValue {
+        &self.month_day
+    }
+
+    pub fn month(&self) -> &CronValue {
+        &self.month
+    }
+
+    pub fn week_day(&self) -> &CronValue {
+        &self.week_day
+    }
+
+    /// The program to run, or a whole shell command line for tasks without
+    /// arguments, such as the ones read from a crontab.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    /// The path followed by the arguments, quoted for the shell when there
+    /// are any.
+    pub fn command_line(&self) -> String {
+        command_line(&self.path, &self.args)
+    }
+
+    /// Variables set for the command on top of the inherited environment.
+    pub fn env(&self) -> &BTreeMap<String, String> {
+        &self.env
+    }
+
+    /// Directory the command runs in, the caller's one when unset.
+    pub fn current_dir(&self) -> Option<&Path> {
+        self.current_dir.as_deref()
+    }
+
+    /// Text written to the command's standard input, what follows the
+    /// first `%` of a crontab command.
+    pub fn stdin(&self) -> Option<&str> {
+        self.stdin.as_deref()
+    }
+
+    /// Account the command runs as, the caller's one when unset.
+    #[cfg(any(feature = "parser", feature = "executor"))]
+    pub fn run_as(&self) -> Option<&RunAs> {
+        self.run_as.as_ref()
+    }
+
+    /// Longest the command may run before being killed.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    #[cfg(feature = "retry")]
+    pub fn retry(&self) -> Option<&RetryPolicy> {
+        self.retry.as_ref()
+    }
+
+    /// Label given to the task, such as the key it is declared under in a
+    /// config file.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    #[cfg(feature = "shard")]
+    pub fn shard(&self) -> Option<&Shard> {
+        self.shard.as_ref()
+    }
+
+    pub fn group(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+
+    #[cfg(feature = "group")]
+    pub fn group_policy(&self) -> Option<&GroupPolicy> {
+        self.group_policy.as_deref()
+    }
+
+    #[cfg(feature = "group")]
+    pub(crate) fn set_group_policy(&mut self, policy: Option<Arc<GroupPolicy>>) {
+        self.group_policy = policy;
+    }
+
+    /// Team or person responsible for the task, such as an email address.
+    pub fn owner(&self) -> Option<&str> {
+        self.owner.as_deref()
+    }
+
+    /// Free-form metadata attached by other tools, such as a deploy id.
+    pub fn annotations(&self) -> &BTreeMap<String, String> {
+        &self.annotations
+    }
+
+    pub fn annotation(&self, key: &str) -> Option<&str> {
+        self.annotations.get(key).map(String::as_str)
+    }
+
+    /// Rank among tasks catching up after downtime, higher first.
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    /// Command verifying the task's prerequisites, see `run_check`.
+    pub fn check(&self) -> Option<&str> {
+        self.check.as_deref()
+    }
+
+    #[cfg(feature = "success")]
+    pub fn success_output(&self) -> Option<&OutputMatch> {
+        self.success_output.as_ref()
+    }
+
+    /// Whether a run exiting with `exit_success` and printing `stdout` counts
+    /// as successful.
+    #[cfg(feature = "success")]
+    pub fn is_success(&self, exit_success: bool, stdout: &str) -> bool {
+        exit_success
+            && self
+                .success_output
+                .as_ref()
+                .is_none_or(|criteria| criteria.is_match(stdout))
+    }
+
+    /// Comment and blank lines attached above the task in a crontab, kept
+    /// verbatim so they survive a round-trip.
+    pub fn comments(&self) -> &[String] {
+        &self.comments
+    }
+
+    /// Whether the given instance should run this task. Unsharded tasks run
+    /// on every instance.
+    #[cfg(feature = "shard")]
+    pub fn runs_on_instance(&self, instance: u32) -> bool {
+        self.shard
+            .as_ref()
+            .is_none_or(|shard| shard.is_owned_by(instance))
+    }
+
+    /// Applies `patch` on top of the current schedule. The task is left
+    /// untouched if the patched schedule fails verification.
+    #[cfg(feature = "patch")]
+    pub fn apply_patch(&mut self, patch: SchedulePatch) -> Result<()> {
+        let mut patched = self.clone();
+
+        patch.second.apply_optional(&mut patched.second);
+        patch.minute.apply(&mut patched.minute);
+        patch.hour.apply(&mut patched.hour);
+        patch.month_day.apply(&mut patched.month_day);
+        patch.month.apply(&mut patched.month);
+        patch.week_day.apply(&mut patched.week_day);
+
+        patched.verify()?;
+        *self = patched;
+
         Ok(())
     }
 
-    pub fn matches(&self, date: DateTime<Local>) -> bool {
-        self.week_day.matches(date.weekday() as u8)
-            && self.month_day.matches(date.day() as u8)
+    /// Without a seconds field, seconds and sub-second precision are
+    /// ignored: a date matches when the minute slot containing it matches.
+    /// Fields are read in `date`'s time zone unless the task has its own
+    /// time zone or offset.
+    pub fn matches<Tz: TimeZone>(&self, date: DateTime<Tz>) -> bool {
+        #[cfg(feature = "chrono-tz")]
+        if let Some(timezone) = self.timezone() {
+            return self.matches_in_zone(date.with_timezone(&timezone));
+        }
+
+        match self.offset() {
+            Some(offset) => self.matches_in_zone(date.with_timezone(&offset)),
+            None => self.matches_in_zone(date),
+        }
+    }
+
+    /// Matches the wall clock of `date`, as the DST policy resolves it,
+    /// then lets the rules override the result.
+    fn matches_in_zone<Tz: TimeZone>(&self, date: DateTime<Tz>) -> bool {
+        let scheduled = self.matches_schedule(date.clone());
+
+        self.all_rules().fold(scheduled, |runs, rule| {
+            if rule.schedule().matches_wall(date.clone()) {
+                rule.action() == RuleAction::Include
+            } else {
+                runs
+            }
+        })
+    }
+
+    fn matches_schedule<Tz: TimeZone>(&self, date: DateTime<Tz>) -> bool {
+        if self.matches_wall(date.clone()) {
+            return self.dst.keeps(&date);
+        }
+
+        let step = match self.second {
+            Some(_) => TimeDelta::seconds(1),
+            None => TimeDelta::minutes(1),
+        };
+        self.kind == ScheduleKind::Calendar
+            && self
+                .dst
+                .shifted_to(&date, step)
+                .into_iter()
+                .any(|skipped| self.matches_wall(skipped))
+    }
+
+    fn matches_wall<Tz: TimeZone>(&self, date: DateTime<Tz>) -> bool {
+        self.kind == ScheduleKind::Calendar
+            && self.matches_day(date.date_naive())
             && self.hour.matches(date.hour() as u8)
-            && self.month.matches(date.month() as u8)
+            && self.month.matches_in(date.month() as u8, 1)
             && self.minute.matches(date.minute() as u8)
+            && self
+                .second
+                .as_ref()
+                .is_none_or(|second| second.matches(date.second() as u8))
+            && self.year.matches(date.year())
+    }
+
+    /// Matches raw clock readings, week days numbered from Monday = 0, with
+    /// no date to build and nothing allocated. The seconds and year fields
+    /// are not checked, and month days using `L` or `W` never match since
+    /// they depend on the rest of the month.
+    pub fn matches_parts(
+        &self,
+        minute: u8,
+        hour: u8,
+        month_day: u8,
+        month: u8,
+        week_day: u8,
+    ) -> bool {
+        self.kind == ScheduleKind::Calendar
+            && self.minute.matches(minute)
+            && self.hour.matches(hour)
+            && self.month.matches_in(month, 1)
+            && (1..=31).contains(&month_day)
+            && self.days_match(
+                self.month_day.matches_in(month_day, 1),
+                self.week_day.matches_week_day(week_day, month_day),
+            )
+    }
+
+    /// Whether the day fields match `date`, combined as the task's
+    /// [`DayMatch`] asks.
+    pub fn matches_day(&self, date: NaiveDate) -> bool {
+        schedule::matches_day(&self.month_day, &self.week_day, self.day_match, date)
+    }
+
+    fn days_match(&self, month_day: bool, week_day: bool) -> bool {
+        if self.matches_either_day() {
+            month_day || week_day
+        } else {
+            month_day && week_day
+        }
+    }
+
+    /// Start of the slot containing `date`: its second when the task has a
+    /// seconds field, its minute otherwise.
+    pub fn slot_start<Tz: TimeZone>(&self, date: DateTime<Tz>) -> DateTime<Tz> {
+        match self.second {
+            Some(_) => date.with_nanosecond(0).unwrap_or(date),
+            None => truncate_to_slot(date),
+        }
+    }
+
+    /// Returns the start of the first matching slot strictly after the slot
+    /// containing `from`, in `from`'s time zone.
+    pub fn try_next_occurrence<Tz: TimeZone>(&self, from: DateTime<Tz>) -> Option<DateTime<Tz>> {
+        self.next_occurrence_until(from, None)
+    }
+
+    /// Returns the start of the last matching slot strictly before `from`,
+    /// when the task last ran or should have.
+    pub fn previous_occurrence<Tz: TimeZone>(&self, from: DateTime<Tz>) -> Option<DateTime<Tz>> {
+        if self.kind == ScheduleKind::Reboot {
+            return None;
+        }
+
+        #[cfg(feature = "chrono-tz")]
+        if let Some(timezone) = self.timezone() {
+            return self
+                .search_back_ruled(from.with_timezone(&timezone))
+                .map(|previous| previous.with_timezone(&from.timezone()));
+        }
+
+        match self.offset() {
+            Some(offset) => self
+                .search_back_ruled(from.with_timezone(&offset))
+                .map(|previous| previous.with_timezone(&from.timezone())),
+            None => self.search_back_ruled(from),
+        }
+    }
+
+    /// Same as `try_next_occurrence`, but gives up once the search goes
+    /// further than `horizon` past `from`.
+    pub fn next_occurrence_within<Tz: TimeZone>(
+        &self,
+        from: DateTime<Tz>,
+        horizon: TimeDelta,
+    ) -> Option<DateTime<Tz>> {
+        // A horizon reaching past the last representable date is no bound.
+        let limit = from.clone().checked_add_signed(horizon);
+        self.next_occurrence_until(from, limit)
+    }
+
+    /// Whether any occurrence falls in `[start, end)`. Jumps from field
+    /// to field instead of enumerating slots, so wide windows over sparse
+    /// schedules stay cheap.
+    pub fn has_occurrence_between(&self, start: DateTime<Local>, end: DateTime<Local>) -> bool {
+        if start >= end {
+            return false;
+        }
+
+        if self.slot_start(start) == start && self.matches(start) {
+            return true;
+        }
+
+        self.next_occurrence_until(start, Some(end))
+            .is_some_and(|next| next < end)
+    }
+
+    /// Whether every occurrence of `other` within `window` follows an
+    /// occurrence of this task by at most `max_gap`, as when an upload must
+    /// come after a dump.
+    pub fn always_precedes(
+        &self,
+        other: &CronTask,
+        max_gap: TimeDelta,
+        window: Range<DateTime<Local>>,
+    ) -> bool {
+        self.first_unpreceded(other, max_gap, window).is_none()
+    }
+
+    /// Every occurrence within `window`, in order. Iterating from the back
+    /// searches backwards from the end of the window.
+    pub fn occurrences_between(&self, window: Range<DateTime<Local>>) -> Occurrences<'_> {
+        let after = window
+            .start
+            .checked_sub_signed(TimeDelta::nanoseconds(1))
+            .unwrap_or(window.start);
+
+        Occurrences {
+            task: self,
+            after,
+            before: window.end,
+        }
+    }
+
+    /// Every occurrence after the slot containing `from`, in order. Ends
+    /// only if the schedule does, such as a task bound to past years.
+    pub fn upcoming(&self, from: DateTime<Local>) -> impl Iterator<Item = DateTime<Local>> + '_ {
+        std::iter::successors(Some(from), move |from| self.try_next_occurrence(*from)).skip(1)
+    }
+
+    /// First occurrence of `other` within `window` that no occurrence of
+    /// this task precedes by at most `max_gap`.
+    pub fn first_unpreceded(
+        &self,
+        other: &CronTask,
+        max_gap: TimeDelta,
+        window: Range<DateTime<Local>>,
+    ) -> Option<DateTime<Local>> {
+        other.occurrences_between(window).find(|next| {
+            let earliest = next.checked_sub_signed(max_gap).unwrap_or(*next);
+            !self.has_occurrence_between(earliest, *next)
+        })
+    }
+
+    fn next_occurrence_until<Tz: TimeZone>(
+        &self,
+        from: DateTime<Tz>,
+        limit: Option<DateTime<Tz>>,
+    ) -> Option<DateTime<Tz>> {
+        self.next_occurrence_observed(from, limit, &mut None)
+    }
+
+    /// Same as `next_occurrence_until`, reporting every wall clock reading
+    /// the search rejects to `observe`.
+    pub(crate) fn next_occurrence_observed<Tz: TimeZone>(
+        &self,
+        from: DateTime<Tz>,
+        limit: Option<DateTime<Tz>>,
+        observe: &mut Observer,
+    ) -> Option<DateTime<Tz>> {
+        if self.kind == ScheduleKind::Reboot {
+            return None;
+        }
+
+        let from = self.slot_start(from);
+        #[cfg(feature = "chrono-tz")]
+        if let Some(timezone) = self.timezone() {
+            return self
+                .search_ruled(
+                    from.with_timezone(&timezone),
+                    limit.map(|l| l.with_timezone(&timezone)),
+                    observe,
+                )
+                .map(|next| next.with_timezone(&from.timezone()));
+        }
+
+        match self.offset() {
+            Some(offset) => self
+                .search_ruled(
+                    from.with_timezone(&offset),
+                    limit.map(|l| l.with_timezone(&offset)),
+                    observe,
+                )
+                .map(|next| next.with_timezone(&from.timezone())),
+            None => self.search_ruled(from, limit, observe),
+        }
+    }
+
+    /// Runs `search` on the task and its include rules, skipping the
+    /// candidates that the rules exclude.
+    fn search_ruled<Tz>(
+        &self,
+        from: DateTime<Tz>,
+        limit: Option<DateTime<Tz>>,
+        observe: &mut Observer,
+    ) -> Option<DateTime<Tz>>
+    where
+        Tz: TimeZone,
+    {
+        if !self.has_rules() {
+            return self.search(from, limit, observe);
+        }
+
+        let mut from = from;
+        for _ in 0..MAX_RULE_CANDIDATES {
+            let mut next: Option<DateTime<Tz>> = None;
+            for schedule in self.candidate_schedules() {
+                let found = schedule.search(from.clone(), limit.clone(), observe);
+                next = next.into_iter().chain(found).min();
+            }
+            let next = next?;
+            if self.matches_in_zone(next.clone()) {
+                return Some(next);
+            }
+            if let Some(observe) = observe {
+                observe(next.naive_local(), Rejection::Rule);
+            }
+            from = next;
+        }
+
+        None
+    }
+
+    /// Mirror of `search_ruled`, for the last run before `from`.
+    fn search_back_ruled<Tz: TimeZone>(&self, from: DateTime<Tz>) -> Option<DateTime<Tz>> {
+        if !self.has_rules() {
+            return self.search_back(from);
+        }
+
+        let mut from = from;
+        for _ in 0..MAX_RULE_CANDIDATES {
+            let previous = self
+                .candidate_schedules()
+                .filter_map(|schedule| schedule.search_back(from.clone()))
+                .max()?;
+            if self.matches_in_zone(previous.clone()) {
+                return Some(previous);
+            }
+            from = previous;
+        }
+
+        None
     }
 
-    pub fn next_occurrence(&self) -> DateTime<Local> {
-        let from = Local::now();
-        self.try_next_occurrence(from)
-            .expect("no future occurrence found for valid cron expression")
+    fn candidate_schedules(&self) -> impl Iterator<Item = &CronTask> {
+        std::iter::once(self).chain(
+            self.all_rules()
+                .filter(|rule| rule.action() == RuleAction::Include)
+                .map(ScheduleRule::schedule),
+        )
     }
 
-    pub fn try_next_occurrence(&self, from: DateTime<Local>) -> Option<DateTime<Local>> {
+    /// Walks the wall clock of `from`'s time zone for the first matching
+    /// slot after `from`.
+    fn search<Tz>(
+        &self,
+        from: DateTime<Tz>,
+        limit: Option<DateTime<Tz>>,
+        observe: &mut Observer,
+    ) -> Option<DateTime<Tz>>
+    where
+        Tz: TimeZone,
+    {
+        let limit = limit.map(|l| {
+            let month = (l.year(), l.month() as u8);
+            (l, month)
+        });
+        let default_second = CronValue::from(0);
+        let second = self.second.as_ref().unwrap_or(&default_second);
         let mut year = from.year();
         let mut month = from.month() as u8;
         let mut day = from.day() as u8;
         let mut hour = from.hour() as u8;
         let mut min = from.minute() as u8;
+        let mut sec = from.second() as u8;
+
+        // The Gregorian calendar, week days included, repeats every 400
+        // years: searching further cannot find anything new.
+        let mut years_searched = 0;
 
         loop {
-            match self.month.next_value(month, 12) {
-                Some(m) => month = m,
+            match self.year.next_value(year) {
+                Some(y) if y != year => {
+                    reject(observe, Rejection::Year, year, [month, day, hour, min, sec]);
+                    year = y;
+                    years_searched += 1;
+                    (month, day, hour, min, sec) = (1, 1, 0, 0, 0);
+                }
+                Some(_) => {}
                 None => {
-                    year += 1;
-                    month = self.month.min_value()?;
-                    day = 1;
-                    hour = 0;
-                    min = 0;
-                    continue;
+                    reject(observe, Rejection::Year, year, [month, day, hour, min, sec]);
+                    return None;
                 }
             }
 
-            let mut found_day = None;
+            if years_searched > 400 || year > NaiveDate::MAX.year() {
+                return None;
+            }
 
-            for d in day..=30 as u8 {
-                let wd = NaiveDate::from_ymd_opt(year, month as u32, d as u32)?.weekday() as u8;
+            if let Some((_, limit_month)) = &limit
+                && (year, month) > *limit_month
+            {
+                return None;
+            }
 
-                if self.month_day.matches(d) && self.week_day.matches(wd) {
-                    found_day = Some(d);
-                    break;
+            match (month..=12).find(|m| self.month.matches_in(*m, 1)) {
+                Some(m) if m != month => {
+                    reject(
+                        observe,
+                        Rejection::Month,
+                        year,
+                        [month, day, hour, min, sec],
+                    );
+                    month = m;
+                    (day, hour, min, sec) = (1, 0, 0, 0);
+                }
+                Some(_) => {}
+                None => {
+                    reject(
+                        observe,
+                        Rejection::Month,
+                        year,
+                        [month, day, hour, min, sec],
+                    );
+                    year += 1;
+                    years_searched += 1;
+                    (month, day, hour, min, sec) = (1, 1, 0, 0, 0);
+                    continue;
                 }
             }
 
-            let d = match found_day {
-                Some(v) => v,
+            let found_day = (day..=31)
+                .map_while(|d| NaiveDate::from_ymd_opt(year, month as u32, d as u32))
+                .find(|date| self.matches_day(*date))
+                .map(|date| date.day() as u8);
+
+            if found_day != Some(day)
+                && let Some(date) = NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+            {
+                let reason = if self.month_day.matches_month_day(date) {
+                    Rejection::WeekDay
+                } else {
+                    Rejection::MonthDay
+                };
+                reject(observe, reason, year, [month, day, hour, min, sec]);
+            }
+            match found_day {
+                Some(d) if d != day => {
+                    day = d;
+                    (hour, min, sec) = (0, 0, 0);
+                }
+                Some(_) => {}
                 None => {
                     month += 1;
-                    day = 1;
-                    hour = 0;
-                    min = 0;
+                    (day, hour, min, sec) = (1, 0, 0, 0);
                     continue;
                 }
-            };
-            day = d;
+            }
 
             match self.hour.next_value(hour, 23) {
-                Some(h) => hour = h,
+                Some(h) if h != hour => {
+                    reject(observe, Rejection::Hour, year, [month, day, hour, min, sec]);
+                    hour = h;
+                    (min, sec) = (0, 0);
+                }
+                Some(_) => {}
                 None => {
+                    reject(observe, Rejection::Hour, year, [month, day, hour, min, sec]);
                     day += 1;
-                    hour = 0;
-                    min = 0;
+                    (hour, min, sec) = (0, 0, 0);
                     continue;
                 }
             }
 
             match self.minute.next_value(min, 59) {
-                Some(m) => min = m,
+                Some(m) if m != min => {
+                    reject(
+                        observe,
+                        Rejection::Minute,
+                        year,
+                        [month, day, hour, min, sec],
+                    );
+                    min = m;
+                    sec = 0;
+                }
+                Some(_) => {}
                 None => {
+                    reject(
+                        observe,
+                        Rejection::Minute,
+                        year,
+                        [month, day, hour, min, sec],
+                    );
                     hour += 1;
-                    min = 0;
+                    (min, sec) = (0, 0);
                     continue;
                 }
             }
 
-            if let Some(date) = NaiveDate::from_ymd_opt(year, month as u32, day as u32) {
-                if let Some(dt) = date.and_hms_opt(hour as u32, min as u32, 0) {
-                    let local = Local.from_local_datetime(&dt).single()?;
-                    if local > from {
-                        return Some(local);
-                    }
+            match second.next_value(sec, 59) {
+                Some(s) if s != sec => {
+                    reject(
+                        observe,
+                        Rejection::Second,
+                        year,
+                        [month, day, hour, min, sec],
+                    );
+                    sec = s;
+                }
+                Some(_) => {}
+                None => {
+                    reject(
+                        observe,
+                        Rejection::Second,
+                        year,
+                        [month, day, hour, min, sec],
+                    );
+                    min += 1;
+                    sec = 0;
+                    continue;
                 }
             }
 
-            min += 1;
-        }
+            if let Some(dt) = NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+                .and_then(|date| date.and_hms_opt(hour as u32, min as u32, sec as u32))
+            {
+                match self.dst.resolve(&from.timezone(), &dt) {
+                    Some(local) if limit.as_ref().is_some_and(|(l, _)| local > *l) => {
+                        return None;
+                    }
+                    Some(local) if local > from => return Some(local),
+                    Some(_) => reject(
+                        observe,
+                        Rejection::NotAfter,
+                        year,
+                        [month, day, hour, min, sec],
+                    ),
+                    None => reject(observe, Rejection::Dst, year, [month, day, hour, min, sec]),
+                }
+            }
+
+            sec += 1;
+        }
+    }
+
+    /// Mirror of `search`, walking the wall clock backwards for the last
+    /// matching slot before `from`. Counters are signed so that stepping
+    /// back from a field's minimum moves on to the previous unit.
+    fn search_back<Tz>(&self, from: DateTime<Tz>) -> Option<DateTime<Tz>>
+    where
+        Tz: TimeZone,
+    {
+        let prev = |value: &CronValue, current: i32, min: u8| {
+            u8::try_from(current)
+                .ok()
+                .and_then(|current| value.prev_value(current, min))
+                .map(i32::from)
+        };
+        let default_second = CronValue::from(0);
+        let second = self.second.as_ref().unwrap_or(&default_second);
+        let mut year = from.year();
+        let mut month = from.month() as i32;
+        let mut day = from.day() as i32;
+        let mut hour = from.hour() as i32;
+        let mut min = from.minute() as i32;
+        let mut sec = from.second() as i32;
+        let mut years_searched = 0;
+
+        loop {
+            match self.year.prev_value(year) {
+                Some(y) if y != year => {
+                    year = y;
+                    years_searched += 1;
+                    (month, day, hour, min, sec) = (12, 31, 23, 59, 59);
+                }
+                Some(_) => {}
+                None => return None,
+            }
+
+            if years_searched > 400 || year < NaiveDate::MIN.year() {
+                return None;
+            }
+
+            match prev(&self.month, month, 1) {
+                Some(m) if m != month => {
+                    month = m;
+                    (day, hour, min, sec) = (31, 23, 59, 59);
+                }
+                Some(_) => {}
+                None => {
+                    year -= 1;
+                    years_searched += 1;
+                    (month, day, hour, min, sec) = (12, 31, 23, 59, 59);
+                    continue;
+                }
+            }
+
+            let found_day = (1..=day)
+                .rev()
+                .filter_map(|d| NaiveDate::from_ymd_opt(year, month as u32, d as u32))
+                .find(|date| self.matches_day(*date))
+                .map(|date| date.day() as i32);
+
+            match found_day {
+                Some(d) if d != day => {
+                    day = d;
+                    (hour, min, sec) = (23, 59, 59);
+                }
+                Some(_) => {}
+                None => {
+                    month -= 1;
+                    (day, hour, min, sec) = (31, 23, 59, 59);
+                    continue;
+                }
+            }
+
+            match prev(&self.hour, hour, 0) {
+                Some(h) if h != hour => {
+                    hour = h;
+                    (min, sec) = (59, 59);
+                }
+                Some(_) => {}
+                None => {
+                    day -= 1;
+                    (hour, min, sec) = (23, 59, 59);
+                    continue;
+                }
+            }
+
+            match prev(&self.minute, min, 0) {
+                Some(m) if m != min => {
+                    min = m;
+                    sec = 59;
+                }
+                Some(_) => {}
+                None => {
+                    hour -= 1;
+                    (min, sec) = (59, 59);
+                    continue;
+                }
+            }
+
+            match prev(second, sec, 0) {
+                Some(s) => sec = s,
+                None => {
+                    min -= 1;
+                    sec = 59;
+                    continue;
+                }
+            }
+
+            if let Some(dt) = NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+                .and_then(|date| date.and_hms_opt(hour as u32, min as u32, sec as u32))
+                && let Some(local) = self.dst.resolve(&from.timezone(), &dt)
+                && local < from
+            {
+                return Some(local);
+            }
+
+            sec -= 1;
+        }
+    }
+}
+
+/// Occurrences of a task between two instants, both excluded, narrowing
+/// as they are yielded from either end.
+#[derive(Debug, Clone)]
+pub struct Occurrences<'a> {
+    task: &'a CronTask,
+    after: DateTime<Local>,
+    before: DateTime<Local>,
+}
+
+impl Iterator for Occurrences<'_> {
+    type Item = DateTime<Local>;
+
+    fn next(&mut self) -> Option<DateTime<Local>> {
+        if self.after >= self.before {
+            return None;
+        }
+
+        let next = self
+            .task
+            .next_occurrence_within(self.after, self.before - self.after)
+            .filter(|next| *next < self.before)?;
+        self.after = next;
+        Some(next)
     }
 }
 
+impl DoubleEndedIterator for Occurrences<'_> {
+    fn next_back(&mut self) -> Option<DateTime<Local>> {
+        if self.after >= self.before {
+            return None;
+        }
+
+        let previous = self
+            .task
+            .previous_occurrence(self.before)
+            .filter(|previous| *previous > self.after)?;
+        self.before = previous;
+        Some(previous)
+    }
+}
+
+/// Truncates `date` to the start of its minute slot, the resolution at which
+/// tasks without a seconds field are scheduled.
+pub fn truncate_to_slot<Tz: TimeZone>(date: DateTime<Tz>) -> DateTime<Tz> {
+    let offset =
+        TimeDelta::seconds(date.second() as i64) + TimeDelta::nanoseconds(date.nanosecond() as i64);
+
+    // Only the first minute chrono can represent has no slot start in range.
+    date.clone().checked_sub_signed(offset).unwrap_or(date)
+}
+
 impl From<CronTaskBuilder> for CronTask {
     fn from(value: CronTaskBuilder) -> Self {
-        CronTask::new(
+        let mut task = CronTask::new(
             value.minute,
             value.hour,
             value.month_day,
             value.month,
             value.week_day,
             value.path,
-        )
+        );
+        task.args = value.args;
+        task.env = value.env;
+        task.current_dir = value.current_dir;
+        task.stdin = value.stdin;
+        #[cfg(any(feature = "parser", feature = "executor"))]
+        {
+            task.run_as = value.run_as;
+        }
+        task.timeout = value.timeout;
+        #[cfg(feature = "retry")]
+        {
+            task.retry = value.retry;
+        }
+        task.kind = value.kind;
+        task.day_match = value.day_match;
+        task.second = value.second;
+        #[cfg(feature = "shard")]
+        {
+            task.shard = value.shard;
+        }
+        task.comments = value.comments;
+        task.name = value.name;
+        task.group = value.group;
+        task.owner = value.owner;
+        task.annotations = value.annotations;
+        task.check = value.check;
+        #[cfg(feature = "success")]
+        {
+            task.success_output = value.success_output;
+        }
+        task.year = value.year;
+        task.offset = value.offset;
+        task.dst = value.dst;
+        task.rules = value.rules;
+        #[cfg(feature = "budget")]
+        {
+            task.budget = value.budget;
+        }
+        #[cfg(feature = "chrono-tz")]
+        {
+            task.timezone = value.timezone;
+        }
+        task.priority = value.priority;
+        task
     }
 }
 
 #[derive(Debug, Default)]
 pub struct CronTaskBuilder {
+    kind: ScheduleKind,
+    day_match: DayMatch,
+    second: Option<CronValue>,
     minute: CronValue,
     hour: CronValue,
     month_day: CronValue,
     month: CronValue,
     week_day: CronValue,
     path: PathBuf,
+    args: Vec<String>,
+    env: BTreeMap<String, String>,
+    current_dir: Option<PathBuf>,
+    stdin: Option<String>,
+    #[cfg(any(feature = "parser", feature = "executor"))]
+    run_as: Option<RunAs>,
+    timeout: Option<Duration>,
+    #[cfg(feature = "retry")]
+    retry: Option<RetryPolicy>,
+    command: Option<String>,
+    name: Option<String>,
+    #[cfg(feature = "shard")]
+    shard: Option<Shard>,
+    comments: Vec<String>,
+    group: Option<String>,
+    owner: Option<String>,
+    annotations: BTreeMap<String, String>,
+    check: Option<String>,
+    #[cfg(feature = "success")]
+    success_output: Option<OutputMatch>,
+    year: YearValue,
+    offset: Option<FixedOffset>,
+    #[cfg(feature = "chrono-tz")]
+    timezone: Option<chrono_tz::Tz>,
+    dst: DstPolicy,
+    rules: Vec<ScheduleRule>,
+    #[cfg(feature = "budget")]
+    budget: Option<RuntimeBudget>,
+    priority: i32,
+    hash_key: Option<String>,
+    commandless: bool,
 }
 
 impl CronTaskBuilder {
+    /// Starts from a copy of `task`, so only the fields that differ need to
+    /// be set.
+    pub fn from_task(task: &CronTask) -> Self {
+        let task = task.clone();
+        // A task built without a command may be rebuilt without one.
+        let commandless = task.path.as_os_str().is_empty();
+
+        Self {
+            kind: task.kind,
+            day_match: task.day_match,
+            second: task.second,
+            minute: task.minute,
+            hour: task.hour,
+            month_day: task.month_day,
+            month: task.month,
+            week_day: task.week_day,
+            path: task.path,
+            args: task.args,
+            env: task.env,
+            current_dir: task.current_dir,
+            stdin: task.stdin,
+            #[cfg(any(feature = "parser", feature = "executor"))]
+            run_as: task.run_as,
+            timeout: task.timeout,
+            #[cfg(feature = "retry")]
+            retry: task.retry,
+            command: None,
+            #[cfg(feature = "shard")]
+            shard: task.shard,
+            comments: task.comments,
+            name: task.name,
+            group: task.group,
+            owner: task.owner,
+            annotations: task.annotations,
+            check: task.check,
+            #[cfg(feature = "success")]
+            success_output: task.success_output,
+            year: task.year,
+            offset: task.offset,
+            #[cfg(feature = "chrono-tz")]
+            timezone: task.timezone,
+            dst: task.dst,
+            rules: task.rules,
+            #[cfg(feature = "budget")]
+            budget: task.budget,
+            priority: task.priority,
+            hash_key: None,
+            commandless,
+        }
+    }
+
+    pub fn seconds<T: Into<CronValue>>(mut self, value: T) -> Self {
+        self.second = Some(value.into());
+        self
+    }
+
     pub fn minutes<T: Into<CronValue>>(mut self, value: T) -> Self {
         self.minute = value.into();
         self
@@ -201,9 +1494,1592 @@ impl CronTaskBuilder {
         self
     }
 
-    pub fn build(self) -> Result<CronTask> {
+    /// Sets the program to run, to be given arguments with `arg` or `args`.
+    pub fn program(mut self, program: impl Into<PathBuf>) -> Self {
+        self.path = program.into();
+        self
+    }
+
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    /// Sets the program and its arguments from a command line split into
+    /// shell words, such as `rsync -av /src '/my dst'`. Quoting errors are
+    /// reported by `build`.
+    pub fn command(mut self, line: impl Into<String>) -> Self {
+        self.command = Some(line.into());
+        self
+    }
+
+    pub fn year<T: Into<YearValue>>(mut self, value: T) -> Self {
+        self.year = value.into();
+        self
+    }
+
+    /// Removes the seconds field, the task running once a minute.
+    pub fn clear_seconds(mut self) -> Self {
+        self.second = None;
+        self
+    }
+
+    pub fn clear_minutes(mut self) -> Self {
+        self.minute = CronValue::default();
+        self
+    }
+
+    pub fn clear_hour(mut self) -> Self {
+        self.hour = CronValue::default();
+        self
+    }
+
+    pub fn clear_month_day(mut self) -> Self {
+        self.month_day = CronValue::default();
+        self
+    }
+
+    pub fn clear_month(mut self) -> Self {
+        self.month = CronValue::default();
+        self
+    }
+
+    pub fn clear_week_day(mut self) -> Self {
+        self.week_day = CronValue::default();
+        self
+    }
+
+    pub fn clear_year(mut self) -> Self {
+        self.year = YearValue::default();
+        self
+    }
+
+    /// Reads the schedule fields at a fixed offset from UTC rather than in
+    /// the local time zone, no time zone database needed.
+    pub fn offset(mut self, offset: FixedOffset) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Reads the schedule fields in `timezone`, daylight saving time
+    /// included, whatever the zone of the host or of the dates given.
+    #[cfg(feature = "chrono-tz")]
+    pub fn timezone(mut self, timezone: chrono_tz::Tz) -> Self {
+        self.timezone = Some(timezone);
+        self
+    }
+
+    /// Sets how times repeated or skipped by daylight saving transitions
+    /// run, the earliest of repeated times and none of the skipped ones by
+    /// default.
+    pub fn dst_policy(mut self, policy: DstPolicy) -> Self {
+        self.dst = policy;
+        self
+    }
+
+    /// Also runs when `schedule` matches, unless a later rule excludes it.
+    pub fn include(self, schedule: CronTask) -> Self {
+        self.rule(ScheduleRule::include(schedule))
+    }
+
+    /// Does not run when `schedule` matches, unless a later rule includes
+    /// it back.
+    pub fn exclude(self, schedule: CronTask) -> Self {
+        self.rule(ScheduleRule::exclude(schedule))
+    }
+
+    pub fn rule(mut self, rule: ScheduleRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Caps the run time of the task per month, runs being skipped once it
+    /// is used up.
+    #[cfg(feature = "budget")]
+    pub fn budget(mut self, budget: RuntimeBudget) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Sets the variable `key` for the command, replacing any previous
+    /// value.
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn current_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.current_dir = Some(dir.into());
+        self
+    }
+
+    pub fn stdin(mut self, stdin: impl Into<String>) -> Self {
+        self.stdin = Some(stdin.into());
+        self
+    }
+
+    /// Runs the command again when it fails or cannot be started.
+    #[cfg(feature = "retry")]
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    #[cfg(any(feature = "parser", feature = "executor"))]
+    pub fn run_as(mut self, run_as: RunAs) -> Self {
+        self.run_as = Some(run_as);
+        self
+    }
+
+    pub fn kind(mut self, kind: ScheduleKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn day_match(mut self, day_match: DayMatch) -> Self {
+        self.day_match = day_match;
+        self
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    #[cfg(feature = "shard")]
+    pub fn shard(mut self, key: impl Into<String>, total: u32) -> Self {
+        self.shard = Some(Shard::new(key, total));
+        self
+    }
+
+    pub fn group(mut self, name: impl Into<String>) -> Self {
+        self.group = Some(name.into());
+        self
+    }
+
+    pub fn owner(mut self, owner: impl Into<String>) -> Self {
+        self.owner = Some(owner.into());
+        self
+    }
+
+    /// Sets the annotation `key`, replacing any previous value.
+    pub fn annotation(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.annotations.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn check(mut self, command: impl Into<String>) -> Self {
+        self.check = Some(command.into());
+        self
+    }
+
+    #[cfg(feature = "success")]
+    pub fn success_output(mut self, criteria: OutputMatch) -> Self {
+        self.success_output = Some(criteria);
+        self
+    }
+
+    pub fn comment(mut self, line: impl Into<String>) -> Self {
+        self.comments.push(line.into());
+        self
+    }
+
+    /// Key from which `H` values are derived, the path being used when
+    /// unset.
+    pub fn hash_key(mut self, key: impl Into<String>) -> Self {
+        self.hash_key = Some(key.into());
+        self
+    }
+
+    /// Allows building a task with no command, for schedules that are only
+    /// queried and never run.
+    pub fn allow_commandless(mut self) -> Self {
+        self.commandless = true;
+        self
+    }
+
+    /// Fails with [`CronError::MissingCommand`] when no command was given,
+    /// unless `allow_commandless` was called.
+    pub fn build(mut self) -> Result<CronTask> {
+        if let Some(line) = self.command.take() {
+            let mut words = command::split(&line)?.into_iter();
+            self.path = words.next().unwrap_or_default().into();
+            self.args = words.collect();
+        }
+        if !self.commandless && self.path.as_os_str().is_empty() {
+            return Err(CronError::MissingCommand);
+        }
+        self.resolve_hashes()?;
         let task = CronTask::from(self);
 
         task.verify().map(|_| task)
     }
+
+    /// Replaces `H` values, each field getting its own hash so they do not
+    /// all land on the same offset.
+    fn resolve_hashes(&mut self) -> Result<()> {
+        let key = match &self.hash_key {
+            Some(key) => key.clone(),
+            None => command_line(&self.path, &self.args),
+        };
+        let hash = |field: u8| fnv1a(&[key.as_bytes(), &[field]].concat());
+
+        self.second = self
+            .second
+            .take()
+            .map(|second| second.resolve_hash(hash(0), 0, 59))
+            .transpose()?;
+        self.minute = mem::take(&mut self.minute).resolve_hash(hash(1), 0, 59)?;
+        self.hour = mem::take(&mut self.hour).resolve_hash(hash(2), 0, 23)?;
+        // Days past the 28th would skip short months.
+        self.month_day = mem::take(&mut self.month_day).resolve_hash(hash(3), 1, 28)?;
+        self.month = mem::take(&mut self.month).resolve_hash(hash(4), 1, 12)?;
+        self.week_day = mem::take(&mut self.week_day).resolve_hash(hash(5), 0, 6)?;
+
+        Ok(())
+    }
+}
+
+fn command_line(path: &Path, args: &[String]) -> String {
+    let path = path.to_string_lossy();
+
+    if args.is_empty() {
+        path.into_owned()
+    } else {
+        std::iter::once(command::quote(&path))
+            .chain(args.iter().map(|arg| command::quote(arg)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::Utc;
+    use chrono_tz::Europe::Paris;
+
+    use crate::{
+        dst::{FoldPolicy, GapPolicy},
+        schedule::PlainDateTime,
+        value::{all, every, last_day, nearest_weekday, nth, on, range},
+    };
+
+    fn make_datetime(
+        year: i32,
+        month: u32,
+        day: u32,
+        hour: u32,
+        min: u32,
+        sec: u32,
+    ) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(year, month, day, hour, min, sec)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_truncate_to_slot() {
+        let date = make_datetime(2024, 6, 15, 14, 30, 45) + TimeDelta::microseconds(123_456);
+
+        assert_eq!(
+            truncate_to_slot(date),
+            make_datetime(2024, 6, 15, 14, 30, 0)
+        );
+    }
+
+    #[test]
+    fn test_truncate_to_slot_aligned() {
+        let date = make_datetime(2024, 6, 15, 14, 30, 0);
+
+        assert_eq!(truncate_to_slot(date), date);
+    }
+
+    #[test]
+    fn test_matches_ignores_seconds() {
+        let task = CronTask::builder()
+            .minutes(on(30))
+            .hour(on(14))
+            .week_day(all())
+            .allow_commandless()
+            .build()
+            .unwrap();
+
+        assert!(task.matches(make_datetime(2024, 6, 15, 14, 30, 59)));
+        assert!(!task.matches(make_datetime(2024, 6, 15, 14, 31, 0)));
+    }
+
+    #[test]
+    fn test_presets() {
+        let date = |d, h, m| make_datetime(2024, 6, d, h, m, 0);
+
+        assert!(CronTask::hourly("/bin/true").matches(date(15, 14, 0)));
+        assert!(!CronTask::hourly("/bin/true").matches(date(15, 14, 1)));
+        assert!(CronTask::daily("/bin/true").matches(date(15, 0, 0)));
+        assert!(!CronTask::daily("/bin/true").matches(date(15, 1, 0)));
+        // 2024-06-16 is a Sunday.
+        assert!(CronTask::weekly("/bin/true").matches(date(16, 0, 0)));
+        assert!(!CronTask::weekly("/bin/true").matches(date(15, 0, 0)));
+        assert!(CronTask::monthly("/bin/true").matches(date(1, 0, 0)));
+        assert!(!CronTask::monthly("/bin/true").matches(date(2, 0, 0)));
+        assert!(CronTask::yearly("/bin/true").matches(make_datetime(2025, 1, 1, 0, 0, 0)));
+        assert!(!CronTask::yearly("/bin/true").matches(date(1, 0, 0)));
+    }
+
+    #[test]
+    fn test_reboot_task() {
+        let task = CronTask::reboot("/usr/bin/warmup");
+        let date = make_datetime(2024, 6, 15, 14, 30, 0);
+
+        assert!(task.is_reboot());
+        assert!(!task.matches(date));
+        assert_eq!(task.try_next_occurrence(date), None);
+        assert!(!task.has_occurrence_between(date, date + TimeDelta::days(365)));
+        assert_eq!(task.to_string(), "@reboot \"/usr/bin/warmup\"");
+    }
+
+    #[test]
+    fn test_builder_reboot_kind() {
+        let task = CronTask::builder()
+            .kind(ScheduleKind::Reboot)
+            .allow_commandless()
+            .build()
+            .unwrap();
+
+        assert_eq!(task.kind(), ScheduleKind::Reboot);
+        assert_eq!(
+            CronTaskBuilder::from_task(&task).build().unwrap().kind(),
+            ScheduleKind::Reboot
+        );
+    }
+
+    #[cfg(feature = "success")]
+    #[test]
+    fn test_is_success() {
+        let plain = CronTask::builder().allow_commandless().build().unwrap();
+        let task = CronTask::builder()
+            .success_output(OutputMatch::contains("OK"))
+            .allow_commandless()
+            .build()
+            .unwrap();
+
+        assert!(plain.is_success(true, ""));
+        assert!(!plain.is_success(false, "OK"));
+        assert!(task.is_success(true, "status: OK"));
+        assert!(!task.is_success(true, "status: degraded"));
+        assert!(!task.is_success(false, "status: OK"));
+    }
+
+    #[test]
+    fn test_accessors() {
+        let task = CronTask::builder()
+            .minutes(on(5))
+            .hour(on(4))
+            .path("/usr/bin/backup".to_string())
+            .build()
+            .unwrap();
+
+        assert_eq!(task.minute().to_string(), "5");
+        assert_eq!(task.hour().to_string(), "4");
+        assert_eq!(task.month_day().to_string(), "*");
+        assert_eq!(task.month().to_string(), "*");
+        assert_eq!(task.week_day().to_string(), "*");
+        assert_eq!(task.path(), Path::new("/usr/bin/backup"));
+    }
+
+    #[test]
+    fn test_builder_from_task() {
+        let backup = CronTask::builder()
+            .minutes(on(30))
+            .hour(on(2))
+            .path("/usr/bin/backup".to_string())
+            .group("maintenance")
+            .comment("# nightly backup")
+            .build()
+            .unwrap();
+
+        let task = CronTaskBuilder::from_task(&backup)
+            .minutes(on(0))
+            .hour(on(4))
+            .build()
+            .unwrap();
+
+        assert_eq!(task.to_string(), "0 4 * * * \"/usr/bin/backup\"");
+        assert_eq!(task.group(), Some("maintenance"));
+        assert_eq!(task.comments(), ["# nightly backup"]);
+        assert_eq!(backup.to_string(), "30 2 * * * \"/usr/bin/backup\"");
+    }
+
+    #[test]
+    fn test_builder_requires_command() {
+        assert!(matches!(
+            CronTask::builder().minutes(on(0)).build(),
+            Err(CronError::MissingCommand)
+        ));
+        assert!(matches!(
+            CronTask::builder().command("  ").build(),
+            Err(CronError::MissingCommand)
+        ));
+
+        let schedule = CronTask::builder()
+            .minutes(on(0))
+            .allow_commandless()
+            .build()
+            .unwrap();
+        assert!(
+            CronTaskBuilder::from_task(&schedule)
+                .hour(on(3))
+                .build()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_builder_clear_fields() {
+        let task = CronTask::builder()
+            .seconds(on(15))
+            .minutes(on(30))
+            .hour(on(2))
+            .month_day(on(1))
+            .month(on(6))
+            .week_day(on(0))
+            .year(2030)
+            .path("/usr/bin/backup".to_string())
+            .clear_seconds()
+            .clear_minutes()
+            .clear_hour()
+            .clear_month_day()
+            .clear_month()
+            .clear_week_day()
+            .clear_year()
+            .build()
+            .unwrap();
+
+        assert_eq!(task.to_string(), "* * * * * \"/usr/bin/backup\"");
+    }
+
+    #[test]
+    fn test_comments_are_kept_in_order() {
+        let task = CronTask::builder()
+            .comment("# nightly backup")
+            .comment("")
+            .comment("# owner: ops")
+            .allow_commandless()
+            .build()
+            .unwrap();
+
+        assert_eq!(task.comments(), ["# nightly backup", "", "# owner: ops"]);
+    }
+
+    #[test]
+    fn test_command_arguments() {
+        let task = CronTask::builder()
+            .command("rsync -av /src '/my dst'")
+            .build()
+            .unwrap();
+
+        assert_eq!(task.path(), Path::new("rsync"));
+        assert_eq!(task.args(), ["-av", "/src", "/my dst"]);
+        assert_eq!(task.command_line(), "rsync -av /src '/my dst'");
+        assert_eq!(task.to_string(), "* * * * * \"rsync -av /src '/my dst'\"");
+
+        let task = CronTask::builder()
+            .program("/usr/bin/backup")
+            .arg("--to")
+            .args(["s3://bucket", "it's"])
+            .build()
+            .unwrap();
+        assert_eq!(
+            task.command_line(),
+            r"/usr/bin/backup --to s3://bucket 'it'\''s'"
+        );
+
+        assert!(CronTask::builder().command("echo 'open").build().is_err());
+        assert!(matches!(
+            CronTask::builder().program("echo").arg("a\nb").build(),
+            Err(CronError::MultilineCommand(_))
+        ));
+    }
+
+    #[test]
+    fn test_annotations() {
+        let task = CronTask::builder()
+            .annotation("deploy.id", "42")
+            .annotation("ui.color", "red")
+            .annotation("deploy.id", "43")
+            .allow_commandless()
+            .build()
+            .unwrap();
+
+        assert_eq!(task.annotation("deploy.id"), Some("43"));
+        assert_eq!(task.annotation("missing"), None);
+        assert_eq!(
+            task.annotations().keys().collect::<Vec<_>>(),
+            ["deploy.id", "ui.color"]
+        );
+    }
+
+    #[test]
+    fn test_next_occurrence_within_horizon() {
+        let task = CronTask::builder()
+            .minutes(on(0))
+            .hour(on(3))
+            .allow_commandless()
+            .build()
+            .unwrap();
+        let from = make_datetime(2024, 6, 15, 14, 30, 0);
+
+        assert_eq!(
+            task.next_occurrence_within(from, TimeDelta::days(1)),
+            Some(make_datetime(2024, 6, 16, 3, 0, 0))
+        );
+        assert_eq!(
+            task.next_occurrence_within(from, TimeDelta::hours(12)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_next_occurrence_within_impossible_schedule() {
+        let task = CronTask::builder()
+            .month_day(on(30))
+            .month(on(2))
+            .allow_commandless()
+            .build()
+            .unwrap();
+        let from = make_datetime(2024, 6, 15, 14, 30, 0);
+
+        assert_eq!(
+            task.next_occurrence_within(from, TimeDelta::days(366 * 4)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_has_occurrence_between() {
+        let task = CronTask::builder()
+            .minutes(on(0))
+            .hour(on(3))
+            .month_day(on(1))
+            .month(on(1))
+            .allow_commandless()
+            .build()
+            .unwrap();
+
+        assert!(task.has_occurrence_between(
+            make_datetime(2024, 6, 15, 0, 0, 0),
+            make_datetime(2026, 1, 1, 0, 0, 0),
+        ));
+        assert!(!task.has_occurrence_between(
+            make_datetime(2024, 6, 15, 0, 0, 0),
+            make_datetime(2025, 1, 1, 3, 0, 0),
+        ));
+    }
+
+    #[test]
+    fn test_has_occurrence_between_bounds() {
+        let task = CronTask::builder()
+            .minutes(on(30))
+            .hour(on(14))
+            .allow_commandless()
+            .build()
+            .unwrap();
+        let occurrence = make_datetime(2024, 6, 15, 14, 30, 0);
+
+        assert!(task.has_occurrence_between(occurrence, occurrence + TimeDelta::minutes(1)));
+        assert!(!task.has_occurrence_between(occurrence - TimeDelta::hours(1), occurrence));
+        assert!(!task.has_occurrence_between(
+            occurrence + TimeDelta::seconds(1),
+            occurrence + TimeDelta::minutes(1)
+        ));
+        assert!(!task.has_occurrence_between(occurrence, occurrence));
+    }
+
+    #[test]
+    fn test_next_occurrence_resets_lower_fields() {
+        let task = CronTask::builder()
+            .month(on(9))
+            .allow_commandless()
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            task.try_next_occurrence(make_datetime(2024, 6, 15, 14, 30, 0)),
+            Some(make_datetime(2024, 9, 1, 0, 0, 0))
+        );
+    }
+
+    #[test]
+    fn test_month_name_matches_its_month() {
+        let task = CronTask::builder()
+            .minutes(on(0))
+            .hour(on(0))
+            .month_day(on(1))
+            .month(chrono::Month::June)
+            .allow_commandless()
+            .build()
+            .unwrap();
+
+        assert!(task.matches(make_datetime(2024, 6, 1, 0, 0, 0)));
+        assert!(!task.matches(make_datetime(2024, 5, 1, 0, 0, 0)));
+        assert_eq!(
+            task.try_next_occurrence(make_datetime(2024, 1, 1, 0, 0, 0)),
+            Some(make_datetime(2024, 6, 1, 0, 0, 0))
+        );
+    }
+
+    #[test]
+    fn test_month_day_and_month_steps_start_at_one() {
+        let task = CronTask::builder()
+            .minutes(on(0))
+            .hour(on(0))
+            .month_day(every(10))
+            .month(every(3))
+            .allow_commandless()
+            .build()
+            .unwrap();
+        let days: Vec<(u32, u32)> = task
+            .occurrences_between(
+                make_datetime(2024, 1, 1, 0, 0, 0)..make_datetime(2024, 5, 1, 0, 0, 0),
+            )
+            .map(|date| (date.month(), date.day()))
+            .collect();
+
+        assert_eq!(
+            days,
+            [(1, 1), (1, 11), (1, 21), (1, 31), (4, 1), (4, 11), (4, 21)]
+        );
+        assert_eq!(
+            task.previous_occurrence(make_datetime(2024, 6, 15, 0, 0, 0)),
+            Some(make_datetime(2024, 4, 21, 0, 0, 0))
+        );
+        assert!(task.matches_parts(0, 0, 31, 1, 2));
+    }
+
+    #[test]
+    fn test_next_occurrence_across_year_end() {
+        let task = CronTask::builder().allow_commandless().build().unwrap();
+
+        assert_eq!(
+            task.try_next_occurrence(make_datetime(2023, 12, 31, 23, 59, 0)),
+            Some(make_datetime(2024, 1, 1, 0, 0, 0))
+        );
+    }
+
+    #[test]
+    fn test_next_occurrence_on_day_31() {
+        let task = CronTask::builder()
+            .minutes(on(0))
+            .hour(on(0))
+            .month_day(on(31))
+            .allow_commandless()
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            task.try_next_occurrence(make_datetime(2024, 6, 15, 0, 0, 0)),
+            Some(make_datetime(2024, 7, 31, 0, 0, 0))
+        );
+    }
+
+    #[test]
+    fn test_next_occurrence_impossible_schedule() {
+        let task = CronTask::builder()
+            .month_day(on(30))
+            .month(on(2))
+            .allow_commandless()
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            task.try_next_occurrence(make_datetime(2024, 6, 15, 0, 0, 0)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_schedule_agrees_with_next_occurrence() {
+        let tasks = [
+            CronTask::builder().minutes(every(15)).hour(range(9..17)),
+            CronTask::builder()
+                .minutes(on(0))
+                .hour(on(3))
+                .month_day(last_day()),
+            CronTask::builder()
+                .minutes(on(30))
+                .week_day(nth(Weekday::Fri, 2))
+                .month(on(3).or(9)),
+            CronTask::builder()
+                .minutes(on(0))
+                .month_day(on(1))
+                .week_day(Weekday::Mon)
+                .day_match(DayMatch::Any),
+            CronTask::builder()
+                .seconds(every(20))
+                .month_day(nearest_weekday(15))
+                .year(2026),
+        ];
+
+        for task in tasks {
+            let task = task.allow_commandless().build().unwrap();
+            let schedule = task.schedule();
+            let mut from = Utc.with_ymd_and_hms(2025, 12, 30, 23, 59, 0).unwrap();
+            let mut plain = PlainDateTime::new(2025, 12, 30, 23, 59, 0).unwrap();
+
+            for _ in 0..20 {
+                from = task.try_next_occurrence(from).unwrap();
+                plain = schedule.next_after(&plain).unwrap();
+                assert!(schedule.matches(&plain));
+                assert_eq!(plain.to_string(), from.format("%F %T").to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn test_occurrences_between() {
+        let daily = CronTask::daily("/bin/true");
+        let year = make_datetime(2025, 1, 1, 0, 0, 0)..make_datetime(2026, 1, 1, 0, 0, 0);
+
+        assert_eq!(daily.occurrences_between(year.clone()).count(), 365);
+        assert_eq!(
+            daily.occurrences_between(year.clone()).next(),
+            Some(year.start)
+        );
+
+        let billing = CronTask::builder()
+            .minutes(on(0))
+            .hour(on(0))
+            .month_day(last_day())
+            .allow_commandless()
+            .build()
+            .unwrap();
+        let ends: Vec<_> = billing.occurrences_between(year).take(2).collect();
+        assert_eq!(
+            ends,
+            [
+                make_datetime(2025, 1, 31, 0, 0, 0),
+                make_datetime(2025, 2, 28, 0, 0, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_previous_occurrence() {
+        let task = CronTask::builder()
+            .minutes(on(30))
+            .hour(on(9))
+            .week_day(range(0..4))
+            .allow_commandless()
+            .build()
+            .unwrap();
+
+        // Monday morning: the last run was on Friday.
+        assert_eq!(
+            task.previous_occurrence(make_datetime(2024, 6, 17, 8, 0, 0)),
+            Some(make_datetime(2024, 6, 14, 9, 30, 0))
+        );
+        assert_eq!(
+            task.previous_occurrence(make_datetime(2024, 6, 17, 9, 30, 20)),
+            Some(make_datetime(2024, 6, 17, 9, 30, 0))
+        );
+        assert_eq!(
+            task.previous_occurrence(make_datetime(2024, 6, 17, 9, 30, 0)),
+            Some(make_datetime(2024, 6, 14, 9, 30, 0))
+        );
+    }
+
+    #[test]
+    fn test_previous_occurrence_across_year_start() {
+        let task = CronTask::builder()
+            .minutes(on(0))
+            .hour(on(0))
+            .month_day(last_day())
+            .allow_commandless()
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            task.previous_occurrence(make_datetime(2024, 1, 15, 0, 0, 0)),
+            Some(make_datetime(2023, 12, 31, 0, 0, 0))
+        );
+        assert_eq!(
+            task.previous_occurrence(make_datetime(2024, 3, 15, 0, 0, 0)),
+            Some(make_datetime(2024, 2, 29, 0, 0, 0))
+        );
+    }
+
+    #[test]
+    fn test_previous_occurrence_impossible_schedule() {
+        let task = CronTask::builder()
+            .month_day(on(30))
+            .month(on(2))
+            .allow_commandless()
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            task.previous_occurrence(make_datetime(2024, 6, 15, 0, 0, 0)),
+            None
+        );
+        assert_eq!(
+            CronTask::builder()
+                .year(2030)
+                .allow_commandless()
+                .build()
+                .unwrap()
+                .previous_occurrence(make_datetime(2024, 6, 15, 0, 0, 0)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_occurrences_between_from_both_ends() {
+        let task = CronTask::builder()
+            .minutes(on(0))
+            .hour(every(6))
+            .allow_commandless()
+            .build()
+            .unwrap();
+        let window = make_datetime(2024, 6, 15, 0, 0, 0)..make_datetime(2024, 6, 16, 0, 0, 0);
+
+        let last: Vec<_> = task
+            .occurrences_between(window.clone())
+            .rev()
+            .take(2)
+            .collect();
+        assert_eq!(
+            last,
+            [
+                make_datetime(2024, 6, 15, 18, 0, 0),
+                make_datetime(2024, 6, 15, 12, 0, 0),
+            ]
+        );
+
+        let mut occurrences = task.occurrences_between(window);
+        assert_eq!(
+            occurrences.next(),
+            Some(make_datetime(2024, 6, 15, 0, 0, 0))
+        );
+        assert_eq!(
+            occurrences.next_back(),
+            Some(make_datetime(2024, 6, 15, 18, 0, 0))
+        );
+        assert_eq!(
+            occurrences.next_back(),
+            Some(make_datetime(2024, 6, 15, 12, 0, 0))
+        );
+        assert_eq!(
+            occurrences.next(),
+            Some(make_datetime(2024, 6, 15, 6, 0, 0))
+        );
+        assert_eq!(occurrences.next(), None);
+        assert_eq!(occurrences.next_back(), None);
+    }
+
+    #[test]
+    fn test_occurrences_in_other_time_zones() {
+        let task = CronTask::builder()
+            .minutes(on(0))
+            .hour(on(3))
+            .allow_commandless()
+            .build()
+            .unwrap();
+        let utc = |day, hour| Utc.with_ymd_and_hms(2024, 6, day, hour, 0, 0).unwrap();
+
+        assert!(task.matches(utc(16, 3)));
+        assert_eq!(task.try_next_occurrence(utc(15, 12)), Some(utc(16, 3)));
+        assert_eq!(task.previous_occurrence(utc(15, 12)), Some(utc(15, 3)));
+
+        let paris = Paris.with_ymd_and_hms(2024, 6, 15, 12, 0, 0).unwrap();
+        assert_eq!(
+            task.try_next_occurrence(paris),
+            Some(Paris.with_ymd_and_hms(2024, 6, 16, 3, 0, 0).unwrap())
+        );
+
+        // A fixed offset still wins over the zone of the date.
+        let offset = CronTaskBuilder::from_task(&task)
+            .offset(FixedOffset::east_opt(3600).unwrap())
+            .build()
+            .unwrap();
+        assert_eq!(offset.try_next_occurrence(utc(15, 12)), Some(utc(16, 2)));
+        assert!(offset.matches(utc(16, 2)));
+    }
+
+    #[test]
+    fn test_matches_parts() {
+        let task = CronTask::builder()
+            .minutes(on(30))
+            .hour(on(9))
+            .week_day(range(0..4))
+            .allow_commandless()
+            .build()
+            .unwrap();
+
+        assert!(task.matches_parts(30, 9, 14, 6, 4));
+        assert!(!task.matches_parts(30, 9, 15, 6, 5));
+        assert!(!task.matches_parts(31, 9, 14, 6, 4));
+
+        let second_monday = CronTask::builder()
+            .week_day(nth(Weekday::Mon, 2))
+            .allow_commandless()
+            .build()
+            .unwrap();
+        assert!(second_monday.matches_parts(0, 0, 10, 6, 0));
+        assert!(!second_monday.matches_parts(0, 0, 3, 6, 0));
+        assert!(!second_monday.matches_parts(0, 0, 0, 6, 0));
+
+        let last_day = CronTask::builder()
+            .month_day(last_day())
+            .allow_commandless()
+            .build()
+            .unwrap();
+        assert!(!last_day.matches_parts(0, 0, 30, 6, 6));
+    }
+
+    #[test]
+    fn test_upcoming() {
+        let task = CronTask::builder()
+            .minutes(on(0))
+            .hour(every(12))
+            .allow_commandless()
+            .build()
+            .unwrap();
+        let upcoming: Vec<_> = task
+            .upcoming(make_datetime(2024, 6, 15, 0, 0, 0))
+            .take(3)
+            .collect();
+
+        assert_eq!(
+            upcoming,
+            [
+                make_datetime(2024, 6, 15, 12, 0, 0),
+                make_datetime(2024, 6, 16, 0, 0, 0),
+                make_datetime(2024, 6, 16, 12, 0, 0),
+            ]
+        );
+
+        let impossible = CronTask::builder()
+            .month_day(on(30))
+            .month(on(2))
+            .allow_commandless()
+            .build()
+            .unwrap();
+        assert_eq!(
+            impossible
+                .upcoming(make_datetime(2024, 6, 15, 0, 0, 0))
+                .next(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_next_occurrence_before_epoch() {
+        let task = CronTask::daily("/bin/true");
+
+        assert_eq!(
+            task.try_next_occurrence(make_datetime(1900, 2, 28, 12, 0, 0)),
+            Some(make_datetime(1900, 3, 1, 0, 0, 0))
+        );
+    }
+
+    #[test]
+    fn test_next_occurrence_far_future() {
+        let task = CronTask::yearly("/bin/true");
+
+        assert_eq!(
+            task.try_next_occurrence(make_datetime(2999, 6, 15, 0, 0, 0)),
+            Some(make_datetime(3000, 1, 1, 0, 0, 0))
+        );
+    }
+
+    #[test]
+    fn test_next_occurrence_at_end_of_time() {
+        let task = CronTask::yearly("/bin/true");
+        let last = Local
+            .from_local_datetime(&NaiveDate::MAX.and_hms_opt(0, 0, 0).unwrap())
+            .unwrap();
+
+        assert_eq!(task.try_next_occurrence(last), None);
+        assert_eq!(task.next_occurrence_within(last, TimeDelta::MAX), None);
+        assert!(!task.has_occurrence_between(last, last + TimeDelta::hours(1)));
+    }
+
+    #[test]
+    fn test_seconds_field() {
+        let task = CronTask::builder()
+            .seconds(every(15))
+            .allow_commandless()
+            .build()
+            .unwrap();
+
+        assert!(task.matches(make_datetime(2024, 6, 15, 14, 30, 45)));
+        assert!(!task.matches(make_datetime(2024, 6, 15, 14, 30, 46)));
+        assert_eq!(
+            task.try_next_occurrence(make_datetime(2024, 6, 15, 14, 30, 46)),
+            Some(make_datetime(2024, 6, 15, 14, 31, 0))
+        );
+        assert_eq!(
+            task.try_next_occurrence(make_datetime(2024, 6, 15, 14, 30, 15)),
+            Some(make_datetime(2024, 6, 15, 14, 30, 30))
+        );
+        assert_eq!(task.to_string(), "*/15 * * * * * \"\"");
+    }
+
+    #[test]
+    fn test_seconds_field_resets_with_minute() {
+        let task = CronTask::builder()
+            .seconds(on(10).or(50))
+            .minutes(on(5))
+            .allow_commandless()
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            task.try_next_occurrence(make_datetime(2024, 6, 15, 14, 4, 30)),
+            Some(make_datetime(2024, 6, 15, 14, 5, 10))
+        );
+        assert_eq!(
+            task.try_next_occurrence(make_datetime(2024, 6, 15, 14, 5, 50)),
+            Some(make_datetime(2024, 6, 15, 15, 5, 10))
+        );
+    }
+
+    #[test]
+    fn test_seconds_field_verify() {
+        assert!(
+            CronTask::builder()
+                .seconds(59)
+                .allow_commandless()
+                .build()
+                .is_ok()
+        );
+        assert!(
+            CronTask::builder()
+                .seconds(60)
+                .allow_commandless()
+                .build()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_has_occurrence_between_with_seconds() {
+        let task = CronTask::builder()
+            .seconds(on(30))
+            .allow_commandless()
+            .build()
+            .unwrap();
+        let start = make_datetime(2024, 6, 15, 14, 30, 31);
+
+        assert!(!task.has_occurrence_between(start, start + TimeDelta::seconds(58)));
+        assert!(task.has_occurrence_between(start, start + TimeDelta::seconds(60)));
+    }
+
+    #[test]
+    fn test_last_day_of_month() {
+        let task = CronTask::builder()
+            .minutes(on(0))
+            .hour(on(0))
+            .month_day(last_day())
+            .allow_commandless()
+            .build()
+            .unwrap();
+
+        assert!(task.matches(make_datetime(2025, 2, 28, 0, 0, 0)));
+        assert!(task.matches(make_datetime(2024, 2, 29, 0, 0, 0)));
+        assert!(!task.matches(make_datetime(2024, 2, 28, 0, 0, 0)));
+        assert!(task.matches(make_datetime(2025, 4, 30, 0, 0, 0)));
+        assert!(!task.matches(make_datetime(2025, 5, 30, 0, 0, 0)));
+
+        let mut from = make_datetime(2024, 1, 15, 0, 0, 0);
+        let expected = [(1, 31), (2, 29), (3, 31), (4, 30)];
+        for (month, day) in expected {
+            from = task.try_next_occurrence(from).unwrap();
+            assert_eq!(from, make_datetime(2024, month, day, 0, 0, 0));
+        }
+    }
+
+    #[test]
+    fn test_last_day_outside_month_day() {
+        assert!(
+            CronTask::builder()
+                .hour(last_day())
+                .allow_commandless()
+                .build()
+                .is_err()
+        );
+        assert!(
+            CronTask::builder()
+                .month_day(CronValue::List(vec![1.into(), last_day()]))
+                .allow_commandless()
+                .build()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_always_precedes() {
+        let dump = CronTask::builder()
+            .minutes(on(0))
+            .hour(on(2))
+            .allow_commandless()
+            .build()
+            .unwrap();
+        let upload = CronTask::builder()
+            .minutes(on(30))
+            .hour(on(2))
+            .allow_commandless()
+            .build()
+            .unwrap();
+        let window = make_datetime(2025, 1, 1, 0, 0, 0)..make_datetime(2025, 2, 1, 0, 0, 0);
+
+        assert!(dump.always_precedes(&upload, TimeDelta::hours(1), window.clone()));
+        assert!(!dump.always_precedes(&upload, TimeDelta::minutes(10), window.clone()));
+        assert!(!upload.always_precedes(&dump, TimeDelta::hours(1), window.clone()));
+        assert_eq!(
+            dump.first_unpreceded(&upload, TimeDelta::minutes(10), window.clone()),
+            Some(make_datetime(2025, 1, 1, 2, 30, 0))
+        );
+
+        // Occurring at the same time is not preceding.
+        assert!(!dump.always_precedes(&dump, TimeDelta::hours(1), window));
+    }
+
+    #[test]
+    fn test_always_precedes_partial_coverage() {
+        let weekdays = CronTask::builder()
+            .minutes(on(0))
+            .hour(on(1))
+            .week_day(CronValue::Range(0..4))
+            .allow_commandless()
+            .build()
+            .unwrap();
+        let daily = CronTask::builder()
+            .minutes(on(0))
+            .hour(on(2))
+            .allow_commandless()
+            .build()
+            .unwrap();
+        // 2025-01-06 is a Monday.
+        let window = make_datetime(2025, 1, 6, 0, 0, 0)..make_datetime(2025, 1, 13, 0, 0, 0);
+
+        assert_eq!(
+            weekdays.first_unpreceded(&daily, TimeDelta::hours(2), window),
+            Some(make_datetime(2025, 1, 11, 2, 0, 0))
+        );
+    }
+
+    #[test]
+    fn test_hashed_values() {
+        let build = |key: &str| {
+            CronTask::builder()
+                .minutes(CronValue::Hashed(None, Some(15)))
+                .hour(CronValue::Hashed(Some((1, 5)), None))
+                .hash_key(key)
+                .allow_commandless()
+                .build()
+                .unwrap()
+        };
+
+        let task = build("tenant-1");
+        assert_eq!(task.to_string(), build("tenant-1").to_string());
+
+        let CronValue::Interval(base, _) = task.minute() else {
+            panic!("Expected Interval variant");
+        };
+        assert!(base.min_value().is_some_and(|start| start < 15));
+        assert!((1..=5).any(|hour| task.hour().matches(hour)));
+
+        let spread: std::collections::HashSet<String> = (0..20)
+            .map(|i| build(&format!("tenant-{i}")).minute().to_string())
+            .collect();
+        assert!(spread.len() > 1);
+
+        let build = |value: CronValue| {
+            CronTask::builder()
+                .minutes(value)
+                .allow_commandless()
+                .build()
+        };
+        assert!(build(CronValue::Hashed(None, Some(0))).is_err());
+        assert!(build(CronValue::Hashed(Some((30, 10)), None)).is_err());
+    }
+
+    #[test]
+    fn test_nearest_weekday() {
+        let task = CronTask::builder()
+            .minutes(on(0))
+            .hour(on(9))
+            .month_day(nearest_weekday(15))
+            .allow_commandless()
+            .build()
+            .unwrap();
+
+        let mut from = make_datetime(2025, 2, 20, 0, 0, 0);
+        for (month, day) in [(3, 14), (4, 15), (5, 15), (6, 16)] {
+            from = task.try_next_occurrence(from).unwrap();
+            assert_eq!(from, make_datetime(2025, month, day, 9, 0, 0));
+        }
+
+        assert!(
+            CronTask::builder()
+                .hour(nearest_weekday(3))
+                .allow_commandless()
+                .build()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_nth_week_day() {
+        let task = CronTask::builder()
+            .minutes(on(0))
+            .hour(on(9))
+            .week_day(nth(Weekday::Mon, 2))
+            .allow_commandless()
+            .build()
+            .unwrap();
+
+        assert!(task.matches(make_datetime(2025, 6, 9, 9, 0, 0)));
+        assert!(!task.matches(make_datetime(2025, 6, 2, 9, 0, 0)));
+
+        let mut from = make_datetime(2025, 6, 9, 9, 0, 0);
+        for (month, day) in [(7, 14), (8, 11), (9, 8)] {
+            from = task.try_next_occurrence(from).unwrap();
+            assert_eq!(from, make_datetime(2025, month, day, 9, 0, 0));
+        }
+
+        assert!(
+            CronTask::builder()
+                .hour(nth(Weekday::Mon, 2))
+                .allow_commandless()
+                .build()
+                .is_err()
+        );
+        assert!(
+            CronTask::builder()
+                .week_day(last_day())
+                .allow_commandless()
+                .build()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_month_day_or_week_day() {
+        let task = CronTask::builder()
+            .minutes(on(0))
+            .hour(on(0))
+            .month_day(on(1))
+            .week_day(on(0))
+            .day_match(DayMatch::Any)
+            .allow_commandless()
+            .build()
+            .unwrap();
+
+        // 2024-06-01 is a Saturday, 2024-06-03 a Monday.
+        assert!(task.matches(make_datetime(2024, 6, 1, 0, 0, 0)));
+        assert!(task.matches(make_datetime(2024, 6, 3, 0, 0, 0)));
+        assert!(!task.matches(make_datetime(2024, 6, 4, 0, 0, 0)));
+        assert!(task.matches_parts(0, 0, 1, 6, 5));
+        assert!(!task.matches_parts(0, 0, 4, 6, 1));
+
+        let mut from = make_datetime(2024, 5, 31, 12, 0, 0);
+        for (month, day) in [(6, 1), (6, 3), (6, 10)] {
+            from = task.try_next_occurrence(from).unwrap();
+            assert_eq!(from, make_datetime(2024, month, day, 0, 0, 0));
+        }
+        assert_eq!(
+            task.previous_occurrence(make_datetime(2024, 6, 3, 0, 0, 0)),
+            Some(make_datetime(2024, 6, 1, 0, 0, 0))
+        );
+
+        // A stepped wildcard still counts as unrestricted.
+        let task = CronTask::builder()
+            .month_day(every(2u8))
+            .week_day(on(0))
+            .day_match(DayMatch::Any)
+            .allow_commandless()
+            .build()
+            .unwrap();
+        assert!(!task.matches(make_datetime(2024, 6, 10, 0, 0, 0)));
+        assert!(!task.matches(make_datetime(2024, 6, 5, 0, 0, 0)));
+        assert!(task.matches(make_datetime(2024, 6, 17, 0, 0, 0)));
+    }
+
+    #[test]
+    fn test_month_day_and_week_day_by_default() {
+        let task = CronTask::builder()
+            .minutes(on(0))
+            .hour(on(0))
+            .month_day(on(1))
+            .week_day(on(0))
+            .allow_commandless()
+            .build()
+            .unwrap();
+
+        assert_eq!(task.day_match(), DayMatch::All);
+        assert!(!task.matches(make_datetime(2024, 6, 1, 0, 0, 0)));
+        assert!(!task.matches(make_datetime(2024, 6, 3, 0, 0, 0)));
+        // 2024-07-01 is a Monday.
+        assert_eq!(
+            task.try_next_occurrence(make_datetime(2024, 6, 1, 0, 0, 0)),
+            Some(make_datetime(2024, 7, 1, 0, 0, 0))
+        );
+    }
+
+    #[test]
+    fn test_year_field() {
+        let task = CronTask::builder()
+            .minutes(on(0))
+            .hour(on(0))
+            .month_day(on(1))
+            .month(on(1))
+            .year(2026..=2027)
+            .allow_commandless()
+            .build()
+            .unwrap();
+
+        assert!(task.matches(make_datetime(2026, 1, 1, 0, 0, 0)));
+        assert!(!task.matches(make_datetime(2025, 1, 1, 0, 0, 0)));
+        assert_eq!(task.to_string(), "0 0 1 1 * 2026-2027 \"\"");
+        assert_eq!(
+            task.try_next_occurrence(make_datetime(2024, 6, 15, 0, 0, 0)),
+            Some(make_datetime(2026, 1, 1, 0, 0, 0))
+        );
+        assert_eq!(
+            task.try_next_occurrence(make_datetime(2026, 1, 1, 0, 0, 0)),
+            Some(make_datetime(2027, 1, 1, 0, 0, 0))
+        );
+    }
+
+    #[test]
+    fn test_year_field_exhausted() {
+        let task = CronTask::builder()
+            .year(2025)
+            .allow_commandless()
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            task.try_next_occurrence(make_datetime(2025, 12, 31, 23, 59, 0)),
+            None
+        );
+        assert_eq!(
+            task.try_next_occurrence(make_datetime(2030, 1, 1, 0, 0, 0)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_year_field_interval() {
+        let task = CronTask::builder()
+            .month_day(on(29))
+            .month(on(2))
+            .year(YearValue::All.every(2))
+            .allow_commandless()
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            task.try_next_occurrence(make_datetime(2024, 6, 15, 0, 0, 0)),
+            Some(make_datetime(2028, 2, 29, 0, 0, 0))
+        );
+    }
+
+    #[test]
+    fn test_next_occurrence_skips_current_slot() {
+        let task = CronTask::builder().allow_commandless().build().unwrap();
+        let from = make_datetime(2024, 6, 15, 14, 30, 0) + TimeDelta::microseconds(1);
+
+        assert_eq!(
+            task.try_next_occurrence(from),
+            Some(make_datetime(2024, 6, 15, 14, 31, 0))
+        );
+        assert_eq!(
+            task.try_next_occurrence(make_datetime(2024, 6, 15, 14, 30, 0)),
+            Some(make_datetime(2024, 6, 15, 14, 31, 0))
+        );
+    }
+
+    #[test]
+    fn test_fixed_offset() {
+        let offset = FixedOffset::east_opt(5 * 3600 + 30 * 60).unwrap();
+        let task = CronTask::builder()
+            .minutes(0)
+            .hour(9)
+            .offset(offset)
+            .path("/bin/true".to_string())
+            .build()
+            .unwrap();
+        let nine = offset
+            .with_ymd_and_hms(2025, 6, 1, 9, 0, 0)
+            .unwrap()
+            .with_timezone(&Local);
+
+        assert!(task.matches(nine));
+        assert!(!task.matches(nine + TimeDelta::hours(1)));
+        assert_eq!(
+            task.try_next_occurrence(nine - TimeDelta::hours(12)),
+            Some(nine)
+        );
+        assert_eq!(
+            task.try_next_occurrence(nine),
+            Some(nine + TimeDelta::days(1))
+        );
+        assert_eq!(task.to_string(), "UTC+05:30 0 9 * * * \"/bin/true\"");
+    }
+
+    #[cfg(feature = "chrono-tz")]
+    #[test]
+    fn test_timezone() {
+        let task = CronTask::builder()
+            .minutes(0)
+            .hour(3)
+            .timezone(Paris)
+            .path("/bin/true".to_string())
+            .build()
+            .unwrap();
+        let utc = |month, day, hour| Utc.with_ymd_and_hms(2025, month, day, hour, 0, 0).unwrap();
+
+        // 03:00 in Paris is 02:00 UTC in winter and 01:00 UTC in summer.
+        assert!(task.matches(utc(1, 15, 2)));
+        assert!(task.matches(utc(7, 15, 1)));
+        assert!(!task.matches(utc(7, 15, 3)));
+        assert_eq!(
+            task.try_next_occurrence(utc(1, 15, 12)),
+            Some(utc(1, 16, 2))
+        );
+        assert_eq!(
+            task.try_next_occurrence(utc(7, 15, 12)),
+            Some(utc(7, 16, 1))
+        );
+        assert_eq!(
+            task.previous_occurrence(utc(7, 15, 12)),
+            Some(utc(7, 15, 1))
+        );
+        assert_eq!(task.to_string(), "Europe/Paris 0 3 * * * \"/bin/true\"");
+    }
+
+    #[test]
+    fn test_dst_policy() {
+        let task = |policy| {
+            CronTask::builder()
+                .minutes(30)
+                .hour(2)
+                .dst_policy(policy)
+                .path("/bin/true".to_string())
+                .build()
+                .unwrap()
+        };
+        let paris = |month, day, hour, minute| {
+            Utc.with_ymd_and_hms(2025, month, day, hour, minute, 0)
+                .unwrap()
+                .with_timezone(&Paris)
+        };
+        let policy = |fold, gap| DstPolicy { fold, gap };
+
+        // 02:30 does not exist in Paris on March 30th.
+        let skip = task(DstPolicy::default());
+        let shift = task(policy(FoldPolicy::Earliest, GapPolicy::ShiftForward));
+        assert_eq!(
+            skip.try_next_occurrence(paris(3, 29, 12, 0)),
+            Some(paris(3, 31, 0, 30))
+        );
+        assert_eq!(
+            shift.try_next_occurrence(paris(3, 29, 12, 0)),
+            Some(paris(3, 30, 1, 0))
+        );
+        assert_eq!(
+            shift.previous_occurrence(paris(3, 30, 12, 0)),
+            Some(paris(3, 30, 1, 0))
+        );
+        assert!(shift.matches(paris(3, 30, 1, 0)));
+        assert!(!skip.matches(paris(3, 30, 1, 0)));
+
+        // 02:30 happens twice on October 26th, at 00:30 and 01:30 UTC.
+        let latest = task(policy(FoldPolicy::Latest, GapPolicy::Skip));
+        let never = task(policy(FoldPolicy::Skip, GapPolicy::Skip));
+        let before = paris(10, 25, 12, 0);
+        assert_eq!(skip.try_next_occurrence(before), Some(paris(10, 26, 0, 30)));
+        assert_eq!(
+            latest.try_next_occurrence(before),
+            Some(paris(10, 26, 1, 30))
+        );
+        assert_eq!(
+            never.try_next_occurrence(before),
+            Some(paris(10, 27, 1, 30))
+        );
+        assert!(skip.matches(paris(10, 26, 0, 30)));
+        assert!(!skip.matches(paris(10, 26, 1, 30)));
+        assert!(latest.matches(paris(10, 26, 1, 30)));
+    }
+
+    #[test]
+    fn test_rules() {
+        let christmas = || CronTask::builder().month_day(25).month(12);
+        let saturday = CronTask::builder()
+            .minutes(0)
+            .hour(10)
+            .week_day(5)
+            .allow_commandless()
+            .build()
+            .unwrap();
+        // Weekdays at 09:00, except on Christmas, and Saturdays at 10:00.
+        let task = CronTask::builder()
+            .minutes(0)
+            .hour(9)
+            .week_day(range(0..4))
+            .exclude(christmas().allow_commandless().build().unwrap())
+            .include(saturday)
+            .path("/usr/bin/report".to_string())
+            .build()
+            .unwrap();
+        let utc = |day, hour| Utc.with_ymd_and_hms(2025, 12, day, hour, 0, 0).unwrap();
+
+        assert!(task.matches(utc(24, 9)));
+        assert!(!task.matches(utc(25, 9)));
+        assert!(task.matches(utc(27, 10)));
+        assert_eq!(task.try_next_occurrence(utc(24, 10)), Some(utc(26, 9)));
+        assert_eq!(task.try_next_occurrence(utc(26, 9)), Some(utc(27, 10)));
+        assert_eq!(task.previous_occurrence(utc(26, 8)), Some(utc(24, 9)));
+
+        // The last matching rule wins.
+        let included_back = CronTaskBuilder::from_task(&task)
+            .include(
+                christmas()
+                    .minutes(0)
+                    .hour(9)
+                    .allow_commandless()
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        assert!(included_back.matches(utc(25, 9)));
+        assert_eq!(
+            included_back.try_next_occurrence(utc(24, 10)),
+            Some(utc(25, 9))
+        );
+    }
 }
@@ -0,0 +1,111 @@
+use std::{collections::BTreeSet, fmt::Write, ops::Range};
+
+use chrono::{DateTime, Local};
+
+use crate::task::CronTask;
+
+/// Occurrences listed per side in a diff before the rest is summarized.
+const DIFF_LIMIT: usize = 10;
+
+/// Asserts that two tasks fire at the same instants within a window,
+/// panicking with the occurrences only one of them has.
+///
+/// ```ignore
+/// assert_same_occurrences!(parsed, built, start..end);
+/// ```
+#[macro_export]
+macro_rules! assert_same_occurrences {
+    ($a:expr, $b:expr, $window:expr $(,)?) => {
+        if let Some(diff) = $crate::test_util::occurrence_diff(&$a, &$b, $window) {
+            panic!(
+                "assertion failed: `{}` and `{}` fire at different times\n{}",
+                stringify!($a),
+                stringify!($b),
+                diff
+            );
+        }
+    };
+}
+
+/// Describes the occurrences within `window` that only one of `a` and `b`
+/// has, `None` when they fire at the same instants.
+pub fn occurrence_diff(
+    a: &CronTask,
+    b: &CronTask,
+    window: Range<DateTime<Local>>,
+) -> Option<String> {
+    let a: BTreeSet<_> = a.occurrences_between(window.clone()).collect();
+    let b: BTreeSet<_> = b.occurrences_between(window).collect();
+
+    if a == b {
+        return None;
+    }
+
+    let mut diff = format!("left: {} occurrences, right: {}\n", a.len(), b.len());
+    for (side, only) in [("left", a.difference(&b)), ("right", b.difference(&a))] {
+        let only: Vec<_> = only.collect();
+        if only.is_empty() {
+            continue;
+        }
+
+        let _ = writeln!(diff, "only in {side}:");
+        for date in only.iter().take(DIFF_LIMIT) {
+            let _ = writeln!(diff, "  {}", date.format("%Y-%m-%d %H:%M:%S %:z"));
+        }
+        if only.len() > DIFF_LIMIT {
+            let _ = writeln!(diff, "  ... and {} more", only.len() - DIFF_LIMIT);
+        }
+    }
+
+    Some(diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeDelta, TimeZone};
+
+    use super::*;
+    use crate::value::{CronValue, all, every, on};
+
+    fn task(minutes: impl Into<CronValue>) -> CronTask {
+        CronTask::builder()
+            .minutes(minutes)
+            .path("/bin/true".to_string())
+            .build()
+            .unwrap()
+    }
+
+    fn window() -> Range<DateTime<Local>> {
+        let start = Local.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap();
+        start..start + TimeDelta::hours(1)
+    }
+
+    #[test]
+    fn test_same_occurrences() {
+        assert_same_occurrences!(task(every(30)), task(on(0).or(30)), window());
+    }
+
+    #[test]
+    fn test_occurrence_diff() {
+        let diff = occurrence_diff(&task(every(15)), &task(every(20)), window()).unwrap();
+        let lines: Vec<_> = diff.lines().collect();
+
+        assert_eq!(lines[0], "left: 4 occurrences, right: 3");
+        assert_eq!(lines[1], "only in left:");
+        assert!(lines[2].starts_with("  2025-06-01 00:15:00"));
+        assert!(diff.contains("only in right:\n  2025-06-01 00:20:00"));
+    }
+
+    #[test]
+    fn test_occurrence_diff_truncated() {
+        let diff = occurrence_diff(&task(all()), &task(0), window()).unwrap();
+
+        assert!(diff.ends_with("  ... and 49 more\n"));
+    }
+
+    #[test]
+    #[should_panic(expected = "fire at different times")]
+    fn test_assert_same_occurrences_panics() {
+        assert_same_occurrences!(task(every(15)), task(every(20)), window());
+    }
+}
@@ -0,0 +1,103 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use chrono::{DateTime, Local};
+use futures_core::Stream;
+use tokio::time::{Instant, Sleep, sleep_until};
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use crate::realtime::SystemClock;
+use crate::{realtime::Clock, task::CronTask};
+
+/// Stream yielding each occurrence of a task once it is reached, ending
+/// when the task has no next occurrence. Must be polled on a Tokio
+/// runtime.
+pub struct TickStream<C: Clock<Tz = Local>> {
+    task: CronTask,
+    clock: C,
+    after: DateTime<Local>,
+    sleep: Option<(DateTime<Local>, Pin<Box<Sleep>>)>,
+}
+
+impl CronTask {
+    /// Occurrences of the task from now on, as they are reached.
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    pub fn tick_stream(&self) -> TickStream<SystemClock> {
+        self.tick_stream_with_clock(SystemClock)
+    }
+
+    /// Same as `tick_stream`, reading the current date from `clock`.
+    pub fn tick_stream_with_clock<C: Clock<Tz = Local>>(&self, clock: C) -> TickStream<C> {
+        TickStream {
+            task: self.clone(),
+            after: clock.now(),
+            clock,
+            sleep: None,
+        }
+    }
+}
+
+impl<C: Clock<Tz = Local> + Unpin> Stream for TickStream<C> {
+    type Item = DateTime<Local>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<DateTime<Local>>> {
+        let this = self.get_mut();
+
+        if this.sleep.is_none() {
+            let Some(next) = this.task.try_next_occurrence(this.after) else {
+                return Poll::Ready(None);
+            };
+            let wait = (next - this.clock.now()).to_std().unwrap_or_default();
+            this.sleep = Some((next, Box::pin(sleep_until(Instant::now() + wait))));
+        }
+
+        let Some((next, sleep)) = &mut this.sleep else {
+            return Poll::Pending;
+        };
+        match sleep.as_mut().poll(cx) {
+            Poll::Ready(()) => {
+                let next = *next;
+                this.after = next;
+                this.sleep = None;
+                Poll::Ready(Some(next))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Datelike, TimeZone, Timelike};
+
+    use super::*;
+    use crate::{
+        realtime::FixedClock,
+        value::{every, on},
+    };
+
+    #[tokio::test(start_paused = true)]
+    async fn test_tick_stream() {
+        let start = Local.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        let task = CronTask::builder()
+            .minutes(every(20))
+            .hour(on(12))
+            .path("/usr/bin/poll".to_string())
+            .build()
+            .unwrap();
+        let mut ticks = task.tick_stream_with_clock(FixedClock(start));
+
+        let mut dates = Vec::new();
+        for _ in 0..3 {
+            let tick = std::future::poll_fn(|cx| Pin::new(&mut ticks).poll_next(cx)).await;
+            dates.push(tick.map(|date| (date.day(), date.hour(), date.minute())));
+        }
+
+        assert_eq!(
+            dates,
+            [Some((1, 12, 20)), Some((1, 12, 40)), Some((2, 12, 0))]
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use chrono::{DateTime, FixedOffset};
+use time::{OffsetDateTime, UtcOffset};
+
+use crate::{cron::Cron, task::CronTask};
+
+impl CronTask {
+    /// Same as `matches`, for a date of the `time` crate.
+    pub fn matches_time(&self, date: OffsetDateTime) -> bool {
+        to_chrono(date).is_some_and(|date| self.matches(date))
+    }
+
+    /// Same as `try_next_occurrence`, for a date of the `time` crate.
+    pub fn next_occurrence_time(&self, from: OffsetDateTime) -> Option<OffsetDateTime> {
+        self.try_next_occurrence(to_chrono(from)?)
+            .and_then(from_chrono)
+    }
+
+    /// Same as `previous_occurrence`, for a date of the `time` crate.
+    pub fn previous_occurrence_time(&self, from: OffsetDateTime) -> Option<OffsetDateTime> {
+        self.previous_occurrence(to_chrono(from)?)
+            .and_then(from_chrono)
+    }
+}
+
+impl Cron {
+    /// Same as `get_all_planified_at`, for a date of the `time` crate.
+    pub fn get_all_planified_at_time(&self, date: OffsetDateTime) -> Vec<&CronTask> {
+        self.into_iter()
+            .filter(|task| {
+                task.matches_time(date)
+                    && to_chrono(date).is_some_and(|date| self.skip_reason_at(task, date).is_none())
+            })
+            .collect()
+    }
+}
+
+fn to_chrono(date: OffsetDateTime) -> Option<DateTime<FixedOffset>> {
+    let offset = FixedOffset::east_opt(date.offset().whole_seconds())?;
+
+    DateTime::from_timestamp(date.unix_timestamp(), date.nanosecond())
+        .map(|date| date.with_timezone(&offset))
+}
+
+fn from_chrono(date: DateTime<FixedOffset>) -> Option<OffsetDateTime> {
+    let offset = UtcOffset::from_whole_seconds(date.offset().local_minus_utc()).ok()?;
+
+    OffsetDateTime::from_unix_timestamp(date.timestamp())
+        .and_then(|utc| utc.replace_nanosecond(date.timestamp_subsec_nanos()))
+        .ok()
+        .map(|utc| utc.to_offset(offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{Date, Month, PrimitiveDateTime, Time};
+
+    use super::*;
+
+    fn at(day: u8, hour: u8, minute: u8) -> OffsetDateTime {
+        let date = Date::from_calendar_date(2025, Month::June, day).unwrap();
+        PrimitiveDateTime::new(date, Time::from_hms(hour, minute, 0).unwrap())
+            .assume_offset(UtcOffset::from_hms(2, 0, 0).unwrap())
+    }
+
+    #[test]
+    fn test_time_dates() {
+        let task = CronTask::builder()
+            .minutes(30)
+            .hour(9)
+            .path("/usr/bin/report".to_string())
+            .build()
+            .unwrap();
+
+        assert!(task.matches_time(at(1, 9, 30)));
+        assert!(!task.matches_time(at(1, 9, 31)));
+
+        let next = task.next_occurrence_time(at(1, 10, 0)).unwrap();
+        assert_eq!(next, at(2, 9, 30));
+        assert_eq!(next.offset(), UtcOffset::from_hms(2, 0, 0).unwrap());
+        assert_eq!(
+            task.previous_occurrence_time(at(1, 10, 0)),
+            Some(at(1, 9, 30))
+        );
+    }
+}
@@ -0,0 +1,326 @@
+use std::{io, time::Duration};
+
+use chrono::{DateTime, Local, TimeDelta};
+use ratatui::{
+    DefaultTerminal, Frame,
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Modifier, Style},
+    widgets::{Block, List, ListItem, ListState, Paragraph},
+};
+
+use crate::{
+    cron::{Cron, SharedCron},
+    realtime::Clock,
+    simulator::Simulator,
+    task::CronTask,
+};
+
+/// Runs listed for the selected task.
+const NEXT_RUNS: usize = 5;
+
+/// What a key press in the [`Browser`] asks of the host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Run the task at this index of the cron now, off schedule.
+    Trigger(usize),
+    Quit,
+}
+
+/// Console over a [`Cron`]: lists its tasks with the next runs of the
+/// selected one and what fires over a window, and triggers or pauses the
+/// selected task.
+///
+/// Up and down select a task, `t` or enter triggers it, `p` pauses or
+/// resumes it, `+` and `-` widen or narrow the window, `q` or escape quits.
+#[derive(Debug, Clone)]
+pub struct Browser {
+    selected: usize,
+    window: TimeDelta,
+}
+
+impl Default for Browser {
+    fn default() -> Self {
+        Self {
+            selected: 0,
+            window: TimeDelta::days(1),
+        }
+    }
+}
+
+impl Browser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index of the selected task in the cron.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// How far ahead the simulation looks.
+    pub fn window(&self) -> TimeDelta {
+        self.window
+    }
+
+    /// Applies a key press. Pausing only applies to named tasks, as
+    /// [`Cron::set_task_paused`] does.
+    pub fn handle_key(&mut self, cron: &mut Cron, key: KeyCode) -> Option<Action> {
+        let tasks = cron.into_iter().count();
+        match key {
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => self.selected = (self.selected + 1).min(tasks.saturating_sub(1)),
+            KeyCode::Char('t') | KeyCode::Enter if self.selected < tasks => {
+                return Some(Action::Trigger(self.selected));
+            }
+            KeyCode::Char('p') => {
+                let name = cron
+                    .into_iter()
+                    .nth(self.selected)
+                    .and_then(CronTask::name)
+                    .map(str::to_string);
+                if let Some(name) = name {
+                    let paused = cron.is_task_paused(&name);
+                    cron.set_task_paused(&name, !paused);
+                }
+            }
+            KeyCode::Char('+') => self.window = self.window.checked_mul(2).unwrap_or(self.window),
+            KeyCode::Char('-') => self.window = (self.window / 2).max(TimeDelta::hours(1)),
+            KeyCode::Char('q') | KeyCode::Esc => return Some(Action::Quit),
+            _ => {}
+        }
+        None
+    }
+
+    pub fn render(&self, frame: &mut Frame, cron: &Cron, now: DateTime<Local>) {
+        let [tasks_area, side] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .areas(frame.area());
+        let [next_area, simulation_area] =
+            Layout::vertical([Constraint::Length(NEXT_RUNS as u16 + 2), Constraint::Min(0)])
+                .areas(side);
+
+        let items: Vec<ListItem> = cron
+            .into_iter()
+            .map(|task| match cron.skip_reason(task) {
+                Some(reason) => ListItem::new(format!("{} ({reason})", label(task))),
+                None => ListItem::new(label(task)),
+            })
+            .collect();
+        let mut state = ListState::default().with_selected(Some(self.selected));
+        frame.render_stateful_widget(
+            List::new(items)
+                .block(Block::bordered().title("Tasks"))
+                .highlight_style(Style::new().add_modifier(Modifier::REVERSED)),
+            tasks_area,
+            &mut state,
+        );
+
+        let next: Vec<String> = cron
+            .into_iter()
+            .nth(self.selected)
+            .map(|task| {
+                task.upcoming(now)
+                    .take(NEXT_RUNS)
+                    .map(|date| date.format("%Y-%m-%d %H:%M:%S").to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        frame.render_widget(
+            Paragraph::new(next.join("\n")).block(Block::bordered().title("Next runs")),
+            next_area,
+        );
+
+        let end = now.checked_add_signed(self.window).unwrap_or(now);
+        let firings: Vec<String> = Simulator::new(cron, now..end)
+            .take(usize::from(simulation_area.height))
+            .map(|firing| {
+                let tasks: Vec<String> = firing.tasks.iter().map(|task| label(task)).collect();
+                format!(
+                    "{}  {}",
+                    firing.date.format("%m-%d %H:%M"),
+                    tasks.join(", ")
+                )
+            })
+            .collect();
+        let title = format!("Next {}h", self.window.num_hours());
+        frame.render_widget(
+            Paragraph::new(firings.join("\n")).block(Block::bordered().title(title)),
+            simulation_area,
+        );
+    }
+}
+
+/// Name of the task, or its command when unnamed.
+fn label(task: &CronTask) -> String {
+    match task.name() {
+        Some(name) => name.to_string(),
+        None => task.path().display().to_string(),
+    }
+}
+
+/// Runs a [`Browser`] on the terminal until it quits, reading dates from
+/// `clock` and handing triggered tasks to `trigger`. Tasks paused from the
+/// console are paused for the schedulers sharing `cron` too.
+pub fn run<C, F>(cron: &SharedCron, clock: &C, mut trigger: F) -> io::Result<()>
+where
+    C: Clock<Tz = Local>,
+    F: FnMut(&CronTask),
+{
+    let mut terminal = ratatui::init();
+    let result = browse(&mut terminal, cron, clock, &mut trigger);
+    ratatui::restore();
+    result
+}
+
+fn browse<C, F>(
+    terminal: &mut DefaultTerminal,
+    cron: &SharedCron,
+    clock: &C,
+    trigger: &mut F,
+) -> io::Result<()>
+where
+    C: Clock<Tz = Local>,
+    F: FnMut(&CronTask),
+{
+    let mut browser = Browser::new();
+
+    loop {
+        terminal.draw(|frame| browser.render(frame, &cron.read(), clock.now()))?;
+
+        // Redraws every second for the runs to move along.
+        if !event::poll(Duration::from_secs(1))? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let action = browser.handle_key(&mut cron.write(), key.code);
+        match action {
+            Some(Action::Trigger(index)) => {
+                // Unlocked while `trigger` runs, which may run the task.
+                let task = cron.read().into_iter().nth(index).cloned();
+                if let Some(task) = task {
+                    trigger(&task);
+                }
+            }
+            Some(Action::Quit) => return Ok(()),
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    use super::*;
+    use crate::value::{every, on};
+
+    fn cron() -> Cron {
+        let mut cron = Cron::new();
+        cron.add_task(
+            CronTask::builder()
+                .minutes(every(30))
+                .name("poll")
+                .path("/usr/bin/poll".to_string())
+                .build()
+                .unwrap(),
+        );
+        cron.add_task(
+            CronTask::builder()
+                .minutes(on(0))
+                .hour(on(3))
+                .path("/usr/bin/backup".to_string())
+                .build()
+                .unwrap(),
+        );
+        cron
+    }
+
+    #[test]
+    fn test_browser_keys() {
+        let mut cron = cron();
+        let mut browser = Browser::new();
+
+        assert_eq!(browser.handle_key(&mut cron, KeyCode::Up), None);
+        assert_eq!(
+            browser.handle_key(&mut cron, KeyCode::Enter),
+            Some(Action::Trigger(0))
+        );
+
+        browser.handle_key(&mut cron, KeyCode::Char('p'));
+        assert!(cron.is_task_paused("poll"));
+        browser.handle_key(&mut cron, KeyCode::Char('p'));
+        assert!(!cron.is_task_paused("poll"));
+
+        browser.handle_key(&mut cron, KeyCode::Down);
+        browser.handle_key(&mut cron, KeyCode::Down);
+        assert_eq!(browser.selected(), 1);
+        assert_eq!(
+            browser.handle_key(&mut cron, KeyCode::Char('t')),
+            Some(Action::Trigger(1))
+        );
+
+        browser.handle_key(&mut cron, KeyCode::Char('+'));
+        assert_eq!(browser.window(), TimeDelta::days(2));
+        for _ in 0..10 {
+            browser.handle_key(&mut cron, KeyCode::Char('-'));
+        }
+        assert_eq!(browser.window(), TimeDelta::hours(1));
+        assert_eq!(
+            browser.handle_key(&mut cron, KeyCode::Char('q')),
+            Some(Action::Quit)
+        );
+    }
+
+    #[cfg(feature = "scheduler")]
+    #[test]
+    fn test_browser_pauses_scheduler() {
+        use crate::{realtime::ManualClock, scheduler::PollingScheduler};
+
+        let start = Local.with_ymd_and_hms(2025, 6, 1, 2, 0, 0).unwrap();
+        let shared = SharedCron::new(cron());
+        let mut scheduler =
+            PollingScheduler::new(shared.clone(), ManualClock::new(start), TimeDelta::hours(1));
+
+        Browser::new().handle_key(&mut shared.write(), KeyCode::Char('p'));
+        scheduler.clock().advance(TimeDelta::hours(2));
+        let runs: Vec<_> = scheduler
+            .poll()
+            .into_iter()
+            .map(|(_, task, _)| label(&task))
+            .collect();
+        assert_eq!(runs, ["/usr/bin/backup"]);
+    }
+
+    #[test]
+    fn test_browser_render() {
+        let mut cron = cron();
+        cron.set_task_paused("poll", true);
+        let now = Local.with_ymd_and_hms(2025, 6, 1, 2, 45, 0).unwrap();
+        let mut terminal = Terminal::new(TestBackend::new(80, 16)).unwrap();
+
+        terminal
+            .draw(|frame| Browser::new().render(frame, &cron, now))
+            .unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+
+        assert!(screen.contains("poll (task is paused)"));
+        assert!(screen.contains("/usr/bin/backup"));
+        assert!(screen.contains("2025-06-01 03:00:00"));
+        // The paused task is left out of the simulation.
+        assert!(screen.contains("06-01 03:00  /usr/bin/backup"));
+        assert!(!screen.contains("03:30  poll"));
+    }
+}
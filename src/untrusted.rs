@@ -0,0 +1,262 @@
+use chrono::{DateTime, Local, TimeDelta};
+use thiserror::Error;
+
+use crate::{error::CronError, parser::Dialect, task::CronTask, value::CronValue};
+
+/// Number of upcoming runs looked at when checking the frequency cap.
+const FREQUENCY_SAMPLES: usize = 64;
+
+/// Why an untrusted schedule was refused.
+#[derive(Debug, Error)]
+pub enum Rejection {
+    #[error("Dialect {0:?} is not allowed.")]
+    Dialect(Dialect),
+    #[error("Expression is {length} bytes long, at most {max} allowed.")]
+    TooLong { length: usize, max: usize },
+    #[error("Expression has {items} items, at most {max} allowed.")]
+    TooComplex { items: usize, max: usize },
+    #[error(
+        "Runs {} seconds apart, at least {} required.",
+        .interval.num_seconds(),
+        .min.num_seconds()
+    )]
+    TooFrequent { interval: TimeDelta, min: TimeDelta },
+    #[error("Never runs within the next {} days.", .horizon.num_days())]
+    Unsatisfiable { horizon: TimeDelta },
+    #[error(transparent)]
+    Invalid(#[from] CronError),
+}
+
+/// Restrictions applied to schedules typed by end users.
+#[derive(Debug, Clone)]
+pub struct Limits {
+    dialects: Vec<Dialect>,
+    max_length: usize,
+    max_items: usize,
+    min_interval: Option<TimeDelta>,
+    horizon: TimeDelta,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            dialects: vec![Dialect::Unix],
+            max_length: 256,
+            max_items: 32,
+            min_interval: None,
+            horizon: TimeDelta::days(366),
+        }
+    }
+}
+
+impl Limits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_dialects(mut self, dialects: &[Dialect]) -> Self {
+        self.dialects = dialects.to_vec();
+        self
+    }
+
+    pub fn with_max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    /// Caps the number of values, ranges and steps across all fields.
+    pub fn with_max_items(mut self, max_items: usize) -> Self {
+        self.max_items = max_items;
+        self
+    }
+
+    pub fn with_min_interval(mut self, min_interval: TimeDelta) -> Self {
+        self.min_interval = Some(min_interval);
+        self
+    }
+
+    /// Tasks must run at least once within `horizon`.
+    pub fn with_horizon(mut self, horizon: TimeDelta) -> Self {
+        self.horizon = horizon;
+        self
+    }
+
+    /// Checks the complexity, frequency and horizon limits of an already
+    /// built task, counting runs from `from`.
+    pub fn vet(&self, task: &CronTask, from: DateTime<Local>) -> Result<(), Rejection> {
+        let fields = [
+            task.minute(),
+            task.hour(),
+            task.month_day(),
+            task.month(),
+            task.week_day(),
+        ];
+        let items = fields.into_iter().chain(task.second()).map(items).sum();
+
+        if items > self.max_items {
+            return Err(Rejection::TooComplex {
+                items,
+                max: self.max_items,
+            });
+        }
+
+        if task.is_reboot() {
+            return Ok(());
+        }
+
+        let Some(mut last) = task.next_occurrence_within(from, self.horizon) else {
+            return Err(Rejection::Unsatisfiable {
+                horizon: self.horizon,
+            });
+        };
+
+        if let Some(min) = self.min_interval {
+            for _ in 1..FREQUENCY_SAMPLES {
+                let Some(next) = task.try_next_occurrence(last) else {
+                    break;
+                };
+                if next - last < min {
+                    return Err(Rejection::TooFrequent {
+                        interval: next - last,
+                        min,
+                    });
+                }
+                last = next;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl CronTask {
+    /// Parses a line typed by an end user, refusing it unless it fits
+    /// `limits`. Runs are counted from `from`.
+    pub fn parse_untrusted(
+        dialect: Dialect,
+        line: &str,
+        limits: &Limits,
+        from: DateTime<Local>,
+    ) -> Result<CronTask, Rejection> {
+        if !limits.dialects.contains(&dialect) {
+            return Err(Rejection::Dialect(dialect));
+        }
+
+        if line.len() > limits.max_length {
+            return Err(Rejection::TooLong {
+                length: line.len(),
+                max: limits.max_length,
+            });
+        }
+
+        let task = CronTask::parse_with(dialect, line)?;
+        limits.vet(&task, from)?;
+
+        Ok(task)
+    }
+}
+
+fn items(value: &CronValue) -> usize {
+    match value {
+        CronValue::List(list) => list.iter().map(items).sum(),
+        CronValue::Interval(base, _) => items(base) + 1,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::TimeZone;
+
+    use crate::value::on;
+
+    fn make_datetime(year: i32, month: u32, day: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(year, month, day, 0, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_parse_untrusted_accepts() {
+        let limits = Limits::new().with_min_interval(TimeDelta::minutes(5));
+        let from = make_datetime(2025, 1, 1);
+        let task =
+            CronTask::parse_untrusted(Dialect::Unix, "*/15 * * * * /bin/true", &limits, from);
+
+        assert!(task.is_ok());
+    }
+
+    #[test]
+    fn test_parse_untrusted_dialect_and_length() {
+        let limits = Limits::new();
+        let from = make_datetime(2025, 1, 1);
+
+        assert!(matches!(
+            CronTask::parse_untrusted(Dialect::Quartz, "0 0 0 ? * * /bin/true", &limits, from),
+            Err(Rejection::Dialect(Dialect::Quartz))
+        ));
+        assert!(matches!(
+            CronTask::parse_untrusted(
+                Dialect::Unix,
+                "* * * * * /bin/true",
+                &limits.with_max_length(10),
+                from
+            ),
+            Err(Rejection::TooLong {
+                length: 19,
+                max: 10
+            })
+        ));
+        assert!(matches!(
+            CronTask::parse_untrusted(Dialect::Unix, "* * * /bin/true", &Limits::new(), from),
+            Err(Rejection::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_vet_complexity() {
+        let task = CronTask::parse("1,2,3,4 */2 * * 1-5 /bin/true").unwrap();
+        let from = make_datetime(2025, 1, 1);
+
+        assert!(Limits::new().with_max_items(9).vet(&task, from).is_ok());
+        assert!(matches!(
+            Limits::new().with_max_items(8).vet(&task, from),
+            Err(Rejection::TooComplex { items: 9, max: 8 })
+        ));
+    }
+
+    #[test]
+    fn test_vet_frequency() {
+        let task = CronTask::parse("0,1,30 * * * * /bin/true").unwrap();
+        let limits = Limits::new().with_min_interval(TimeDelta::minutes(5));
+        let rejection = limits.vet(&task, make_datetime(2025, 1, 1)).unwrap_err();
+
+        assert!(matches!(rejection, Rejection::TooFrequent { .. }));
+        assert_eq!(
+            rejection.to_string(),
+            "Runs 60 seconds apart, at least 300 required."
+        );
+    }
+
+    #[test]
+    fn test_vet_horizon() {
+        // Parsing refuses February 30th, the builder does not.
+        let task = CronTask::builder()
+            .minutes(on(0))
+            .hour(on(0))
+            .month_day(on(30))
+            .month(on(2))
+            .path("/bin/true".to_string())
+            .build()
+            .unwrap();
+        let rejection = Limits::new()
+            .vet(&task, make_datetime(2025, 1, 1))
+            .unwrap_err();
+
+        assert!(matches!(rejection, Rejection::Unsatisfiable { .. }));
+        assert_eq!(
+            rejection.to_string(),
+            "Never runs within the next 366 days."
+        );
+    }
+}
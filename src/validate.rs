@@ -0,0 +1,303 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    cron::Cron,
+    crontab::Crontab,
+    error::{CronError, Result},
+};
+
+/// Outcome of [`Cron::validate_dir`], one entry per schedule file found.
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    pub files: Vec<FileReport>,
+}
+
+#[derive(Debug)]
+pub struct FileReport {
+    pub path: PathBuf,
+    /// Number of tasks declared, when the file is valid.
+    pub tasks: Option<usize>,
+    pub error: Option<FileError>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileError {
+    /// Line of the failing task in crontab files.
+    pub line: Option<usize>,
+    /// Name of the failing task in config files.
+    pub task: Option<String>,
+    pub message: String,
+    /// Replacement for the faulty value, for tools offering a fix.
+    pub suggestion: Option<String>,
+}
+
+impl From<CronError> for FileError {
+    fn from(error: CronError) -> Self {
+        match error {
+            CronError::Line { line, source } => FileError {
+                line: Some(line),
+                ..(*source).into()
+            },
+            CronError::Task { name, source } => FileError {
+                task: Some(name),
+                ..(*source).into()
+            },
+            error => FileError {
+                line: None,
+                task: None,
+                suggestion: error.suggestion().map(str::to_string),
+                message: error.to_string(),
+            },
+        }
+    }
+}
+
+impl ValidationReport {
+    pub fn is_ok(&self) -> bool {
+        self.files.iter().all(|file| file.error.is_none())
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &FileReport> {
+        self.files.iter().filter(|file| file.error.is_some())
+    }
+
+    /// Renders the report as a JSON object, for CI tooling.
+    pub fn to_json(&self) -> String {
+        let files = self
+            .files
+            .iter()
+            .map(|file| {
+                let mut fields = vec![format!(
+                    "\"path\":{}",
+                    json_string(&file.path.display().to_string())
+                )];
+                if let Some(tasks) = file.tasks {
+                    fields.push(format!("\"tasks\":{tasks}"));
+                }
+                if let Some(error) = &file.error {
+                    let mut error_fields = Vec::new();
+                    if let Some(line) = error.line {
+                        error_fields.push(format!("\"line\":{line}"));
+                    }
+                    if let Some(task) = &error.task {
+                        error_fields.push(format!("\"task\":{}", json_string(task)));
+                    }
+                    error_fields.push(format!("\"message\":{}", json_string(&error.message)));
+                    if let Some(suggestion) = &error.suggestion {
+                        error_fields.push(format!("\"suggestion\":{}", json_string(suggestion)));
+                    }
+                    fields.push(format!("\"error\":{{{}}}", error_fields.join(",")));
+                }
+
+                format!("{{{}}}", fields.join(","))
+            })
+            .collect::<Vec<_>>();
+
+        format!(
+            "{{\"ok\":{},\"files\":[{}]}}",
+            self.is_ok(),
+            files.join(",")
+        )
+    }
+}
+
+impl Cron {
+    /// Parses every schedule file under `dir`, recursively: crontabs, named
+    /// without extension or with `.cron` or `.crontab`, and config files
+    /// when the `toml` or `yaml` feature is enabled. Hidden files are
+    /// skipped. Invalid files are reported rather than failing the walk.
+    pub fn validate_dir(dir: impl AsRef<Path>) -> Result<ValidationReport> {
+        validate(dir.as_ref(), false)
+    }
+
+    /// Same as `validate_dir`, also failing files with a task that has no
+    /// owner.
+    pub fn validate_dir_strict(dir: impl AsRef<Path>) -> Result<ValidationReport> {
+        validate(dir.as_ref(), true)
+    }
+}
+
+fn validate(dir: &Path, strict: bool) -> Result<ValidationReport> {
+    let mut paths = Vec::new();
+    collect(dir, &mut paths)?;
+    paths.sort();
+
+    let files = paths
+        .into_iter()
+        .filter_map(|path| {
+            let result =
+                load(&path)?.and_then(|cron| if strict { check_owners(cron) } else { Ok(cron) });
+            Some(FileReport {
+                path,
+                tasks: result.as_ref().ok().map(|cron| cron.into_iter().count()),
+                error: result.err().map(FileError::from),
+            })
+        })
+        .collect();
+
+    Ok(ValidationReport { files })
+}
+
+fn check_owners(cron: Cron) -> Result<Cron> {
+    match cron.into_iter().find(|task| task.owner().is_none()) {
+        Some(task) => Err(CronError::Task {
+            name: task
+                .name()
+                .map(str::to_string)
+                .unwrap_or_else(|| task.command_line()),
+            source: Box::new(CronError::MissingOwner),
+        }),
+        None => Ok(cron),
+    }
+}
+
+fn collect(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+        {
+            continue;
+        }
+
+        if path.is_dir() {
+            collect(&path, paths)?;
+        } else {
+            paths.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Loads `path` according to its extension, `None` when it is not a
+/// schedule file.
+fn load(path: &Path) -> Option<Result<Cron>> {
+    let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase());
+
+    match extension.as_deref() {
+        None | Some("cron" | "crontab") => Some(Crontab::from_file(path).map(Crontab::into_cron)),
+        #[cfg(feature = "toml")]
+        Some("toml") => Some(Cron::from_toml(path)),
+        #[cfg(feature = "yaml")]
+        Some("yaml" | "yml") => Some(Cron::from_yaml(path)),
+        _ => None,
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("cron_dsl_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_validate_dir() {
+        let dir = temp_dir("validate");
+        fs::write(dir.join("backup"), "0 3 * * * /usr/bin/backup\n").unwrap();
+        fs::write(
+            dir.join("nested/report.cron"),
+            "# daily\n0 9 * * * /usr/bin/report\n61 * * * * /usr/bin/broken\n",
+        )
+        .unwrap();
+        fs::write(dir.join("README.md"), "not a crontab").unwrap();
+        fs::write(dir.join(".hidden"), "not a crontab").unwrap();
+
+        let report = Cron::validate_dir(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(!report.is_ok());
+        assert_eq!(report.files.len(), 2);
+        assert_eq!(report.files[0].tasks, Some(1));
+
+        let failure = report.failures().next().unwrap();
+        assert!(failure.path.ends_with("nested/report.cron"));
+        let error = failure.error.as_ref().unwrap();
+        assert_eq!(error.line, Some(3));
+        assert_eq!(error.suggestion.as_deref(), Some("59"));
+    }
+
+    #[test]
+    fn test_validate_dir_strict() {
+        let dir = temp_dir("validate_strict");
+        fs::write(
+            dir.join("owned"),
+            "# owner: ops@example.com\n0 3 * * * /usr/bin/backup\n",
+        )
+        .unwrap();
+        fs::write(dir.join("nested/orphan"), "0 9 * * * /usr/bin/report\n").unwrap();
+
+        let lenient = Cron::validate_dir(&dir).unwrap();
+        let strict = Cron::validate_dir_strict(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(lenient.is_ok());
+        let failures: Vec<&FileReport> = strict.failures().collect();
+        assert_eq!(failures.len(), 1);
+        assert!(failures[0].path.ends_with("nested/orphan"));
+        assert_eq!(
+            failures[0].error,
+            Some(FileError {
+                line: None,
+                task: Some("/usr/bin/report".to_string()),
+                message: "Missing owner.".to_string(),
+                suggestion: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_report_json() {
+        let report = ValidationReport {
+            files: vec![
+                FileReport {
+                    path: "ok".into(),
+                    tasks: Some(2),
+                    error: None,
+                },
+                FileReport {
+                    path: "bad\"name".into(),
+                    tasks: None,
+                    error: Some(FileError {
+                        line: Some(1),
+                        task: None,
+                        message: "Invalid cron expression: Hour must be within 0-23, try `23` instead of `24`.".to_string(),
+                        suggestion: Some("23".to_string()),
+                    }),
+                },
+            ],
+        };
+
+        assert_eq!(
+            report.to_json(),
+            r#"{"ok":false,"files":[{"path":"ok","tasks":2},{"path":"bad\"name","error":{"line":1,"message":"Invalid cron expression: Hour must be within 0-23, try `23` instead of `24`.","suggestion":"23"}}]}"#
+        );
+    }
+}
@@ -1,6 +1,15 @@
-use std::{fmt::Display, ops::Range};
-
-use chrono::{Month, Weekday};
+#[cfg(not(feature = "std"))]
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{fmt::Display, ops::Range};
+#[cfg(feature = "std")]
+use std::hash::{BuildHasher, RandomState};
+
+use chrono::{Datelike, Month, NaiveDate, Weekday};
 use cronvalue::FromTuple;
 
 use crate::error::{CronError, Result};
@@ -13,7 +22,7 @@ pub enum ValueKind {
 }
 
 impl Display for ValueKind {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::Day(d) => write!(f, "{d}"),
             Self::Month(m) => write!(f, "{m:?}"),
@@ -44,8 +53,8 @@ impl From<ValueKind> for u8 {
     fn from(value: ValueKind) -> Self {
         match value {
             ValueKind::Day(d) => d as u8,
-            ValueKind::Month(m) => m as u8,
-            ValueKind::Number(n) => n as u8,
+            ValueKind::Month(m) => m.number_from_month() as u8,
+            ValueKind::Number(n) => n,
         }
     }
 }
@@ -54,8 +63,8 @@ impl From<&ValueKind> for u8 {
     fn from(value: &ValueKind) -> Self {
         match value {
             ValueKind::Day(d) => *d as u8,
-            ValueKind::Month(m) => *m as u8,
-            ValueKind::Number(n) => *n as u8,
+            ValueKind::Month(m) => m.number_from_month() as u8,
+            ValueKind::Number(n) => *n,
         }
     }
 }
@@ -64,7 +73,7 @@ impl From<ValueKind> for usize {
     fn from(value: ValueKind) -> Self {
         match value {
             ValueKind::Day(d) => d as usize,
-            ValueKind::Month(m) => m as usize,
+            ValueKind::Month(m) => m.number_from_month() as usize,
             ValueKind::Number(n) => n as usize,
         }
     }
@@ -74,27 +83,34 @@ impl From<&ValueKind> for usize {
     fn from(value: &ValueKind) -> Self {
         match value {
             ValueKind::Day(d) => *d as usize,
-            ValueKind::Month(m) => *m as usize,
+            ValueKind::Month(m) => m.number_from_month() as usize,
             ValueKind::Number(n) => *n as usize,
         }
     }
 }
 
-#[derive(Debug, FromTuple, Clone)]
+#[derive(Debug, FromTuple, Clone, Default)]
 pub enum CronValue {
     Range(Range<u8>),
     Value(ValueKind),
     List(Vec<CronValue>),
     Interval(Box<CronValue>, ValueKind),
+    /// Last day of the month, only meaningful in the month day field.
+    Last,
+    /// Week day nearest to the given day of the month, without leaving the
+    /// month. Only meaningful in the month day field.
+    NearestWeekday(u8),
+    /// Nth occurrence of a week day in the month, such as the second Monday.
+    /// Only meaningful in the week day field.
+    Nth(ValueKind, u8),
+    /// Jenkins-style `H`: a value derived from the task's hash key, within
+    /// optional inclusive bounds and repeating every optional step. Resolved
+    /// when the task is built.
+    Hashed(Option<(u8, u8)>, Option<u8>),
+    #[default]
     All,
 }
 
-impl Default for CronValue {
-    fn default() -> Self {
-        CronValue::All
-    }
-}
-
 impl From<u8> for CronValue {
     fn from(value: u8) -> Self {
         CronValue::Value(value.into())
@@ -111,7 +127,7 @@ where
 }
 
 impl Display for CronValue {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             CronValue::Range(r) => write!(f, "{}-{}", r.start, r.end),
             CronValue::Value(v) => v.fmt(f),
@@ -123,7 +139,20 @@ impl Display for CronValue {
                     .join(",");
                 write!(f, "{}", fmt)
             }
-            CronValue::Interval(base, step) => write!(f, "{}/{}", base.as_ref().to_string(), step),
+            CronValue::Interval(base, step) => write!(f, "{}/{}", base.as_ref(), step),
+            CronValue::Last => write!(f, "L"),
+            CronValue::NearestWeekday(day) => write!(f, "{day}W"),
+            CronValue::Nth(day, n) => write!(f, "{day}#{n}"),
+            CronValue::Hashed(bounds, step) => {
+                write!(f, "H")?;
+                if let Some((start, end)) = bounds {
+                    write!(f, "({start}-{end})")?;
+                }
+                if let Some(step) = step {
+                    write!(f, "/{step}")?;
+                }
+                Ok(())
+            }
             CronValue::All => write!(f, "*"),
         }
     }
@@ -156,6 +185,29 @@ impl CronValue {
         }
     }
 
+    /// Shifts an interval by `offset`, so `*/15` fires at 7, 22, 37 and 52
+    /// with an offset of 7. The offset is taken modulo the step, and other
+    /// values are left unchanged.
+    pub fn phase(self, offset: u8) -> Self {
+        match self {
+            CronValue::Interval(base, step) => {
+                let Some(offset) = offset.checked_rem(u8::from(&step)) else {
+                    return CronValue::Interval(base, step);
+                };
+
+                match *base {
+                    CronValue::All => CronValue::Interval(Box::new(offset.into()), step),
+                    CronValue::Range(r) => CronValue::Interval(
+                        Box::new(CronValue::Range(r.start.saturating_add(offset)..r.end)),
+                        step,
+                    ),
+                    base => CronValue::Interval(Box::new(base), step),
+                }
+            }
+            v => v,
+        }
+    }
+
     pub fn verify_for_minute(&self) -> Result<()> {
         self.verify(0, 60)?;
 
@@ -183,10 +235,7 @@ impl CronValue {
                     Err(CronError::InvalidCronValue)
                 }
             }
-            CronValue::List(l) => l
-                .iter()
-                .map(|v| v.verify_for_minute())
-                .fold(Ok(()), |acc, v| if v.is_err() { v } else { acc }),
+            CronValue::List(l) => l.iter().try_for_each(|v| v.verify_for_minute()),
             _ => Ok(()),
         }
     }
@@ -194,16 +243,17 @@ impl CronValue {
     pub fn verify(&self, min: u8, max: u8) -> Result<()> {
         match self {
             CronValue::Range(r) => {
-                if r.start < r.end && r.start >= min && r.end <= max {
+                if r.start <= r.end && r.start >= min && r.end < max {
                     Ok(())
                 } else {
                     Err(CronError::InvalidCronValue)
                 }
             }
-            CronValue::Interval(_, v) => {
+            CronValue::Interval(base, v) => {
+                base.verify(min, max)?;
                 let v: u8 = v.into();
 
-                if v < max {
+                if v > 0 && v < max {
                     Ok(())
                 } else {
                     Err(CronError::InvalidCronValue)
@@ -212,60 +262,172 @@ impl CronValue {
             CronValue::Value(v) => {
                 let v: u8 = v.into();
 
-                if v < max {
+                if v >= min && v < max {
+                    Ok(())
+                } else {
+                    Err(CronError::InvalidCronValue)
+                }
+            }
+            CronValue::List(l) => l.iter().try_for_each(|v| v.verify(min, max)),
+            CronValue::NearestWeekday(day) => {
+                if *day >= 1 && *day < max {
                     Ok(())
                 } else {
                     Err(CronError::InvalidCronValue)
                 }
             }
-            CronValue::List(l) => l
-                .iter()
-                .map(|v| v.verify(min, max))
-                .fold(Ok(()), |acc, v| if v.is_err() { v } else { acc }),
+            CronValue::Nth(day, n) => {
+                if u8::from(day) < max && (1..=5).contains(n) {
+                    Ok(())
+                } else {
+                    Err(CronError::InvalidCronValue)
+                }
+            }
+            CronValue::Hashed(..) => Err(CronError::InvalidCronValue),
             _ => Ok(()),
         }
     }
 
     pub fn matches(&self, value: u8) -> bool {
+        self.matches_in(value, 0)
+    }
+
+    /// Matches `value` in a field whose values start at `first`, which
+    /// steps over `*` count from as in Vixie cron: `*/10` matches 1, 11, 21
+    /// and 31 as a month day.
+    pub fn matches_in(&self, value: u8, first: u8) -> bool {
         match self {
             CronValue::Range(r) => r.start <= value && value <= r.end,
             CronValue::Value(v) => u8::from(v) == value,
-            CronValue::List(cron_values) => cron_values.iter().any(|v| v.matches(value)),
-            CronValue::Interval(base, step) => match base.as_ref() {
-                CronValue::All => value % u8::from(step) == 0,
-                CronValue::Range(r) => {
-                    if value < r.start || value > r.end {
-                        return false;
-                    }
-                    (value - r.start) % u8::from(step) == 0
-                }
-                CronValue::Value(v) => value == u8::from(v) && value % u8::from(step) == 0,
-                CronValue::List(list) => list
-                    .iter()
-                    .any(|v| CronValue::Interval(v.clone().into(), step.clone()).matches(value)),
-                _ => false,
-            },
+            CronValue::List(cron_values) => cron_values.iter().any(|v| v.matches_in(value, first)),
+            CronValue::Interval(base, step) => interval_matches(base, u8::from(step), value, first),
+            CronValue::Last
+            | CronValue::NearestWeekday(_)
+            | CronValue::Nth(..)
+            | CronValue::Hashed(..) => false,
             CronValue::All => true,
         }
     }
 
+    /// Matches the day of `date`, resolving the values that depend on the
+    /// rest of its month which [`CronValue::matches`] cannot.
+    pub fn matches_month_day(&self, date: NaiveDate) -> bool {
+        let day = date.day() as u8;
+
+        match self {
+            CronValue::Last => day == last_day_of_month(date),
+            CronValue::NearestWeekday(target) => nearest_weekday_in(date, *target) == Some(day),
+            CronValue::List(list) => list.iter().any(|v| v.matches_month_day(date)),
+            _ => self.matches_in(day, 1),
+        }
+    }
+
+    /// Matches a week day falling on the given day of its month, resolving
+    /// [`CronValue::Nth`] which [`CronValue::matches`] cannot.
+    pub fn matches_week_day(&self, week_day: u8, day: u8) -> bool {
+        match self {
+            CronValue::Nth(d, n) => u8::from(d) == week_day && (day - 1) / 7 + 1 == *n,
+            CronValue::List(list) => list.iter().any(|v| v.matches_week_day(week_day, day)),
+            _ => self.matches(week_day),
+        }
+    }
+
+    /// Whether the value narrows its field, that is does not start with a
+    /// `*`. Cron matches a date when either day field does once both are
+    /// restricted, as `*/2` still counts as unrestricted there. Week days
+    /// stepped over `*` parse to a list, the parser reading the star from
+    /// the text instead.
+    pub fn is_restricted(&self) -> bool {
+        match self {
+            CronValue::All => false,
+            CronValue::Interval(base, _) => base.is_restricted(),
+            _ => true,
+        }
+    }
+
+    /// Whether the value uses items only meaningful in the month day field.
+    pub fn has_month_day_items(&self) -> bool {
+        self.any_item(&|v| matches!(v, CronValue::Last | CronValue::NearestWeekday(_)))
+    }
+
+    /// Whether the value uses items only meaningful in the week day field.
+    pub fn has_week_day_items(&self) -> bool {
+        self.any_item(&|v| matches!(v, CronValue::Nth(..)))
+    }
+
+    fn any_item(&self, f: &impl Fn(&CronValue) -> bool) -> bool {
+        match self {
+            CronValue::List(list) => list.iter().any(|v| v.any_item(f)),
+            CronValue::Interval(base, _) => base.any_item(f),
+            v => f(v),
+        }
+    }
+
     pub fn min_value(&self) -> Option<u8> {
         match self {
             CronValue::Value(v) => Some(u8::from(v)),
             CronValue::Range(r) => Some(r.start),
             CronValue::Interval(base, step) => base.min_value().map(|v| v - (v % u8::from(step))),
             CronValue::List(list) => list.iter().filter_map(|v| v.min_value()).min(),
+            CronValue::Last => Some(28),
+            CronValue::NearestWeekday(day) => Some(day.saturating_sub(2).max(1)),
+            CronValue::Nth(day, _) => Some(u8::from(day)),
+            CronValue::Hashed(bounds, _) => bounds.map(|(start, _)| start),
             CronValue::All => Some(0),
         }
     }
 
     pub fn next_value(&self, current: u8, max: u8) -> Option<u8> {
-        for v in current..=max {
-            if self.matches(v) {
-                return Some(v);
+        (current..=max).find(|v| self.matches(*v))
+    }
+
+    /// Previous matching value down to `min`, the first value of the field.
+    pub fn prev_value(&self, current: u8, min: u8) -> Option<u8> {
+        (min..=current).rev().find(|v| self.matches_in(*v, min))
+    }
+
+    /// Replaces [`CronValue::Hashed`] items with concrete values derived
+    /// from `hash`, unbounded ones spreading over `min..=max`. Fails on a
+    /// zero step or bounds ending before they start.
+    pub fn resolve_hash(self, hash: u64, min: u8, max: u8) -> Result<CronValue> {
+        Ok(match self {
+            CronValue::Hashed(bounds, step) => {
+                let (start, end) = bounds.unwrap_or((min, max));
+                if start > end || step == Some(0) {
+                    return Err(CronError::InvalidCronValue);
+                }
+                let span = (end - start) as u64 + 1;
+
+                match step {
+                    Some(step) if (step as u64) < span => {
+                        let first = start + (hash % step as u64) as u8;
+                        interval(CronValue::Range(first..end), step)
+                    }
+                    _ => (start + (hash % span) as u8).into(),
+                }
+            }
+            CronValue::List(list) => CronValue::List(
+                list.into_iter()
+                    .map(|v| v.resolve_hash(hash, min, max))
+                    .collect::<Result<_>>()?,
+            ),
+            v => v,
+        })
+    }
+}
+
+fn interval_matches(base: &CronValue, step: u8, value: u8, first: u8) -> bool {
+    match base {
+        CronValue::All => value >= first && (value - first).is_multiple_of(step),
+        CronValue::Range(r) => {
+            if value < r.start || value > r.end {
+                return false;
             }
+            (value - r.start).is_multiple_of(step)
         }
-        None
+        CronValue::Value(v) => value >= u8::from(v) && (value - u8::from(v)).is_multiple_of(step),
+        CronValue::List(list) => list.iter().any(|v| interval_matches(v, step, value, first)),
+        _ => false,
     }
 }
 
@@ -284,11 +446,10 @@ pub fn every(step: impl Into<ValueKind>) -> CronValue {
     CronValue::Interval(Box::new(CronValue::All), step.into())
 }
 
+/// Range from `begin` to `end`, both included as ranges are matched.
 pub fn from<T: Into<CronValue>, Y: Into<CronValue>>(begin: T, end: Y) -> FromState {
-    let v: u8 = end.into().into();
-    let end = CronValue::Value(ValueKind::Number(v + 1));
     FromState {
-        value: begin.into()..end,
+        value: begin.into()..end.into(),
     }
 }
 
@@ -296,6 +457,18 @@ pub fn all() -> CronValue {
     CronValue::All
 }
 
+pub fn last_day() -> CronValue {
+    CronValue::Last
+}
+
+pub fn nearest_weekday(day: u8) -> CronValue {
+    CronValue::NearestWeekday(day)
+}
+
+pub fn nth(day: Weekday, n: u8) -> CronValue {
+    CronValue::Nth(day.into(), n)
+}
+
 pub fn on(value: u8) -> OnState {
     OnState {
         value: CronValue::Value(value.into()),
@@ -306,9 +479,9 @@ pub struct OnState {
     value: CronValue,
 }
 
-impl Into<CronValue> for OnState {
-    fn into(self) -> CronValue {
-        self.value
+impl From<OnState> for CronValue {
+    fn from(val: OnState) -> Self {
+        val.value
     }
 }
 
@@ -320,21 +493,104 @@ impl OnState {
     }
 }
 
+/// Picks a single value among candidates when converted into a
+/// [`CronValue`] with `try_from`. The pick is random unless a seed is
+/// given, in which case it is stable across restarts.
+pub fn one_of(candidates: &[u8]) -> OneOfState {
+    OneOfState {
+        candidates: candidates.iter().map(|v| (*v, 1)).collect(),
+        seed: None,
+    }
+}
+
+pub struct OneOfState {
+    candidates: Vec<(u8, u32)>,
+    seed: Option<String>,
+}
+
+impl OneOfState {
+    /// Weights the candidates, in order. Missing weights count as 1.
+    pub fn weights(mut self, weights: &[u32]) -> Self {
+        for ((_, weight), w) in self.candidates.iter_mut().zip(weights) {
+            *weight = *w;
+        }
+        self
+    }
+
+    pub fn seeded(mut self, seed: impl Into<String>) -> Self {
+        self.seed = Some(seed.into());
+        self
+    }
+}
+
+impl TryFrom<OneOfState> for CronValue {
+    type Error = CronError;
+
+    /// Fails without candidates, or with only zero weights, as there is
+    /// nothing to pick.
+    fn try_from(val: OneOfState) -> Result<Self> {
+        let total: u64 = val.candidates.iter().map(|(_, w)| *w as u64).sum();
+        if total == 0 {
+            return Err(CronError::InvalidExpression(
+                "no candidate with a weight to pick from".to_string(),
+            ));
+        }
+
+        let hash = match &val.seed {
+            Some(seed) => fnv1a(seed.as_bytes()),
+            #[cfg(feature = "std")]
+            None => RandomState::new().hash_one(()),
+            #[cfg(not(feature = "std"))]
+            None => {
+                return Err(CronError::InvalidExpression(
+                    "picking at random needs the std feature, seed the pick instead".to_string(),
+                ));
+            }
+        };
+        let mut pick = hash % total;
+
+        for (value, weight) in val.candidates {
+            if pick < weight as u64 {
+                return Ok(value.into());
+            }
+            pick -= weight as u64;
+        }
+        unreachable!("pick is below the total weight")
+    }
+}
+
 pub struct FromState {
     value: Range<CronValue>,
 }
 
 impl FromState {
     pub fn every(self, value: u8) -> CronValue {
-        let v: u8 = self.value.end.into();
-        let v = CronValue::Value(ValueKind::Number(v + 1));
-        CronValue::Interval(
-            Box::new(CronValue::Range(self.value.start.into()..v.into())),
-            value.into(),
-        )
+        CronValue::Interval(Box::new(self.into()), value.into())
     }
 }
 
+fn last_day_of_month(date: NaiveDate) -> u8 {
+    (28..=31)
+        .rev()
+        .find(|day| date.with_day(*day as u32).is_some())
+        .unwrap_or(28) as u8
+}
+
+/// Day of `date`'s month on which the week day nearest to `target` falls,
+/// if the month has such a day.
+fn nearest_weekday_in(date: NaiveDate, target: u8) -> Option<u8> {
+    let target = date.with_day(target as u32)?;
+    let day = target.day() as u8;
+
+    Some(match target.weekday() {
+        Weekday::Sat if day == 1 => 3,
+        Weekday::Sat => day - 1,
+        Weekday::Sun if day == last_day_of_month(date) => day - 2,
+        Weekday::Sun => day + 1,
+        _ => day,
+    })
+}
+
 impl From<Weekday> for CronValue {
     fn from(value: Weekday) -> Self {
         CronValue::Value(ValueKind::Day(value))
@@ -347,18 +603,18 @@ impl From<Month> for CronValue {
     }
 }
 
-impl Into<CronValue> for FromState {
-    fn into(self) -> CronValue {
-        CronValue::Range(self.value.start.into()..self.value.end.into())
+impl From<FromState> for CronValue {
+    fn from(val: FromState) -> Self {
+        CronValue::Range(val.value.start.into()..val.value.end.into())
     }
 }
 
-impl Into<u8> for CronValue {
-    fn into(self) -> u8 {
-        match self {
+impl From<CronValue> for u8 {
+    fn from(val: CronValue) -> Self {
+        match val {
             CronValue::Value(value_kind) => match value_kind {
                 ValueKind::Day(weekday) => weekday as u8,
-                ValueKind::Month(month) => month as u8,
+                ValueKind::Month(month) => month.number_from_month() as u8,
                 ValueKind::Number(v) => v,
             },
             _ => unreachable!("Unreachable"),
@@ -366,11 +622,23 @@ impl Into<u8> for CronValue {
     }
 }
 
+/// FNV-1a hash, stable across processes and releases unlike the standard
+/// hashers.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ *b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use chrono::{Month, Weekday};
 
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
     #[test]
     fn test_value_kind_display() {
         assert_eq!(ValueKind::Number(5).to_string(), "5");
@@ -384,7 +652,7 @@ mod tests {
         assert_eq!(u8::from(&day), Weekday::Mon as u8);
 
         let month = ValueKind::Month(Month::March);
-        assert_eq!(u8::from(&month), Month::March as u8);
+        assert_eq!(u8::from(&month), 3);
 
         let num = ValueKind::Number(42);
         assert_eq!(u8::from(&num), 42);
@@ -498,6 +766,207 @@ mod tests {
         assert!(!interval.matches(35));
     }
 
+    #[test]
+    fn test_interval_matches_from_field_minimum() {
+        let tens = every(10);
+        let days: Vec<u8> = (1..=31).filter(|d| tens.matches_in(*d, 1)).collect();
+        assert_eq!(days, [1, 11, 21, 31]);
+
+        // Vixie cron expands `*/N` to every Nth value from the first of the
+        // field, and `a-b/N` to every Nth value from `a`.
+        for (first, last) in [(0, 59), (0, 23), (1, 31), (1, 12)] {
+            for step in 1..=last {
+                let all = every(step);
+                let vixie: Vec<u8> = (first..=last).step_by(step as usize).collect();
+                let matched: Vec<u8> = (first..=last)
+                    .filter(|v| all.matches_in(*v, first))
+                    .collect();
+                assert_eq!(matched, vixie, "*/{step} from {first}");
+
+                let range = interval(CronValue::Range(first + 1..last), step);
+                let vixie: Vec<u8> = (first + 1..=last).step_by(step as usize).collect();
+                let matched: Vec<u8> = (first..=last)
+                    .filter(|v| range.matches_in(*v, first))
+                    .collect();
+                assert_eq!(matched, vixie, "{}-{last}/{step}", first + 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_last_day() {
+        let last = last_day();
+        assert_eq!(last.to_string(), "L");
+        assert!(!last.matches(31));
+        assert!(last.matches_month_day(date(2025, 2, 28)));
+        assert!(last.matches_month_day(date(2025, 4, 30)));
+        assert!(!last.matches_month_day(date(2025, 5, 30)));
+
+        let list = CronValue::List(vec![value(1), last_day()]);
+        assert!(list.matches_month_day(date(2025, 4, 1)));
+        assert!(list.matches_month_day(date(2025, 4, 30)));
+        assert!(!list.matches_month_day(date(2025, 4, 15)));
+        assert!(list.has_month_day_items());
+        assert!(!list.has_week_day_items());
+        assert!(!value(1).has_month_day_items());
+    }
+
+    #[test]
+    fn test_nearest_weekday() {
+        let value = nearest_weekday(15);
+        assert_eq!(value.to_string(), "15W");
+        assert!(!value.matches(15));
+        assert!(value.has_month_day_items());
+
+        // 2025-06-15 is a Sunday, 2025-03-15 a Saturday.
+        assert!(value.matches_month_day(date(2025, 6, 16)));
+        assert!(!value.matches_month_day(date(2025, 6, 15)));
+        assert!(value.matches_month_day(date(2025, 3, 14)));
+        assert!(value.matches_month_day(date(2025, 5, 15)));
+
+        // 2025-03-01 is a Saturday and 2025-08-31 a Sunday: the month is
+        // never left.
+        assert!(nearest_weekday(1).matches_month_day(date(2025, 3, 3)));
+        assert!(nearest_weekday(31).matches_month_day(date(2025, 8, 29)));
+        assert!(!nearest_weekday(31).matches_month_day(date(2025, 4, 30)));
+
+        assert!(value.verify(0, 32).is_ok());
+        assert!(nearest_weekday(0).verify(0, 32).is_err());
+        assert!(nearest_weekday(32).verify(0, 32).is_err());
+    }
+
+    #[test]
+    fn test_nth_week_day() {
+        let second_monday = nth(Weekday::Mon, 2);
+        assert_eq!(second_monday.to_string(), "Mon#2");
+        assert!(!second_monday.matches(0));
+        assert!(second_monday.matches_week_day(0, 8));
+        assert!(second_monday.matches_week_day(0, 14));
+        assert!(!second_monday.matches_week_day(0, 7));
+        assert!(!second_monday.matches_week_day(0, 15));
+        assert!(!second_monday.matches_week_day(1, 9));
+        assert!(second_monday.has_week_day_items());
+
+        assert!(second_monday.verify(0, 7).is_ok());
+        assert!(nth(Weekday::Mon, 0).verify(0, 7).is_err());
+        assert!(nth(Weekday::Mon, 6).verify(0, 7).is_err());
+    }
+
+    #[test]
+    fn test_one_of() {
+        let pick = |seed: &str| CronValue::try_from(one_of(&[0, 15, 30, 45]).seeded(seed)).unwrap();
+
+        assert_eq!(pick("tenant-1").to_string(), pick("tenant-1").to_string());
+        assert!([0, 15, 30, 45].iter().any(|v| pick("tenant-2").matches(*v)));
+
+        let random = CronValue::try_from(one_of(&[5, 10]));
+        #[cfg(feature = "std")]
+        assert!(random.is_ok_and(|random| random.matches(5) || random.matches(10)));
+        #[cfg(not(feature = "std"))]
+        assert!(matches!(random, Err(CronError::InvalidExpression(_))));
+    }
+
+    #[test]
+    fn test_one_of_weights() {
+        for seed in ["a", "b", "c", "d"] {
+            let value = one_of(&[1, 2, 3]).weights(&[0, 1, 0]).seeded(seed);
+            assert!(CronValue::try_from(value).unwrap().matches(2));
+        }
+
+        let never = CronValue::try_from(one_of(&[1, 2]).weights(&[0, 0]));
+        assert!(matches!(never, Err(CronError::InvalidExpression(_))));
+        let empty = CronValue::try_from(one_of(&[]));
+        assert!(matches!(empty, Err(CronError::InvalidExpression(_))));
+    }
+
+    #[test]
+    fn test_hashed() {
+        assert_eq!(CronValue::Hashed(None, None).to_string(), "H");
+        assert_eq!(
+            CronValue::Hashed(Some((0, 29)), Some(10)).to_string(),
+            "H(0-29)/10"
+        );
+        assert!(CronValue::Hashed(None, None).verify(0, 60).is_err());
+
+        assert_eq!(
+            CronValue::Hashed(None, None)
+                .resolve_hash(42, 0, 59)
+                .unwrap()
+                .to_string(),
+            "42"
+        );
+        assert_eq!(
+            CronValue::Hashed(None, Some(15))
+                .resolve_hash(22, 0, 59)
+                .unwrap()
+                .to_string(),
+            "7-59/15"
+        );
+        assert_eq!(
+            CronValue::Hashed(Some((10, 19)), None)
+                .resolve_hash(25, 0, 59)
+                .unwrap()
+                .to_string(),
+            "15"
+        );
+        assert_eq!(
+            CronValue::List(vec![value(1), CronValue::Hashed(None, None)])
+                .resolve_hash(70, 0, 59)
+                .unwrap()
+                .to_string(),
+            "1,10"
+        );
+
+        assert!(
+            CronValue::Hashed(None, Some(0))
+                .resolve_hash(1, 0, 59)
+                .is_err()
+        );
+        assert!(
+            CronValue::Hashed(Some((30, 10)), None)
+                .resolve_hash(1, 0, 59)
+                .is_err()
+        );
+        assert!(
+            CronValue::List(vec![value(1), CronValue::Hashed(None, Some(0))])
+                .resolve_hash(1, 0, 59)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_phase() {
+        let shifted = every(15u8).phase(7);
+
+        assert_eq!(shifted.to_string(), "7/15");
+        assert_eq!(
+            (0..60).filter(|v| shifted.matches(*v)).collect::<Vec<_>>(),
+            [7, 22, 37, 52]
+        );
+
+        let shifted = range(10..30).every(10).phase(5);
+        assert_eq!(shifted.to_string(), "15-30/10");
+        assert!(shifted.matches(25));
+        assert!(!shifted.matches(20));
+
+        assert_eq!(value(5).phase(2).to_string(), "5");
+    }
+
+    #[test]
+    fn test_phase_wraps_around_the_step() {
+        let shifted = every(15u8).phase(70);
+        assert_eq!(shifted.to_string(), "10/15");
+        assert!(shifted.verify(0, 60).is_ok());
+
+        let shifted = range(250..255).every(10).phase(9);
+        assert!(shifted.verify(0, 60).is_err());
+
+        let shifted = range(10..12).every(5).phase(4);
+        assert!(shifted.verify(0, 60).is_err());
+
+        assert_eq!(every(0u8).phase(7).to_string(), "*/0");
+    }
+
     #[test]
     fn test_all_display() {
         let all = CronValue::All;
@@ -602,6 +1071,28 @@ mod tests {
         assert!(value.verify(0, 10).is_err());
     }
 
+    #[test]
+    fn test_verify_bounds_are_inclusive() {
+        assert!(range(50..59).verify(0, 60).is_ok());
+        assert!(range(50..60).verify(0, 60).is_err());
+        assert!(range(5..5).verify(0, 60).is_ok());
+        assert!(
+            CronValue::Range(Range { start: 6, end: 5 })
+                .verify(0, 60)
+                .is_err()
+        );
+        assert!(value(0).verify(1, 13).is_err());
+        assert!(range(0..3).verify(1, 13).is_err());
+    }
+
+    #[test]
+    fn test_verify_interval() {
+        assert!(every(0).verify(0, 60).is_err());
+        assert!(interval(range(10..70), 5).verify(0, 60).is_err());
+        assert!(interval(value(0), 2).verify(1, 32).is_err());
+        assert!(interval(range(10..50), 5).verify(0, 60).is_ok());
+    }
+
     #[test]
     fn test_next_value_range() {
         let range = range(10..20);
@@ -682,9 +1173,9 @@ mod tests {
         let cron_value: CronValue = from_state.into();
         assert!(cron_value.matches(10));
         assert!(cron_value.matches(15));
-        assert!(cron_value.matches(21));
+        assert!(cron_value.matches(20));
         assert!(!cron_value.matches(9));
-        assert!(!cron_value.matches(22));
+        assert!(!cron_value.matches(21));
     }
 
     #[test]
@@ -696,6 +1187,7 @@ mod tests {
         assert!(interval.matches(25));
         assert!(interval.matches(30));
         assert!(!interval.matches(12));
+        assert_eq!(interval.to_string(), "10-30/5");
         assert!(!interval.matches(32));
     }
 
@@ -729,4 +1221,10 @@ mod tests {
             _ => panic!("Expected Month variant"),
         }
     }
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+    }
 }
@@ -0,0 +1,111 @@
+use chrono::{NaiveTime, Weekday};
+
+use crate::{
+    error::{CronError, Result},
+    task::CronTask,
+    value::CronValue,
+    year::YearValue,
+};
+
+impl CronTask {
+    /// Wall clock slots of a schedule repeating every week, such as
+    /// `(Mon, 09:30)`, sorted from Monday. Schedules depending on the day
+    /// of the month, the month or the year do not repeat weekly and are
+    /// refused, the parts at fault all listed in the error.
+    pub fn weekly_pattern(&self) -> Result<Vec<(Weekday, NaiveTime)>> {
+        if self.is_reboot() {
+            return Err(CronError::Unconvertible(vec!["@reboot".to_string()]));
+        }
+
+        let mut unconvertible = Vec::new();
+        if !matches!(self.month_day(), CronValue::All) {
+            unconvertible.push(format!("month day `{}`", self.month_day()));
+        }
+        if !matches!(self.month(), CronValue::All) {
+            unconvertible.push(format!("month `{}`", self.month()));
+        }
+        if self.week_day().has_week_day_items() {
+            unconvertible.push(format!("week day `{}`", self.week_day()));
+        }
+        if self.year() != &YearValue::All {
+            unconvertible.push(format!("year `{}`", self.year()));
+        }
+        if self.has_rules() {
+            unconvertible.push("rules".to_string());
+        }
+        if !unconvertible.is_empty() {
+            return Err(CronError::Unconvertible(unconvertible));
+        }
+
+        let seconds: Vec<u8> = match self.second() {
+            Some(second) => (0..60).filter(|s| second.matches(*s)).collect(),
+            None => vec![0],
+        };
+        let times: Vec<NaiveTime> = (0..24)
+            .filter(|hour| self.hour().matches(*hour))
+            .flat_map(|hour| {
+                (0..60)
+                    .filter(|minute| self.minute().matches(*minute))
+                    .map(move |minute| (hour, minute))
+            })
+            .flat_map(|(hour, minute)| {
+                seconds.iter().filter_map(move |second| {
+                    NaiveTime::from_hms_opt(hour.into(), minute.into(), (*second).into())
+                })
+            })
+            .collect();
+
+        Ok((0..7)
+            .filter(|day| self.week_day().matches(*day))
+            .filter_map(|day| Weekday::try_from(day).ok())
+            .flat_map(|day| times.iter().map(move |time| (day, *time)))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value::{every, on};
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_weekly_pattern() {
+        let task = CronTask::builder()
+            .minutes(every(30))
+            .hour(on(9))
+            .week_day(CronValue::from(Weekday::Mon).and(Weekday::Tue))
+            .path("/usr/bin/standup".to_string())
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            task.weekly_pattern().unwrap(),
+            [
+                (Weekday::Mon, time(9, 0)),
+                (Weekday::Mon, time(9, 30)),
+                (Weekday::Tue, time(9, 0)),
+                (Weekday::Tue, time(9, 30)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_weekly_pattern_not_weekly() {
+        let task = CronTask::builder()
+            .minutes(on(0))
+            .month_day(on(1))
+            .month(on(6))
+            .path("/usr/bin/report".to_string())
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            task.weekly_pattern().unwrap_err().to_string(),
+            "Cannot convert month day `1`, month `6`."
+        );
+    }
+}
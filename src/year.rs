@@ -0,0 +1,171 @@
+#[cfg(not(feature = "std"))]
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt::Display, ops::RangeInclusive};
+
+use crate::error::{CronError, Result};
+
+/// Quartz-style year field. Years do not fit the `u8` based `CronValue`, so
+/// they get their own, smaller, value type.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum YearValue {
+    Value(i32),
+    Range(RangeInclusive<i32>),
+    List(Vec<YearValue>),
+    Interval(Box<YearValue>, u16),
+    #[default]
+    All,
+}
+
+impl From<i32> for YearValue {
+    fn from(value: i32) -> Self {
+        YearValue::Value(value)
+    }
+}
+
+impl From<RangeInclusive<i32>> for YearValue {
+    fn from(value: RangeInclusive<i32>) -> Self {
+        YearValue::Range(value)
+    }
+}
+
+impl Display for YearValue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            YearValue::Value(v) => write!(f, "{v}"),
+            YearValue::Range(r) => write!(f, "{}-{}", r.start(), r.end()),
+            YearValue::List(values) => {
+                let fmt = values
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<String>>()
+                    .join(",");
+                write!(f, "{fmt}")
+            }
+            YearValue::Interval(base, step) => write!(f, "{base}/{step}"),
+            YearValue::All => write!(f, "*"),
+        }
+    }
+}
+
+impl YearValue {
+    pub fn every(self, step: u16) -> Self {
+        YearValue::Interval(Box::new(self), step)
+    }
+
+    pub fn verify(&self) -> Result<()> {
+        match self {
+            YearValue::Range(r) if r.start() > r.end() => Err(CronError::InvalidCronValue),
+            YearValue::List(values) => values.iter().try_for_each(|v| v.verify()),
+            YearValue::Interval(_, 0) => Err(CronError::InvalidCronValue),
+            YearValue::Interval(base, _) => base.verify(),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn matches(&self, year: i32) -> bool {
+        match self {
+            YearValue::Value(v) => *v == year,
+            YearValue::Range(r) => r.contains(&year),
+            YearValue::List(values) => values.iter().any(|v| v.matches(year)),
+            YearValue::Interval(base, step) => {
+                let start = match base.as_ref() {
+                    YearValue::All => 0,
+                    base => match base.min_value() {
+                        Some(start) => start,
+                        None => return false,
+                    },
+                };
+
+                base.matches(year) && year >= start && (year - start) % *step as i32 == 0
+            }
+            YearValue::All => true,
+        }
+    }
+
+    /// Smallest matching year, `None` when unbounded below.
+    pub fn min_value(&self) -> Option<i32> {
+        match self {
+            YearValue::Value(v) => Some(*v),
+            YearValue::Range(r) => Some(*r.start()),
+            YearValue::List(values) => values.iter().filter_map(|v| v.min_value()).min(),
+            YearValue::Interval(base, _) => base.min_value(),
+            YearValue::All => None,
+        }
+    }
+
+    /// Largest matching year, `None` when unbounded above.
+    pub fn max_value(&self) -> Option<i32> {
+        match self {
+            YearValue::Value(v) => Some(*v),
+            YearValue::Range(r) => Some(*r.end()),
+            YearValue::List(values) => values
+                .iter()
+                .map(|v| v.max_value())
+                .collect::<Option<Vec<_>>>()?
+                .into_iter()
+                .max(),
+            YearValue::Interval(base, _) => base.max_value(),
+            YearValue::All => None,
+        }
+    }
+
+    /// First matching year at or after `current`, `None` once the field is
+    /// exhausted.
+    pub fn next_value(&self, current: i32) -> Option<i32> {
+        let max = self.max_value().unwrap_or(i32::MAX);
+
+        (current..=max).find(|year| self.matches(*year))
+    }
+
+    pub fn prev_value(&self, current: i32) -> Option<i32> {
+        let min = self.min_value().unwrap_or(i32::MIN);
+
+        (min..=current).rev().find(|year| self.matches(*year))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_year_display() {
+        assert_eq!(YearValue::from(2025).to_string(), "2025");
+        assert_eq!(YearValue::from(2025..=2030).to_string(), "2025-2030");
+        assert_eq!(YearValue::All.every(2).to_string(), "*/2");
+        assert_eq!(
+            YearValue::List(vec![2025.into(), 2027.into()]).to_string(),
+            "2025,2027"
+        );
+    }
+
+    #[test]
+    fn test_year_matches() {
+        assert!(YearValue::from(2025..=2030).matches(2030));
+        assert!(!YearValue::from(2025..=2030).matches(2031));
+        assert!(YearValue::All.every(2).matches(2024));
+        assert!(!YearValue::All.every(2).matches(2025));
+        assert!(YearValue::from(2025..=2035).every(5).matches(2030));
+        assert!(!YearValue::from(2025..=2035).every(5).matches(2028));
+    }
+
+    #[test]
+    fn test_year_next_value() {
+        assert_eq!(YearValue::from(2025..=2030).next_value(2020), Some(2025));
+        assert_eq!(YearValue::from(2025..=2030).next_value(2031), None);
+        assert_eq!(YearValue::All.every(4).next_value(2025), Some(2028));
+        assert_eq!(YearValue::All.next_value(2025), Some(2025));
+    }
+
+    #[test]
+    fn test_year_verify() {
+        assert!(YearValue::from(2025..=2030).verify().is_ok());
+        let (start, end) = (2030, 2025);
+        assert!(YearValue::Range(start..=end).verify().is_err());
+        assert!(YearValue::All.every(0).verify().is_err());
+    }
+}
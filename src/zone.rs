@@ -0,0 +1,117 @@
+#[cfg(feature = "crontab")]
+use std::fmt::Display;
+
+use chrono::FixedOffset;
+
+#[cfg(feature = "crontab")]
+use crate::task::CronTask;
+use crate::task::CronTaskBuilder;
+
+/// Zone named by a `CRON_TZ` assignment or a `TZ` variable.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Zone {
+    Local,
+    Fixed(FixedOffset),
+    #[cfg(feature = "chrono-tz")]
+    Named(chrono_tz::Tz),
+}
+
+impl Zone {
+    #[cfg(feature = "crontab")]
+    pub(crate) fn of(task: &CronTask) -> Zone {
+        #[cfg(feature = "chrono-tz")]
+        if let Some(timezone) = task.timezone() {
+            return Zone::Named(timezone);
+        }
+
+        task.offset().map_or(Zone::Local, Zone::Fixed)
+    }
+
+    /// `None` for zones this build cannot resolve.
+    pub(crate) fn parse(s: &str) -> Option<Zone> {
+        match parse_posix_offset(s) {
+            Some(offset) => Some(offset.map_or(Zone::Local, Zone::Fixed)),
+            #[cfg(feature = "chrono-tz")]
+            None => s.parse().ok().map(Zone::Named),
+            #[cfg(not(feature = "chrono-tz"))]
+            None => None,
+        }
+    }
+
+    /// Runs the task being built in the zone.
+    pub(crate) fn apply(self, builder: CronTaskBuilder) -> CronTaskBuilder {
+        match self {
+            Zone::Local => builder,
+            Zone::Fixed(offset) => builder.offset(offset),
+            #[cfg(feature = "chrono-tz")]
+            Zone::Named(timezone) => builder.timezone(timezone),
+        }
+    }
+}
+
+#[cfg(feature = "crontab")]
+impl Display for Zone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Zone::Local => Ok(()),
+            Zone::Fixed(offset) => write!(f, "{}", posix_offset(*offset)),
+            #[cfg(feature = "chrono-tz")]
+            Zone::Named(timezone) => write!(f, "{timezone}"),
+        }
+    }
+}
+
+/// Writes `offset` as a POSIX `TZ` value, whose sign counts hours west of
+/// UTC: `+05:30` becomes `UTC-05:30`.
+#[cfg(feature = "crontab")]
+fn posix_offset(offset: FixedOffset) -> String {
+    let west = -offset.local_minus_utc();
+    let sign = if west < 0 { '-' } else { '+' };
+    let (hours, minutes) = (west.abs() / 3600, west.abs() / 60 % 60);
+
+    match (west, minutes) {
+        (0, _) => "UTC".to_string(),
+        (_, 0) => format!("UTC{sign}{hours:02}"),
+        _ => format!("UTC{sign}{hours:02}:{minutes:02}"),
+    }
+}
+
+/// Reads a fixed POSIX `TZ` value such as `UTC` or `UTC-05:30`. Returns
+/// `Some(None)` for an empty value, meaning local time, and `None` for
+/// values naming a zone.
+fn parse_posix_offset(s: &str) -> Option<Option<FixedOffset>> {
+    if s.is_empty() {
+        return Some(None);
+    }
+
+    let rest = s.strip_prefix("UTC").or_else(|| s.strip_prefix("GMT"))?;
+    if rest.is_empty() {
+        return Some(FixedOffset::east_opt(0));
+    }
+
+    let (sign, rest) = match rest.split_at_checked(1)? {
+        ("+", rest) => (1, rest),
+        ("-", rest) => (-1, rest),
+        _ => return None,
+    };
+    let (hours, minutes) = rest.split_once(':').unwrap_or((rest, "0"));
+    let west = hours.parse::<i32>().ok()? * 3600 + minutes.parse::<i32>().ok()? * 60;
+
+    FixedOffset::west_opt(sign * west).map(Some)
+}
+
+#[cfg(all(test, feature = "crontab"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_posix_offset() {
+        let east = |seconds| FixedOffset::east_opt(seconds).unwrap();
+
+        assert_eq!(posix_offset(east(0)), "UTC");
+        assert_eq!(posix_offset(east(-5 * 3600)), "UTC+05");
+        assert_eq!(parse_posix_offset("UTC+05"), Some(Some(east(-5 * 3600))));
+        assert_eq!(parse_posix_offset("GMT"), Some(Some(east(0))));
+        assert_eq!(parse_posix_offset("UTC+x"), None);
+    }
+}